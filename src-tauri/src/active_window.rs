@@ -0,0 +1,183 @@
+use serde::{Deserialize, Serialize};
+
+#[cfg(target_os = "macos")]
+use crate::permissions::{self, PermissionKind};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ActiveWindow {
+    // Executable or application name, e.g. "firefox" or "Slack"
+    pub app: String,
+    pub title: Option<String>,
+}
+
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+fn run(program: &str, args: &[&str]) -> Result<String, String> {
+    let output = std::process::Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| format!("{}: {}", program, e))?;
+    if !output.status.success() {
+        return Err(format!("{} exited with {}", program, output.status));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(any(windows, target_os = "linux"))]
+fn process_name(pid: u32) -> Option<String> {
+    let pid = sysinfo::Pid::from_u32(pid);
+    let mut sys = sysinfo::System::new();
+    sys.refresh_process(pid);
+    sys.process(pid).map(|p| p.name().to_string())
+}
+
+#[cfg(windows)]
+mod win32 {
+    use std::ffi::c_void;
+
+    #[link(name = "user32")]
+    extern "system" {
+        pub fn GetForegroundWindow() -> *mut c_void;
+        pub fn GetWindowTextW(hwnd: *mut c_void, text: *mut u16, max: i32) -> i32;
+        pub fn GetWindowThreadProcessId(hwnd: *mut c_void, pid: *mut u32) -> u32;
+        pub fn GetWindowRect(hwnd: *mut c_void, rect: *mut Rect) -> i32;
+        pub fn GetShellWindow() -> *mut c_void;
+        pub fn GetDesktopWindow() -> *mut c_void;
+        pub fn MonitorFromWindow(hwnd: *mut c_void, flags: u32) -> *mut c_void;
+        pub fn GetMonitorInfoW(monitor: *mut c_void, info: *mut MonitorInfo) -> i32;
+    }
+
+    pub const MONITOR_DEFAULTTONEAREST: u32 = 2;
+
+    #[repr(C)]
+    #[derive(Default, PartialEq)]
+    pub struct Rect {
+        pub left: i32,
+        pub top: i32,
+        pub right: i32,
+        pub bottom: i32,
+    }
+
+    #[repr(C)]
+    #[derive(Default)]
+    pub struct MonitorInfo {
+        pub size: u32,
+        pub monitor: Rect,
+        pub work: Rect,
+        pub flags: u32,
+    }
+}
+
+#[cfg(windows)]
+pub fn current() -> Result<ActiveWindow, String> {
+    // SAFETY: plain Win32 queries; the buffers are owned by this frame and sized correctly
+    unsafe {
+        let hwnd = win32::GetForegroundWindow();
+        if hwnd.is_null() {
+            return Err("no foreground window".to_string());
+        }
+        let mut buf = [0u16; 512];
+        let len = win32::GetWindowTextW(hwnd, buf.as_mut_ptr(), buf.len() as i32);
+        let title = (len > 0).then(|| String::from_utf16_lossy(&buf[..len as usize]));
+        let mut pid = 0u32;
+        win32::GetWindowThreadProcessId(hwnd, &mut pid);
+        let app = process_name(pid).ok_or_else(|| format!("process {} not found", pid))?;
+        Ok(ActiveWindow { app, title })
+    }
+}
+
+// System Events only answers with the Accessibility permission, which also unlocks the
+// window title.
+#[cfg(target_os = "macos")]
+pub fn current() -> Result<ActiveWindow, String> {
+    if !permissions::is_granted(PermissionKind::Accessibility) {
+        return Err("the Accessibility permission has not been granted".to_string());
+    }
+    let app = run(
+        "osascript",
+        &[
+            "-e",
+            "tell application \"System Events\" to get name of first application process whose frontmost is true",
+        ],
+    )?;
+    // Apps without windows have no front window
+    let title = run(
+        "osascript",
+        &[
+            "-e",
+            "tell application \"System Events\" to get name of front window of (first application process whose frontmost is true)",
+        ],
+    )
+    .ok()
+    .filter(|t| !t.is_empty());
+    Ok(ActiveWindow { app, title })
+}
+
+// X11 only; Wayland compositors don't expose the focused window to other clients.
+#[cfg(target_os = "linux")]
+pub fn current() -> Result<ActiveWindow, String> {
+    let pid: u32 = run("xdotool", &["getactivewindow", "getwindowpid"])?
+        .parse()
+        .map_err(|e| format!("xdotool: {}", e))?;
+    let title = run("xdotool", &["getactivewindow", "getwindowname"]).ok();
+    let app = process_name(pid).ok_or_else(|| format!("process {} not found", pid))?;
+    Ok(ActiveWindow { app, title })
+}
+
+#[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+pub fn current() -> Result<ActiveWindow, String> {
+    Err("active window detection is not supported on this platform".to_string())
+}
+
+// Whether the focused window covers its whole monitor. The desktop itself doesn't count.
+#[cfg(windows)]
+pub fn is_fullscreen() -> Result<bool, String> {
+    // SAFETY: plain Win32 queries into structs owned by this frame; `size` is set as
+    // GetMonitorInfoW requires
+    unsafe {
+        let hwnd = win32::GetForegroundWindow();
+        if hwnd.is_null() || hwnd == win32::GetShellWindow() || hwnd == win32::GetDesktopWindow() {
+            return Ok(false);
+        }
+        let mut window = win32::Rect::default();
+        if win32::GetWindowRect(hwnd, &mut window) == 0 {
+            return Err("GetWindowRect failed".to_string());
+        }
+        let monitor = win32::MonitorFromWindow(hwnd, win32::MONITOR_DEFAULTTONEAREST);
+        let mut info = win32::MonitorInfo {
+            size: std::mem::size_of::<win32::MonitorInfo>() as u32,
+            ..Default::default()
+        };
+        if win32::GetMonitorInfoW(monitor, &mut info) == 0 {
+            return Err("GetMonitorInfoW failed".to_string());
+        }
+        Ok(window == info.monitor)
+    }
+}
+
+#[cfg(target_os = "macos")]
+pub fn is_fullscreen() -> Result<bool, String> {
+    if !permissions::is_granted(PermissionKind::Accessibility) {
+        return Err("the Accessibility permission has not been granted".to_string());
+    }
+    let value = run(
+        "osascript",
+        &[
+            "-e",
+            "tell application \"System Events\" to get value of attribute \"AXFullScreen\" of front window of (first application process whose frontmost is true)",
+        ],
+    )?;
+    Ok(value == "true")
+}
+
+// X11 only, like current()
+#[cfg(target_os = "linux")]
+pub fn is_fullscreen() -> Result<bool, String> {
+    let window = run("xdotool", &["getactivewindow"])?;
+    let state = run("xprop", &["-id", &window, "_NET_WM_STATE"])?;
+    Ok(state.contains("_NET_WM_STATE_FULLSCREEN"))
+}
+
+#[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+pub fn is_fullscreen() -> Result<bool, String> {
+    Err("fullscreen detection is not supported on this platform".to_string())
+}
@@ -0,0 +1,83 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use tauri::State;
+
+pub const MAX_ACTIVITY_LOGS: usize = 1000;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActivityKind {
+    Idle,
+    Active,
+    // Idle monitoring was suspended by the user; time until the matching resume is
+    // neither idle nor active
+    MonitoringPaused,
+    MonitoringResumed,
+    // Monitors were connected or disconnected, e.g. docking or undocking a laptop
+    DisplayChanged,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ActivityEvent {
+    pub at: DateTime<Utc>,
+    pub kind: ActivityKind,
+    pub idle_seconds: u64,
+    // Connected monitors after a DisplayChanged event
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub displays: Option<usize>,
+}
+
+// Bounded in-memory log of recent activity transitions; the oldest entries are dropped
+// once MAX_ACTIVITY_LOGS is reached.
+#[derive(Default)]
+pub struct ActivityLog {
+    events: Mutex<VecDeque<ActivityEvent>>,
+}
+
+impl ActivityLog {
+    pub fn push(&self, kind: ActivityKind, idle_seconds: u64) {
+        self.push_event(ActivityEvent {
+            at: Utc::now(),
+            kind,
+            idle_seconds,
+            displays: None,
+        });
+    }
+
+    pub fn push_display_change(&self, displays: usize) {
+        self.push_event(ActivityEvent {
+            at: Utc::now(),
+            kind: ActivityKind::DisplayChanged,
+            idle_seconds: 0,
+            displays: Some(displays),
+        });
+    }
+
+    fn push_event(&self, event: ActivityEvent) {
+        let Ok(mut events) = self.events.lock() else {
+            return;
+        };
+        if events.len() == MAX_ACTIVITY_LOGS {
+            events.pop_front();
+        }
+        events.push_back(event);
+    }
+
+    // Newest first
+    pub fn recent(&self, limit: usize) -> Vec<ActivityEvent> {
+        match self.events.lock() {
+            Ok(events) => events.iter().rev().take(limit).cloned().collect(),
+            Err(_) => Vec::new(),
+        }
+    }
+}
+
+#[tauri::command]
+pub fn get_recent_activity(
+    log: State<'_, ActivityLog>,
+    limit: Option<usize>,
+) -> Vec<ActivityEvent> {
+    log.recent(limit.unwrap_or(MAX_ACTIVITY_LOGS))
+}
@@ -0,0 +1,334 @@
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::ipc::Invoke;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::kiosk;
+use crate::secrets;
+use crate::settings::SettingsStore;
+
+pub(crate) const PIN_KEY: &str = "app_lock_pin";
+const MIN_PIN_LEN: usize = 4;
+// Wrong PINs allowed before unlocking is paused, and the first pause; each further
+// wrong PIN doubles it
+const FREE_ATTEMPTS: u32 = 5;
+const FIRST_LOCKOUT: Duration = Duration::from_secs(30);
+const MAX_LOCKOUT: Duration = Duration::from_secs(30 * 60);
+
+// The only commands the lock screen can reach; everything else is refused while locked
+const LOCK_SCREEN_COMMANDS: &[&str] = &[
+    "get_app_lock_status",
+    "unlock",
+    "unlock_with_biometric",
+    "lock_app",
+    "hide_to_tray",
+    "quit_app",
+    // Metrics from the frontend's invoke wrapper
+    "record_command_result",
+];
+
+// Optional lock for shared machines. The PIN itself lives in the keyring as an
+// argon2 hash.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppLockSettings {
+    pub enabled: bool,
+    // Lock again whenever the window is hidden to the tray
+    pub lock_on_hide: bool,
+}
+
+impl Default for AppLockSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            lock_on_hide: true,
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+pub struct AppLockStatus {
+    pub enabled: bool,
+    pub locked: bool,
+    pub biometric_available: bool,
+    // Seconds until another PIN attempt is accepted
+    pub retry_after_secs: Option<u64>,
+}
+
+#[derive(Default)]
+struct Attempts {
+    failures: u32,
+    blocked_until: Option<Instant>,
+}
+
+#[derive(Default)]
+pub struct AppLock {
+    locked: AtomicBool,
+    attempts: Mutex<Attempts>,
+}
+
+fn hash_pin(pin: &str) -> Result<String, String> {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(pin.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| e.to_string())
+}
+
+fn verify_pin(pin: &str) -> Result<bool, String> {
+    let Some(stored) = secrets::get(PIN_KEY)? else {
+        return Ok(false);
+    };
+    let hash = PasswordHash::new(&stored).map_err(|e| e.to_string())?;
+    Ok(Argon2::default()
+        .verify_password(pin.as_bytes(), &hash)
+        .is_ok())
+}
+
+fn is_enabled(app: &AppHandle) -> bool {
+    app.state::<SettingsStore>()
+        .get()
+        .is_ok_and(|s| s.app_lock.enabled)
+}
+
+impl AppLock {
+    pub fn is_locked(&self) -> bool {
+        self.locked.load(Ordering::SeqCst)
+    }
+
+    pub fn lock(&self, app: &AppHandle) {
+        if is_enabled(app) && !self.locked.swap(true, Ordering::SeqCst) {
+            log::info!("app locked");
+            let _ = app.emit("app-locked", ());
+        }
+    }
+
+    fn unlock(&self, app: &AppHandle) -> Result<(), String> {
+        *self.attempts.lock().map_err(|e| e.to_string())? = Attempts::default();
+        self.locked.store(false, Ordering::SeqCst);
+        let _ = app.emit("app-unlocked", ());
+        Ok(())
+    }
+
+    fn retry_after(&self) -> Option<Duration> {
+        let attempts = self.attempts.lock().ok()?;
+        attempts
+            .blocked_until
+            .and_then(|until| until.checked_duration_since(Instant::now()))
+    }
+
+    // Checks the PIN, pausing further attempts after repeated failures.
+    pub(crate) fn check_pin(&self, pin: &str) -> Result<bool, String> {
+        if let Some(wait) = self.retry_after() {
+            return Err(format!(
+                "Too many attempts; try again in {} seconds",
+                wait.as_secs() + 1
+            ));
+        }
+        let valid = verify_pin(pin)?;
+        let mut attempts = self.attempts.lock().map_err(|e| e.to_string())?;
+        if valid {
+            *attempts = Attempts::default();
+        } else {
+            attempts.failures += 1;
+            if attempts.failures >= FREE_ATTEMPTS {
+                let doublings = (attempts.failures - FREE_ATTEMPTS).min(16);
+                let lockout = (FIRST_LOCKOUT * 2u32.pow(doublings)).min(MAX_LOCKOUT);
+                attempts.blocked_until = Some(Instant::now() + lockout);
+            }
+        }
+        Ok(valid)
+    }
+}
+
+// Starts locked when the lock is enabled. A missing PIN hash (e.g. the keyring was
+// reset) disables the lock rather than locking the user out.
+pub fn init(app: &AppHandle) {
+    let lock = AppLock::default();
+    if is_enabled(app) {
+        match secrets::get(PIN_KEY) {
+            Ok(Some(_)) => lock.locked.store(true, Ordering::SeqCst),
+            Ok(None) => {
+                log::warn!("app lock is enabled but no PIN is stored; disabling it");
+                let _ = app
+                    .state::<SettingsStore>()
+                    .update(|s| s.app_lock.enabled = false);
+            }
+            Err(e) => {
+                log::warn!("app lock PIN unavailable: {}", e);
+                lock.locked.store(true, Ordering::SeqCst);
+            }
+        }
+    }
+    app.manage(lock);
+}
+
+// Called whenever the main window is hidden.
+pub fn on_hide(app: &AppHandle) {
+    let lock_on_hide = app
+        .state::<SettingsStore>()
+        .get()
+        .is_ok_and(|s| s.app_lock.lock_on_hide);
+    if lock_on_hide {
+        app.state::<AppLock>().lock(app);
+    }
+}
+
+// Why a caller outside the webview (local API, CLI, RPC) is refused, if it is. A kiosk
+// only takes punches, through its own screen.
+pub fn refusal(app: &AppHandle) -> Option<&'static str> {
+    if app
+        .try_state::<AppLock>()
+        .is_some_and(|lock| lock.is_locked())
+    {
+        Some("App is locked")
+    } else if kiosk::is_active(app) {
+        Some("Not available in kiosk mode")
+    } else {
+        None
+    }
+}
+
+// Wraps the generated command handler to refuse everything but the lock screen's
+// commands while locked. An active kiosk is left to kiosk::guarded, which only lets
+// its own commands through.
+pub fn guarded(
+    handler: impl Fn(Invoke) -> bool + Send + Sync + 'static,
+) -> impl Fn(Invoke) -> bool + Send + Sync + 'static {
+    move |invoke: Invoke| {
+        let command = invoke.message.command();
+        let app = invoke.message.webview().app_handle().clone();
+        let locked = app
+            .try_state::<AppLock>()
+            .is_some_and(|lock| lock.is_locked());
+        if locked && !LOCK_SCREEN_COMMANDS.contains(&command) && !kiosk::is_active(&app) {
+            log::debug!(target: "commands", "{} refused while locked", command);
+            invoke.resolver.reject("App is locked");
+            return true;
+        }
+        handler(invoke)
+    }
+}
+
+#[cfg(windows)]
+mod biometric {
+    use windows::core::HSTRING;
+    use windows::Security::Credentials::UI::{
+        UserConsentVerificationResult, UserConsentVerifier, UserConsentVerifierAvailability,
+    };
+
+    pub fn available() -> bool {
+        UserConsentVerifier::CheckAvailabilityAsync()
+            .and_then(|op| op.get())
+            .is_ok_and(|a| a == UserConsentVerifierAvailability::Available)
+    }
+
+    pub fn verify(reason: &str) -> Result<bool, String> {
+        let result = UserConsentVerifier::RequestVerificationAsync(&HSTRING::from(reason))
+            .and_then(|op| op.get())
+            .map_err(|e| e.to_string())?;
+        Ok(result == UserConsentVerificationResult::Verified)
+    }
+}
+
+#[cfg(not(windows))]
+mod biometric {
+    pub fn available() -> bool {
+        false
+    }
+
+    pub fn verify(_reason: &str) -> Result<bool, String> {
+        Err("Biometric unlock is not supported on this platform".to_string())
+    }
+}
+
+#[tauri::command]
+pub fn get_app_lock_status(
+    lock: State<'_, AppLock>,
+    settings: State<'_, SettingsStore>,
+) -> Result<AppLockStatus, String> {
+    let enabled = settings.get()?.app_lock.enabled;
+    Ok(AppLockStatus {
+        enabled,
+        locked: lock.is_locked(),
+        biometric_available: enabled && biometric::available(),
+        retry_after_secs: lock.retry_after().map(|d| d.as_secs() + 1),
+    })
+}
+
+// Sets or changes the PIN; `pin: None` turns the lock off. Changing or removing an
+// existing PIN requires the current one.
+#[tauri::command]
+pub fn set_app_lock(
+    lock: State<'_, AppLock>,
+    settings: State<'_, SettingsStore>,
+    pin: Option<String>,
+    current_pin: Option<String>,
+    lock_on_hide: Option<bool>,
+) -> Result<(), String> {
+    if settings.get()?.app_lock.enabled {
+        let current = current_pin.ok_or("The current PIN is required")?;
+        if !lock.check_pin(&current)? {
+            return Err("Incorrect PIN".to_string());
+        }
+    }
+    match pin {
+        Some(pin) => {
+            if pin.chars().count() < MIN_PIN_LEN {
+                return Err(format!("PIN must be at least {} characters", MIN_PIN_LEN));
+            }
+            secrets::set(PIN_KEY, &hash_pin(&pin)?)?;
+            settings.update(|s| {
+                s.app_lock.enabled = true;
+                if let Some(lock_on_hide) = lock_on_hide {
+                    s.app_lock.lock_on_hide = lock_on_hide;
+                }
+            })?;
+        }
+        None => {
+            settings.update(|s| s.app_lock.enabled = false)?;
+            secrets::delete(PIN_KEY)?;
+            lock.locked.store(false, Ordering::SeqCst);
+        }
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn unlock(app: AppHandle, lock: State<'_, AppLock>, pin: String) -> Result<(), String> {
+    if !lock.is_locked() {
+        return Ok(());
+    }
+    if !lock.check_pin(&pin)? {
+        return Err("Incorrect PIN".to_string());
+    }
+    log::info!("app unlocked");
+    lock.unlock(&app)
+}
+
+// Prompts for Windows Hello; other platforms return an error and fall back to the PIN.
+#[tauri::command]
+pub async fn unlock_with_biometric(app: AppHandle) -> Result<(), String> {
+    if !app.state::<AppLock>().is_locked() {
+        return Ok(());
+    }
+    let verified =
+        tauri::async_runtime::spawn_blocking(|| biometric::verify("Unlock time tracking"))
+            .await
+            .map_err(|e| e.to_string())??;
+    if !verified {
+        return Err("Verification failed".to_string());
+    }
+    log::info!("app unlocked with biometric");
+    app.state::<AppLock>().unlock(&app)
+}
+
+#[tauri::command]
+pub fn lock_app(app: AppHandle, lock: State<'_, AppLock>) {
+    lock.lock(&app);
+}
@@ -0,0 +1,63 @@
+use reqwest::{Method, RequestBuilder};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
+
+use crate::org_policy;
+use crate::secrets;
+use crate::settings::SettingsStore;
+
+pub(crate) const TOKEN_KEY: &str = "backend_token";
+
+// Connection to the FTT team backend; team features stay off until it is configured
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BackendSettings {
+    pub base_url: Option<String>,
+}
+
+pub fn base_url(app: &AppHandle) -> Result<String, String> {
+    app.state::<SettingsStore>()
+        .get()?
+        .backend
+        .base_url
+        .map(|url| url.trim_end_matches('/').to_string())
+        .ok_or_else(|| "The FTT backend is not configured".to_string())
+}
+
+pub fn token() -> Result<String, String> {
+    secrets::get(TOKEN_KEY)?.ok_or_else(|| "The FTT backend token is missing".to_string())
+}
+
+pub fn is_configured(app: &AppHandle) -> bool {
+    base_url(app).is_ok()
+}
+
+// Authenticated request to `path` under the backend's base URL.
+pub fn request(app: &AppHandle, method: Method, path: &str) -> Result<RequestBuilder, String> {
+    let url = format!("{}{}", base_url(app)?, path);
+    Ok(reqwest::Client::new()
+        .request(method, url)
+        .bearer_auth(token()?))
+}
+
+#[tauri::command]
+pub fn set_backend_config(
+    app: AppHandle,
+    settings: State<'_, SettingsStore>,
+    base_url: Option<String>,
+    token: Option<String>,
+) -> Result<(), String> {
+    match token.as_deref() {
+        Some("") => secrets::delete(TOKEN_KEY)?,
+        Some(token) => secrets::set(TOKEN_KEY, token)?,
+        None => {}
+    }
+    settings.update(|s| s.backend = BackendSettings { base_url })?;
+    // Signing in applies the organization's policy right away
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = org_policy::refresh(&app).await {
+            log::warn!("organization policy refresh failed: {}", e);
+        }
+    });
+    Ok(())
+}
@@ -0,0 +1,232 @@
+use chrono::{DateTime, TimeZone, Utc};
+use futures_util::future::BoxFuture;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+use tokio_util::sync::CancellationToken;
+
+// How far past its expected wake-up a task may be before it is reported as stalled
+const STALL_GRACE: Duration = Duration::from_secs(60);
+
+#[derive(Clone, Copy)]
+enum Body {
+    // Runs on a dedicated thread
+    Thread(fn(AppHandle, TaskContext)),
+    Async(fn(AppHandle, TaskContext) -> BoxFuture<'static, ()>),
+}
+
+#[derive(Default)]
+struct Progress {
+    // Unix millis by which the task should next be back in `sleep`; 0 before the first
+    wake_deadline: AtomicI64,
+    exited: AtomicBool,
+}
+
+// Handed to each run of a task. Loops sleep through it so a restart can interrupt them
+// and so the registry can tell a stuck iteration from an idle one.
+#[derive(Clone)]
+pub struct TaskContext {
+    token: CancellationToken,
+    progress: Arc<Progress>,
+}
+
+impl TaskContext {
+    fn new() -> Self {
+        Self {
+            token: CancellationToken::new(),
+            progress: Arc::default(),
+        }
+    }
+
+    fn expect_wake(&self, duration: Duration) {
+        let deadline = Utc::now() + chrono::Duration::from_std(duration).unwrap_or_default();
+        self.progress
+            .wake_deadline
+            .store(deadline.timestamp_millis(), Ordering::Relaxed);
+    }
+
+    // Sleeps for `duration`; returns false once the task has been cancelled.
+    pub async fn sleep(&self, duration: Duration) -> bool {
+        self.expect_wake(duration);
+        tokio::time::timeout(duration, self.token.cancelled())
+            .await
+            .is_err()
+    }
+
+    // `sleep` for tasks on their own thread.
+    pub fn sleep_blocking(&self, duration: Duration) -> bool {
+        let context = self.clone();
+        tauri::async_runtime::block_on(async move { context.sleep(duration).await })
+    }
+}
+
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskState {
+    Running,
+    // Hasn't returned to its sleep long after it was due to
+    Stalled,
+    // The loop returned on its own
+    Exited,
+}
+
+#[derive(Clone, Serialize)]
+pub struct TaskStatus {
+    pub name: String,
+    pub state: TaskState,
+    pub started_at: DateTime<Utc>,
+    pub restarts: u32,
+    // When the task is next expected back in its sleep
+    pub wake_deadline: Option<DateTime<Utc>>,
+}
+
+struct Entry {
+    body: Body,
+    context: TaskContext,
+    started_at: DateTime<Utc>,
+    restarts: u32,
+}
+
+// Long-running background loops by name, so one can be restarted without restarting
+// the app.
+#[derive(Default)]
+pub struct TaskRegistry {
+    tasks: Mutex<BTreeMap<&'static str, Entry>>,
+}
+
+fn launch(app: &AppHandle, name: &'static str, body: Body, context: TaskContext) {
+    let app = app.clone();
+    match body {
+        Body::Thread(run) => {
+            let spawned = std::thread::Builder::new()
+                .name(format!("task-{}", name))
+                .spawn(move || {
+                    run(app, context.clone());
+                    context.progress.exited.store(true, Ordering::Relaxed);
+                });
+            if let Err(e) = spawned {
+                log::error!("failed to spawn background task {}: {}", name, e);
+            }
+        }
+        Body::Async(run) => {
+            tauri::async_runtime::spawn(async move {
+                run(app, context.clone()).await;
+                context.progress.exited.store(true, Ordering::Relaxed);
+            });
+        }
+    }
+}
+
+impl TaskRegistry {
+    fn start(&self, app: &AppHandle, name: &'static str, body: Body) {
+        let context = TaskContext::new();
+        let Ok(mut tasks) = self.tasks.lock() else {
+            return;
+        };
+        if let Some(previous) = tasks.insert(
+            name,
+            Entry {
+                body,
+                context: context.clone(),
+                started_at: Utc::now(),
+                restarts: 0,
+            },
+        ) {
+            previous.context.token.cancel();
+        }
+        drop(tasks);
+        launch(app, name, body, context);
+    }
+
+    // Cancels the current run and starts a fresh one. A run stuck outside its sleep
+    // exits when it next reaches it.
+    pub fn restart(&self, app: &AppHandle, name: &str) -> Result<(), String> {
+        let mut tasks = self.tasks.lock().map_err(|e| e.to_string())?;
+        let (&name, entry) = tasks
+            .iter_mut()
+            .find(|(n, _)| **n == name)
+            .ok_or_else(|| format!("Unknown background task: {}", name))?;
+        entry.context.token.cancel();
+        entry.context = TaskContext::new();
+        entry.started_at = Utc::now();
+        entry.restarts += 1;
+        let (body, context) = (entry.body, entry.context.clone());
+        drop(tasks);
+        log::info!("restarting background task {}", name);
+        launch(app, name, body, context);
+        Ok(())
+    }
+
+    // Cancels every task for good, e.g. before the data directory is removed.
+    pub fn stop_all(&self) {
+        let Ok(tasks) = self.tasks.lock() else {
+            return;
+        };
+        for entry in tasks.values() {
+            entry.context.token.cancel();
+        }
+    }
+
+    pub fn status(&self) -> Result<Vec<TaskStatus>, String> {
+        let tasks = self.tasks.lock().map_err(|e| e.to_string())?;
+        let now = Utc::now();
+        Ok(tasks
+            .iter()
+            .map(|(name, entry)| {
+                let progress = &entry.context.progress;
+                let wake_deadline = match progress.wake_deadline.load(Ordering::Relaxed) {
+                    0 => None,
+                    millis => Utc.timestamp_millis_opt(millis).single(),
+                };
+                let overdue = wake_deadline.is_some_and(|deadline| {
+                    now > deadline + chrono::Duration::from_std(STALL_GRACE).unwrap_or_default()
+                });
+                let state = if progress.exited.load(Ordering::Relaxed) {
+                    TaskState::Exited
+                } else if overdue {
+                    TaskState::Stalled
+                } else {
+                    TaskState::Running
+                };
+                TaskStatus {
+                    name: name.to_string(),
+                    state,
+                    started_at: entry.started_at,
+                    restarts: entry.restarts,
+                    wake_deadline,
+                }
+            })
+            .collect())
+    }
+}
+
+pub fn spawn_thread(app: &AppHandle, name: &'static str, run: fn(AppHandle, TaskContext)) {
+    app.state::<TaskRegistry>()
+        .start(app, name, Body::Thread(run));
+}
+
+pub fn spawn_async(
+    app: &AppHandle,
+    name: &'static str,
+    run: fn(AppHandle, TaskContext) -> BoxFuture<'static, ()>,
+) {
+    app.state::<TaskRegistry>()
+        .start(app, name, Body::Async(run));
+}
+
+#[tauri::command]
+pub fn restart_task(
+    app: AppHandle,
+    registry: State<'_, TaskRegistry>,
+    name: String,
+) -> Result<(), String> {
+    registry.restart(&app, &name)
+}
+
+#[tauri::command]
+pub fn get_task_status(registry: State<'_, TaskRegistry>) -> Result<Vec<TaskStatus>, String> {
+    registry.status()
+}
@@ -0,0 +1,158 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::encryption;
+use crate::lifecycle;
+use crate::migrations::STORE_VERSION;
+use crate::sound;
+use crate::storage;
+
+const MANIFEST: &str = "manifest.json";
+const META_FILE: &str = "store.json";
+
+#[derive(Serialize, Deserialize)]
+pub struct BackupManifest {
+    pub app_version: String,
+    pub store_version: u32,
+    pub created_at: DateTime<Utc>,
+    pub files: Vec<String>,
+}
+
+fn data_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let meta = storage::data_file(app, META_FILE)?;
+    meta.parent()
+        .map(Path::to_path_buf)
+        .ok_or_else(|| "invalid data directory".to_string())
+}
+
+// `sealed` copies the stores as they are on disk, still encrypted when encryption is on;
+// otherwise they're decrypted so the backup restores even after the keyring is lost.
+fn write_backup(
+    app: &AppHandle,
+    dir: &Path,
+    path: &Path,
+    sealed: bool,
+) -> Result<BackupManifest, String> {
+    let names: Vec<&str> = storage::DATA_FILES
+        .iter()
+        .copied()
+        .chain([META_FILE, sound::CONFIG_FILE])
+        .filter(|name| dir.join(name).exists())
+        .collect();
+    let manifest = BackupManifest {
+        app_version: app.package_info().version.to_string(),
+        store_version: STORE_VERSION,
+        created_at: Utc::now(),
+        files: names.iter().map(|n| n.to_string()).collect(),
+    };
+
+    let file = File::create(path).map_err(|e| e.to_string())?;
+    let mut zip = ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    for name in &names {
+        let path = dir.join(name);
+        let contents = if sealed {
+            fs::read(&path).map_err(|e| e.to_string())?
+        } else {
+            storage::read_text(&path)?.into_bytes()
+        };
+        zip.start_file(*name, options).map_err(|e| e.to_string())?;
+        zip.write_all(&contents).map_err(|e| e.to_string())?;
+    }
+    zip.start_file(MANIFEST, options)
+        .map_err(|e| e.to_string())?;
+    let manifest_json = serde_json::to_vec_pretty(&manifest).map_err(|e| e.to_string())?;
+    zip.write_all(&manifest_json).map_err(|e| e.to_string())?;
+    zip.finish().map_err(|e| e.to_string())?;
+    Ok(manifest)
+}
+
+// Reads and checks every file in the archive before anything on disk is touched.
+fn read_backup(path: &Path) -> Result<(BackupManifest, Vec<(String, String)>), String> {
+    let file = File::open(path).map_err(|e| e.to_string())?;
+    let mut archive = ZipArchive::new(file).map_err(|e| e.to_string())?;
+    // Entries of a sealed snapshot are opened with the current key
+    let read_entry = |archive: &mut ZipArchive<File>, name: &str| -> Result<String, String> {
+        let mut entry = archive
+            .by_name(name)
+            .map_err(|e| format!("{}: {}", name, e))?;
+        let mut raw = Vec::new();
+        entry
+            .read_to_end(&mut raw)
+            .map_err(|e| format!("{}: {}", name, e))?;
+        let contents = encryption::decode_bytes(Path::new(name), raw)?;
+        String::from_utf8(contents).map_err(|e| format!("{}: {}", name, e))
+    };
+
+    let manifest: BackupManifest = serde_json::from_str(&read_entry(&mut archive, MANIFEST)?)
+        .map_err(|e| format!("invalid backup manifest: {}", e))?;
+    if manifest.store_version > STORE_VERSION {
+        return Err(format!(
+            "backup was made by a newer version ({}) of the app",
+            manifest.app_version
+        ));
+    }
+
+    let mut files = Vec::new();
+    for name in &manifest.files {
+        let contents = read_entry(&mut archive, name)?;
+        if name == sound::CONFIG_FILE {
+            toml::from_str::<toml::Value>(&contents).map_err(|e| format!("{}: {}", name, e))?;
+        } else if storage::DATA_FILES.contains(&name.as_str()) || name == META_FILE {
+            serde_json::from_str::<serde_json::Value>(&contents)
+                .map_err(|e| format!("{}: {}", name, e))?;
+        } else {
+            return Err(format!("unexpected file in backup: {}", name));
+        }
+        files.push((name.clone(), contents));
+    }
+    Ok((manifest, files))
+}
+
+#[tauri::command]
+pub fn create_backup(app: AppHandle, path: PathBuf) -> Result<BackupManifest, String> {
+    let dir = data_dir(&app)?;
+    write_backup(&app, &dir, &path, false)
+}
+
+// Replaces the local data with the backup's contents and restarts so every store reloads.
+// The current data is first saved to backups/pre-restore-<time>.zip.
+#[tauri::command]
+pub async fn restore_backup(app: AppHandle, path: PathBuf) -> Result<(), String> {
+    let (manifest, files) = read_backup(&path)?;
+    let dir = data_dir(&app)?;
+
+    let snapshots = dir.join("backups");
+    fs::create_dir_all(&snapshots).map_err(|e| e.to_string())?;
+    let snapshot = snapshots.join(format!(
+        "pre-restore-{}.zip",
+        Utc::now().format("%Y%m%dT%H%M%SZ")
+    ));
+    // Stays next to the encrypted stores, so it's kept encrypted too
+    write_backup(&app, &dir, &snapshot, true)?;
+
+    // Written after shutdown saves the stores, which would otherwise overwrite them
+    lifecycle::restart_with(&app, move || {
+        for name in storage::DATA_FILES {
+            if !manifest.files.iter().any(|f| f == name) {
+                let _ = fs::remove_file(dir.join(name));
+            }
+        }
+        for (name, contents) in files {
+            storage::write_text(&dir.join(&name), contents)?;
+        }
+        log::info!(
+            "restored backup from {} (snapshot of previous data in {})",
+            path.display(),
+            snapshot.display()
+        );
+        Ok(())
+    })
+    .await
+}
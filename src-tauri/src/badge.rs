@@ -0,0 +1,49 @@
+use tauri::window::{ProgressBarState, ProgressBarStatus};
+use tauri::{AppHandle, Manager};
+
+pub enum BadgeState {
+    Running { elapsed_seconds: u64 },
+    Paused,
+    Cleared,
+}
+
+fn format_short(elapsed_seconds: u64) -> String {
+    format!(
+        "{}:{:02}",
+        elapsed_seconds / 3600,
+        (elapsed_seconds % 3600) / 60
+    )
+}
+
+// Dock badge on macOS; taskbar progress on Windows and Linux, where the bar fills over
+// each hour and turns yellow while the timer is paused.
+pub fn update(app: &AppHandle, state: BadgeState) {
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+
+    if cfg!(target_os = "macos") {
+        let label = match state {
+            BadgeState::Running { elapsed_seconds } => Some(format_short(elapsed_seconds)),
+            BadgeState::Paused => Some("❚❚".to_string()),
+            BadgeState::Cleared => None,
+        };
+        let _ = window.set_badge_label(label);
+    } else {
+        let progress = match state {
+            BadgeState::Running { elapsed_seconds } => ProgressBarState {
+                status: Some(ProgressBarStatus::Normal),
+                progress: Some((elapsed_seconds % 3600) * 100 / 3600),
+            },
+            BadgeState::Paused => ProgressBarState {
+                status: Some(ProgressBarStatus::Paused),
+                progress: Some(100),
+            },
+            BadgeState::Cleared => ProgressBarState {
+                status: Some(ProgressBarStatus::None),
+                progress: None,
+            },
+        };
+        let _ = window.set_progress_bar(progress);
+    }
+}
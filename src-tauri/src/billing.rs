@@ -0,0 +1,481 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::State;
+
+use crate::csv;
+use crate::expenses::{self, Expense, ExpenseStore};
+use crate::org_policy::{self, OrgPolicies};
+use crate::pdf;
+use crate::rounding::{self, RoundingPolicy};
+use crate::sessions::{DateRange, SessionStore};
+use crate::settings::SettingsStore;
+use crate::storage;
+use crate::time::ReportZone;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Client {
+    pub id: u64,
+    pub name: String,
+    #[serde(default)]
+    pub tax_rate_percent: f64,
+    // Overrides BillingConfig::rounding for this client
+    #[serde(default)]
+    pub rounding: Option<RoundingPolicy>,
+}
+
+// A rate bump taking effect for sessions from `effective_from` on
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RateChange {
+    pub effective_from: NaiveDate,
+    pub hourly_rate: f64,
+}
+
+// Hourly rate for a task, billed to a client
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ProjectRate {
+    pub task_id: u64,
+    pub client_id: u64,
+    // Rate before the first change
+    pub hourly_rate: f64,
+    // ISO 4217 code; the base currency when unset
+    #[serde(default)]
+    pub currency: Option<String>,
+    #[serde(default)]
+    pub changes: Vec<RateChange>,
+}
+
+impl ProjectRate {
+    // The rate for sessions started on `date`
+    pub fn rate_on(&self, date: NaiveDate) -> (f64, Option<NaiveDate>) {
+        self.changes
+            .iter()
+            .filter(|c| c.effective_from <= date)
+            .max_by_key(|c| c.effective_from)
+            .map_or((self.hourly_rate, None), |c| {
+                (c.hourly_rate, Some(c.effective_from))
+            })
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BillingConfig {
+    pub clients: Vec<Client>,
+    pub rates: Vec<ProjectRate>,
+    pub rounding: RoundingPolicy,
+    pub base_currency: String,
+    // Value of one unit of each currency in the base currency
+    pub exchange_rates: BTreeMap<String, f64>,
+}
+
+impl Default for BillingConfig {
+    fn default() -> Self {
+        Self {
+            clients: Vec::new(),
+            rates: Vec::new(),
+            rounding: RoundingPolicy::default(),
+            base_currency: "USD".to_string(),
+            exchange_rates: BTreeMap::new(),
+        }
+    }
+}
+
+impl BillingConfig {
+    pub fn currency_of<'a>(&'a self, rate: &'a ProjectRate) -> &'a str {
+        rate.currency.as_deref().unwrap_or(&self.base_currency)
+    }
+
+    fn to_base(&self, currency: &str) -> Result<f64, String> {
+        if currency == self.base_currency {
+            return Ok(1.0);
+        }
+        self.exchange_rates
+            .get(currency)
+            .copied()
+            .filter(|r| *r > 0.0)
+            .ok_or_else(|| format!("No exchange rate for {}", currency))
+    }
+
+    pub fn convert(&self, amount: f64, from: &str, to: &str) -> Result<f64, String> {
+        if from == to {
+            return Ok(amount);
+        }
+        Ok(amount * self.to_base(from)? / self.to_base(to)?)
+    }
+}
+
+#[derive(Serialize)]
+pub struct InvoiceLine {
+    pub task_id: u64,
+    pub description: String,
+    // Notes of the billed sessions, in order
+    pub notes: Vec<String>,
+    // Tracked hours before rounding; `hours` is what's billed
+    pub raw_hours: f64,
+    pub hours: f64,
+    pub hourly_rate: f64,
+    // Set when the rate came from a rate change; a task gets a line per rate
+    pub rate_from: Option<NaiveDate>,
+    pub currency: String,
+    // In the line's currency
+    pub amount: f64,
+}
+
+#[derive(Serialize)]
+pub struct InvoiceData {
+    pub client: Client,
+    pub range: DateRange,
+    pub rounding: RoundingPolicy,
+    pub lines: Vec<InvoiceLine>,
+    // Billed at cost, in their own currencies
+    pub expenses: Vec<Expense>,
+    // Totals are in this currency, converted from the lines' currencies where they differ
+    pub currency: String,
+    pub subtotal: f64,
+    pub tax: f64,
+    pub total: f64,
+}
+
+pub struct BillingStore {
+    path: PathBuf,
+    config: Mutex<BillingConfig>,
+}
+
+impl BillingStore {
+    pub fn load(app: &tauri::AppHandle) -> Result<Self, String> {
+        let path = storage::data_file(app, "billing.json")?;
+        let config = storage::load_json(&path)?;
+        Ok(Self {
+            path,
+            config: Mutex::new(config),
+        })
+    }
+
+    pub fn get(&self) -> Result<BillingConfig, String> {
+        Ok(self.config.lock().map_err(|e| e.to_string())?.clone())
+    }
+
+    pub fn set(&self, config: BillingConfig) -> Result<(), String> {
+        let mut current = self.config.lock().map_err(|e| e.to_string())?;
+        storage::save_json(&self.path, &config)?;
+        *current = config;
+        Ok(())
+    }
+}
+
+fn round_cents(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+// Totals go in `currency` if given, else in the lines' and expenses' currency when they all
+// share one, else in the base currency.
+pub fn build_invoice(
+    config: &BillingConfig,
+    sessions: &SessionStore,
+    expense_store: &ExpenseStore,
+    zone: ReportZone,
+    client_id: u64,
+    range: DateRange,
+    currency: Option<&str>,
+) -> Result<InvoiceData, String> {
+    let client = config
+        .clients
+        .iter()
+        .find(|c| c.id == client_id)
+        .cloned()
+        .ok_or_else(|| format!("Client {} not found", client_id))?;
+    let rates: BTreeMap<u64, &ProjectRate> = config
+        .rates
+        .iter()
+        .filter(|r| r.client_id == client_id)
+        .map(|r| (r.task_id, r))
+        .collect();
+    let policy = rounding::for_client(config, client_id);
+
+    // Only the part of each session inside the range is billed, rounded per session, at
+    // the rate in effect on the day it started
+    type Bucket = (i64, i64, Option<String>, Vec<String>);
+    let mut seconds: BTreeMap<(u64, Option<NaiveDate>), (f64, Bucket)> = BTreeMap::new();
+    for session in sessions.in_range(range)? {
+        let Some(rate) = rates.get(&session.task_id) else {
+            continue;
+        };
+        let (hourly_rate, rate_from) = rate.rate_on(zone.date_of(session.start));
+        let start = session.start.max(range.start);
+        let end = session.end.min(range.end);
+        let (_, entry) = seconds
+            .entry((session.task_id, rate_from))
+            .or_insert((hourly_rate, (0, 0, None, Vec::new())));
+        let raw = (end - start).num_seconds();
+        entry.0 += raw;
+        entry.1 += policy.apply(raw);
+        if entry.2.is_none() {
+            entry.2 = session.title;
+        }
+        entry.3.extend(session.note);
+    }
+
+    let lines: Vec<InvoiceLine> = seconds
+        .into_iter()
+        .map(
+            |((task_id, rate_from), (hourly_rate, (raw, rounded, title, notes)))| {
+                // Billed from the exact rounded time; `hours` is only rounded for display
+                let hours = rounded as f64 / 3600.0;
+                InvoiceLine {
+                    task_id,
+                    description: title.unwrap_or_else(|| format!("Task #{}", task_id)),
+                    notes,
+                    raw_hours: round_cents(raw as f64 / 3600.0),
+                    hours: round_cents(hours),
+                    hourly_rate,
+                    rate_from,
+                    currency: config.currency_of(rates[&task_id]).to_string(),
+                    amount: round_cents(hours * hourly_rate),
+                }
+            },
+        )
+        .collect();
+
+    let expenses = expenses::for_client(
+        config,
+        sessions,
+        expense_store,
+        client_id,
+        zone.date_of(range.start),
+        zone.date_of(range.end - chrono::Duration::seconds(1)),
+    )?;
+
+    let amounts: Vec<(f64, &str)> = lines
+        .iter()
+        .map(|l| (l.amount, l.currency.as_str()))
+        .chain(expenses.iter().map(|e| (e.amount, e.currency.as_str())))
+        .collect();
+    let currency = match currency {
+        Some(currency) => currency.to_string(),
+        None => match amounts.first() {
+            Some((_, first)) if amounts.iter().all(|(_, c)| c == first) => first.to_string(),
+            _ => config.base_currency.clone(),
+        },
+    };
+    let mut subtotal = 0.0;
+    for (amount, from) in amounts {
+        subtotal += config.convert(amount, from, &currency)?;
+    }
+    let subtotal = round_cents(subtotal);
+    let tax = round_cents(subtotal * client.tax_rate_percent / 100.0);
+    Ok(InvoiceData {
+        client,
+        range,
+        rounding: policy,
+        lines,
+        expenses,
+        currency,
+        subtotal,
+        tax,
+        total: round_cents(subtotal + tax),
+    })
+}
+
+pub fn invoice_csv(invoice: &InvoiceData) -> String {
+    let mut out = String::new();
+    csv::push_row(
+        &mut out,
+        &[
+            "Description",
+            "Hours",
+            "Rate",
+            "Amount",
+            "Currency",
+            "Notes",
+        ],
+    );
+    for line in &invoice.lines {
+        csv::push_row(
+            &mut out,
+            &[
+                line.description.clone(),
+                format!("{:.2}", line.hours),
+                format!("{:.2}", line.hourly_rate),
+                format!("{:.2}", line.amount),
+                line.currency.clone(),
+                line.notes.join("; "),
+            ],
+        );
+    }
+    for expense in &invoice.expenses {
+        csv::push_row(
+            &mut out,
+            &[
+                format!("Expense: {}", expense.description),
+                String::new(),
+                String::new(),
+                format!("{:.2}", expense.amount),
+                expense.currency.clone(),
+                expense.date.to_string(),
+            ],
+        );
+    }
+    csv::push_row(
+        &mut out,
+        &[
+            "Subtotal",
+            "",
+            "",
+            format!("{:.2}", invoice.subtotal).as_str(),
+            invoice.currency.as_str(),
+        ],
+    );
+    csv::push_row(
+        &mut out,
+        &[
+            "Tax",
+            "",
+            "",
+            format!("{:.2}", invoice.tax).as_str(),
+            invoice.currency.as_str(),
+        ],
+    );
+    csv::push_row(
+        &mut out,
+        &[
+            "Total",
+            "",
+            "",
+            format!("{:.2}", invoice.total).as_str(),
+            invoice.currency.as_str(),
+        ],
+    );
+    out
+}
+
+#[tauri::command]
+pub fn get_billing_config(billing: State<'_, BillingStore>) -> Result<BillingConfig, String> {
+    billing.get()
+}
+
+#[tauri::command]
+pub fn set_billing_config(
+    billing: State<'_, BillingStore>,
+    config: BillingConfig,
+) -> Result<(), String> {
+    rounding::validate(&config.rounding)?;
+    for client in &config.clients {
+        if let Some(policy) = &client.rounding {
+            rounding::validate(policy)?;
+        }
+    }
+    for rate in &config.rates {
+        let currency = config.currency_of(rate);
+        config.to_base(currency)?;
+        let mut dates: Vec<_> = rate.changes.iter().map(|c| c.effective_from).collect();
+        dates.sort();
+        dates.dedup();
+        if dates.len() != rate.changes.len() {
+            return Err(format!(
+                "Task #{} has two rate changes on the same day",
+                rate.task_id
+            ));
+        }
+    }
+    billing.set(config)
+}
+
+#[tauri::command]
+pub fn generate_invoice_data(
+    billing: State<'_, BillingStore>,
+    sessions: State<'_, SessionStore>,
+    expenses: State<'_, ExpenseStore>,
+    settings: State<'_, SettingsStore>,
+    policy: State<'_, OrgPolicies>,
+    client_id: u64,
+    range: DateRange,
+    csv_path: Option<PathBuf>,
+    pdf_path: Option<PathBuf>,
+    currency: Option<String>,
+) -> Result<InvoiceData, String> {
+    if csv_path.is_some() {
+        policy.check_export(org_policy::EXPORT_CSV)?;
+    }
+    if pdf_path.is_some() {
+        policy.check_export(org_policy::EXPORT_PDF)?;
+    }
+    let settings = settings.get()?;
+    let zone = ReportZone::from_settings(&settings);
+    let invoice = build_invoice(
+        &billing.get()?,
+        &sessions,
+        &expenses,
+        zone,
+        client_id,
+        range,
+        currency.as_deref(),
+    )?;
+    if let Some(path) = csv_path {
+        std::fs::write(path, invoice_csv(&invoice)).map_err(|e| e.to_string())?;
+    }
+    if let Some(path) = pdf_path {
+        let pdf = pdf::render_invoice(&invoice, zone, &settings.report_branding)?;
+        std::fs::write(path, pdf).map_err(|e| e.to_string())?;
+    }
+    Ok(invoice)
+}
+
+#[derive(Serialize)]
+pub struct ClientEarnings {
+    pub client_id: u64,
+    pub name: String,
+    pub hours: f64,
+    pub total: f64,
+}
+
+#[derive(Serialize)]
+pub struct EarningsSummary {
+    pub range: DateRange,
+    pub currency: String,
+    pub clients: Vec<ClientEarnings>,
+    pub total: f64,
+}
+
+// Every client's invoice total for the range, converted into one currency (the base
+// currency by default)
+#[tauri::command]
+pub fn get_earnings(
+    billing: State<'_, BillingStore>,
+    sessions: State<'_, SessionStore>,
+    expenses: State<'_, ExpenseStore>,
+    settings: State<'_, SettingsStore>,
+    range: DateRange,
+    currency: Option<String>,
+) -> Result<EarningsSummary, String> {
+    let config = billing.get()?;
+    let zone = ReportZone::from_settings(&settings.get()?);
+    let currency = currency.unwrap_or_else(|| config.base_currency.clone());
+    let mut clients = Vec::new();
+    for client in &config.clients {
+        let invoice = build_invoice(
+            &config,
+            &sessions,
+            &expenses,
+            zone,
+            client.id,
+            range,
+            Some(&currency),
+        )?;
+        clients.push(ClientEarnings {
+            client_id: client.id,
+            name: client.name.clone(),
+            hours: round_cents(invoice.lines.iter().map(|l| l.hours).sum()),
+            total: invoice.total,
+        });
+    }
+    let total = round_cents(clients.iter().map(|c| c.total).sum());
+    Ok(EarningsSummary {
+        range,
+        currency,
+        clients,
+        total,
+    })
+}
@@ -0,0 +1,223 @@
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::active_window::ActiveWindow;
+use crate::privacy;
+use crate::settings::SettingsStore;
+
+// Loopback port the native-messaging host relays tab reports to. Fixed, since the host
+// is started by the browser and can't read the app's settings.
+const BRIDGE_PORT: u16 = 47612;
+const HOST_NAME: &str = "com.time_tracker.browser_bridge";
+// A report older than this no longer describes the focused tab
+const TAB_FRESHNESS: TimeDelta = TimeDelta::minutes(2);
+const MAX_MESSAGE_BYTES: u32 = 64 * 1024;
+// Focused apps whose time is attributed to the reported tab
+const BROWSERS: &[&str] = &[
+    "chrome", "chromium", "firefox", "msedge", "edge", "brave", "opera", "vivaldi", "safari", "arc",
+];
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BrowserSettings {
+    pub enabled: bool,
+}
+
+// What the companion extension sends when the active tab changes
+#[derive(Deserialize)]
+struct TabReport {
+    url: String,
+}
+
+#[derive(Clone, Serialize)]
+pub struct BrowserTab {
+    // Host of the tab's URL after privacy rules; the path and query are never kept
+    pub domain: Option<String>,
+    pub at: DateTime<Utc>,
+}
+
+#[derive(Default)]
+pub struct BrowserBridge {
+    current: Mutex<Option<BrowserTab>>,
+}
+
+// Compares whole words, so "Google Chrome" and "msedge.exe" match but "Search" doesn't
+fn is_browser(app: &str) -> bool {
+    app.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .any(|word| BROWSERS.contains(&word))
+}
+
+impl BrowserBridge {
+    // The app name to record for `focused`, with the reported domain appended when it
+    // is a browser, so timeline categories can match sites.
+    pub fn label(&self, focused: &str) -> String {
+        let tab = self.current.lock().ok().and_then(|t| t.clone());
+        match tab {
+            Some(BrowserTab {
+                domain: Some(domain),
+                at,
+            }) if is_browser(focused) && Utc::now() - at < TAB_FRESHNESS => {
+                format!("{} - {}", focused, domain)
+            }
+            _ => focused.to_string(),
+        }
+    }
+
+    fn report(&self, app: &AppHandle, report: TabReport) -> Result<(), String> {
+        let url = reqwest::Url::parse(&report.url).map_err(|e| e.to_string())?;
+        // Browser-internal pages (about:, chrome://) have no host and aren't recorded
+        let host = url
+            .host_str()
+            .filter(|_| matches!(url.scheme(), "http" | "https"))
+            .map(|h| h.trim_start_matches("www.").to_string());
+        // Domains go through the same rules as window titles, as the app "browser"
+        let domain = host.and_then(|host| {
+            privacy::scrub(
+                app,
+                ActiveWindow {
+                    app: "browser".to_string(),
+                    title: Some(host),
+                },
+            )
+            .title
+        });
+        let tab = BrowserTab {
+            domain,
+            at: Utc::now(),
+        };
+        *self.current.lock().map_err(|e| e.to_string())? = Some(tab.clone());
+        let _ = app.emit("browser-tab-changed", tab);
+        Ok(())
+    }
+}
+
+fn handle_connection(app: &AppHandle, stream: TcpStream) {
+    for line in BufReader::new(stream).lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        // Anything that isn't a tab report (e.g. an HTTP request from a web page) ends
+        // the connection
+        let Ok(report) = serde_json::from_str::<TabReport>(&line) else {
+            log::debug!("browser bridge: dropping malformed connection");
+            break;
+        };
+        if !app
+            .state::<SettingsStore>()
+            .get()
+            .is_ok_and(|s| s.browser.enabled)
+        {
+            continue;
+        }
+        if let Err(e) = app.state::<BrowserBridge>().report(app, report) {
+            log::debug!("browser bridge: ignored tab report: {}", e);
+        }
+    }
+}
+
+// Listens for the native-messaging host. Reports are ignored while the bridge is
+// disabled, so the listener can run for the whole session.
+pub fn init(app: &AppHandle) {
+    app.manage(BrowserBridge::default());
+    let listener = match TcpListener::bind(("127.0.0.1", BRIDGE_PORT)) {
+        Ok(listener) => listener,
+        Err(e) => {
+            log::warn!("browser bridge unavailable: {}", e);
+            return;
+        }
+    };
+    let handle = app.clone();
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let app = handle.clone();
+            std::thread::spawn(move || handle_connection(&app, stream));
+        }
+    });
+}
+
+// The browser starts the native-messaging host with the extension's origin (Chrome) or
+// the host manifest path (Firefox) as an argument.
+pub fn is_native_host_launch(args: &[String]) -> bool {
+    args.iter().skip(1).any(|arg| {
+        arg.starts_with("chrome-extension://") || arg.ends_with(&format!("{}.json", HOST_NAME))
+    })
+}
+
+// Native-messaging host: relays the extension's length-prefixed JSON messages from stdin
+// to the running app, one per line, until the browser closes the pipe.
+pub fn run_native_host() {
+    let mut stdin = std::io::stdin().lock();
+    let mut app = None;
+    loop {
+        let mut length = [0u8; 4];
+        if stdin.read_exact(&mut length).is_err() {
+            return;
+        }
+        let length = u32::from_ne_bytes(length);
+        if length > MAX_MESSAGE_BYTES {
+            return;
+        }
+        let mut message = vec![0u8; length as usize];
+        if stdin.read_exact(&mut message).is_err() {
+            return;
+        }
+        // The app may start after the browser; connect on demand and drop reports
+        // while it isn't running
+        if app.is_none() {
+            app = TcpStream::connect(("127.0.0.1", BRIDGE_PORT)).ok();
+        }
+        if let Some(stream) = app.as_mut() {
+            let mut line = message;
+            line.retain(|b| *b != b'\n');
+            line.push(b'\n');
+            if stream.write_all(&line).is_err() {
+                app = None;
+            }
+        }
+    }
+}
+
+// Host manifest for the installer to register with the browser; `browser` is "chrome"
+// (also used by Edge and other Chromium browsers) or "firefox".
+#[tauri::command]
+pub fn get_native_host_manifest(browser: String, extension_id: String) -> Result<String, String> {
+    let path = std::env::current_exe().map_err(|e| e.to_string())?;
+    let mut manifest = serde_json::json!({
+        "name": HOST_NAME,
+        "description": "Time tracker browser bridge",
+        "path": path,
+        "type": "stdio",
+    });
+    match browser.as_str() {
+        "chrome" => {
+            manifest["allowed_origins"] =
+                serde_json::json!([format!("chrome-extension://{}/", extension_id)])
+        }
+        "firefox" => manifest["allowed_extensions"] = serde_json::json!([extension_id]),
+        other => return Err(format!("Unsupported browser: {}", other)),
+    }
+    serde_json::to_string_pretty(&manifest).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn set_browser_bridge(
+    bridge: State<'_, BrowserBridge>,
+    settings: State<'_, SettingsStore>,
+    enabled: bool,
+) -> Result<(), String> {
+    settings.update(|s| s.browser.enabled = enabled)?;
+    if !enabled {
+        *bridge.current.lock().map_err(|e| e.to_string())? = None;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_browser_tab(bridge: State<'_, BrowserBridge>) -> Result<Option<BrowserTab>, String> {
+    Ok(bridge.current.lock().map_err(|e| e.to_string())?.clone())
+}
@@ -0,0 +1,405 @@
+use chrono::{
+    DateTime, Datelike, Duration as ChronoDuration, Local, NaiveDate, NaiveDateTime, TimeZone, Utc,
+    Weekday,
+};
+use futures_util::FutureExt;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::background;
+use crate::idle::IdleMonitor;
+use crate::org_policy::{self, OrgPolicies};
+use crate::sessions::{DateRange, Session, SessionStore};
+use crate::settings::SettingsStore;
+use crate::timer::TimerManager;
+
+const SCHEDULE_POLL_INTERVAL: Duration = Duration::from_secs(60 * 60);
+const MEETING_POLL_INTERVAL: Duration = Duration::from_secs(60);
+const MEETING_REFRESH_EVERY: u32 = 15;
+const UPCOMING_WINDOW_DAYS: i64 = 7;
+// Only suggest a meeting entry if activity shows up within this long of the start
+const SUGGESTION_WINDOW_MINUTES: i64 = 10;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct IcsExportSchedule {
+    pub enabled: bool,
+    pub directory: PathBuf,
+    pub weekday: Weekday,
+    #[serde(default)]
+    pub last_export: Option<DateTime<Utc>>,
+}
+
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+// RFC 5545 limits content lines to 75 octets; continuation lines start with a space.
+fn push_line(out: &mut String, line: &str) {
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+fn format_utc(dt: DateTime<Utc>) -> String {
+    dt.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+pub fn render_ics(sessions: &[Session]) -> String {
+    let mut out = String::new();
+    push_line(&mut out, "BEGIN:VCALENDAR");
+    push_line(&mut out, "VERSION:2.0");
+    push_line(&mut out, "PRODID:-//Time Tracker//Sessions//EN");
+    push_line(&mut out, "CALSCALE:GREGORIAN");
+    let stamp = format_utc(Utc::now());
+    for session in sessions {
+        let summary = session
+            .title
+            .clone()
+            .unwrap_or_else(|| format!("Task #{}", session.task_id));
+        push_line(&mut out, "BEGIN:VEVENT");
+        push_line(
+            &mut out,
+            &format!("UID:session-{}@time-tracker", session.id),
+        );
+        push_line(&mut out, &format!("DTSTAMP:{}", stamp));
+        push_line(&mut out, &format!("DTSTART:{}", format_utc(session.start)));
+        push_line(&mut out, &format!("DTEND:{}", format_utc(session.end)));
+        push_line(&mut out, &format!("SUMMARY:{}", escape_text(&summary)));
+        if let Some(note) = &session.note {
+            push_line(&mut out, &format!("DESCRIPTION:{}", escape_text(note)));
+        }
+        push_line(&mut out, "END:VEVENT");
+    }
+    push_line(&mut out, "END:VCALENDAR");
+    out
+}
+
+fn write_ics(store: &SessionStore, range: DateRange, path: &Path) -> Result<usize, String> {
+    let sessions = store.in_range(range)?;
+    std::fs::write(path, render_ics(&sessions)).map_err(|e| e.to_string())?;
+    Ok(sessions.len())
+}
+
+// Export the past week once the configured weekday comes around.
+fn run_scheduled_export(app: &AppHandle) -> Result<(), String> {
+    let settings = app.state::<SettingsStore>();
+    let Some(schedule) = settings.get()?.ics_schedule.filter(|s| s.enabled) else {
+        return Ok(());
+    };
+    let now = Utc::now();
+    let due = Local::now().weekday() == schedule.weekday
+        && schedule
+            .last_export
+            .map_or(true, |last| now - last > ChronoDuration::days(6));
+    if !due {
+        return Ok(());
+    }
+
+    let range = DateRange {
+        start: now - ChronoDuration::days(7),
+        end: now,
+    };
+    app.state::<OrgPolicies>()
+        .check_export(org_policy::EXPORT_ICS)?;
+    std::fs::create_dir_all(&schedule.directory).map_err(|e| e.to_string())?;
+    let path = schedule
+        .directory
+        .join(format!("timesheet-{}.ics", Local::now().format("%Y-%m-%d")));
+    write_ics(&app.state::<SessionStore>(), range, &path)?;
+    settings.update(|s| {
+        if let Some(schedule) = s.ics_schedule.as_mut() {
+            schedule.last_export = Some(now);
+        }
+    })?;
+    Ok(())
+}
+
+pub fn start_export_scheduler(app: AppHandle) {
+    background::spawn_thread(&app, "ics_export", |app, task| loop {
+        if let Err(e) = run_scheduled_export(&app) {
+            log::warn!("scheduled ICS export failed: {}", e);
+        }
+        if !task.sleep_blocking(SCHEDULE_POLL_INTERVAL) {
+            break;
+        }
+    });
+}
+
+#[tauri::command]
+pub fn export_ics(
+    store: State<'_, SessionStore>,
+    policy: State<'_, OrgPolicies>,
+    range: DateRange,
+    path: PathBuf,
+) -> Result<usize, String> {
+    policy.check_export(org_policy::EXPORT_ICS)?;
+    write_ics(&store, range, &path)
+}
+
+#[tauri::command]
+pub fn set_ics_schedule(
+    settings: State<'_, SettingsStore>,
+    schedule: Option<IcsExportSchedule>,
+) -> Result<(), String> {
+    settings.update(|s| s.ics_schedule = schedule)?;
+    Ok(())
+}
+
+#[derive(Clone, Serialize)]
+pub struct Meeting {
+    pub uid: String,
+    pub summary: String,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub source: String,
+}
+
+#[derive(Default)]
+pub struct MeetingCache {
+    meetings: Mutex<Vec<Meeting>>,
+    suggested: Mutex<HashSet<String>>,
+}
+
+// Joins folded continuation lines (RFC 5545 section 3.1).
+fn unfold(text: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in text.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+fn unescape_text(text: &str) -> String {
+    text.replace("\\n", "\n")
+        .replace("\\N", "\n")
+        .replace("\\,", ",")
+        .replace("\\;", ";")
+        .replace("\\\\", "\\")
+}
+
+// Handles UTC ("...Z") and floating/TZID times. TZID zones are treated as local time;
+// all-day (date-only) values return None since they aren't meetings.
+fn parse_ics_time(value: &str) -> Option<DateTime<Utc>> {
+    if let Some(utc) = value.strip_suffix('Z') {
+        let naive = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+        return Some(Utc.from_utc_datetime(&naive));
+    }
+    if NaiveDate::parse_from_str(value, "%Y%m%d").is_ok() {
+        return None;
+    }
+    let naive = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?;
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+struct Recurrence {
+    step: ChronoDuration,
+    count: Option<usize>,
+    until: Option<DateTime<Utc>>,
+}
+
+// Supports the common FREQ=DAILY/WEEKLY rules with INTERVAL, COUNT and UNTIL.
+fn parse_rrule(rule: &str) -> Option<Recurrence> {
+    let mut step = None;
+    let mut interval = 1;
+    let mut count = None;
+    let mut until = None;
+    for part in rule.split(';') {
+        match part.split_once('=')? {
+            ("FREQ", "DAILY") => step = Some(ChronoDuration::days(1)),
+            ("FREQ", "WEEKLY") => step = Some(ChronoDuration::weeks(1)),
+            ("INTERVAL", n) => interval = n.parse().ok()?,
+            ("COUNT", n) => count = n.parse().ok(),
+            ("UNTIL", v) => until = parse_ics_time(v),
+            _ => {}
+        }
+    }
+    Some(Recurrence {
+        step: step? * interval,
+        count,
+        until,
+    })
+}
+
+pub fn parse_meetings(text: &str, source: &str, range: DateRange) -> Vec<Meeting> {
+    let mut meetings = Vec::new();
+    let mut event: Option<Vec<(String, String)>> = None;
+
+    for line in unfold(text) {
+        match line.as_str() {
+            "BEGIN:VEVENT" => event = Some(Vec::new()),
+            "END:VEVENT" => {
+                let Some(props) = event.take() else { continue };
+                let get = |name: &str| {
+                    props
+                        .iter()
+                        .find(|(key, _)| key.split(';').next() == Some(name))
+                        .map(|(_, value)| value.as_str())
+                };
+                let (Some(start), Some(end)) = (
+                    get("DTSTART").and_then(parse_ics_time),
+                    get("DTEND").and_then(parse_ics_time),
+                ) else {
+                    continue;
+                };
+                let uid = get("UID").unwrap_or_default().to_string();
+                let summary = unescape_text(get("SUMMARY").unwrap_or("Meeting"));
+                let duration = end - start;
+
+                let mut occurrences = vec![start];
+                if let Some(rule) = get("RRULE").and_then(parse_rrule) {
+                    let mut next = start + rule.step;
+                    while next < range.end
+                        && rule.until.map_or(true, |until| next <= until)
+                        && rule.count.map_or(true, |count| occurrences.len() < count)
+                    {
+                        occurrences.push(next);
+                        next += rule.step;
+                    }
+                }
+                for occurrence in occurrences {
+                    let meeting = Meeting {
+                        uid: format!("{}@{}", uid, occurrence.timestamp()),
+                        summary: summary.clone(),
+                        start: occurrence,
+                        end: occurrence + duration,
+                        source: source.to_string(),
+                    };
+                    if meeting.end > range.start && meeting.start < range.end {
+                        meetings.push(meeting);
+                    }
+                }
+            }
+            _ => {
+                if let (Some(props), Some((key, value))) = (event.as_mut(), line.split_once(':')) {
+                    props.push((key.to_string(), value.to_string()));
+                }
+            }
+        }
+    }
+    meetings
+}
+
+async fn fetch_source(source: &str) -> Result<String, String> {
+    if source.starts_with("http://") || source.starts_with("https://") {
+        reqwest::get(source)
+            .await
+            .map_err(|e| e.to_string())?
+            .error_for_status()
+            .map_err(|e| e.to_string())?
+            .text()
+            .await
+            .map_err(|e| e.to_string())
+    } else {
+        std::fs::read_to_string(source).map_err(|e| e.to_string())
+    }
+}
+
+async fn refresh_meetings(app: &AppHandle) -> Result<Vec<Meeting>, String> {
+    let sources = app.state::<SettingsStore>().get()?.calendar_sources;
+    let now = Utc::now();
+    let range = DateRange {
+        start: now - ChronoDuration::hours(1),
+        end: now + ChronoDuration::days(UPCOMING_WINDOW_DAYS),
+    };
+
+    let mut meetings = Vec::new();
+    for source in &sources {
+        match fetch_source(source).await {
+            Ok(text) => meetings.extend(parse_meetings(&text, source, range)),
+            Err(e) => log::warn!("calendar source {} failed: {}", source, e),
+        }
+    }
+    meetings.sort_by_key(|m| m.start);
+
+    let cache = app.state::<MeetingCache>();
+    *cache.meetings.lock().map_err(|e| e.to_string())? = meetings.clone();
+    Ok(meetings)
+}
+
+// Suggest a "Meeting" entry when an event has just begun, the user is active and no
+// timer is running. Each occurrence is suggested at most once.
+fn suggest_meeting(app: &AppHandle) {
+    // The watcher starts before the idle monitor is managed; skip ticks until it is
+    let Some(idle) = app.try_state::<IdleMonitor>() else {
+        return;
+    };
+    if app.state::<TimerManager>().active().is_some() || idle.is_idle() {
+        return;
+    }
+    let cache = app.state::<MeetingCache>();
+    let now = Utc::now();
+    let Some(meeting) = cache.meetings.lock().ok().and_then(|meetings| {
+        meetings
+            .iter()
+            .find(|m| {
+                m.start <= now && now - m.start < ChronoDuration::minutes(SUGGESTION_WINDOW_MINUTES)
+            })
+            .cloned()
+    }) else {
+        return;
+    };
+    let Ok(mut suggested) = cache.suggested.lock() else {
+        return;
+    };
+    if suggested.insert(meeting.uid.clone()) {
+        let _ = app.emit("meeting-suggestion", &meeting);
+    }
+}
+
+pub fn start_meeting_watcher(app: AppHandle) {
+    background::spawn_async(&app, "meeting_watcher", |app, task| {
+        async move {
+            let mut ticks = 0;
+            loop {
+                if ticks % MEETING_REFRESH_EVERY == 0 {
+                    if let Err(e) = refresh_meetings(&app).await {
+                        log::warn!("calendar refresh failed: {}", e);
+                    }
+                }
+                ticks += 1;
+                suggest_meeting(&app);
+                if !task.sleep(MEETING_POLL_INTERVAL).await {
+                    break;
+                }
+            }
+        }
+        .boxed()
+    });
+}
+
+#[tauri::command]
+pub fn get_upcoming_meetings(cache: State<'_, MeetingCache>) -> Result<Vec<Meeting>, String> {
+    let now = Utc::now();
+    let meetings = cache.meetings.lock().map_err(|e| e.to_string())?;
+    Ok(meetings.iter().filter(|m| m.end > now).cloned().collect())
+}
+
+#[tauri::command]
+pub async fn set_calendar_sources(
+    app: AppHandle,
+    sources: Vec<String>,
+) -> Result<Vec<Meeting>, String> {
+    app.state::<SettingsStore>()
+        .update(|s| s.calendar_sources = sources)?;
+    refresh_meetings(&app).await
+}
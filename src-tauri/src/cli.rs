@@ -0,0 +1,265 @@
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::app_lock;
+use crate::sessions::SessionStore;
+use crate::storage;
+use crate::tasks_remote::RemoteTasks;
+use crate::timer::TimerManager;
+
+// Matches `identifier` in tauri.conf.json; the CLI finds the running app's control file
+// under it without starting Tauri
+const IDENTIFIER: &str = "com.time-tracker.dev";
+const CONTROL_FILE: &str = "cli.json";
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum CliCommand {
+    // `task` is a task id or a title
+    Start { task: String },
+    Stop,
+    Status,
+}
+
+pub struct CliInvocation {
+    pub command: CliCommand,
+    pub json: bool,
+}
+
+// Where the running app is listening, readable only by the user
+#[derive(Serialize, Deserialize)]
+struct ControlInfo {
+    port: u16,
+    token: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ControlRequest {
+    token: String,
+    #[serde(flatten)]
+    command: CliCommand,
+}
+
+const USAGE: &str = "usage: ftt start <task> | ftt stop | ftt status [--json]";
+
+// None when the arguments aren't a CLI invocation (a normal launch or a deep link).
+pub fn parse(args: &[String]) -> Option<Result<CliInvocation, String>> {
+    let json = args.iter().any(|a| a == "--json");
+    let mut words = args.iter().skip(1).filter(|a| !a.starts_with("--"));
+    let command = match words.next()?.as_str() {
+        "start" => match words.next() {
+            Some(task) => CliCommand::Start { task: task.clone() },
+            None => return Some(Err(USAGE.to_string())),
+        },
+        "stop" => CliCommand::Stop,
+        "status" => CliCommand::Status,
+        _ => return None,
+    };
+    Some(Ok(CliInvocation { command, json }))
+}
+
+// A numeric argument is a task id; otherwise the title is looked up among assigned
+// tasks, then recently tracked ones.
+fn resolve_task(app: &AppHandle, task: &str) -> Result<(u64, Option<String>), String> {
+    if let Ok(id) = task.parse() {
+        return Ok((id, None));
+    }
+    let wanted = task.to_lowercase();
+    let assigned = app.state::<RemoteTasks>().get()?.tasks;
+    if let Some(found) = assigned.iter().find(|t| t.title.to_lowercase() == wanted) {
+        return Ok((found.id, Some(found.title.clone())));
+    }
+    let recent = app.state::<SessionStore>().read(|data| {
+        data.sessions
+            .iter()
+            .rev()
+            .find(|s| s.title.as_ref().is_some_and(|t| t.to_lowercase() == wanted))
+            .map(|s| (s.task_id, s.title.clone()))
+    })?;
+    recent.ok_or_else(|| format!("Unknown task: {}", task))
+}
+
+pub fn execute(app: &AppHandle, command: &CliCommand) -> Result<Value, String> {
+    if let Some(reason) = app_lock::refusal(app) {
+        return Err(reason.to_string());
+    }
+    let timers = app.state::<TimerManager>();
+    match command {
+        CliCommand::Start { task } => {
+            let (task_id, title) = resolve_task(app, task)?;
+            let timer = timers.start(app, task_id, title)?;
+            Ok(json!({ "timer": timer }))
+        }
+        CliCommand::Stop => Ok(json!({ "session": timers.stop(app)? })),
+        CliCommand::Status => Ok(json!({
+            "active": timers.active(),
+            "timers": timers.list(),
+        })),
+    }
+}
+
+fn handle_connection(app: &AppHandle, token: &str, mut stream: TcpStream) -> Result<(), String> {
+    let mut line = String::new();
+    BufReader::new(&stream)
+        .read_line(&mut line)
+        .map_err(|e| e.to_string())?;
+    let response = match serde_json::from_str::<ControlRequest>(&line) {
+        Ok(request) if request.token == token => match execute(app, &request.command) {
+            Ok(result) => json!({ "result": result }),
+            Err(e) => json!({ "error": e }),
+        },
+        Ok(_) => json!({ "error": "Unauthorized" }),
+        Err(e) => json!({ "error": e.to_string() }),
+    };
+    writeln!(stream, "{}", response).map_err(|e| e.to_string())
+}
+
+fn write_control_file(path: &PathBuf, info: &ControlInfo) -> Result<(), String> {
+    let contents = serde_json::to_string(info).map_err(|e| e.to_string())?;
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options
+        .open(path)
+        .and_then(|mut file| file.write_all(contents.as_bytes()))
+        .map_err(|e| e.to_string())
+}
+
+// Listens on a random loopback port for CLI invocations, and records the port and a
+// fresh token in the control file.
+pub fn start_server(app: &AppHandle) -> Result<(), String> {
+    let listener = TcpListener::bind(("127.0.0.1", 0)).map_err(|e| e.to_string())?;
+    let info = ControlInfo {
+        port: listener.local_addr().map_err(|e| e.to_string())?.port(),
+        token: rand::thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(32)
+            .map(char::from)
+            .collect(),
+    };
+    write_control_file(&storage::root_file(app, CONTROL_FILE)?, &info)?;
+    let handle = app.clone();
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            if let Err(e) = handle_connection(&handle, &info.token, stream) {
+                log::debug!("CLI connection failed: {}", e);
+            }
+        }
+    });
+    Ok(())
+}
+
+fn connect() -> Result<(TcpStream, String), String> {
+    let path = dirs::data_dir()
+        .ok_or("No data directory")?
+        .join(IDENTIFIER)
+        .join(CONTROL_FILE);
+    let not_running = |_| "Time Tracker is not running".to_string();
+    let info: ControlInfo =
+        serde_json::from_str(&std::fs::read_to_string(path).map_err(not_running)?)
+            .map_err(|e| e.to_string())?;
+    let stream = TcpStream::connect(("127.0.0.1", info.port)).map_err(not_running)?;
+    Ok((stream, info.token))
+}
+
+// Whether another instance is running and accepting CLI connections.
+pub fn is_app_running() -> bool {
+    connect().is_ok()
+}
+
+fn send(command: &CliCommand) -> Result<Value, String> {
+    let (mut stream, token) = connect()?;
+    stream
+        .set_read_timeout(Some(RESPONSE_TIMEOUT))
+        .map_err(|e| e.to_string())?;
+    let request = ControlRequest {
+        token,
+        command: command.clone(),
+    };
+    writeln!(
+        stream,
+        "{}",
+        serde_json::to_string(&request).map_err(|e| e.to_string())?
+    )
+    .map_err(|e| e.to_string())?;
+    let mut line = String::new();
+    BufReader::new(stream)
+        .read_line(&mut line)
+        .map_err(|e| e.to_string())?;
+    let mut response: Value = serde_json::from_str(&line).map_err(|e| e.to_string())?;
+    match response["error"].as_str() {
+        Some(error) => Err(error.to_string()),
+        None => Ok(response["result"].take()),
+    }
+}
+
+fn describe(command: &CliCommand, result: &Value) -> String {
+    let title = |timer: &Value| {
+        timer["title"]
+            .as_str()
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("task #{}", timer["task_id"]))
+    };
+    match command {
+        CliCommand::Start { .. } => format!("Started {}", title(&result["timer"])),
+        CliCommand::Stop if result["session"].is_null() => "No timer was running".to_string(),
+        CliCommand::Stop => format!("Stopped {}", title(&result["session"])),
+        CliCommand::Status if result["active"].is_null() => "No timer running".to_string(),
+        CliCommand::Status => format!(
+            "Tracking {} since {}",
+            title(&result["active"]),
+            result["active"]["started_at"].as_str().unwrap_or_default()
+        ),
+    }
+}
+
+// Windows release builds use the GUI subsystem and have no console of their own
+#[cfg(windows)]
+fn attach_console() {
+    use windows::Win32::System::Console::{AttachConsole, ATTACH_PARENT_PROCESS};
+    // SAFETY: no preconditions; fails harmlessly without a parent console
+    let _ = unsafe { AttachConsole(ATTACH_PARENT_PROCESS) };
+}
+
+// Runs a CLI invocation against the running app and returns the exit code.
+pub fn run(invocation: Result<CliInvocation, String>) -> i32 {
+    #[cfg(windows)]
+    attach_console();
+    let invocation = match invocation {
+        Ok(invocation) => invocation,
+        Err(usage) => {
+            eprintln!("{}", usage);
+            return 2;
+        }
+    };
+    match send(&invocation.command) {
+        Ok(result) if invocation.json => {
+            println!("{}", result);
+            0
+        }
+        Ok(result) => {
+            println!("{}", describe(&invocation.command, &result));
+            0
+        }
+        Err(e) if invocation.json => {
+            println!("{}", json!({ "error": e }));
+            1
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            1
+        }
+    }
+}
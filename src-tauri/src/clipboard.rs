@@ -0,0 +1,141 @@
+// Share actions that put ready-made text on the clipboard, so the frontend doesn't have
+// to rebuild it from raw queries. Each returns the copied text.
+use tauri::{AppHandle, Manager, State};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+use crate::billing::{self, BillingStore};
+use crate::deep_link;
+use crate::expenses::ExpenseStore;
+use crate::heuristics::ActivityHeuristics;
+use crate::org_policy::{self, OrgPolicies};
+use crate::reports::{self, Report};
+use crate::sessions::{DateRange, SessionStore};
+use crate::settings::SettingsStore;
+use crate::time::{self, ReportZone};
+
+// Copying counts as an export, so org policy can block it
+pub fn copy(app: &AppHandle, text: String) -> Result<(), String> {
+    app.state::<OrgPolicies>()
+        .check_export(org_policy::EXPORT_CLIPBOARD)?;
+    app.clipboard().write_text(text).map_err(|e| e.to_string())
+}
+
+// Table cells can't contain pipes or line breaks
+fn cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+fn report_markdown(report: &Report, zone: ReportZone) -> String {
+    let first = zone.date_of(report.range.start);
+    let last = zone.date_of(report.range.end - chrono::Duration::seconds(1));
+    let mut out = if first == last {
+        format!("**Time report: {}**\n\n", first)
+    } else {
+        format!("**Time report: {} – {}**\n\n", first, last)
+    };
+    if let Some(tag) = &report.tag {
+        out.push_str(&format!("Tag: {}\n", tag));
+    }
+    out.push_str(&format!(
+        "Total: {}\n",
+        time::format_hours_minutes(report.total_seconds)
+    ));
+    if report.overlapping_seconds > 0 {
+        out.push_str(&format!(
+            "Wall-clock: {}\n",
+            time::format_hours_minutes(report.tracked_seconds)
+        ));
+    }
+    if report.tasks.is_empty() {
+        out.push_str("\nNothing tracked.\n");
+        return out;
+    }
+
+    out.push_str("\n| Task | Time |\n| --- | ---: |\n");
+    for task in &report.tasks {
+        let title = task
+            .title
+            .clone()
+            .unwrap_or_else(|| format!("Task #{}", task.task_id));
+        out.push_str(&format!(
+            "| {} | {} |\n",
+            cell(&title),
+            time::format_hours_minutes(task.seconds)
+        ));
+    }
+    if !report.tags.is_empty() {
+        out.push_str("\n| Tag | Time |\n| --- | ---: |\n");
+        for tag in &report.tags {
+            out.push_str(&format!(
+                "| {} | {} |\n",
+                cell(&tag.tag),
+                time::format_hours_minutes(tag.seconds)
+            ));
+        }
+    }
+    out
+}
+
+#[tauri::command]
+pub fn copy_report_markdown(
+    app: AppHandle,
+    store: State<'_, SessionStore>,
+    heuristics: State<'_, ActivityHeuristics>,
+    settings: State<'_, SettingsStore>,
+    range: DateRange,
+    tag: Option<String>,
+) -> Result<String, String> {
+    let zone = ReportZone::from_settings(&settings.get()?);
+    let tag = tag.as_deref().map(str::trim).filter(|t| !t.is_empty());
+    let report = reports::build_report(
+        &store.in_range(range)?,
+        &heuristics.in_range(range)?,
+        range,
+        zone,
+        tag,
+    );
+    let markdown = report_markdown(&report, zone);
+    copy(&app, markdown.clone())?;
+    Ok(markdown)
+}
+
+// The same CSV generate_invoice_data writes to a file
+#[tauri::command]
+pub fn copy_invoice_csv(
+    app: AppHandle,
+    billing: State<'_, BillingStore>,
+    sessions: State<'_, SessionStore>,
+    expenses: State<'_, ExpenseStore>,
+    settings: State<'_, SettingsStore>,
+    client_id: u64,
+    range: DateRange,
+    currency: Option<String>,
+) -> Result<String, String> {
+    app.state::<OrgPolicies>()
+        .check_export(org_policy::EXPORT_CSV)?;
+    let invoice = billing::build_invoice(
+        &billing.get()?,
+        &sessions,
+        &expenses,
+        ReportZone::from_settings(&settings.get()?),
+        client_id,
+        range,
+        currency.as_deref(),
+    )?;
+    let csv = billing::invoice_csv(&invoice);
+    copy(&app, csv.clone())?;
+    Ok(csv)
+}
+
+// An ftt:// link that opens the session in the app
+#[tauri::command]
+pub fn copy_session_link(
+    app: AppHandle,
+    store: State<'_, SessionStore>,
+    id: u64,
+) -> Result<String, String> {
+    let session = store.get(id)?;
+    let link = deep_link::session_link(session.id);
+    copy(&app, link.clone())?;
+    Ok(link)
+}
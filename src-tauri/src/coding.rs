@@ -0,0 +1,180 @@
+use chrono::{DateTime, TimeDelta, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, State};
+
+use crate::sessions::DateRange;
+use crate::storage;
+use crate::timer::TimerManager;
+
+// Gap after which consecutive heartbeats no longer count as continuous coding, as in
+// WakaTime's default keystroke timeout
+const HEARTBEAT_TIMEOUT: TimeDelta = TimeDelta::minutes(15);
+const MAX_HEARTBEATS: usize = 100_000;
+
+// The subset of a WakaTime heartbeat that is kept
+#[derive(Deserialize)]
+pub struct IncomingHeartbeat {
+    pub entity: String,
+    // Unix seconds, fractional
+    pub time: f64,
+    #[serde(default)]
+    pub project: Option<String>,
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub branch: Option<String>,
+    #[serde(default)]
+    pub is_write: bool,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct Heartbeat {
+    at: DateTime<Utc>,
+    // Task of the primary timer when the heartbeat arrived
+    task_id: Option<u64>,
+    project: Option<String>,
+    language: Option<String>,
+    branch: Option<String>,
+    // File name only; the directory layout is not needed for reports
+    file: Option<String>,
+    is_write: bool,
+}
+
+#[derive(Serialize)]
+pub struct CodingTotal {
+    pub name: String,
+    pub seconds: i64,
+}
+
+#[derive(Serialize)]
+pub struct TaskCodingTotal {
+    pub task_id: u64,
+    pub seconds: i64,
+}
+
+#[derive(Serialize)]
+pub struct CodingSummary {
+    pub total_seconds: i64,
+    pub projects: Vec<CodingTotal>,
+    pub languages: Vec<CodingTotal>,
+    pub tasks: Vec<TaskCodingTotal>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct CodingData {
+    heartbeats: Vec<Heartbeat>,
+}
+
+pub struct CodingStore {
+    path: PathBuf,
+    data: Mutex<CodingData>,
+}
+
+fn sorted_totals(map: HashMap<String, i64>) -> Vec<CodingTotal> {
+    let mut totals: Vec<CodingTotal> = map
+        .into_iter()
+        .map(|(name, seconds)| CodingTotal { name, seconds })
+        .collect();
+    totals.sort_by(|a, b| b.seconds.cmp(&a.seconds).then(a.name.cmp(&b.name)));
+    totals
+}
+
+impl CodingStore {
+    pub fn load(app: &AppHandle) -> Result<Self, String> {
+        let path = storage::data_file(app, "coding.json")?;
+        let data = storage::load_json(&path)?;
+        Ok(Self {
+            path,
+            data: Mutex::new(data),
+        })
+    }
+
+    // Stores heartbeats against the running timer. Returns how many were accepted.
+    pub fn record(
+        &self,
+        app: &AppHandle,
+        incoming: Vec<IncomingHeartbeat>,
+    ) -> Result<usize, String> {
+        let task_id = app.state::<TimerManager>().active().map(|t| t.task_id);
+        let mut data = self.data.lock().map_err(|e| e.to_string())?;
+        let mut accepted = 0;
+        for heartbeat in incoming {
+            let millis = (heartbeat.time * 1000.0) as i64;
+            let Some(at) = Utc.timestamp_millis_opt(millis).single() else {
+                continue;
+            };
+            data.heartbeats.push(Heartbeat {
+                at,
+                task_id,
+                project: heartbeat.project,
+                language: heartbeat.language,
+                branch: heartbeat.branch,
+                file: Path::new(&heartbeat.entity)
+                    .file_name()
+                    .map(|f| f.to_string_lossy().into_owned()),
+                is_write: heartbeat.is_write,
+            });
+            accepted += 1;
+        }
+        // Offline editors flush queued heartbeats late and out of order
+        data.heartbeats.sort_by_key(|h| h.at);
+        let overflow = data.heartbeats.len().saturating_sub(MAX_HEARTBEATS);
+        data.heartbeats.drain(..overflow);
+        storage::save_json(&self.path, &*data)?;
+        Ok(accepted)
+    }
+
+    // Each heartbeat is credited with the time until the next one, up to the timeout.
+    pub fn summary(&self, range: DateRange) -> Result<CodingSummary, String> {
+        let data = self.data.lock().map_err(|e| e.to_string())?;
+        let heartbeats: Vec<&Heartbeat> = data
+            .heartbeats
+            .iter()
+            .filter(|h| h.at >= range.start && h.at < range.end)
+            .collect();
+        let mut projects = HashMap::new();
+        let mut languages = HashMap::new();
+        let mut tasks: HashMap<u64, i64> = HashMap::new();
+        let mut total_seconds = 0;
+        for pair in heartbeats.windows(2) {
+            let gap = pair[1].at - pair[0].at;
+            if gap > HEARTBEAT_TIMEOUT {
+                continue;
+            }
+            let seconds = gap.num_seconds();
+            let heartbeat = pair[0];
+            total_seconds += seconds;
+            if let Some(project) = &heartbeat.project {
+                *projects.entry(project.clone()).or_default() += seconds;
+            }
+            if let Some(language) = &heartbeat.language {
+                *languages.entry(language.clone()).or_default() += seconds;
+            }
+            if let Some(task_id) = heartbeat.task_id {
+                *tasks.entry(task_id).or_default() += seconds;
+            }
+        }
+        let mut tasks: Vec<TaskCodingTotal> = tasks
+            .into_iter()
+            .map(|(task_id, seconds)| TaskCodingTotal { task_id, seconds })
+            .collect();
+        tasks.sort_by(|a, b| b.seconds.cmp(&a.seconds).then(a.task_id.cmp(&b.task_id)));
+        Ok(CodingSummary {
+            total_seconds,
+            projects: sorted_totals(projects),
+            languages: sorted_totals(languages),
+            tasks,
+        })
+    }
+}
+
+#[tauri::command]
+pub fn get_coding_activity(
+    store: State<'_, CodingStore>,
+    range: DateRange,
+) -> Result<CodingSummary, String> {
+    store.summary(range)
+}
@@ -1,12 +1,15 @@
 use serde::{Deserialize, Serialize};
 use sysinfo::System;
-use tauri::Emitter;
+use tauri::{Emitter, State};
+
+use crate::timer::{Countdown, TimerManager};
 
 #[derive(Serialize, Deserialize)]
 pub struct TimerState {
     pub active: bool,
     pub title: Option<String>,
     pub elapsed_seconds: Option<u64>,
+    pub remaining_seconds: Option<u64>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -23,20 +26,54 @@ pub fn greet(name: String) -> String {
 }
 
 #[tauri::command]
-pub fn get_timer_state() -> TimerState {
-    // TODO: Implement actual timer state retrieval
-    // For now, return inactive
-    TimerState {
-        active: false,
-        title: None,
-        elapsed_seconds: None,
+pub fn get_timer_state(timer: State<'_, TimerManager>) -> TimerState {
+    match timer.active() {
+        Some(active) => TimerState {
+            active: true,
+            elapsed_seconds: Some(active.elapsed_seconds()),
+            remaining_seconds: active.remaining_seconds(),
+            title: active.title,
+        },
+        None => TimerState {
+            active: false,
+            title: None,
+            elapsed_seconds: None,
+            remaining_seconds: None,
+        },
     }
 }
 
+// Without an id this switches the primary timer; with an id it starts (or restarts) a
+// concurrent named timer. A `countdown` makes it count down from a fixed duration.
+#[tauri::command]
+pub fn start_timer(
+    app: tauri::AppHandle,
+    timer: State<'_, TimerManager>,
+    task_id: u64,
+    title: Option<String>,
+    id: Option<String>,
+    primary: Option<bool>,
+    countdown: Option<Countdown>,
+) -> Result<(), String> {
+    match id {
+        Some(id) => {
+            timer.start_named(&app, id, task_id, title, primary.unwrap_or(false), countdown)?
+        }
+        None => timer.start_with(&app, task_id, title, countdown)?,
+    };
+    Ok(())
+}
+
 #[tauri::command]
-pub fn stop_timer() -> Result<(), String> {
-    // TODO: Implement timer stopping
-    // For now, just emit event to frontend
+pub fn stop_timer(
+    app: tauri::AppHandle,
+    timer: State<'_, TimerManager>,
+    id: Option<String>,
+) -> Result<(), String> {
+    match id {
+        Some(id) => timer.stop_named(&app, &id)?,
+        None => timer.stop(&app)?,
+    };
     Ok(())
 }
 
@@ -68,6 +105,6 @@ pub fn toggle_devtools(window: tauri::Window) -> Result<(), String> {
     // opening on the renderer side. Renderer has a fallback to dispatch
     // an F12 key event if necessary.
     let _ = window.emit("open-devtools", ());
-    println!("toggle_devtools: emitted open-devtools event");
+    log::debug!("toggle_devtools: emitted open-devtools event");
     Ok(())
 }
@@ -0,0 +1,304 @@
+use chrono::{DateTime, Duration as ChronoDuration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::background;
+use crate::holidays;
+use crate::notifications::{self, NotificationImportance, NotificationKind};
+use crate::project_policy;
+use crate::sessions::{DateRange, Session, SessionStore};
+use crate::settings::SettingsStore;
+use crate::time::ReportZone;
+use crate::timer::TimerManager;
+
+const EVALUATE_INTERVAL: Duration = Duration::from_secs(60);
+
+// Working-time limits, e.g. from the EU Working Time Directive or national law
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ComplianceSettings {
+    pub enabled: bool,
+    pub max_daily_hours: f64,
+    // Work stretching longer than this needs a break of at least min_break_mins;
+    // shorter gaps don't count as a break
+    pub break_after_hours: f64,
+    pub min_break_mins: u64,
+}
+
+impl Default for ComplianceSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_daily_hours: 10.0,
+            break_after_hours: 6.0,
+            min_break_mins: 30,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ComplianceRule {
+    DailyMaximum,
+    MissingBreak,
+}
+
+#[derive(Clone, Serialize)]
+pub struct ComplianceBreach {
+    pub date: NaiveDate,
+    pub rule: ComplianceRule,
+    // When the limit was crossed
+    pub at: DateTime<Utc>,
+    // Worked that day, or in the stretch without a break
+    pub worked_seconds: i64,
+    pub limit_seconds: i64,
+}
+
+#[derive(Clone, Serialize)]
+pub struct ComplianceSummary {
+    pub days: usize,
+    pub compliant_days: usize,
+    pub breaches: Vec<ComplianceBreach>,
+}
+
+// (date, rule) pairs already notified
+#[derive(Default)]
+pub struct ComplianceMonitor {
+    notified: Mutex<HashSet<(NaiveDate, ComplianceRule)>>,
+}
+
+// Worked time within [start, end), overlapping sessions merged
+fn worked_spans(
+    spans: &[(DateTime<Utc>, DateTime<Utc>)],
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+    let mut clipped: Vec<_> = spans
+        .iter()
+        .map(|&(s, e)| (s.max(start), e.min(end)))
+        .filter(|(s, e)| s < e)
+        .collect();
+    clipped.sort();
+    let mut merged: Vec<(DateTime<Utc>, DateTime<Utc>)> = Vec::new();
+    for (s, e) in clipped {
+        match merged.last_mut() {
+            Some(last) if s <= last.1 => last.1 = last.1.max(e),
+            _ => merged.push((s, e)),
+        }
+    }
+    merged
+}
+
+// When `limit` seconds of the spans have been worked, if they ever are
+fn crossed_at(spans: &[(DateTime<Utc>, DateTime<Utc>)], limit: i64) -> Option<DateTime<Utc>> {
+    let mut worked = 0;
+    for &(s, e) in spans {
+        let length = (e - s).num_seconds();
+        if worked + length > limit {
+            return Some(s + ChronoDuration::seconds(limit - worked));
+        }
+        worked += length;
+    }
+    None
+}
+
+fn total(spans: &[(DateTime<Utc>, DateTime<Utc>)]) -> i64 {
+    spans.iter().map(|(s, e)| (*e - *s).num_seconds()).sum()
+}
+
+fn check_day(
+    rules: &ComplianceSettings,
+    date: NaiveDate,
+    spans: &[(DateTime<Utc>, DateTime<Utc>)],
+) -> Vec<ComplianceBreach> {
+    let mut breaches = Vec::new();
+    let max_daily = (rules.max_daily_hours * 3600.0) as i64;
+    if let Some(at) = crossed_at(spans, max_daily) {
+        breaches.push(ComplianceBreach {
+            date,
+            rule: ComplianceRule::DailyMaximum,
+            at,
+            worked_seconds: total(spans),
+            limit_seconds: max_daily,
+        });
+    }
+
+    // Split the day into stretches at every gap long enough to count as a break
+    let min_break = ChronoDuration::minutes(rules.min_break_mins as i64);
+    let break_after = (rules.break_after_hours * 3600.0) as i64;
+    let mut stretches: Vec<Vec<(DateTime<Utc>, DateTime<Utc>)>> = Vec::new();
+    for &span in spans {
+        match stretches.last_mut() {
+            Some(stretch)
+                if stretch
+                    .last()
+                    .is_some_and(|last| span.0 - last.1 < min_break) =>
+            {
+                stretch.push(span)
+            }
+            _ => stretches.push(vec![span]),
+        }
+    }
+    let missing_break = stretches.iter().find_map(|stretch| {
+        let at = crossed_at(stretch, break_after)?;
+        Some((at, total(stretch)))
+    });
+    if let Some((at, worked_seconds)) = missing_break {
+        breaches.push(ComplianceBreach {
+            date,
+            rule: ComplianceRule::MissingBreak,
+            at,
+            worked_seconds,
+            limit_seconds: break_after,
+        });
+    }
+    breaches
+}
+
+// Checks each day of `range` against the rules. Running timers count as worked until now.
+pub fn summarize(
+    sessions: &[Session],
+    running: &[(DateTime<Utc>, DateTime<Utc>)],
+    range: DateRange,
+    zone: ReportZone,
+    rules: &ComplianceSettings,
+) -> ComplianceSummary {
+    let spans: Vec<_> = sessions
+        .iter()
+        .map(|s| (s.start, s.end))
+        .chain(running.iter().copied())
+        .collect();
+    let mut summary = ComplianceSummary {
+        days: 0,
+        compliant_days: 0,
+        breaches: Vec::new(),
+    };
+    let mut date = zone.date_of(range.start);
+    while zone.start_of_day(date) < range.end {
+        let Some(next) = date.succ_opt() else {
+            break;
+        };
+        let day = worked_spans(
+            &spans,
+            zone.start_of_day(date).max(range.start),
+            zone.start_of_day(next).min(range.end),
+        );
+        let breaches = check_day(rules, date, &day);
+        summary.days += 1;
+        if breaches.is_empty() {
+            summary.compliant_days += 1;
+        }
+        summary.breaches.extend(breaches);
+        date = next;
+    }
+    summary
+}
+
+fn running_spans(app: &AppHandle) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+    let now = Utc::now();
+    app.state::<TimerManager>()
+        .list()
+        .into_iter()
+        .map(|t| (t.timer.started_at, now))
+        .collect()
+}
+
+fn evaluate(app: &AppHandle) -> Result<(), String> {
+    let settings = app.state::<SettingsStore>().get()?;
+    if !settings.compliance.enabled {
+        return Ok(());
+    }
+    let zone = ReportZone::from_settings(&settings);
+    let now = Utc::now();
+    let date = zone.date_of(now);
+    // Work on a holiday or during leave is the user's call; don't remind them about breaks
+    if holidays::day_off(&settings.holidays, date).is_some() {
+        return Ok(());
+    }
+    let today = DateRange {
+        start: zone.start_of_day(date),
+        end: now,
+    };
+    let sessions = app.state::<SessionStore>().in_range(today)?;
+    let mut rules = settings.compliance.clone();
+    project_policy::adjust_compliance(app, &mut rules);
+    let summary = summarize(&sessions, &running_spans(app), today, zone, &rules);
+    let monitor = app.state::<ComplianceMonitor>();
+    let mut notified = monitor.notified.lock().map_err(|e| e.to_string())?;
+    notified.retain(|(notified_on, _)| *notified_on == date);
+    for breach in summary.breaches {
+        if !notified.insert((breach.date, breach.rule)) {
+            continue;
+        }
+        let (title, body) = match breach.rule {
+            ComplianceRule::DailyMaximum => (
+                "Daily working time exceeded",
+                format!(
+                    "You've worked more than {:.1} h today.",
+                    breach.limit_seconds as f64 / 3600.0
+                ),
+            ),
+            ComplianceRule::MissingBreak => (
+                "Time for a break",
+                format!(
+                    "You've worked {:.1} h without a {} min break.",
+                    breach.limit_seconds as f64 / 3600.0,
+                    rules.min_break_mins
+                ),
+            ),
+        };
+        log::info!("working-time rule breached: {}", title);
+        let _ = app.emit("compliance-breach", &breach);
+        notifications::notify(
+            app,
+            NotificationKind::Compliance,
+            NotificationImportance::High,
+            title,
+            &body,
+        );
+    }
+    Ok(())
+}
+
+pub fn start_monitor(app: AppHandle) {
+    app.manage(ComplianceMonitor::default());
+    background::spawn_thread(&app, "compliance", |app, task| {
+        while task.sleep_blocking(EVALUATE_INTERVAL) {
+            if let Err(e) = evaluate(&app) {
+                log::warn!("compliance check failed: {}", e);
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub fn get_compliance_breaches(
+    app: AppHandle,
+    store: State<'_, SessionStore>,
+    settings: State<'_, SettingsStore>,
+    range: DateRange,
+) -> Result<ComplianceSummary, String> {
+    let settings = settings.get()?;
+    Ok(summarize(
+        &store.in_range(range)?,
+        &running_spans(&app),
+        range,
+        ReportZone::from_settings(&settings),
+        &settings.compliance,
+    ))
+}
+
+#[tauri::command]
+pub fn set_compliance_settings(
+    settings: State<'_, SettingsStore>,
+    compliance: ComplianceSettings,
+) -> Result<(), String> {
+    if compliance.max_daily_hours <= 0.0 || compliance.break_after_hours <= 0.0 {
+        return Err("Working-time limits must be positive".to_string());
+    }
+    settings.update(|s| s.compliance = compliance)?;
+    Ok(())
+}
@@ -0,0 +1,84 @@
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, Url};
+
+use crate::app_lock;
+use crate::timer::TimerManager;
+
+pub const SCHEME: &str = "ftt";
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum DeepLinkAction {
+    Start { task_id: u64, title: Option<String> },
+    Stop,
+    Report { period: String },
+    Session { id: u64 },
+    Show,
+}
+
+// ftt://start?task=123[&title=...], ftt://stop, ftt://report/today, ftt://session/42,
+// ftt://show
+pub fn parse(raw: &str) -> Option<DeepLinkAction> {
+    let url = Url::parse(raw).ok()?;
+    if url.scheme() != SCHEME {
+        return None;
+    }
+    let query = |key: &str| {
+        url.query_pairs()
+            .find_map(|(k, v)| (k == key).then(|| v.into_owned()))
+    };
+
+    match url.host_str()? {
+        "start" => Some(DeepLinkAction::Start {
+            task_id: query("task")?.parse().ok()?,
+            title: query("title"),
+        }),
+        "stop" => Some(DeepLinkAction::Stop),
+        "report" => {
+            let period = url.path().trim_matches('/');
+            Some(DeepLinkAction::Report {
+                period: if period.is_empty() { "today" } else { period }.to_string(),
+            })
+        }
+        "session" => Some(DeepLinkAction::Session {
+            id: url.path().trim_matches('/').parse().ok()?,
+        }),
+        "show" | "" => Some(DeepLinkAction::Show),
+        _ => None,
+    }
+}
+
+pub fn dispatch(app: &AppHandle, action: DeepLinkAction) {
+    // Links can come from any app, so they get no further than the lock screen
+    if let Some(reason) = app_lock::refusal(app) {
+        log::warn!("deep link {:?} refused: {}", action, reason);
+        return;
+    }
+    let timer = app.state::<TimerManager>();
+    let result = match &action {
+        DeepLinkAction::Start { task_id, title } => {
+            timer.start(app, *task_id, title.clone()).map(|_| ())
+        }
+        DeepLinkAction::Stop => timer.stop(app).map(|_| ()),
+        DeepLinkAction::Report { .. } | DeepLinkAction::Session { .. } | DeepLinkAction::Show => {
+            crate::show_main_window(app);
+            Ok(())
+        }
+    };
+    if let Err(e) = result {
+        log::error!("deep link {:?} failed: {}", action, e);
+    }
+    let _ = app.emit("deep-link", &action);
+}
+
+// A link that opens the session in the app, for pasting into chats or tickets
+pub fn session_link(id: u64) -> String {
+    format!("{}://session/{}", SCHEME, id)
+}
+
+// Handle any ftt:// URLs found in a process argv (first launch or a secondary instance).
+pub fn handle_args(app: &AppHandle, args: &[String]) {
+    for action in args.iter().filter_map(|arg| parse(arg)) {
+        dispatch(app, action);
+    }
+}
@@ -0,0 +1,207 @@
+// Whether the microphone, camera or speakers are in use right now, as far as each
+// platform can tell.
+
+#[cfg(windows)]
+mod platform {
+    use winreg::enums::HKEY_CURRENT_USER;
+    use winreg::RegKey;
+
+    const CONSENT_STORE: &str =
+        r"Software\Microsoft\Windows\CurrentVersion\CapabilityAccessManager\ConsentStore";
+
+    // Windows records per-app capability use; an app using it now has no stop time
+    fn any_active(key: &RegKey) -> bool {
+        key.enum_keys().flatten().any(|name| {
+            key.open_subkey(&name)
+                .and_then(|app| app.get_value::<u64, _>("LastUsedTimeStop"))
+                .is_ok_and(|stop| stop == 0)
+        })
+    }
+
+    fn in_use(capability: &str) -> bool {
+        let Ok(root) = RegKey::predef(HKEY_CURRENT_USER)
+            .open_subkey(format!(r"{}\{}", CONSENT_STORE, capability))
+        else {
+            return false;
+        };
+        // Desktop apps are listed under NonPackaged, store apps directly
+        any_active(&root)
+            || root
+                .open_subkey("NonPackaged")
+                .is_ok_and(|k| any_active(&k))
+    }
+
+    pub fn microphone() -> bool {
+        in_use("microphone")
+    }
+
+    pub fn camera() -> bool {
+        in_use("webcam")
+    }
+
+    // Anything audible on the default output device
+    pub fn speaker() -> bool {
+        use windows::Win32::Media::Audio::Endpoints::IAudioMeterInformation;
+        use windows::Win32::Media::Audio::{
+            eConsole, eRender, IMMDeviceEnumerator, MMDeviceEnumerator,
+        };
+        use windows::Win32::System::Com::{
+            CoCreateInstance, CoInitializeEx, CLSCTX_ALL, COINIT_MULTITHREADED,
+        };
+        // SAFETY: COM is initialized on this thread before any interface is created
+        let peak = unsafe {
+            let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+            CoCreateInstance::<_, IMMDeviceEnumerator>(&MMDeviceEnumerator, None, CLSCTX_ALL)
+                .and_then(|enumerator| enumerator.GetDefaultAudioEndpoint(eRender, eConsole))
+                .and_then(|device| device.Activate::<IAudioMeterInformation>(CLSCTX_ALL, None))
+                .and_then(|meter| meter.GetPeakValue())
+        };
+        peak.is_ok_and(|peak| peak > 0.0)
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::ffi::c_void;
+
+    #[repr(C)]
+    struct PropertyAddress {
+        selector: u32,
+        scope: u32,
+        element: u32,
+    }
+
+    #[link(name = "CoreAudio", kind = "framework")]
+    extern "C" {
+        fn AudioObjectGetPropertyData(
+            object: u32,
+            address: *const PropertyAddress,
+            qualifier_size: u32,
+            qualifier: *const c_void,
+            data_size: *mut u32,
+            data: *mut c_void,
+        ) -> i32;
+    }
+
+    const SYSTEM_OBJECT: u32 = 1;
+    const SCOPE_GLOBAL: u32 = u32::from_be_bytes(*b"glob");
+    const DEFAULT_INPUT_DEVICE: u32 = u32::from_be_bytes(*b"dIn ");
+    const DEFAULT_OUTPUT_DEVICE: u32 = u32::from_be_bytes(*b"dOut");
+    const RUNNING_SOMEWHERE: u32 = u32::from_be_bytes(*b"gone");
+
+    fn get_u32(object: u32, selector: u32) -> Option<u32> {
+        let address = PropertyAddress {
+            selector,
+            scope: SCOPE_GLOBAL,
+            element: 0,
+        };
+        let mut value = 0u32;
+        let mut size = std::mem::size_of::<u32>() as u32;
+        // SAFETY: `value` is a u32 and `size` matches it
+        let status = unsafe {
+            AudioObjectGetPropertyData(
+                object,
+                &address,
+                0,
+                std::ptr::null(),
+                &mut size,
+                &mut value as *mut u32 as *mut c_void,
+            )
+        };
+        (status == 0).then_some(value)
+    }
+
+    fn device_running(default_device: u32) -> bool {
+        get_u32(SYSTEM_OBJECT, default_device)
+            .filter(|device| *device != 0)
+            .and_then(|device| get_u32(device, RUNNING_SOMEWHERE))
+            .is_some_and(|running| running != 0)
+    }
+
+    // Whether any process is recording from the default input device
+    pub fn microphone() -> bool {
+        device_running(DEFAULT_INPUT_DEVICE)
+    }
+
+    // Whether any process is playing to the default output device
+    pub fn speaker() -> bool {
+        device_running(DEFAULT_OUTPUT_DEVICE)
+    }
+
+    // No public API reports camera use; meetings are detected from the microphone
+    pub fn camera() -> bool {
+        false
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use std::fs;
+
+    // An ALSA substream of the given direction is running (PipeWire and PulseAudio hold
+    // it open while something records or plays). Devices are named pcm<N>c for capture
+    // and pcm<N>p for playback.
+    fn substream_running(direction: char) -> bool {
+        let Ok(cards) = fs::read_dir("/proc/asound") else {
+            return false;
+        };
+        cards
+            .flatten()
+            .filter(|card| card.file_name().to_string_lossy().starts_with("card"))
+            .filter_map(|card| fs::read_dir(card.path()).ok())
+            .flatten()
+            .flatten()
+            .filter(|pcm| {
+                let name = pcm.file_name().to_string_lossy().into_owned();
+                name.starts_with("pcm") && name.ends_with(direction)
+            })
+            .filter_map(|pcm| fs::read_dir(pcm.path()).ok())
+            .flatten()
+            .flatten()
+            .any(|sub| {
+                fs::read_to_string(sub.path().join("status"))
+                    .is_ok_and(|status| status.contains("RUNNING"))
+            })
+    }
+
+    pub fn microphone() -> bool {
+        substream_running('c')
+    }
+
+    pub fn speaker() -> bool {
+        substream_running('p')
+    }
+
+    // Some process holds a /dev/video device open
+    pub fn camera() -> bool {
+        let Ok(processes) = fs::read_dir("/proc") else {
+            return false;
+        };
+        processes
+            .flatten()
+            .filter_map(|process| fs::read_dir(process.path().join("fd")).ok())
+            .flatten()
+            .flatten()
+            .any(|fd| {
+                fs::read_link(fd.path())
+                    .is_ok_and(|target| target.to_string_lossy().starts_with("/dev/video"))
+            })
+    }
+}
+
+#[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+mod platform {
+    pub fn microphone() -> bool {
+        false
+    }
+
+    pub fn camera() -> bool {
+        false
+    }
+
+    pub fn speaker() -> bool {
+        false
+    }
+}
+
+pub use platform::{camera, microphone, speaker};
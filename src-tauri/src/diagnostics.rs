@@ -0,0 +1,250 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use crate::idle::{IdleMonitor, IdleProviderInfo};
+use crate::integrations::jira::{JiraQueue, PendingWorklog};
+use crate::metrics::{self, CommandMetrics, CommandStats};
+use crate::screenshots::{self, ScreenshotUploadStatus};
+use crate::sound::{self, AudioDevice, SoundManager};
+use crate::storage;
+use crate::sync;
+
+const DIAGNOSTICS_DIR: &str = "diagnostics";
+// Only the end of each log file is included
+const MAX_LOG_BYTES: u64 = 512 * 1024;
+// Fragments that mark a log line as possibly carrying a credential
+const SECRET_MARKERS: &[&str] = &[
+    "token",
+    "password",
+    "secret",
+    "api_key",
+    "apikey",
+    "authorization",
+    "bearer",
+    "webhook",
+];
+
+#[derive(Serialize)]
+struct OsInfo {
+    platform: String,
+    os_type: String,
+    version: String,
+    arch: String,
+    family: String,
+    locale: Option<String>,
+}
+
+#[derive(Serialize)]
+struct AudioStatus {
+    enabled: bool,
+    output_device: Option<String>,
+    devices: Vec<AudioDevice>,
+    error: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+enum FileCheck {
+    Ok,
+    Missing,
+    Corrupt { error: String },
+}
+
+#[derive(Serialize)]
+struct SyncStatus {
+    // Jira worklogs
+    pending: usize,
+    failing: Vec<PendingWorklog>,
+    // Session sync: local changes not pushed yet, and the last run
+    sessions_pending: usize,
+    sessions: sync::SyncStatus,
+    screenshots: ScreenshotUploadStatus,
+}
+
+#[derive(Serialize)]
+struct Report {
+    generated_at: DateTime<Utc>,
+    app_version: String,
+    os: OsInfo,
+    idle: IdleProviderInfo,
+    audio: AudioStatus,
+    store: Vec<(String, FileCheck)>,
+    sync: Result<SyncStatus, String>,
+    commands: Vec<CommandStats>,
+}
+
+fn os_info() -> OsInfo {
+    OsInfo {
+        platform: tauri_plugin_os::platform().to_string(),
+        os_type: tauri_plugin_os::type_().to_string(),
+        version: tauri_plugin_os::version().to_string(),
+        arch: tauri_plugin_os::arch().to_string(),
+        family: tauri_plugin_os::family().to_string(),
+        locale: tauri_plugin_os::locale(),
+    }
+}
+
+fn audio_status(app: &AppHandle) -> AudioStatus {
+    let manager = app.state::<SoundManager>();
+    let config = manager.config().unwrap_or_default();
+    let (devices, error) = match manager.audio_devices() {
+        Ok(devices) => (devices, None),
+        Err(e) => (Vec::new(), Some(e)),
+    };
+    AudioStatus {
+        enabled: config.enabled,
+        output_device: config.output_device,
+        devices,
+        error,
+    }
+}
+
+fn check_file(path: &Path) -> FileCheck {
+    if !path.exists() {
+        return FileCheck::Missing;
+    }
+    let contents = match storage::read_text(path) {
+        Ok(contents) => contents,
+        Err(error) => return FileCheck::Corrupt { error },
+    };
+    let parsed = if path.extension().is_some_and(|e| e == "toml") {
+        toml::from_str::<toml::Value>(&contents)
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    } else {
+        serde_json::from_str::<Value>(&contents)
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    };
+    match parsed {
+        Ok(()) => FileCheck::Ok,
+        Err(error) => FileCheck::Corrupt { error },
+    }
+}
+
+fn check_store(dir: &Path) -> Vec<(String, FileCheck)> {
+    storage::DATA_FILES
+        .iter()
+        .copied()
+        .chain(["store.json", sound::CONFIG_FILE])
+        .map(|name| (name.to_string(), check_file(&dir.join(name))))
+        .collect()
+}
+
+fn sync_status(app: &AppHandle) -> Result<SyncStatus, String> {
+    let pending = app.state::<JiraQueue>().pending()?;
+    Ok(SyncStatus {
+        pending: pending.len(),
+        failing: pending
+            .into_iter()
+            .filter(|p| p.last_error.is_some())
+            .collect(),
+        sessions_pending: sync::pending_count(app)?,
+        sessions: sync::get_sync_status(app.state(), app.state())?,
+        screenshots: screenshots::get_screenshot_upload_status(app.state())?,
+    })
+}
+
+// Drops anything after a credential-looking marker on each line.
+fn scrub(log: &str) -> String {
+    log.lines()
+        .map(|line| {
+            let lower = line.to_lowercase();
+            match SECRET_MARKERS.iter().filter_map(|m| lower.find(m)).min() {
+                // Lowercasing can change byte offsets for non-ASCII text, so redact it all
+                Some(_) if lower.len() != line.len() => "[redacted]".to_string(),
+                Some(at) => format!("{}[redacted]", &line[..at]),
+                None => line.to_string(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn read_tail(path: &Path) -> Result<String, String> {
+    let mut file = File::open(path).map_err(|e| e.to_string())?;
+    let len = file.metadata().map_err(|e| e.to_string())?.len();
+    file.seek(SeekFrom::Start(len.saturating_sub(MAX_LOG_BYTES)))
+        .map_err(|e| e.to_string())?;
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes).map_err(|e| e.to_string())?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+fn log_files(app: &AppHandle) -> Vec<PathBuf> {
+    let Some(entries) = app
+        .path()
+        .app_log_dir()
+        .ok()
+        .and_then(|d| fs::read_dir(d).ok())
+    else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(Result::ok)
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|e| e == "log"))
+        .collect()
+}
+
+// Bundles system, provider and store status plus recent logs into a zip for bug
+// reports. Settings and logs are scrubbed of credentials first.
+#[tauri::command]
+pub fn generate_diagnostics(app: AppHandle) -> Result<PathBuf, String> {
+    let meta = storage::data_file(&app, "store.json")?;
+    let data_dir = meta.parent().ok_or("invalid data directory")?;
+    let report = Report {
+        generated_at: Utc::now(),
+        app_version: app.package_info().version.to_string(),
+        os: os_info(),
+        idle: app.state::<IdleMonitor>().info(),
+        audio: audio_status(&app),
+        store: check_store(data_dir),
+        sync: sync_status(&app),
+        commands: app.state::<CommandMetrics>().stats()?,
+    };
+
+    let dir = data_dir.join(DIAGNOSTICS_DIR);
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let path = dir.join(format!(
+        "diagnostics-{}.zip",
+        report.generated_at.format("%Y%m%d-%H%M%S")
+    ));
+    let file = File::create(&path).map_err(|e| e.to_string())?;
+    let mut zip = ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    let mut add = |name: &str, contents: &[u8]| -> Result<(), String> {
+        zip.start_file(name, options).map_err(|e| e.to_string())?;
+        zip.write_all(contents).map_err(|e| e.to_string())
+    };
+    add(
+        "diagnostics.json",
+        &serde_json::to_vec_pretty(&report).map_err(|e| e.to_string())?,
+    )?;
+    if let Ok(settings) = storage::read_text(&data_dir.join("settings.json")) {
+        if let Ok(value) = serde_json::from_str::<Value>(&settings) {
+            let redacted =
+                serde_json::to_vec_pretty(&metrics::redact(&value)).map_err(|e| e.to_string())?;
+            add("settings.json", &redacted)?;
+        }
+    }
+    for log in log_files(&app) {
+        let Some(name) = log.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        match read_tail(&log) {
+            Ok(contents) => add(&format!("logs/{}", name), scrub(&contents).as_bytes())?,
+            Err(e) => log::warn!("skipping log {}: {}", log.display(), e),
+        }
+    }
+    zip.finish().map_err(|e| e.to_string())?;
+    Ok(path)
+}
@@ -0,0 +1,132 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::activity::ActivityLog;
+use crate::background;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+#[derive(Clone, PartialEq, Serialize)]
+pub struct Display {
+    pub name: Option<String>,
+    pub width: u32,
+    pub height: u32,
+    pub x: i32,
+    pub y: i32,
+    pub scale_factor: f64,
+    pub primary: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DisplayChangeKind {
+    // More monitors than before
+    Docked,
+    // Fewer monitors than before
+    Undocked,
+    // Same count, different arrangement or resolution
+    Rearranged,
+}
+
+#[derive(Clone, Serialize)]
+pub struct DisplayChange {
+    pub at: DateTime<Utc>,
+    pub kind: DisplayChangeKind,
+    pub from_count: usize,
+    pub to_count: usize,
+}
+
+#[derive(Clone, Default, Serialize)]
+pub struct DisplayInfo {
+    pub displays: Vec<Display>,
+    pub last_change: Option<DisplayChange>,
+}
+
+#[derive(Default)]
+pub struct DisplayWatcher {
+    info: Mutex<DisplayInfo>,
+}
+
+fn read_displays(app: &AppHandle) -> Result<Vec<Display>, String> {
+    let primary = app
+        .primary_monitor()
+        .map_err(|e| e.to_string())?
+        .map(|m| *m.position());
+    let mut displays: Vec<Display> = app
+        .available_monitors()
+        .map_err(|e| e.to_string())?
+        .iter()
+        .map(|m| Display {
+            name: m.name().cloned(),
+            width: m.size().width,
+            height: m.size().height,
+            x: m.position().x,
+            y: m.position().y,
+            scale_factor: m.scale_factor(),
+            primary: primary == Some(*m.position()),
+        })
+        .collect();
+    // Platforms don't list monitors in a stable order
+    displays.sort_by_key(|d| (d.x, d.y));
+    Ok(displays)
+}
+
+fn poll(app: &AppHandle) -> Result<(), String> {
+    let displays = read_displays(app)?;
+    let watcher = app.state::<DisplayWatcher>();
+    let change = {
+        let mut info = watcher.info.lock().map_err(|e| e.to_string())?;
+        if info.displays == displays {
+            return Ok(());
+        }
+        let (from_count, to_count) = (info.displays.len(), displays.len());
+        let kind = match to_count.cmp(&from_count) {
+            std::cmp::Ordering::Greater => DisplayChangeKind::Docked,
+            std::cmp::Ordering::Less => DisplayChangeKind::Undocked,
+            std::cmp::Ordering::Equal => DisplayChangeKind::Rearranged,
+        };
+        // The first reading is the starting topology, not a change
+        let change = (!info.displays.is_empty()).then(|| DisplayChange {
+            at: Utc::now(),
+            kind,
+            from_count,
+            to_count,
+        });
+        info.displays = displays;
+        if change.is_some() {
+            info.last_change = change.clone();
+        }
+        change
+    };
+    if let Some(change) = change {
+        log::info!(
+            "display topology changed: {} -> {} monitors",
+            change.from_count,
+            change.to_count
+        );
+        app.state::<ActivityLog>()
+            .push_display_change(change.to_count);
+        let _ = app.emit("display-changed", &change);
+    }
+    Ok(())
+}
+
+pub fn start_watcher(app: AppHandle) {
+    app.manage(DisplayWatcher::default());
+    background::spawn_thread(&app, "display_watcher", |app, task| loop {
+        if let Err(e) = poll(&app) {
+            log::debug!("display query failed: {}", e);
+        }
+        if !task.sleep_blocking(POLL_INTERVAL) {
+            break;
+        }
+    });
+}
+
+#[tauri::command]
+pub fn get_display_info(watcher: State<'_, DisplayWatcher>) -> Result<DisplayInfo, String> {
+    Ok(watcher.info.lock().map_err(|e| e.to_string())?.clone())
+}
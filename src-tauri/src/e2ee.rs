@@ -0,0 +1,285 @@
+use argon2::Argon2;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+use rand::Rng;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::sync::RwLock;
+use tauri::AppHandle;
+
+use crate::secrets;
+use crate::sessions::Session;
+use crate::storage;
+
+// Key that synced entries are encrypted with, base64 in the OS keyring. End-to-end
+// encryption is on exactly when it exists.
+pub(crate) const KEY_NAME: &str = "e2ee_key";
+const NONCE_LEN: usize = 24;
+const SALT_LEN: usize = 16;
+// Unambiguous characters; 30 of them carry 147 bits
+const RECOVERY_ALPHABET: &[u8] = b"ABCDEFGHJKMNPQRSTVWXYZ23456789";
+const RECOVERY_GROUPS: usize = 6;
+const RECOVERY_GROUP_LEN: usize = 5;
+
+static CIPHER: RwLock<Option<XChaCha20Poly1305>> = RwLock::new(None);
+
+// The sync key encrypted under a key derived from a passphrase or recovery phrase
+#[derive(Clone, Serialize, Deserialize)]
+pub struct WrappedKey {
+    pub salt: String,
+    pub key: String,
+}
+
+// What export_e2ee_key hands out and import_e2ee_key takes on another device. Safe to
+// store anywhere as long as the passphrase and recovery phrase aren't stored with it.
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KeyBundle {
+    pub passphrase: Option<WrappedKey>,
+    pub recovery: Option<WrappedKey>,
+}
+
+#[derive(Clone, Serialize)]
+pub struct E2eeStatus {
+    pub enabled: bool,
+    pub has_recovery_phrase: bool,
+}
+
+// An entry as it travels to the backend while end-to-end encryption is on
+#[derive(Serialize, Deserialize)]
+struct Sealed {
+    sealed: String,
+}
+
+fn cipher_from(key: &[u8]) -> Result<XChaCha20Poly1305, String> {
+    if key.len() != 32 {
+        return Err("invalid sync key".to_string());
+    }
+    Ok(XChaCha20Poly1305::new(Key::from_slice(key)))
+}
+
+fn seal(cipher: &XChaCha20Poly1305, plain: &[u8]) -> Result<String, String> {
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, plain)
+        .map_err(|_| "encryption failed".to_string())?;
+    let mut out = nonce.to_vec();
+    out.extend_from_slice(&ciphertext);
+    Ok(STANDARD.encode(out))
+}
+
+fn open(cipher: &XChaCha20Poly1305, sealed: &str) -> Result<Vec<u8>, String> {
+    let raw = STANDARD.decode(sealed).map_err(|e| e.to_string())?;
+    if raw.len() < NONCE_LEN {
+        return Err("truncated ciphertext".to_string());
+    }
+    let (nonce, ciphertext) = raw.split_at(NONCE_LEN);
+    cipher
+        .decrypt(XNonce::from_slice(nonce), ciphertext)
+        .map_err(|_| "decryption failed".to_string())
+}
+
+// Argon2id over the secret; the salt travels in the bundle so another device derives
+// the same key.
+fn derive(secret: &str, salt: &[u8]) -> Result<XChaCha20Poly1305, String> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(secret.as_bytes(), salt, &mut key)
+        .map_err(|e| e.to_string())?;
+    cipher_from(&key)
+}
+
+fn wrap(key: &[u8], secret: &str) -> Result<WrappedKey, String> {
+    let salt: [u8; SALT_LEN] = rand::thread_rng().gen();
+    Ok(WrappedKey {
+        salt: STANDARD.encode(salt),
+        key: seal(&derive(secret, &salt)?, key)?,
+    })
+}
+
+fn unwrap(wrapped: &WrappedKey, secret: &str) -> Result<Vec<u8>, String> {
+    let salt = STANDARD.decode(&wrapped.salt).map_err(|e| e.to_string())?;
+    open(&derive(secret, &salt)?, &wrapped.key)
+}
+
+// Recovery phrases are read back from paper, so case, spaces and dashes don't matter
+fn normalize_recovery(phrase: &str) -> String {
+    phrase
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+}
+
+fn load_bundle(app: &AppHandle) -> Result<KeyBundle, String> {
+    storage::load_json(&storage::data_file(app, "e2ee.json")?)
+}
+
+fn save_bundle(app: &AppHandle, bundle: &KeyBundle) -> Result<(), String> {
+    storage::save_json(&storage::data_file(app, "e2ee.json")?, bundle)
+}
+
+fn current_key() -> Result<Vec<u8>, String> {
+    let key = secrets::get(KEY_NAME)?
+        .ok_or_else(|| "End-to-end encryption is not enabled".to_string())?;
+    STANDARD.decode(key).map_err(|e| e.to_string())
+}
+
+fn install(key: &[u8]) -> Result<(), String> {
+    let cipher = cipher_from(key)?;
+    secrets::set(KEY_NAME, &STANDARD.encode(key))?;
+    *CIPHER.write().map_err(|e| e.to_string())? = Some(cipher);
+    Ok(())
+}
+
+// Loads the active profile's sync key; call after the profile is selected.
+pub fn init() -> Result<(), String> {
+    let cipher = match secrets::get(KEY_NAME)? {
+        Some(key) => Some(cipher_from(
+            &STANDARD.decode(key).map_err(|e| e.to_string())?,
+        )?),
+        None => None,
+    };
+    *CIPHER.write().map_err(|e| e.to_string())? = cipher;
+    Ok(())
+}
+
+pub fn is_enabled() -> bool {
+    CIPHER.read().is_ok_and(|c| c.is_some())
+}
+
+// An entry as the backend should store it: sealed while end-to-end encryption is on.
+pub fn encode(session: &Session) -> Result<serde_json::Value, String> {
+    let cipher = CIPHER.read().map_err(|e| e.to_string())?;
+    match cipher.as_ref() {
+        Some(cipher) => {
+            let plain = serde_json::to_vec(session).map_err(|e| e.to_string())?;
+            serde_json::to_value(Sealed {
+                sealed: seal(cipher, &plain)?,
+            })
+        }
+        None => serde_json::to_value(session),
+    }
+    .map_err(|e| e.to_string())
+}
+
+// Reads an entry from the backend, sealed or not, along with whether it was sealed.
+pub fn decode(value: serde_json::Value) -> Result<(Session, bool), String> {
+    let Ok(Sealed { sealed }) = serde_json::from_value::<Sealed>(value.clone()) else {
+        let session = serde_json::from_value(value).map_err(|e| e.to_string())?;
+        return Ok((session, false));
+    };
+    let cipher = CIPHER.read().map_err(|e| e.to_string())?;
+    let cipher = cipher.as_ref().ok_or_else(|| {
+        "Entry is end-to-end encrypted; import the sync key to read it".to_string()
+    })?;
+    let session = serde_json::from_slice(&open(cipher, &sealed)?).map_err(|e| e.to_string())?;
+    Ok((session, true))
+}
+
+pub fn serialize_session<S: Serializer>(
+    session: &Session,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    encode(session)
+        .map_err(serde::ser::Error::custom)?
+        .serialize(serializer)
+}
+
+pub fn deserialize_session<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<(Session, bool)>, D::Error> {
+    Option::<serde_json::Value>::deserialize(deserializer)?
+        .map(decode)
+        .transpose()
+        .map_err(serde::de::Error::custom)
+}
+
+// Generates a sync key protected by `passphrase`. Entries already on the backend are
+// re-uploaded sealed on the next sync.
+#[tauri::command]
+pub fn enable_e2ee(app: AppHandle, passphrase: String) -> Result<E2eeStatus, String> {
+    if is_enabled() {
+        return Err("End-to-end encryption is already enabled".to_string());
+    }
+    if passphrase.chars().count() < 8 {
+        return Err("The passphrase must be at least 8 characters".to_string());
+    }
+    let key = XChaCha20Poly1305::generate_key(&mut OsRng);
+    save_bundle(
+        &app,
+        &KeyBundle {
+            passphrase: Some(wrap(&key, &passphrase)?),
+            recovery: None,
+        },
+    )?;
+    install(&key)?;
+    log::info!("end-to-end sync encryption enabled");
+    get_e2ee_status(app)
+}
+
+// Forgets the sync key; entries are re-uploaded in the clear on the next sync.
+#[tauri::command]
+pub fn disable_e2ee(app: AppHandle) -> Result<E2eeStatus, String> {
+    secrets::delete(KEY_NAME)?;
+    *CIPHER.write().map_err(|e| e.to_string())? = None;
+    save_bundle(&app, &KeyBundle::default())?;
+    log::info!("end-to-end sync encryption disabled");
+    get_e2ee_status(app)
+}
+
+// Replaces any earlier recovery phrase. The phrase is only ever shown here.
+#[tauri::command]
+pub fn generate_recovery_phrase(app: AppHandle) -> Result<String, String> {
+    let key = current_key()?;
+    let mut rng = rand::thread_rng();
+    let groups: Vec<String> = (0..RECOVERY_GROUPS)
+        .map(|_| {
+            (0..RECOVERY_GROUP_LEN)
+                .map(|_| RECOVERY_ALPHABET[rng.gen_range(0..RECOVERY_ALPHABET.len())] as char)
+                .collect()
+        })
+        .collect();
+    let phrase = groups.join("-");
+    let mut bundle = load_bundle(&app)?;
+    bundle.recovery = Some(wrap(&key, &normalize_recovery(&phrase))?);
+    save_bundle(&app, &bundle)?;
+    Ok(phrase)
+}
+
+#[tauri::command]
+pub fn export_e2ee_key(app: AppHandle) -> Result<KeyBundle, String> {
+    current_key()?;
+    load_bundle(&app)
+}
+
+// Sets up another device from an exported bundle, unlocked with either the passphrase
+// or the recovery phrase.
+#[tauri::command]
+pub fn import_e2ee_key(
+    app: AppHandle,
+    bundle: KeyBundle,
+    secret: String,
+) -> Result<E2eeStatus, String> {
+    let key = bundle
+        .passphrase
+        .as_ref()
+        .and_then(|wrapped| unwrap(wrapped, &secret).ok())
+        .or_else(|| {
+            let wrapped = bundle.recovery.as_ref()?;
+            unwrap(wrapped, &normalize_recovery(&secret)).ok()
+        })
+        .ok_or_else(|| "Wrong passphrase or recovery phrase".to_string())?;
+    save_bundle(&app, &bundle)?;
+    install(&key)?;
+    get_e2ee_status(app)
+}
+
+#[tauri::command]
+pub fn get_e2ee_status(app: AppHandle) -> Result<E2eeStatus, String> {
+    Ok(E2eeStatus {
+        enabled: is_enabled(),
+        has_recovery_phrase: load_bundle(&app)?.recovery.is_some(),
+    })
+}
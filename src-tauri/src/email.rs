@@ -0,0 +1,237 @@
+use chrono::{DateTime, Datelike, Duration as ChronoDuration, Local, NaiveTime, Utc, Weekday};
+use lettre::message::header::ContentType;
+use lettre::message::{Attachment, Mailbox, MultiPart, SinglePart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{Message, SmtpTransport, Transport};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+
+use crate::background;
+use crate::notifications::{self, NotificationImportance, NotificationKind};
+use crate::org_policy::{self, OrgPolicies};
+use crate::pdf;
+use crate::reports;
+use crate::secrets;
+use crate::sessions::{DateRange, SessionStore};
+use crate::settings::SettingsStore;
+use crate::time::ReportZone;
+
+pub(crate) const PASSWORD_KEY: &str = "smtp_password";
+const SCHEDULE_POLL_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct EmailSchedule {
+    pub enabled: bool,
+    pub recipient: String,
+    pub weekday: Weekday,
+    // Local time on `weekday` after which the summary is sent
+    pub time: NaiveTime,
+    #[serde(default)]
+    pub last_sent: Option<DateTime<Utc>>,
+}
+
+// SMTP account used to send reports; the password is kept in the keyring
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EmailSettings {
+    pub smtp_host: Option<String>,
+    pub smtp_port: u16,
+    pub username: Option<String>,
+    // Sender address; defaults to `username`
+    pub from: Option<String>,
+    pub schedule: Option<EmailSchedule>,
+}
+
+impl Default for EmailSettings {
+    fn default() -> Self {
+        Self {
+            smtp_host: None,
+            smtp_port: 587,
+            username: None,
+            from: None,
+            schedule: None,
+        }
+    }
+}
+
+fn hours(seconds: i64) -> String {
+    format!("{:.1} h", seconds as f64 / 3600.0)
+}
+
+fn mailbox(address: &str) -> Result<Mailbox, String> {
+    address
+        .parse()
+        .map_err(|e| format!("Invalid address {}: {}", address, e))
+}
+
+// Plain-text summary with the PDF timesheet attached.
+fn build_message(app: &AppHandle, range: DateRange, recipient: &str) -> Result<Message, String> {
+    let settings = app.state::<SettingsStore>().get()?;
+    let from = settings
+        .email
+        .from
+        .clone()
+        .or_else(|| settings.email.username.clone())
+        .ok_or("No sender address is configured")?;
+    let zone = ReportZone::from_settings(&settings);
+    let sessions = app.state::<SessionStore>().in_range(range)?;
+    let report = reports::build_report(&sessions, &[], range, zone, None);
+
+    let first = zone.date_of(range.start);
+    let last = zone.date_of(range.end - ChronoDuration::seconds(1));
+    let mut body = format!(
+        "Time tracked {} to {}: {}\n\n",
+        first,
+        last,
+        hours(report.total_seconds)
+    );
+    for task in &report.tasks {
+        let name = task
+            .title
+            .clone()
+            .unwrap_or_else(|| format!("Task #{}", task.task_id));
+        body.push_str(&format!("  {:<40} {}\n", name, hours(task.seconds)));
+    }
+    body.push_str("\nThe full timesheet is attached.\n");
+
+    let pdf = pdf::render_timesheet(&sessions, range, zone, &settings.report_branding)?;
+    let attachment = Attachment::new(format!("timesheet-{}.pdf", first)).body(
+        pdf,
+        ContentType::parse("application/pdf").map_err(|e| e.to_string())?,
+    );
+    Message::builder()
+        .from(mailbox(&from)?)
+        .to(mailbox(recipient)?)
+        .subject(format!("Your time summary, {} to {}", first, last))
+        .multipart(
+            MultiPart::mixed()
+                .singlepart(SinglePart::plain(body))
+                .singlepart(attachment),
+        )
+        .map_err(|e| e.to_string())
+}
+
+// Blocking; runs on the scheduler thread or a blocking task. The attached timesheet is
+// a PDF export, so it's subject to org policy like any other.
+fn send(app: &AppHandle, range: DateRange, recipient: &str) -> Result<(), String> {
+    app.state::<OrgPolicies>()
+        .check_export(org_policy::EXPORT_PDF)?;
+    let email = app.state::<SettingsStore>().get()?.email;
+    let host = email.smtp_host.ok_or("SMTP is not configured")?;
+    let mut transport = SmtpTransport::starttls_relay(&host)
+        .map_err(|e| e.to_string())?
+        .port(email.smtp_port);
+    if let Some(username) = email.username {
+        let password = secrets::get(PASSWORD_KEY)?.unwrap_or_default();
+        transport = transport.credentials(Credentials::new(username, password));
+    }
+    let message = build_message(app, range, recipient)?;
+    transport
+        .build()
+        .send(&message)
+        .map_err(|e| e.to_string())?;
+    log::info!("sent time summary to {}", recipient);
+    Ok(())
+}
+
+// Sends this week's summary once the configured weekday and time have passed.
+fn run_schedule(app: &AppHandle) -> Result<(), String> {
+    let settings = app.state::<SettingsStore>();
+    let Some(schedule) = settings.get()?.email.schedule.filter(|s| s.enabled) else {
+        return Ok(());
+    };
+    let now = Local::now();
+    let due = now.weekday() == schedule.weekday
+        && now.time() >= schedule.time
+        && schedule
+            .last_sent
+            .map_or(true, |last| Utc::now() - last > ChronoDuration::days(6));
+    if !due {
+        return Ok(());
+    }
+
+    let zone = ReportZone::from_settings(&settings.get()?);
+    let range = DateRange {
+        end: Utc::now(),
+        ..pdf::week_range(zone.date_of(Utc::now()), zone)
+    };
+    send(app, range, &schedule.recipient)?;
+    settings.update(|s| {
+        if let Some(schedule) = s.email.schedule.as_mut() {
+            schedule.last_sent = Some(Utc::now());
+        }
+    })?;
+    Ok(())
+}
+
+pub fn start_scheduler(app: AppHandle) {
+    background::spawn_thread(&app, "email_reports", |app, task| {
+        // A failing send is retried every poll but reported once a day
+        let mut reported_on = None;
+        while task.sleep_blocking(SCHEDULE_POLL_INTERVAL) {
+            let Err(e) = run_schedule(&app) else {
+                continue;
+            };
+            log::warn!("scheduled report email failed: {}", e);
+            let today = Local::now().date_naive();
+            if reported_on != Some(today) {
+                reported_on = Some(today);
+                notifications::notify(
+                    &app,
+                    NotificationKind::System,
+                    NotificationImportance::High,
+                    "Weekly summary not sent",
+                    &e,
+                );
+            }
+        }
+    });
+}
+
+// `password`: None keeps the stored one, an empty string removes it.
+#[tauri::command]
+pub fn set_email_settings(
+    settings: State<'_, SettingsStore>,
+    email: EmailSettings,
+    password: Option<String>,
+) -> Result<(), String> {
+    if let Some(schedule) = email.schedule.as_ref().filter(|s| s.enabled) {
+        mailbox(&schedule.recipient)?;
+    }
+    match password.as_deref() {
+        Some("") => secrets::delete(PASSWORD_KEY)?,
+        Some(password) => secrets::set(PASSWORD_KEY, password)?,
+        None => {}
+    }
+    settings.update(|s| {
+        let last_sent = s.email.schedule.as_ref().and_then(|s| s.last_sent);
+        s.email = email;
+        if let Some(schedule) = s.email.schedule.as_mut() {
+            schedule.last_sent = schedule.last_sent.or(last_sent);
+        }
+    })?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn send_report_now(
+    app: AppHandle,
+    range: DateRange,
+    recipient: String,
+) -> Result<(), String> {
+    let handle = app.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || send(&handle, range, &recipient))
+        .await
+        .map_err(|e| e.to_string())?;
+    if let Err(e) = &result {
+        notifications::notify(
+            &app,
+            NotificationKind::System,
+            NotificationImportance::Normal,
+            "Report email failed",
+            e,
+        );
+    }
+    result
+}
@@ -0,0 +1,194 @@
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use std::fs;
+use std::path::Path;
+use std::sync::RwLock;
+use tauri::AppHandle;
+
+use crate::secrets;
+use crate::storage;
+
+// Per-profile data key, hex-encoded in the OS keyring. Encryption is on exactly when
+// the key exists.
+pub(crate) const KEY_NAME: &str = "data_encryption_key";
+// Prefix of an encrypted store; followed by the 12-byte nonce and the ciphertext
+const MAGIC: &[u8] = b"FTTENC1\0";
+const NONCE_LEN: usize = 12;
+
+static CIPHER: RwLock<Option<Aes256Gcm>> = RwLock::new(None);
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn from_hex(text: &str) -> Result<Vec<u8>, String> {
+    if text.len() % 2 != 0 {
+        return Err("invalid data key".to_string());
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&text[i..i + 2], 16).map_err(|_| "invalid data key".to_string())
+        })
+        .collect()
+}
+
+fn cipher_from(hex: &str) -> Result<Aes256Gcm, String> {
+    let bytes = from_hex(hex)?;
+    if bytes.len() != 32 {
+        return Err("invalid data key".to_string());
+    }
+    Ok(Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&bytes)))
+}
+
+fn seal(cipher: &Aes256Gcm, plain: &[u8]) -> Result<Vec<u8>, String> {
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, plain)
+        .map_err(|_| "encryption failed".to_string())?;
+    let mut out = Vec::with_capacity(MAGIC.len() + NONCE_LEN + ciphertext.len());
+    out.extend_from_slice(MAGIC);
+    out.extend_from_slice(&nonce);
+    out.extend_from_slice(&ciphertext);
+    Ok(out)
+}
+
+// Plaintext files pass through unchanged, which is also how files written before
+// encryption was enabled keep loading.
+fn open_bytes(cipher: Option<&Aes256Gcm>, path: &Path, raw: Vec<u8>) -> Result<Vec<u8>, String> {
+    let Some(sealed) = raw.strip_prefix(MAGIC) else {
+        return Ok(raw);
+    };
+    let cipher =
+        cipher.ok_or_else(|| format!("{} is encrypted but no data key is set", path.display()))?;
+    if sealed.len() < NONCE_LEN {
+        return Err(format!("{}: truncated", path.display()));
+    }
+    let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+    cipher
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| format!("{}: decryption failed", path.display()))
+}
+
+fn open(cipher: Option<&Aes256Gcm>, path: &Path, raw: Vec<u8>) -> Result<String, String> {
+    String::from_utf8(open_bytes(cipher, path, raw)?)
+        .map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+fn is_store(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|n| storage::DATA_FILES.contains(&n))
+}
+
+// Loads the active profile's key; call after the profile is selected.
+pub fn init() -> Result<(), String> {
+    let cipher = secrets::get(KEY_NAME)?
+        .map(|hex| cipher_from(&hex))
+        .transpose()?;
+    *CIPHER.write().map_err(|e| e.to_string())? = cipher;
+    Ok(())
+}
+
+pub fn is_enabled() -> bool {
+    CIPHER.read().is_ok_and(|c| c.is_some())
+}
+
+// Reads and writes hold the read lock across the file access, so set_encryption can't
+// rewrite a store between a save encoding it and the save landing on disk.
+pub(crate) fn read(path: &Path) -> Result<String, String> {
+    let cipher = CIPHER.read().map_err(|e| e.to_string())?;
+    let raw = fs::read(path).map_err(|e| e.to_string())?;
+    open(cipher.as_ref(), path, raw)
+}
+
+// Only the data stores are encrypted; shared files such as profiles.json stay plain.
+pub(crate) fn write(path: &Path, plain: String) -> Result<(), String> {
+    let cipher = CIPHER.read().map_err(|e| e.to_string())?;
+    let contents = match cipher.as_ref() {
+        Some(cipher) if is_store(path) => seal(cipher, plain.as_bytes())?,
+        _ => plain.into_bytes(),
+    };
+    storage::replace(path, &contents)
+}
+
+// Opens file contents read some other way, e.g. from a zip of the raw stores
+pub(crate) fn decode_bytes(path: &Path, raw: Vec<u8>) -> Result<Vec<u8>, String> {
+    let cipher = CIPHER.read().map_err(|e| e.to_string())?;
+    open_bytes(cipher.as_ref(), path, raw)
+}
+
+// Binary files such as screenshots; sealed whenever encryption is on
+pub(crate) fn read_bytes(path: &Path) -> Result<Vec<u8>, String> {
+    let cipher = CIPHER.read().map_err(|e| e.to_string())?;
+    let raw = fs::read(path).map_err(|e| e.to_string())?;
+    open_bytes(cipher.as_ref(), path, raw)
+}
+
+pub(crate) fn write_bytes(path: &Path, plain: Vec<u8>) -> Result<(), String> {
+    let cipher = CIPHER.read().map_err(|e| e.to_string())?;
+    let contents = match cipher.as_ref() {
+        Some(cipher) => seal(cipher, &plain)?,
+        None => plain,
+    };
+    storage::replace(path, &contents)
+}
+
+// Rewrites every store and sealed file in the profile with `next`, holding the write
+// lock so nothing saves in between.
+fn rewrite(
+    app: &AppHandle,
+    current: Option<&Aes256Gcm>,
+    next: Option<&Aes256Gcm>,
+) -> Result<(), String> {
+    let mut paths = Vec::new();
+    for name in storage::DATA_FILES {
+        paths.push(storage::data_file(app, name)?);
+    }
+    for name in storage::SEALED_DIRS {
+        let dir = storage::data_file(app, name)?;
+        if let Ok(entries) = fs::read_dir(&dir) {
+            paths.extend(entries.filter_map(Result::ok).map(|e| e.path()));
+        }
+    }
+    for path in paths {
+        if !path.is_file() {
+            continue;
+        }
+        let raw = fs::read(&path).map_err(|e| e.to_string())?;
+        let plain = open_bytes(current, &path, raw)?;
+        let contents = match next {
+            Some(cipher) => seal(cipher, &plain)?,
+            None => plain,
+        };
+        storage::replace(&path, &contents)?;
+    }
+    Ok(())
+}
+
+// Encrypts the existing plaintext stores under a new key, or decrypts them and deletes
+// the key.
+#[tauri::command]
+pub fn set_encryption(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let mut cipher = CIPHER.write().map_err(|e| e.to_string())?;
+    if enabled == cipher.is_some() {
+        return Ok(());
+    }
+    if enabled {
+        let hex = to_hex(&Aes256Gcm::generate_key(&mut OsRng));
+        let next = cipher_from(&hex)?;
+        // Stored first so a partly rewritten store stays readable
+        secrets::set(KEY_NAME, &hex)?;
+        let result = rewrite(&app, None, Some(&next));
+        *cipher = Some(next);
+        result?;
+        log::info!("data stores encrypted");
+    } else {
+        // On failure the key stays, since plaintext and encrypted stores both load
+        rewrite(&app, cipher.as_ref(), None)?;
+        *cipher = None;
+        secrets::delete(KEY_NAME)?;
+        log::info!("data stores decrypted");
+    }
+    Ok(())
+}
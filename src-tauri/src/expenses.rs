@@ -0,0 +1,243 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, State};
+
+use crate::billing::{BillingConfig, BillingStore};
+use crate::sessions::{DateRange, SessionStore};
+use crate::settings::SettingsStore;
+use crate::storage;
+use crate::time::ReportZone;
+
+const RECEIPTS_DIR: &str = "receipts";
+const RECEIPT_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png", "webp", "heic", "pdf"];
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Expense {
+    pub id: u64,
+    // Attached to a session, or to a day and client
+    pub session_id: Option<u64>,
+    pub client_id: Option<u64>,
+    pub date: NaiveDate,
+    pub description: String,
+    pub amount: f64,
+    pub currency: String,
+    // File name inside the receipts directory
+    pub receipt: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize)]
+pub struct NewExpense {
+    pub session_id: Option<u64>,
+    pub client_id: Option<u64>,
+    pub date: Option<NaiveDate>,
+    pub description: String,
+    pub amount: f64,
+    // The billing base currency when unset
+    pub currency: Option<String>,
+    // Copied into app data, so the original can be moved or deleted
+    pub receipt_path: Option<PathBuf>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct ExpenseData {
+    next_id: u64,
+    expenses: Vec<Expense>,
+}
+
+pub struct ExpenseStore {
+    path: PathBuf,
+    receipts: PathBuf,
+    data: Mutex<ExpenseData>,
+}
+
+impl ExpenseStore {
+    pub fn load(app: &AppHandle) -> Result<Self, String> {
+        let path = storage::data_file(app, "expenses.json")?;
+        let receipts = storage::data_file(app, RECEIPTS_DIR)?;
+        let data = storage::load_json(&path)?;
+        Ok(Self {
+            path,
+            receipts,
+            data: Mutex::new(data),
+        })
+    }
+
+    fn write<T>(&self, f: impl FnOnce(&mut ExpenseData) -> Result<T, String>) -> Result<T, String> {
+        let mut data = self.data.lock().map_err(|e| e.to_string())?;
+        let result = f(&mut data)?;
+        storage::save_json(&self.path, &*data)?;
+        Ok(result)
+    }
+
+    pub fn all(&self) -> Result<Vec<Expense>, String> {
+        Ok(self
+            .data
+            .lock()
+            .map_err(|e| e.to_string())?
+            .expenses
+            .clone())
+    }
+
+    pub fn receipt_path(&self, expense: &Expense) -> Option<PathBuf> {
+        expense
+            .receipt
+            .as_ref()
+            .map(|name| self.receipts.join(name))
+    }
+
+    fn copy_receipt(&self, id: u64, source: &Path) -> Result<String, String> {
+        let extension = source
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(str::to_lowercase)
+            .filter(|e| RECEIPT_EXTENSIONS.contains(&e.as_str()))
+            .ok_or_else(|| "Receipts must be an image or a PDF".to_string())?;
+        fs::create_dir_all(&self.receipts).map_err(|e| e.to_string())?;
+        let name = format!("{}.{}", id, extension);
+        fs::copy(source, self.receipts.join(&name)).map_err(|e| e.to_string())?;
+        Ok(name)
+    }
+}
+
+// The client an expense is billed to: its own, or the one its session's task is billed to
+pub fn client_of(
+    config: &BillingConfig,
+    sessions: &SessionStore,
+    expense: &Expense,
+) -> Option<u64> {
+    if expense.client_id.is_some() {
+        return expense.client_id;
+    }
+    let session = sessions.get(expense.session_id?).ok()?;
+    config
+        .rates
+        .iter()
+        .find(|r| r.task_id == session.task_id)
+        .map(|r| r.client_id)
+}
+
+// Expenses billed to the client and dated within `from..=to`
+pub fn for_client(
+    config: &BillingConfig,
+    sessions: &SessionStore,
+    expenses: &ExpenseStore,
+    client_id: u64,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<Vec<Expense>, String> {
+    Ok(expenses
+        .all()?
+        .into_iter()
+        .filter(|e| from <= e.date && e.date <= to)
+        .filter(|e| client_of(config, sessions, e) == Some(client_id))
+        .collect())
+}
+
+#[tauri::command]
+pub fn add_expense(
+    app: AppHandle,
+    store: State<'_, ExpenseStore>,
+    sessions: State<'_, SessionStore>,
+    billing: State<'_, BillingStore>,
+    expense: NewExpense,
+) -> Result<Expense, String> {
+    if expense.amount <= 0.0 {
+        return Err("Expense amount must be positive".to_string());
+    }
+    if expense.session_id.is_none() && expense.client_id.is_none() {
+        return Err("Attach the expense to a session or a client".to_string());
+    }
+    let config = billing.get()?;
+    if let Some(client_id) = expense.client_id {
+        if !config.clients.iter().any(|c| c.id == client_id) {
+            return Err(format!("Client {} not found", client_id));
+        }
+    }
+    let currency = expense
+        .currency
+        .map(|c| c.trim().to_uppercase())
+        .unwrap_or_else(|| config.base_currency.clone());
+    // Check now rather than failing at invoice time
+    config.convert(expense.amount, &currency, &config.base_currency)?;
+    let date = match (expense.date, expense.session_id) {
+        (Some(date), _) => date,
+        (None, Some(id)) => {
+            let zone = ReportZone::from_settings(&app.state::<SettingsStore>().get()?);
+            zone.date_of(sessions.get(id)?.start)
+        }
+        (None, None) => return Err("Expenses not on a session need a date".to_string()),
+    };
+    store.write(|data| {
+        data.next_id += 1;
+        let id = data.next_id;
+        let receipt = match &expense.receipt_path {
+            Some(source) => Some(store.copy_receipt(id, source)?),
+            None => None,
+        };
+        let added = Expense {
+            id,
+            session_id: expense.session_id,
+            client_id: expense.client_id,
+            date,
+            description: expense.description.trim().to_string(),
+            amount: expense.amount,
+            currency,
+            receipt,
+            created_at: Utc::now(),
+        };
+        data.expenses.push(added.clone());
+        Ok(added)
+    })
+}
+
+// Removes the expense and its copy of the receipt.
+#[tauri::command]
+pub fn delete_expense(store: State<'_, ExpenseStore>, id: u64) -> Result<(), String> {
+    let removed = store.write(|data| {
+        let index = data
+            .expenses
+            .iter()
+            .position(|e| e.id == id)
+            .ok_or_else(|| format!("Expense {} not found", id))?;
+        Ok(data.expenses.remove(index))
+    })?;
+    if let Some(path) = store.receipt_path(&removed) {
+        let _ = fs::remove_file(path);
+    }
+    Ok(())
+}
+
+// Expenses dated within the range's days, optionally for one session
+#[tauri::command]
+pub fn list_expenses(
+    store: State<'_, ExpenseStore>,
+    settings: State<'_, SettingsStore>,
+    range: DateRange,
+    session_id: Option<u64>,
+) -> Result<Vec<Expense>, String> {
+    let zone = ReportZone::from_settings(&settings.get()?);
+    let from = zone.date_of(range.start);
+    let to = zone.date_of(range.end - chrono::Duration::seconds(1));
+    Ok(store
+        .all()?
+        .into_iter()
+        .filter(|e| from <= e.date && e.date <= to)
+        .filter(|e| session_id.map_or(true, |id| e.session_id == Some(id)))
+        .collect())
+}
+
+#[tauri::command]
+pub fn get_receipt_path(store: State<'_, ExpenseStore>, id: u64) -> Result<PathBuf, String> {
+    let expense = store
+        .all()?
+        .into_iter()
+        .find(|e| e.id == id)
+        .ok_or_else(|| format!("Expense {} not found", id))?;
+    store
+        .receipt_path(&expense)
+        .ok_or_else(|| "This expense has no receipt".to_string())
+}
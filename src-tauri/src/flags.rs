@@ -0,0 +1,167 @@
+use futures_util::FutureExt;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::background;
+use crate::settings::{AppSettings, SettingsStore};
+
+pub const IDLE_DETECTION: &str = "idle_detection";
+pub const INTEGRATIONS: &str = "integrations";
+pub const SCREENSHOTS: &str = "screenshots";
+
+// Every flag the app knows about, with its built-in default
+const KNOWN: &[(&str, bool)] = &[
+    (IDLE_DETECTION, true),
+    (INTEGRATIONS, true),
+    (SCREENSHOTS, false),
+];
+
+const REFRESH_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FeatureFlagSettings {
+    // Local choices; these win over remote values
+    pub overrides: BTreeMap<String, bool>,
+    // Endpoint returning a JSON object of flag name -> bool
+    pub remote_url: Option<String>,
+}
+
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FlagSource {
+    Default,
+    Remote,
+    Override,
+    // Forced by the organization policy
+    Policy,
+}
+
+#[derive(Clone, Serialize)]
+pub struct FeatureFlag {
+    pub name: String,
+    pub enabled: bool,
+    pub source: FlagSource,
+}
+
+pub struct FeatureFlags {
+    overrides: Mutex<BTreeMap<String, bool>>,
+    remote: Mutex<BTreeMap<String, bool>>,
+    policy: Mutex<BTreeMap<String, bool>>,
+}
+
+impl FeatureFlags {
+    pub fn load(settings: &AppSettings) -> Self {
+        Self {
+            overrides: Mutex::new(settings.feature_flags.overrides.clone()),
+            remote: Mutex::default(),
+            policy: Mutex::default(),
+        }
+    }
+
+    pub fn set_policy(&self, forced: BTreeMap<String, bool>) -> Result<(), String> {
+        *self.policy.lock().map_err(|e| e.to_string())? = forced;
+        Ok(())
+    }
+
+    fn resolve(&self, name: &str, default: bool) -> (bool, FlagSource) {
+        let forced = self.policy.lock().ok().and_then(|p| p.get(name).copied());
+        if let Some(enabled) = forced {
+            return (enabled, FlagSource::Policy);
+        }
+        let overridden = self
+            .overrides
+            .lock()
+            .ok()
+            .and_then(|o| o.get(name).copied());
+        if let Some(enabled) = overridden {
+            return (enabled, FlagSource::Override);
+        }
+        match self.remote.lock().ok().and_then(|r| r.get(name).copied()) {
+            Some(enabled) => (enabled, FlagSource::Remote),
+            None => (default, FlagSource::Default),
+        }
+    }
+
+    pub fn is_enabled(&self, name: &str) -> bool {
+        KNOWN
+            .iter()
+            .find(|(known, _)| *known == name)
+            .is_some_and(|(_, default)| self.resolve(name, *default).0)
+    }
+
+    pub fn all(&self) -> Vec<FeatureFlag> {
+        KNOWN
+            .iter()
+            .map(|(name, default)| {
+                let (enabled, source) = self.resolve(name, *default);
+                FeatureFlag {
+                    name: name.to_string(),
+                    enabled,
+                    source,
+                }
+            })
+            .collect()
+    }
+}
+
+async fn refresh(app: &AppHandle) -> Result<(), String> {
+    let Some(url) = app.state::<SettingsStore>().get()?.feature_flags.remote_url else {
+        return Ok(());
+    };
+    let remote: BTreeMap<String, bool> = reqwest::get(&url)
+        .await
+        .map_err(|e| e.to_string())?
+        .error_for_status()
+        .map_err(|e| e.to_string())?
+        .json()
+        .await
+        .map_err(|e| e.to_string())?;
+    let flags = app.state::<FeatureFlags>();
+    *flags.remote.lock().map_err(|e| e.to_string())? = remote;
+    let _ = app.emit("feature-flags-changed", flags.all());
+    Ok(())
+}
+
+pub fn start_refresh(app: AppHandle) {
+    background::spawn_async(&app, "feature_flags", |app, task| {
+        async move {
+            loop {
+                if let Err(e) = refresh(&app).await {
+                    log::warn!("feature flag refresh failed: {}", e);
+                }
+                if !task.sleep(REFRESH_INTERVAL).await {
+                    break;
+                }
+            }
+        }
+        .boxed()
+    });
+}
+
+#[tauri::command]
+pub fn get_feature_flags(flags: State<'_, FeatureFlags>) -> Vec<FeatureFlag> {
+    flags.all()
+}
+
+#[tauri::command]
+pub fn set_feature_flag(
+    app: AppHandle,
+    flags: State<'_, FeatureFlags>,
+    settings: State<'_, SettingsStore>,
+    name: String,
+    enabled: bool,
+) -> Result<(), String> {
+    if !KNOWN.iter().any(|(known, _)| *known == name) {
+        return Err(format!("Unknown feature flag: {}", name));
+    }
+    let updated = settings.update(|s| {
+        s.feature_flags.overrides.insert(name.clone(), enabled);
+    })?;
+    *flags.overrides.lock().map_err(|e| e.to_string())? = updated.feature_flags.overrides;
+    let _ = app.emit("feature-flags-changed", flags.all());
+    Ok(())
+}
@@ -0,0 +1,138 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::active_window;
+use crate::background;
+use crate::notifications::{self, NotificationImportance, NotificationKind};
+use crate::privacy;
+use crate::settings::SettingsStore;
+use crate::timer::TimerManager;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FocusSettings {
+    pub enabled: bool,
+    // App names or site names, matched case-insensitively against the focused app and
+    // window title (browsers put the page title there)
+    pub blocklist: Vec<String>,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct FocusBreach {
+    pub at: DateTime<Utc>,
+    pub app: String,
+    pub title: Option<String>,
+    // Blocklist entry that matched
+    pub matched: String,
+}
+
+#[derive(Clone, Serialize)]
+pub struct FocusBreachPayload {
+    pub timer_id: String,
+    pub breach: FocusBreach,
+    // Breaches during this timer so far
+    pub count: usize,
+}
+
+fn find_match(blocklist: &[String], window: &active_window::ActiveWindow) -> Option<String> {
+    let app = window.app.to_lowercase();
+    let title = window.title.as_deref().unwrap_or_default().to_lowercase();
+    blocklist
+        .iter()
+        .find(|entry| {
+            let entry = entry.trim().to_lowercase();
+            !entry.is_empty() && (app.contains(&entry) || title.contains(&entry))
+        })
+        .cloned()
+}
+
+fn warn(app: &AppHandle, breach: &FocusBreach, count: usize) {
+    let (importance, title) = match count {
+        1 => (NotificationImportance::Low, "Stay focused"),
+        2 => (NotificationImportance::Normal, "Focus mode is on"),
+        _ => (NotificationImportance::High, "Still distracted?"),
+    };
+    let body = format!(
+        "{} is on your focus blocklist ({} time{} this session).",
+        breach.app,
+        count,
+        if count == 1 { "" } else { "s" }
+    );
+    notifications::notify(app, NotificationKind::Focus, importance, title, &body);
+}
+
+// Checks the focused window while a timer runs with focus mode on. A breach is counted
+// when a blocked app gains focus, not for every poll it stays focused.
+pub fn start_focus_watcher(app: AppHandle) {
+    background::spawn_thread(&app, "focus_watcher", |app, task| {
+        let mut current_match: Option<String> = None;
+        while task.sleep_blocking(POLL_INTERVAL) {
+            let Ok(settings) = app.state::<SettingsStore>().get() else {
+                continue;
+            };
+            let timer = app.state::<TimerManager>();
+            let Some(active) = timer.active().filter(|_| settings.focus.enabled) else {
+                current_match = None;
+                continue;
+            };
+            let window = match active_window::current() {
+                Ok(window) => window,
+                Err(e) => {
+                    log::debug!("focus watcher: {}", e);
+                    continue;
+                }
+            };
+            let matched = find_match(&settings.focus.blocklist, &window);
+            let key = matched.as_ref().map(|m| format!("{}|{}", window.app, m));
+            if key.is_none() || key == current_match {
+                current_match = key;
+                continue;
+            }
+            current_match = key;
+
+            // Breaches are saved with the session, so the title is scrubbed first
+            let window = privacy::scrub(&app, window);
+            let breach = FocusBreach {
+                at: Utc::now(),
+                app: window.app,
+                title: window.title,
+                matched: matched.unwrap_or_default(),
+            };
+            let count = match timer.record_focus_breach(&active.id, breach.clone()) {
+                Ok(count) => count,
+                Err(e) => {
+                    log::warn!("failed to record focus breach: {}", e);
+                    continue;
+                }
+            };
+            warn(&app, &breach, count);
+            let _ = app.emit(
+                "focus-breach",
+                FocusBreachPayload {
+                    timer_id: active.id,
+                    breach,
+                    count,
+                },
+            );
+        }
+    });
+}
+
+#[tauri::command]
+pub fn set_focus_blocklist(
+    settings: State<'_, SettingsStore>,
+    list: Vec<String>,
+) -> Result<(), String> {
+    settings.update(|s| s.focus.blocklist = list)?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_focus_mode(settings: State<'_, SettingsStore>, enabled: bool) -> Result<(), String> {
+    settings.update(|s| s.focus.enabled = enabled)?;
+    Ok(())
+}
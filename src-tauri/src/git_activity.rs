@@ -0,0 +1,311 @@
+use chrono::{DateTime, TimeZone, Utc};
+use git2::{Repository, Sort};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::background;
+use crate::integrations::jira;
+use crate::sessions::SessionStore;
+use crate::settings::SettingsStore;
+use crate::storage;
+use crate::tasks_remote::RemoteTasks;
+use crate::timer::TimerManager;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+// Commits read per poll; anything beyond this is a rebase or a pull, not work
+const MAX_NEW_COMMITS: usize = 50;
+const MAX_EVENTS: usize = 10_000;
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GitSettings {
+    pub repositories: Vec<PathBuf>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum GitEventKind {
+    Commit { id: String, summary: String },
+    BranchSwitch { from: Option<String>, to: String },
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct GitEvent {
+    pub repository: PathBuf,
+    pub at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub kind: GitEventKind,
+}
+
+#[derive(Clone, Serialize)]
+pub struct TaskSuggestion {
+    pub task_id: u64,
+    pub title: String,
+    pub branch: String,
+    pub repository: PathBuf,
+}
+
+// Where HEAD pointed when it last changed, and when that was seen
+#[derive(Clone, Serialize, Deserialize)]
+struct RepoHead {
+    branch: Option<String>,
+    commit: Option<String>,
+    checked_at: DateTime<Utc>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct GitActivityData {
+    events: Vec<GitEvent>,
+    heads: HashMap<PathBuf, RepoHead>,
+}
+
+pub struct GitActivityStore {
+    path: PathBuf,
+    data: Mutex<GitActivityData>,
+}
+
+// HEAD's branch (None when detached) and commit (None in an empty repository)
+fn read_head(repo: &Repository) -> (Option<String>, Option<String>) {
+    match repo.head() {
+        Ok(head) => {
+            let branch = if head.is_branch() {
+                head.shorthand().map(str::to_string)
+            } else {
+                None
+            };
+            (branch, head.target().map(|id| id.to_string()))
+        }
+        Err(_) => (None, None),
+    }
+}
+
+// Commits reachable from HEAD but not from `previous`, made by this user since
+// `since`; oldest first.
+fn new_commits(
+    repo: &Repository,
+    previous: Option<&str>,
+    since: DateTime<Utc>,
+) -> Result<Vec<(String, String, DateTime<Utc>)>, String> {
+    let email = repo.config().and_then(|c| c.get_string("user.email")).ok();
+    let mut walk = repo.revwalk().map_err(|e| e.to_string())?;
+    walk.set_sorting(Sort::TIME).map_err(|e| e.to_string())?;
+    walk.push_head().map_err(|e| e.to_string())?;
+    if let Some(previous) = previous.and_then(|p| git2::Oid::from_str(p).ok()) {
+        // The old head may have been garbage-collected after a rebase
+        let _ = walk.hide(previous);
+    }
+    let mut commits = Vec::new();
+    for id in walk.take(MAX_NEW_COMMITS) {
+        let commit = repo
+            .find_commit(id.map_err(|e| e.to_string())?)
+            .map_err(|e| e.to_string())?;
+        let Some(at) = Utc.timestamp_opt(commit.time().seconds(), 0).single() else {
+            continue;
+        };
+        if at < since {
+            break;
+        }
+        let mine = email
+            .as_deref()
+            .map_or(true, |email| commit.committer().email() == Some(email));
+        if mine {
+            commits.push((
+                commit.id().to_string(),
+                commit.summary().unwrap_or_default().to_string(),
+                at,
+            ));
+        }
+    }
+    commits.reverse();
+    Ok(commits)
+}
+
+impl GitActivityStore {
+    pub fn load(app: &AppHandle) -> Result<Self, String> {
+        let path = storage::data_file(app, "git_activity.json")?;
+        let data = storage::load_json(&path)?;
+        Ok(Self {
+            path,
+            data: Mutex::new(data),
+        })
+    }
+
+    // Records what changed in `path` since the last poll. Returns the branch switched
+    // to, if any.
+    fn poll(&self, path: &Path) -> Result<Option<String>, String> {
+        let repo = Repository::open(path).map_err(|e| e.to_string())?;
+        let (branch, commit) = read_head(&repo);
+        let now = Utc::now();
+        let mut data = self.data.lock().map_err(|e| e.to_string())?;
+        let Some(previous) = data.heads.get(path).cloned() else {
+            // First sight of this repository: nothing to compare against yet
+            data.heads.insert(
+                path.to_path_buf(),
+                RepoHead {
+                    branch,
+                    commit,
+                    checked_at: now,
+                },
+            );
+            storage::save_json(&self.path, &*data)?;
+            return Ok(None);
+        };
+        if previous.branch == branch && previous.commit == commit {
+            return Ok(None);
+        }
+
+        let mut switched = None;
+        if previous.branch != branch {
+            if let Some(to) = &branch {
+                data.events.push(GitEvent {
+                    repository: path.to_path_buf(),
+                    at: now,
+                    kind: GitEventKind::BranchSwitch {
+                        from: previous.branch.clone(),
+                        to: to.clone(),
+                    },
+                });
+                switched = Some(to.clone());
+            }
+        }
+        if commit.is_some() && previous.commit != commit {
+            for (id, summary, at) in
+                new_commits(&repo, previous.commit.as_deref(), previous.checked_at)?
+            {
+                data.events.push(GitEvent {
+                    repository: path.to_path_buf(),
+                    at,
+                    kind: GitEventKind::Commit { id, summary },
+                });
+            }
+        }
+        let overflow = data.events.len().saturating_sub(MAX_EVENTS);
+        data.events.drain(..overflow);
+        data.heads.insert(
+            path.to_path_buf(),
+            RepoHead {
+                branch,
+                commit,
+                checked_at: now,
+            },
+        );
+        storage::save_json(&self.path, &*data)?;
+        Ok(switched)
+    }
+
+    fn between(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Result<Vec<GitEvent>, String> {
+        let data = self.data.lock().map_err(|e| e.to_string())?;
+        Ok(data
+            .events
+            .iter()
+            .filter(|e| e.at >= start && e.at < end)
+            .cloned()
+            .collect())
+    }
+
+    fn current_branches(&self) -> Result<Vec<(PathBuf, String)>, String> {
+        let data = self.data.lock().map_err(|e| e.to_string())?;
+        let mut heads: Vec<(&PathBuf, &RepoHead)> = data.heads.iter().collect();
+        // Most recently changed first
+        heads.sort_by_key(|(_, head)| std::cmp::Reverse(head.checked_at));
+        Ok(heads
+            .into_iter()
+            .filter_map(|(path, head)| Some((path.clone(), head.branch.clone()?)))
+            .collect())
+    }
+}
+
+// Matches a branch like "feature/1234-login" or "FTT-42-fix" to an assigned task, by
+// task id or by an issue key that appears in the task's title.
+fn match_task(app: &AppHandle, repository: &Path, branch: &str) -> Option<TaskSuggestion> {
+    let tasks = app.state::<RemoteTasks>().get().ok()?.tasks;
+    let issue_key = jira::parse_issue_key(branch);
+    let ids: Vec<u64> = branch
+        .split(|c: char| !c.is_ascii_digit())
+        .filter_map(|n| n.parse().ok())
+        .collect();
+    let task = tasks
+        .iter()
+        .find(|t| {
+            issue_key
+                .as_ref()
+                .is_some_and(|k| t.title.contains(k.as_str()))
+        })
+        .or_else(|| tasks.iter().find(|t| ids.contains(&t.id)))?;
+    Some(TaskSuggestion {
+        task_id: task.id,
+        title: task.title.clone(),
+        branch: branch.to_string(),
+        repository: repository.to_path_buf(),
+    })
+}
+
+fn poll_all(app: &AppHandle) -> Result<(), String> {
+    let repositories = app.state::<SettingsStore>().get()?.git.repositories;
+    let store = app.state::<GitActivityStore>();
+    for path in repositories {
+        match store.poll(&path) {
+            Ok(Some(branch)) => {
+                let Some(suggestion) = match_task(app, &path, &branch) else {
+                    continue;
+                };
+                let running = app.state::<TimerManager>().active().map(|t| t.task_id);
+                if running != Some(suggestion.task_id) {
+                    let _ = app.emit("task-suggested", &suggestion);
+                }
+            }
+            Ok(None) => {}
+            Err(e) => log::debug!("failed to read {}: {}", path.display(), e),
+        }
+    }
+    Ok(())
+}
+
+pub fn start_watcher(app: AppHandle) {
+    background::spawn_thread(&app, "git_activity", |app, task| {
+        while task.sleep_blocking(POLL_INTERVAL) {
+            if let Err(e) = poll_all(&app) {
+                log::warn!("git activity poll failed: {}", e);
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub fn set_git_repositories(
+    settings: State<'_, SettingsStore>,
+    repositories: Vec<PathBuf>,
+) -> Result<(), String> {
+    for path in &repositories {
+        Repository::open(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    }
+    settings.update(|s| s.git.repositories = repositories)?;
+    Ok(())
+}
+
+// Commits and branch switches made while the entry was being tracked
+#[tauri::command]
+pub fn get_session_git_activity(
+    store: State<'_, GitActivityStore>,
+    sessions: State<'_, SessionStore>,
+    session_id: u64,
+) -> Result<Vec<GitEvent>, String> {
+    let session = sessions.get(session_id)?;
+    store.between(session.start, session.end)
+}
+
+// A task for the branch currently checked out in the most recently active repository
+#[tauri::command]
+pub fn suggest_task_from_branch(
+    app: AppHandle,
+    store: State<'_, GitActivityStore>,
+) -> Result<Option<TaskSuggestion>, String> {
+    Ok(store
+        .current_branches()?
+        .into_iter()
+        .find_map(|(path, branch)| match_task(&app, &path, &branch)))
+}
@@ -0,0 +1,224 @@
+use chrono::{Datelike, Duration as ChronoDuration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::background;
+use crate::holidays;
+use crate::notifications::{self, NotificationImportance, NotificationKind};
+use crate::sessions::{DateRange, SessionStore};
+use crate::settings::SettingsStore;
+use crate::storage;
+use crate::time::ReportZone;
+use crate::timer::TimerManager;
+
+const EVALUATE_INTERVAL: Duration = Duration::from_secs(30 * 60);
+// Goals are set for a full working week and paced over its workdays
+const WORK_DAYS: f64 = 5.0;
+// Don't nag about small shortfalls
+const BEHIND_THRESHOLD_SECS: i64 = 60 * 60;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Goal {
+    pub task_id: u64,
+    pub title: Option<String>,
+    pub weekly_hours: f64,
+}
+
+#[derive(Clone, Serialize)]
+pub struct GoalProgress {
+    pub goal: Goal,
+    pub week: DateRange,
+    pub target_seconds: i64,
+    pub tracked_seconds: i64,
+    // Where the user should be by now if the target is spread evenly over the work week
+    pub expected_seconds: i64,
+    // Negative when ahead of pace
+    pub behind_seconds: i64,
+    pub complete: bool,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct GoalData {
+    goals: Vec<Goal>,
+}
+
+pub struct GoalStore {
+    path: PathBuf,
+    data: Mutex<GoalData>,
+    // Task id -> day a "behind" reminder was last sent, and week a goal was last completed
+    reminded: Mutex<HashMap<u64, NaiveDate>>,
+    completed: Mutex<HashMap<u64, NaiveDate>>,
+}
+
+impl GoalStore {
+    pub fn load(app: &AppHandle) -> Result<Self, String> {
+        let path = storage::data_file(app, "goals.json")?;
+        let data = storage::load_json(&path)?;
+        Ok(Self {
+            path,
+            data: Mutex::new(data),
+            reminded: Mutex::default(),
+            completed: Mutex::default(),
+        })
+    }
+
+    pub fn goals(&self) -> Result<Vec<Goal>, String> {
+        Ok(self.data.lock().map_err(|e| e.to_string())?.goals.clone())
+    }
+
+    pub fn set(&self, goals: Vec<Goal>) -> Result<(), String> {
+        let mut data = self.data.lock().map_err(|e| e.to_string())?;
+        data.goals = goals;
+        storage::save_json(&self.path, &*data)
+    }
+}
+
+fn current_week(zone: ReportZone) -> DateRange {
+    let today = zone.date_of(Utc::now());
+    let monday = today - ChronoDuration::days(today.weekday().num_days_from_monday() as i64);
+    DateRange {
+        start: zone.start_of_day(monday),
+        end: zone.start_of_day(monday + ChronoDuration::days(7)),
+    }
+}
+
+fn report_zone(app: &AppHandle) -> Result<ReportZone, String> {
+    Ok(ReportZone::from_settings(
+        &app.state::<SettingsStore>().get()?,
+    ))
+}
+
+// Workdays in the week, and how many of them have passed so far (fractional for today).
+// Holidays and leave don't count, so targets shrink around them.
+fn workdays(app: &AppHandle, zone: ReportZone, week: DateRange) -> Result<(f64, f64), String> {
+    let settings = app.state::<SettingsStore>().get()?.holidays;
+    let now = Utc::now();
+    let (mut total, mut elapsed) = (0.0, 0.0);
+    let monday = zone.date_of(week.start);
+    for date in monday.iter_days().take(7) {
+        if !holidays::is_workday(&settings, date) {
+            continue;
+        }
+        total += 1.0;
+        let into_day = (now - zone.start_of_day(date)).num_seconds() as f64 / 86_400.0;
+        elapsed += into_day.clamp(0.0, 1.0);
+    }
+    Ok((total, elapsed))
+}
+
+pub fn progress(app: &AppHandle) -> Result<Vec<GoalProgress>, String> {
+    let zone = report_zone(app)?;
+    let week = current_week(zone);
+    let sessions = app.state::<SessionStore>().in_range(week)?;
+    let running = app.state::<TimerManager>().list();
+
+    let (work_days, days_elapsed) = workdays(app, zone, week)?;
+    let mut result = Vec::new();
+    for goal in app.state::<GoalStore>().goals()? {
+        let mut tracked: i64 = sessions
+            .iter()
+            .filter(|s| s.task_id == goal.task_id)
+            .map(|s| (s.end.min(week.end) - s.start.max(week.start)).num_seconds())
+            .sum();
+        tracked += running
+            .iter()
+            .filter(|t| t.timer.task_id == goal.task_id)
+            .map(|t| t.elapsed_seconds as i64)
+            .sum::<i64>();
+
+        let target_seconds = (goal.weekly_hours * 3600.0 * work_days / WORK_DAYS) as i64;
+        let expected_seconds = if work_days > 0.0 {
+            (target_seconds as f64 * days_elapsed / work_days) as i64
+        } else {
+            0
+        };
+        result.push(GoalProgress {
+            goal,
+            week,
+            target_seconds,
+            tracked_seconds: tracked,
+            expected_seconds,
+            behind_seconds: expected_seconds - tracked,
+            complete: target_seconds > 0 && tracked >= target_seconds,
+        });
+    }
+    Ok(result)
+}
+
+fn format_hours(seconds: i64) -> String {
+    format!("{:.1} h", seconds as f64 / 3600.0)
+}
+
+fn evaluate(app: &AppHandle) -> Result<(), String> {
+    let store = app.state::<GoalStore>();
+    let zone = report_zone(app)?;
+    let today = zone.date_of(Utc::now());
+    let week_start = zone.date_of(current_week(zone).start);
+
+    for p in progress(app)? {
+        let name = p
+            .goal
+            .title
+            .clone()
+            .unwrap_or_else(|| format!("Task #{}", p.goal.task_id));
+        if p.complete {
+            let mut completed = store.completed.lock().map_err(|e| e.to_string())?;
+            if completed.get(&p.goal.task_id) != Some(&week_start) {
+                completed.insert(p.goal.task_id, week_start);
+                let _ = app.emit("goal-completed", &p);
+                notifications::notify(
+                    app,
+                    NotificationKind::Goal,
+                    NotificationImportance::Normal,
+                    "Weekly goal reached",
+                    &format!(
+                        "You hit your {} goal for {}.",
+                        format_hours(p.target_seconds),
+                        name
+                    ),
+                );
+            }
+        } else if p.behind_seconds >= BEHIND_THRESHOLD_SECS {
+            let mut reminded = store.reminded.lock().map_err(|e| e.to_string())?;
+            if reminded.get(&p.goal.task_id) != Some(&today) {
+                reminded.insert(p.goal.task_id, today);
+                notifications::notify(
+                    app,
+                    NotificationKind::Goal,
+                    NotificationImportance::Low,
+                    "Behind on weekly goal",
+                    &format!(
+                        "You're {} behind your weekly goal for {}.",
+                        format_hours(p.behind_seconds),
+                        name
+                    ),
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn start_evaluator(app: AppHandle) {
+    background::spawn_thread(&app, "goals", |app, task| {
+        while task.sleep_blocking(EVALUATE_INTERVAL) {
+            if let Err(e) = evaluate(&app) {
+                log::warn!("goal evaluation failed: {}", e);
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub fn get_goal_progress(app: AppHandle) -> Result<Vec<GoalProgress>, String> {
+    progress(&app)
+}
+
+#[tauri::command]
+pub fn set_goals(store: State<'_, GoalStore>, goals: Vec<Goal>) -> Result<(), String> {
+    store.set(goals)
+}
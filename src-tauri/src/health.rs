@@ -0,0 +1,181 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::Instant;
+use tauri::plugin::PermissionState;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::idle::IdleMonitor;
+use crate::notifications::{self, NotificationImportance, NotificationKind};
+use crate::sound::SoundManager;
+use crate::storage;
+
+const PROBE_FILE: &str = "health-probe.json";
+
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Subsystem {
+    IdleDetection,
+    Audio,
+    Notifications,
+    Storage,
+}
+
+impl Subsystem {
+    fn label(self) -> &'static str {
+        match self {
+            Subsystem::IdleDetection => "Idle detection",
+            Subsystem::Audio => "Audio output",
+            Subsystem::Notifications => "Notifications",
+            Subsystem::Storage => "Data storage",
+        }
+    }
+
+    // User-facing features that stop working when the subsystem is unavailable
+    fn affects(self) -> &'static [&'static str] {
+        match self {
+            Subsystem::IdleDetection => &["pause on idle", "idle rules", "activity heuristics"],
+            Subsystem::Audio => &["alert sounds", "sound previews"],
+            Subsystem::Notifications => &["reminders", "goal and focus alerts"],
+            Subsystem::Storage => &["saving sessions", "saving settings"],
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ProbeStatus {
+    Ok,
+    Degraded { reason: String },
+}
+
+#[derive(Clone, Serialize)]
+pub struct ProbeResult {
+    pub subsystem: Subsystem,
+    pub status: ProbeStatus,
+    pub affects: Vec<String>,
+    pub duration_ms: u64,
+}
+
+#[derive(Clone, Serialize)]
+pub struct StartupHealth {
+    pub checked_at: DateTime<Utc>,
+    pub degraded: bool,
+    pub probes: Vec<ProbeResult>,
+}
+
+// None until the startup probes have finished
+#[derive(Default)]
+pub struct HealthState(Mutex<Option<StartupHealth>>);
+
+fn probe_idle(app: &AppHandle) -> Result<(), String> {
+    let info = app.state::<IdleMonitor>().info();
+    if info.available {
+        return Ok(());
+    }
+    let tried: Vec<String> = info
+        .rejected
+        .iter()
+        .map(|(name, error)| format!("{}: {}", name, error))
+        .collect();
+    Err(format!("No idle provider works ({})", tried.join("; ")))
+}
+
+fn probe_notifications(app: &AppHandle) -> Result<(), String> {
+    match app
+        .notification()
+        .permission_state()
+        .map_err(|e| e.to_string())?
+    {
+        PermissionState::Granted => Ok(()),
+        PermissionState::Denied => Err("Notification permission was denied".to_string()),
+        _ => Err("Notification permission hasn't been granted yet".to_string()),
+    }
+}
+
+// Writes and removes a small file in the data directory
+fn probe_storage(app: &AppHandle) -> Result<(), String> {
+    let path = storage::data_file(app, PROBE_FILE)?;
+    storage::save_json(&path, &Utc::now())?;
+    std::fs::remove_file(&path).map_err(|e| e.to_string())
+}
+
+fn probe(subsystem: Subsystem, check: impl FnOnce() -> Result<(), String>) -> ProbeResult {
+    let started = Instant::now();
+    let status = match check() {
+        Ok(()) => ProbeStatus::Ok,
+        Err(reason) => {
+            log::warn!("{} unavailable: {}", subsystem.label(), reason);
+            ProbeStatus::Degraded { reason }
+        }
+    };
+    ProbeResult {
+        subsystem,
+        status,
+        affects: subsystem.affects().iter().map(|s| s.to_string()).collect(),
+        duration_ms: started.elapsed().as_millis() as u64,
+    }
+}
+
+fn run_probes(app: &AppHandle) -> StartupHealth {
+    let probes = vec![
+        probe(Subsystem::IdleDetection, || probe_idle(app)),
+        probe(Subsystem::Audio, || {
+            app.state::<SoundManager>().probe_output()
+        }),
+        probe(Subsystem::Notifications, || probe_notifications(app)),
+        probe(Subsystem::Storage, || probe_storage(app)),
+    ];
+    StartupHealth {
+        checked_at: Utc::now(),
+        degraded: probes
+            .iter()
+            .any(|p| matches!(p.status, ProbeStatus::Degraded { .. })),
+        probes,
+    }
+}
+
+// One line per failed subsystem, naming the features that are off
+fn summary(health: &StartupHealth) -> String {
+    health
+        .probes
+        .iter()
+        .filter(|p| matches!(p.status, ProbeStatus::Degraded { .. }))
+        .map(|p| {
+            format!(
+                "{} unavailable: {} won't work.",
+                p.subsystem.label(),
+                p.affects.join(", ")
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// Probes run off the setup thread; the app keeps going whatever they find.
+pub fn check(app: &AppHandle) {
+    app.manage(HealthState::default());
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let health = run_probes(&app);
+        if let Ok(mut state) = app.state::<HealthState>().0.lock() {
+            *state = Some(health.clone());
+        }
+        if health.degraded {
+            notifications::notify(
+                &app,
+                NotificationKind::System,
+                NotificationImportance::Normal,
+                "Running with reduced features",
+                &summary(&health),
+            );
+        }
+        let _ = app.emit("startup-health", &health);
+    });
+}
+
+#[tauri::command]
+pub fn get_startup_health(state: State<'_, HealthState>) -> Result<Option<StartupHealth>, String> {
+    Ok(state.0.lock().map_err(|e| e.to_string())?.clone())
+}
@@ -0,0 +1,147 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::State;
+
+use crate::sessions::DateRange;
+use crate::settings::SettingsStore;
+use crate::storage;
+
+// Number of consecutive input bursts compared when looking for a fixed rhythm
+const RESET_WINDOW: usize = 8;
+// Input closer together than this looks like normal use
+const MIN_PERIOD_SECS: f64 = 10.0;
+// Coefficient of variation below which the rhythm is considered machine-generated
+const MAX_VARIATION: f64 = 0.05;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct LowConfidenceSegment {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub reason: String,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct HeuristicsData {
+    segments: Vec<LowConfidenceSegment>,
+}
+
+// Tracks when input resumed, as seen through the idle provider's idle time dropping
+// between polls. Only timing is used; no input content is ever observed.
+#[derive(Default)]
+struct Detector {
+    last_idle: Option<Duration>,
+    resets: VecDeque<DateTime<Utc>>,
+    // Whether the newest stored segment is still being extended
+    open: bool,
+}
+
+pub struct ActivityHeuristics {
+    path: PathBuf,
+    data: Mutex<HeuristicsData>,
+    detector: Mutex<Detector>,
+}
+
+fn is_periodic(resets: &VecDeque<DateTime<Utc>>) -> bool {
+    if resets.len() < RESET_WINDOW {
+        return false;
+    }
+    let intervals: Vec<f64> = resets
+        .iter()
+        .zip(resets.iter().skip(1))
+        .map(|(a, b)| (*b - *a).num_milliseconds() as f64 / 1000.0)
+        .collect();
+    let mean = intervals.iter().sum::<f64>() / intervals.len() as f64;
+    if mean < MIN_PERIOD_SECS {
+        return false;
+    }
+    let variance =
+        intervals.iter().map(|i| (i - mean).powi(2)).sum::<f64>() / intervals.len() as f64;
+    variance.sqrt() / mean <= MAX_VARIATION
+}
+
+impl ActivityHeuristics {
+    pub fn load(app: &tauri::AppHandle) -> Result<Self, String> {
+        let path = storage::data_file(app, "heuristics.json")?;
+        let data = storage::load_json(&path)?;
+        Ok(Self {
+            path,
+            data: Mutex::new(data),
+            detector: Mutex::default(),
+        })
+    }
+
+    // Fed by the idle monitor on every poll.
+    pub fn observe(&self, now: DateTime<Utc>, idle: Duration) -> Result<(), String> {
+        let mut detector = self.detector.lock().map_err(|e| e.to_string())?;
+        if detector.last_idle.is_some_and(|last| idle < last) {
+            let at = now - chrono::Duration::from_std(idle).map_err(|e| e.to_string())?;
+            detector.resets.push_back(at);
+            if detector.resets.len() > RESET_WINDOW {
+                detector.resets.pop_front();
+            }
+        }
+        detector.last_idle = Some(idle);
+
+        if !is_periodic(&detector.resets) {
+            detector.open = false;
+            return Ok(());
+        }
+        let start = detector.resets[0];
+        let open = detector.open;
+        detector.open = true;
+        drop(detector);
+
+        let mut data = self.data.lock().map_err(|e| e.to_string())?;
+        match data.segments.last_mut() {
+            Some(segment) if open => segment.end = now,
+            _ => data.segments.push(LowConfidenceSegment {
+                start,
+                end: now,
+                reason: "periodic_input".to_string(),
+            }),
+        }
+        storage::save_json(&self.path, &*data)
+    }
+
+    pub fn prune(&self, before: DateTime<Utc>) -> Result<usize, String> {
+        let mut data = self.data.lock().map_err(|e| e.to_string())?;
+        let len = data.segments.len();
+        data.segments.retain(|s| s.end >= before);
+        let pruned = len - data.segments.len();
+        if pruned > 0 {
+            storage::save_json(&self.path, &*data)?;
+        }
+        Ok(pruned)
+    }
+
+    pub fn in_range(&self, range: DateRange) -> Result<Vec<LowConfidenceSegment>, String> {
+        let data = self.data.lock().map_err(|e| e.to_string())?;
+        Ok(data
+            .segments
+            .iter()
+            .filter(|s| s.start < range.end && range.start < s.end)
+            .cloned()
+            .collect())
+    }
+}
+
+#[tauri::command]
+pub fn set_activity_heuristics(
+    settings: State<'_, SettingsStore>,
+    enabled: bool,
+) -> Result<(), String> {
+    settings.update(|s| s.activity_heuristics = enabled)?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_low_confidence_segments(
+    heuristics: State<'_, ActivityHeuristics>,
+    range: DateRange,
+) -> Result<Vec<LowConfidenceSegment>, String> {
+    heuristics.in_range(range)
+}
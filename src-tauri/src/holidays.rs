@@ -0,0 +1,258 @@
+use chrono::{Datelike, Duration as ChronoDuration, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::settings::{AppSettings, SettingsStore};
+
+// How a holiday's date is found each year
+enum Rule {
+    Fixed(u32, u32),
+    // Days after Easter Sunday
+    Easter(i64),
+    // nth weekday of the month; negative counts from the end
+    Nth(u32, Weekday, i32),
+}
+
+use Rule::{Easter, Fixed, Nth};
+
+// Nationwide public holidays. Regional ones and substitute days for holidays falling on a
+// weekend aren't included; add those as leave.
+const COUNTRIES: &[(&str, &[(Rule, &str)])] = &[
+    (
+        "DE",
+        &[
+            (Fixed(1, 1), "Neujahr"),
+            (Easter(-2), "Karfreitag"),
+            (Easter(1), "Ostermontag"),
+            (Fixed(5, 1), "Tag der Arbeit"),
+            (Easter(39), "Christi Himmelfahrt"),
+            (Easter(50), "Pfingstmontag"),
+            (Fixed(10, 3), "Tag der Deutschen Einheit"),
+            (Fixed(12, 25), "1. Weihnachtstag"),
+            (Fixed(12, 26), "2. Weihnachtstag"),
+        ],
+    ),
+    (
+        "FR",
+        &[
+            (Fixed(1, 1), "Jour de l'an"),
+            (Easter(1), "Lundi de Pâques"),
+            (Fixed(5, 1), "Fête du Travail"),
+            (Fixed(5, 8), "Victoire 1945"),
+            (Easter(39), "Ascension"),
+            (Easter(50), "Lundi de Pentecôte"),
+            (Fixed(7, 14), "Fête nationale"),
+            (Fixed(8, 15), "Assomption"),
+            (Fixed(11, 1), "Toussaint"),
+            (Fixed(11, 11), "Armistice 1918"),
+            (Fixed(12, 25), "Noël"),
+        ],
+    ),
+    (
+        "GB",
+        &[
+            (Fixed(1, 1), "New Year's Day"),
+            (Easter(-2), "Good Friday"),
+            (Easter(1), "Easter Monday"),
+            (Nth(5, Weekday::Mon, 1), "Early May bank holiday"),
+            (Nth(5, Weekday::Mon, -1), "Spring bank holiday"),
+            (Nth(8, Weekday::Mon, -1), "Summer bank holiday"),
+            (Fixed(12, 25), "Christmas Day"),
+            (Fixed(12, 26), "Boxing Day"),
+        ],
+    ),
+    (
+        "NL",
+        &[
+            (Fixed(1, 1), "Nieuwjaarsdag"),
+            (Easter(1), "Tweede Paasdag"),
+            (Fixed(4, 27), "Koningsdag"),
+            (Easter(39), "Hemelvaartsdag"),
+            (Easter(50), "Tweede Pinksterdag"),
+            (Fixed(12, 25), "Eerste Kerstdag"),
+            (Fixed(12, 26), "Tweede Kerstdag"),
+        ],
+    ),
+    (
+        "US",
+        &[
+            (Fixed(1, 1), "New Year's Day"),
+            (Nth(1, Weekday::Mon, 3), "Martin Luther King Jr. Day"),
+            (Nth(2, Weekday::Mon, 3), "Presidents' Day"),
+            (Nth(5, Weekday::Mon, -1), "Memorial Day"),
+            (Fixed(6, 19), "Juneteenth"),
+            (Fixed(7, 4), "Independence Day"),
+            (Nth(9, Weekday::Mon, 1), "Labor Day"),
+            (Nth(10, Weekday::Mon, 2), "Columbus Day"),
+            (Fixed(11, 11), "Veterans Day"),
+            (Nth(11, Weekday::Thu, 4), "Thanksgiving Day"),
+            (Fixed(12, 25), "Christmas Day"),
+        ],
+    ),
+];
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LeaveKind {
+    Vacation,
+    Sick,
+    Other,
+}
+
+// Days off entered by the user, both dates inclusive
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Leave {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub kind: LeaveKind,
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HolidaySettings {
+    // ISO 3166 code from COUNTRIES
+    pub country: Option<String>,
+    pub leave: Vec<Leave>,
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DayOffKind {
+    Holiday,
+    Leave,
+}
+
+#[derive(Clone, Serialize)]
+pub struct DayOff {
+    pub date: NaiveDate,
+    pub kind: DayOffKind,
+    pub name: String,
+}
+
+// Anonymous Gregorian algorithm
+fn easter(year: i32) -> Option<NaiveDate> {
+    let a = year % 19;
+    let b = year / 100;
+    let c = year % 100;
+    let d = (19 * a + b - b / 4 - (b - (8 * b + 13) / 25) + 15) % 30;
+    let e = (32 + 2 * (b % 4) + 2 * (c / 4) - d - c % 4) % 7;
+    let f = d + e - 7 * ((a + 11 * d + 22 * e) / 451) + 114;
+    NaiveDate::from_ymd_opt(year, (f / 31) as u32, (f % 31 + 1) as u32)
+}
+
+fn nth_weekday(year: i32, month: u32, weekday: Weekday, n: i32) -> Option<NaiveDate> {
+    if n > 0 {
+        return NaiveDate::from_weekday_of_month_opt(year, month, weekday, n as u8);
+    }
+    let next_month = if month == 12 {
+        NaiveDate::from_ymd_opt(year + 1, 1, 1)
+    } else {
+        NaiveDate::from_ymd_opt(year, month + 1, 1)
+    }?;
+    let mut date = next_month.pred_opt()?;
+    while date.weekday() != weekday {
+        date = date.pred_opt()?;
+    }
+    Some(date - ChronoDuration::weeks((-n - 1) as i64))
+}
+
+pub fn is_supported(country: &str) -> bool {
+    COUNTRIES.iter().any(|(code, _)| *code == country)
+}
+
+pub fn public_holidays(country: &str, year: i32) -> Vec<(NaiveDate, &'static str)> {
+    let Some((_, rules)) = COUNTRIES.iter().find(|(code, _)| *code == country) else {
+        return Vec::new();
+    };
+    let mut holidays: Vec<_> = rules
+        .iter()
+        .filter_map(|(rule, name)| {
+            let date = match rule {
+                Fixed(month, day) => NaiveDate::from_ymd_opt(year, *month, *day),
+                Easter(offset) => easter(year).map(|e| e + ChronoDuration::days(*offset)),
+                Nth(month, weekday, n) => nth_weekday(year, *month, *weekday, *n),
+            }?;
+            Some((date, *name))
+        })
+        .collect();
+    holidays.sort();
+    holidays
+}
+
+// Leave wins over a public holiday on the same day.
+pub fn day_off(settings: &HolidaySettings, date: NaiveDate) -> Option<DayOff> {
+    if let Some(leave) = settings
+        .leave
+        .iter()
+        .find(|l| l.start <= date && date <= l.end)
+    {
+        let name = leave.note.clone().unwrap_or_else(|| {
+            match leave.kind {
+                LeaveKind::Vacation => "Vacation",
+                LeaveKind::Sick => "Sick leave",
+                LeaveKind::Other => "Leave",
+            }
+            .to_string()
+        });
+        return Some(DayOff {
+            date,
+            kind: DayOffKind::Leave,
+            name,
+        });
+    }
+    let country = settings.country.as_deref()?;
+    public_holidays(country, date.year())
+        .into_iter()
+        .find(|(holiday, _)| *holiday == date)
+        .map(|(_, name)| DayOff {
+            date,
+            kind: DayOffKind::Holiday,
+            name: name.to_string(),
+        })
+}
+
+// Monday to Friday, except days off
+pub fn is_workday(settings: &HolidaySettings, date: NaiveDate) -> bool {
+    date.weekday().num_days_from_monday() < 5 && day_off(settings, date).is_none()
+}
+
+// Both dates inclusive
+pub fn days_off(settings: &HolidaySettings, from: NaiveDate, to: NaiveDate) -> Vec<DayOff> {
+    from.iter_days()
+        .take_while(|date| *date <= to)
+        .filter_map(|date| day_off(settings, date))
+        .collect()
+}
+
+#[tauri::command]
+pub fn get_days_off(
+    settings: State<'_, SettingsStore>,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<Vec<DayOff>, String> {
+    Ok(days_off(&settings.get()?.holidays, from, to))
+}
+
+#[tauri::command]
+pub fn get_holiday_countries() -> Vec<&'static str> {
+    COUNTRIES.iter().map(|(code, _)| *code).collect()
+}
+
+#[tauri::command]
+pub fn set_holiday_settings(
+    settings: State<'_, SettingsStore>,
+    holidays: HolidaySettings,
+) -> Result<(), String> {
+    if let Some(country) = &holidays.country {
+        if !is_supported(country) {
+            return Err(format!("No holiday calendar for {}", country));
+        }
+    }
+    if holidays.leave.iter().any(|l| l.end < l.start) {
+        return Err("Leave can't end before it starts".to_string());
+    }
+    settings.update(|s: &mut AppSettings| s.holidays = holidays)?;
+    Ok(())
+}
@@ -0,0 +1,52 @@
+// Whether the session's screen is locked; None where it can't be determined.
+
+#[cfg(windows)]
+mod win32 {
+    use std::ffi::c_void;
+
+    pub const DESKTOP_SWITCHDESKTOP: u32 = 0x0100;
+
+    #[link(name = "user32")]
+    extern "system" {
+        pub fn OpenInputDesktop(flags: u32, inherit: i32, access: u32) -> *mut c_void;
+        pub fn CloseDesktop(desktop: *mut c_void) -> i32;
+    }
+}
+
+// The input desktop can't be opened while the lock screen owns it
+#[cfg(windows)]
+pub fn screen_locked() -> Option<bool> {
+    // SAFETY: plain Win32 calls; the handle is closed before returning
+    unsafe {
+        let desktop = win32::OpenInputDesktop(0, 0, win32::DESKTOP_SWITCHDESKTOP);
+        if desktop.is_null() {
+            return Some(true);
+        }
+        win32::CloseDesktop(desktop);
+    }
+    Some(false)
+}
+
+#[cfg(target_os = "macos")]
+pub fn screen_locked() -> Option<bool> {
+    let output = super::providers::run("ioreg", &["-n", "Root", "-d1"]).ok()?;
+    Some(output.contains("\"CGSSessionScreenIsLocked\"=Yes"))
+}
+
+// Set by the screen locker through logind; most desktop environments maintain it
+#[cfg(target_os = "linux")]
+pub fn screen_locked() -> Option<bool> {
+    let session = std::env::var("XDG_SESSION_ID").unwrap_or_else(|_| "self".to_string());
+    let output =
+        super::providers::run("loginctl", &["show-session", &session, "-p", "LockedHint"]).ok()?;
+    match output.trim() {
+        "LockedHint=yes" => Some(true),
+        "LockedHint=no" => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+pub fn screen_locked() -> Option<bool> {
+    None
+}
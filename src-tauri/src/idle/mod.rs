@@ -0,0 +1,524 @@
+mod lock;
+mod power;
+mod providers;
+#[cfg(feature = "simulated-idle")]
+mod simulated;
+mod state;
+
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::activity::{ActivityKind, ActivityLog};
+use crate::background;
+use crate::flags::{self, FeatureFlags};
+use crate::heuristics::ActivityHeuristics;
+use crate::meeting_detection;
+use crate::project_policy;
+use crate::reading_mode;
+use crate::settings::SettingsStore;
+use crate::speech;
+use crate::timer::TimerManager;
+use crate::work_context;
+use power::PowerState;
+pub use state::IdleState;
+use state::{IdleStateMachine, IdleStateSnapshot, Observation, Transition};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+const BATTERY_POLL_INTERVAL: Duration = Duration::from_secs(15);
+const DISPLAY_OFF_POLL_INTERVAL: Duration = Duration::from_secs(30);
+// Power state is re-read this often rather than on every poll
+const POWER_REFRESH: Duration = Duration::from_secs(60);
+
+pub trait IdleProvider: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn idle_time(&self) -> Result<Duration, String>;
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IdleSettings {
+    pub threshold_secs: u64,
+    // Extra idle time tolerated before the timer is considered idle
+    pub grace_secs: u64,
+    // Continuous activity required to leave the idle state
+    pub resume_secs: u64,
+    // Idle time after which an idle user counts as away; 0 disables the Away state
+    pub away_secs: u64,
+    // Slow polling on battery or with the display off, speed it up near the threshold
+    pub adaptive_polling: bool,
+}
+
+impl Default for IdleSettings {
+    fn default() -> Self {
+        Self {
+            threshold_secs: 300,
+            grace_secs: 30,
+            resume_secs: 10,
+            away_secs: 30 * 60,
+            adaptive_polling: true,
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+pub struct IdleProviderInfo {
+    pub name: String,
+    pub available: bool,
+    // Providers probed before the active one, with the reason each was rejected
+    pub rejected: Vec<(String, String)>,
+}
+
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PollReason {
+    // Adaptive polling is turned off
+    Fixed,
+    Normal,
+    OnBattery,
+    DisplayOff,
+    NearThreshold,
+}
+
+// The polling policy currently in effect
+#[derive(Clone, Serialize)]
+pub struct IdleMonitorConfig {
+    pub adaptive: bool,
+    pub interval_secs: u64,
+    pub reason: PollReason,
+    pub on_battery: Option<bool>,
+    pub display_off: Option<bool>,
+    pub normal_interval_secs: u64,
+    pub battery_interval_secs: u64,
+    pub display_off_interval_secs: u64,
+}
+
+// A user-requested suspension of idle monitoring
+#[derive(Clone, Serialize)]
+pub struct IdlePause {
+    pub since: DateTime<Utc>,
+    // None pauses until resume_idle_monitoring is called
+    pub until: Option<DateTime<Utc>>,
+}
+
+pub struct IdleMonitor {
+    provider: Box<dyn IdleProvider>,
+    info: IdleProviderInfo,
+    machine: Mutex<IdleStateMachine>,
+    // The simulated provider skips the OS lock check so runs stay deterministic
+    detect_lock: bool,
+    running: AtomicBool,
+    config: Mutex<IdleMonitorConfig>,
+    pause: Mutex<Option<IdlePause>>,
+}
+
+#[derive(Clone, Serialize)]
+pub struct IdlePayload {
+    pub idle_seconds: u64,
+}
+
+#[cfg(target_os = "linux")]
+fn is_wayland() -> bool {
+    std::env::var_os("WAYLAND_DISPLAY").is_some()
+        || std::env::var("XDG_SESSION_TYPE").is_ok_and(|t| t == "wayland")
+}
+
+#[cfg(windows)]
+fn candidates() -> Vec<Box<dyn IdleProvider>> {
+    vec![Box::new(providers::Windows) as Box<dyn IdleProvider>]
+}
+
+#[cfg(target_os = "macos")]
+fn candidates() -> Vec<Box<dyn IdleProvider>> {
+    vec![Box::new(providers::MacOs) as Box<dyn IdleProvider>]
+}
+
+#[cfg(target_os = "linux")]
+fn candidates() -> Vec<Box<dyn IdleProvider>> {
+    if is_wayland() {
+        vec![
+            Box::new(providers::MutterIdleMonitor) as Box<dyn IdleProvider>,
+            Box::new(providers::FreedesktopScreenSaver),
+            Box::new(providers::Logind),
+        ]
+    } else {
+        vec![
+            Box::new(providers::X11) as Box<dyn IdleProvider>,
+            Box::new(providers::Logind),
+        ]
+    }
+}
+
+#[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+fn candidates() -> Vec<Box<dyn IdleProvider>> {
+    Vec::new()
+}
+
+impl IdleMonitor {
+    // Probe each platform provider in order and keep the first one that answers.
+    #[cfg_attr(feature = "simulated-idle", allow(dead_code))]
+    pub fn detect() -> Self {
+        let mut rejected = Vec::new();
+        for provider in candidates() {
+            match provider.idle_time() {
+                Ok(_) => {
+                    let info = IdleProviderInfo {
+                        name: provider.name().to_string(),
+                        available: true,
+                        rejected,
+                    };
+                    return Self::new(provider, info, true);
+                }
+                Err(e) => rejected.push((provider.name().to_string(), e)),
+            }
+        }
+        log::warn!("no idle provider available: {:?}", rejected);
+        let info = IdleProviderInfo {
+            name: providers::Unavailable.name().to_string(),
+            available: false,
+            rejected,
+        };
+        Self::new(Box::new(providers::Unavailable), info, true)
+    }
+
+    #[cfg(feature = "simulated-idle")]
+    pub fn simulated(provider: std::sync::Arc<simulated::SimulatedIdleProvider>) -> Self {
+        let info = IdleProviderInfo {
+            name: provider.name().to_string(),
+            available: true,
+            rejected: Vec::new(),
+        };
+        Self::new(Box::new(provider), info, false)
+    }
+
+    fn new(provider: Box<dyn IdleProvider>, info: IdleProviderInfo, detect_lock: bool) -> Self {
+        Self {
+            provider,
+            info,
+            machine: Mutex::default(),
+            detect_lock,
+            running: AtomicBool::new(true),
+            config: Mutex::new(IdleMonitorConfig {
+                adaptive: false,
+                interval_secs: POLL_INTERVAL.as_secs(),
+                reason: PollReason::Fixed,
+                on_battery: None,
+                display_off: None,
+                normal_interval_secs: POLL_INTERVAL.as_secs(),
+                battery_interval_secs: BATTERY_POLL_INTERVAL.as_secs(),
+                display_off_interval_secs: DISPLAY_OFF_POLL_INTERVAL.as_secs(),
+            }),
+            pause: Mutex::new(None),
+        }
+    }
+
+    pub fn idle_time(&self) -> Duration {
+        self.provider.idle_time().unwrap_or(Duration::ZERO)
+    }
+
+    pub fn info(&self) -> IdleProviderInfo {
+        self.info.clone()
+    }
+
+    pub fn config(&self) -> Result<IdleMonitorConfig, String> {
+        Ok(self.config.lock().map_err(|e| e.to_string())?.clone())
+    }
+
+    // Picks the next poll interval and records it as the current policy.
+    fn next_interval(
+        &self,
+        settings: &IdleSettings,
+        power: PowerState,
+        idle_seconds: u64,
+    ) -> Duration {
+        let (mut interval, mut reason) = if !settings.adaptive_polling {
+            (POLL_INTERVAL, PollReason::Fixed)
+        } else if power.display_off == Some(true) {
+            (DISPLAY_OFF_POLL_INTERVAL, PollReason::DisplayOff)
+        } else if power.on_battery == Some(true) {
+            (BATTERY_POLL_INTERVAL, PollReason::OnBattery)
+        } else {
+            (POLL_INTERVAL, PollReason::Normal)
+        };
+        // Poll again right when the threshold would be crossed instead of overshooting it
+        if settings.adaptive_polling && !self.is_idle() {
+            let remaining =
+                (settings.threshold_secs + settings.grace_secs).saturating_sub(idle_seconds);
+            if remaining < interval.as_secs() {
+                interval = Duration::from_secs(remaining.max(1));
+                reason = PollReason::NearThreshold;
+            }
+        }
+        if let Ok(mut config) = self.config.lock() {
+            config.adaptive = settings.adaptive_polling;
+            config.interval_secs = interval.as_secs();
+            config.reason = reason;
+            config.on_battery = power.on_battery;
+            config.display_off = power.display_off;
+        }
+        interval
+    }
+
+    pub fn state(&self) -> IdleState {
+        self.machine
+            .lock()
+            .map(|m| m.state())
+            .unwrap_or(IdleState::Active)
+    }
+
+    pub fn is_idle(&self) -> bool {
+        self.state().is_idle()
+    }
+
+    fn screen_locked(&self) -> bool {
+        self.detect_lock && lock::screen_locked().unwrap_or(false)
+    }
+
+    pub fn pause(&self) -> Option<IdlePause> {
+        self.pause.lock().ok()?.clone()
+    }
+
+    // Whether monitoring is paused, resuming first if a timed pause has run out.
+    fn check_pause(&self, app: &AppHandle) -> bool {
+        let expired = match self.pause() {
+            None => return false,
+            Some(pause) => pause.until.is_some_and(|until| Utc::now() >= until),
+        };
+        if expired {
+            resume(app);
+        }
+        !expired
+    }
+
+    // Ends the polling thread after its current sleep.
+    pub fn stop(&self) {
+        self.running.store(false, Ordering::Relaxed);
+    }
+}
+
+// Builds with the `simulated-idle` feature swap the OS provider for one driven by
+// the `simulate_idle` command.
+pub fn init(app: &AppHandle) {
+    #[cfg(feature = "simulated-idle")]
+    let monitor = {
+        let provider = std::sync::Arc::new(simulated::SimulatedIdleProvider::default());
+        app.manage(provider.clone());
+        IdleMonitor::simulated(provider)
+    };
+    #[cfg(not(feature = "simulated-idle"))]
+    let monitor = IdleMonitor::detect();
+
+    app.manage(monitor);
+    start_idle_monitor(app.clone());
+}
+
+// Emits `idle-state-changed` for every transition, plus `user-idle` / `user-active`
+// when the timer moves between idle and not idle.
+fn on_transition(app: &AppHandle, transition: &Transition) {
+    log::debug!(
+        "idle state {:?} -> {:?} ({:?})",
+        transition.from,
+        transition.to,
+        transition.event
+    );
+    let _ = app.emit("idle-state-changed", transition);
+    let (was_idle, now_idle) = (transition.from.is_idle(), transition.to.is_idle());
+    if was_idle == now_idle {
+        return;
+    }
+    let idle_seconds = transition.idle_seconds;
+    let (event, kind) = if now_idle {
+        ("user-idle", ActivityKind::Idle)
+    } else {
+        ("user-active", ActivityKind::Active)
+    };
+    app.state::<ActivityLog>().push(kind, idle_seconds);
+    let _ = app.emit(event, IdlePayload { idle_seconds });
+    if now_idle && app.state::<TimerManager>().active().is_some() {
+        speech::announce(
+            app,
+            &format!("You have been idle for {} minutes", idle_seconds / 60),
+        );
+    }
+}
+
+pub fn pause(app: &AppHandle, duration_secs: Option<u64>) -> Result<IdlePause, String> {
+    let monitor = app.state::<IdleMonitor>();
+    let since = Utc::now();
+    let until = duration_secs
+        .map(|secs| {
+            i64::try_from(secs)
+                .ok()
+                .and_then(TimeDelta::try_seconds)
+                .and_then(|delta| since.checked_add_signed(delta))
+                .ok_or_else(|| "Pause duration is out of range".to_string())
+        })
+        .transpose()?;
+    let pause = IdlePause { since, until };
+    let previous = monitor
+        .pause
+        .lock()
+        .map_err(|e| e.to_string())?
+        .replace(pause.clone());
+    // An idle period in progress ends here rather than running through the pause
+    let transition = monitor.machine.lock().map_err(|e| e.to_string())?.reset();
+    if let Some(transition) = transition {
+        on_transition(app, &transition);
+    }
+    // Extending a pause doesn't start a new span
+    if previous.is_none() {
+        app.state::<ActivityLog>()
+            .push(ActivityKind::MonitoringPaused, 0);
+    }
+    let _ = app.emit("idle-monitoring-paused", &pause);
+    Ok(pause)
+}
+
+pub fn resume(app: &AppHandle) {
+    let monitor = app.state::<IdleMonitor>();
+    let previous = monitor.pause.lock().ok().and_then(|mut p| p.take());
+    if previous.is_some() {
+        app.state::<ActivityLog>()
+            .push(ActivityKind::MonitoringResumed, 0);
+        let _ = app.emit("idle-monitoring-resumed", ());
+    }
+}
+
+pub fn start_idle_monitor(app: AppHandle) {
+    background::spawn_thread(&app, "idle_monitor", |app, task| {
+        let mut interval = POLL_INTERVAL;
+        let mut power = PowerState::read();
+        let mut power_read_at = Instant::now();
+        while task.sleep_blocking(interval) {
+            if power_read_at.elapsed() >= POWER_REFRESH {
+                power = PowerState::read();
+                power_read_at = Instant::now();
+            }
+
+            let monitor = app.state::<IdleMonitor>();
+            if !monitor.running.load(Ordering::Relaxed) {
+                break;
+            }
+            if !app
+                .state::<FeatureFlags>()
+                .is_enabled(flags::IDLE_DETECTION)
+                || monitor.check_pause(&app)
+            {
+                continue;
+            }
+            let mut settings = match app.state::<SettingsStore>().get() {
+                Ok(settings) => settings,
+                Err(_) => continue,
+            };
+            work_context::adjust_idle(&app, &mut settings.idle);
+            project_policy::adjust_idle(&app, &mut settings.idle);
+            let idle = monitor.idle_time();
+            let idle_seconds = idle.as_secs();
+            if settings.activity_heuristics && app.state::<TimerManager>().active().is_some() {
+                if let Err(e) = app.state::<ActivityHeuristics>().observe(Utc::now(), idle) {
+                    log::warn!("activity heuristics failed: {}", e);
+                }
+            }
+            let observation = Observation {
+                // Listening on a call or watching a video without touching the
+                // keyboard isn't idle
+                idle_secs: if meeting_detection::in_meeting(&app) || reading_mode::is_active(&app) {
+                    0
+                } else {
+                    idle_seconds
+                },
+                interval_secs: interval.as_secs(),
+                locked: monitor.screen_locked(),
+            };
+            let transitions = match monitor.machine.lock() {
+                Ok(mut machine) => machine.step(observation, &settings.idle),
+                Err(_) => continue,
+            };
+            for transition in &transitions {
+                on_transition(&app, transition);
+            }
+            interval = monitor.next_interval(&settings.idle, power, idle_seconds);
+        }
+    });
+}
+
+#[tauri::command]
+pub fn set_idle_hysteresis(
+    settings: State<'_, SettingsStore>,
+    grace_secs: u64,
+    resume_secs: u64,
+) -> Result<(), String> {
+    settings.update(|s| {
+        s.idle.grace_secs = grace_secs;
+        s.idle.resume_secs = resume_secs;
+    })?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_idle_state_machine(
+    monitor: State<'_, IdleMonitor>,
+) -> Result<IdleStateSnapshot, String> {
+    monitor
+        .machine
+        .lock()
+        .map(|m| m.snapshot())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_idle_monitor_config(
+    monitor: State<'_, IdleMonitor>,
+) -> Result<IdleMonitorConfig, String> {
+    monitor.config()
+}
+
+#[tauri::command]
+pub fn set_adaptive_idle_polling(
+    settings: State<'_, SettingsStore>,
+    enabled: bool,
+) -> Result<(), String> {
+    settings.update(|s| s.idle.adaptive_polling = enabled)?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn pause_idle_monitoring(
+    app: AppHandle,
+    duration_secs: Option<u64>,
+) -> Result<IdlePause, String> {
+    pause(&app, duration_secs)
+}
+
+#[tauri::command]
+pub fn resume_idle_monitoring(app: AppHandle) {
+    resume(&app);
+}
+
+#[tauri::command]
+pub fn get_idle_pause(monitor: State<'_, IdleMonitor>) -> Option<IdlePause> {
+    monitor.pause()
+}
+
+#[tauri::command]
+pub fn get_idle_provider_info(monitor: State<'_, IdleMonitor>) -> IdleProviderInfo {
+    monitor.info()
+}
+
+#[tauri::command]
+pub fn simulate_idle(app: AppHandle, seconds: u64) -> Result<(), String> {
+    #[cfg(feature = "simulated-idle")]
+    {
+        app.state::<std::sync::Arc<simulated::SimulatedIdleProvider>>()
+            .set_idle(seconds);
+        Ok(())
+    }
+    #[cfg(not(feature = "simulated-idle"))]
+    {
+        let _ = (app, seconds);
+        Err("simulate_idle requires a build with the simulated-idle feature".to_string())
+    }
+}
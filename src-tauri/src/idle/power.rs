@@ -0,0 +1,132 @@
+// Power source and display state used to pick the idle polling interval. Each value is
+// None where the platform doesn't report it.
+#[derive(Clone, Copy, Default)]
+pub struct PowerState {
+    pub on_battery: Option<bool>,
+    pub display_off: Option<bool>,
+}
+
+impl PowerState {
+    pub fn read() -> Self {
+        Self {
+            on_battery: on_battery(),
+            display_off: display_off(),
+        }
+    }
+}
+
+#[cfg(windows)]
+mod win32 {
+    #[repr(C)]
+    #[derive(Default)]
+    pub struct SystemPowerStatus {
+        pub ac_line_status: u8,
+        pub battery_flag: u8,
+        pub battery_life_percent: u8,
+        pub system_status_flag: u8,
+        pub battery_life_time: u32,
+        pub battery_full_life_time: u32,
+    }
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        pub fn GetSystemPowerStatus(status: *mut SystemPowerStatus) -> i32;
+    }
+}
+
+#[cfg(windows)]
+fn on_battery() -> Option<bool> {
+    let mut status = win32::SystemPowerStatus::default();
+    // SAFETY: `status` is a properly laid out SYSTEM_POWER_STATUS owned by this frame
+    let ok = unsafe { win32::GetSystemPowerStatus(&mut status) };
+    // 0 = offline, 1 = online, 255 = unknown
+    match (ok, status.ac_line_status) {
+        (0, _) => None,
+        (_, 0) => Some(true),
+        (_, 1) => Some(false),
+        _ => None,
+    }
+}
+
+// Windows only reports display power through window messages
+#[cfg(windows)]
+fn display_off() -> Option<bool> {
+    None
+}
+
+// Prints e.g. "Now drawing from 'Battery Power'"
+#[cfg(target_os = "macos")]
+fn on_battery() -> Option<bool> {
+    let output = super::providers::run("pmset", &["-g", "batt"]).ok()?;
+    if output.contains("'Battery Power'") {
+        Some(true)
+    } else if output.contains("'AC Power'") {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+// IODisplayWrangler's power state drops below 4 when the display sleeps
+#[cfg(target_os = "macos")]
+fn display_off() -> Option<bool> {
+    let output = super::providers::run("pmset", &["-g", "powerstate", "IODisplayWrangler"]).ok()?;
+    output
+        .lines()
+        .find(|line| line.trim_start().starts_with("IODisplayWrangler"))
+        .and_then(|line| line.split_whitespace().nth(1))
+        .and_then(|state| state.parse::<u32>().ok())
+        .map(|state| state < 4)
+}
+
+#[cfg(target_os = "linux")]
+fn read_trimmed(path: &std::path::Path) -> Option<String> {
+    std::fs::read_to_string(path)
+        .ok()
+        .map(|s| s.trim().to_string())
+}
+
+// Desktops without a mains supply entry report None rather than "on battery"
+#[cfg(target_os = "linux")]
+fn on_battery() -> Option<bool> {
+    let supplies = std::fs::read_dir("/sys/class/power_supply").ok()?;
+    let mut online = None;
+    for supply in supplies.flatten() {
+        let path = supply.path();
+        if read_trimmed(&path.join("type")).as_deref() != Some("Mains") {
+            continue;
+        }
+        let is_online = read_trimmed(&path.join("online")).as_deref() == Some("1");
+        online = Some(online.unwrap_or(false) || is_online);
+    }
+    online.map(|online| !online)
+}
+
+// Off only when every connected DRM output reports DPMS off
+#[cfg(target_os = "linux")]
+fn display_off() -> Option<bool> {
+    let connectors = std::fs::read_dir("/sys/class/drm").ok()?;
+    let mut any_off = None;
+    for connector in connectors.flatten() {
+        let path = connector.path();
+        if read_trimmed(&path.join("status")).as_deref() != Some("connected") {
+            continue;
+        }
+        match read_trimmed(&path.join("dpms")).as_deref() {
+            Some("On") => return Some(false),
+            Some(_) => any_off = Some(true),
+            None => {}
+        }
+    }
+    any_off
+}
+
+#[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+fn on_battery() -> Option<bool> {
+    None
+}
+
+#[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+fn display_off() -> Option<bool> {
+    None
+}
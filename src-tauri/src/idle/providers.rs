@@ -0,0 +1,227 @@
+#[cfg(unix)]
+use std::process::Command;
+use std::time::Duration;
+
+use super::IdleProvider;
+
+#[cfg(unix)]
+pub(super) fn run(program: &str, args: &[&str]) -> Result<String, String> {
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| format!("{}: {}", program, e))?;
+    if !output.status.success() {
+        return Err(format!("{} exited with {}", program, output.status));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+// Used when no platform backend works; never reports idle time.
+pub struct Unavailable;
+
+impl IdleProvider for Unavailable {
+    fn name(&self) -> &'static str {
+        "unavailable"
+    }
+
+    fn idle_time(&self) -> Result<Duration, String> {
+        Ok(Duration::ZERO)
+    }
+}
+
+#[cfg(windows)]
+pub struct Windows;
+
+#[cfg(windows)]
+mod win32 {
+    #[repr(C)]
+    pub struct LastInputInfo {
+        pub cb_size: u32,
+        pub dw_time: u32,
+    }
+
+    #[link(name = "user32")]
+    extern "system" {
+        pub fn GetLastInputInfo(plii: *mut LastInputInfo) -> i32;
+    }
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        pub fn GetTickCount() -> u32;
+    }
+}
+
+#[cfg(windows)]
+impl IdleProvider for Windows {
+    fn name(&self) -> &'static str {
+        "windows"
+    }
+
+    fn idle_time(&self) -> Result<Duration, String> {
+        let mut info = win32::LastInputInfo {
+            cb_size: std::mem::size_of::<win32::LastInputInfo>() as u32,
+            dw_time: 0,
+        };
+        // SAFETY: `info` is a properly sized LASTINPUTINFO owned by this frame
+        let ok = unsafe { win32::GetLastInputInfo(&mut info) };
+        if ok == 0 {
+            return Err("GetLastInputInfo failed".to_string());
+        }
+        let now = unsafe { win32::GetTickCount() };
+        Ok(Duration::from_millis(now.wrapping_sub(info.dw_time) as u64))
+    }
+}
+
+// Reads HIDIdleTime (nanoseconds) from the IOHIDSystem registry entry.
+#[cfg(target_os = "macos")]
+pub struct MacOs;
+
+#[cfg(target_os = "macos")]
+impl IdleProvider for MacOs {
+    fn name(&self) -> &'static str {
+        "macos-iohid"
+    }
+
+    fn idle_time(&self) -> Result<Duration, String> {
+        let output = run("ioreg", &["-c", "IOHIDSystem", "-d", "4"])?;
+        output
+            .lines()
+            .find(|line| line.contains("\"HIDIdleTime\""))
+            .and_then(|line| line.rsplit('=').next())
+            .and_then(|value| value.trim().parse::<u64>().ok())
+            .map(Duration::from_nanos)
+            .ok_or_else(|| "HIDIdleTime not found".to_string())
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub struct X11;
+
+#[cfg(target_os = "linux")]
+impl IdleProvider for X11 {
+    fn name(&self) -> &'static str {
+        "x11-xprintidle"
+    }
+
+    fn idle_time(&self) -> Result<Duration, String> {
+        let output = run("xprintidle", &[])?;
+        output
+            .trim()
+            .parse::<u64>()
+            .map(Duration::from_millis)
+            .map_err(|e| e.to_string())
+    }
+}
+
+// GNOME's compositor exposes idle time over D-Bus, which works under Wayland where X11
+// screensaver queries do not.
+#[cfg(target_os = "linux")]
+pub struct MutterIdleMonitor;
+
+#[cfg(target_os = "linux")]
+impl IdleProvider for MutterIdleMonitor {
+    fn name(&self) -> &'static str {
+        "wayland-mutter"
+    }
+
+    fn idle_time(&self) -> Result<Duration, String> {
+        // Prints e.g. "(uint64 12345,)"
+        let output = run(
+            "gdbus",
+            &[
+                "call",
+                "--session",
+                "--dest",
+                "org.gnome.Mutter.IdleMonitor",
+                "--object-path",
+                "/org/gnome/Mutter/IdleMonitor/Core",
+                "--method",
+                "org.gnome.Mutter.IdleMonitor.GetIdletime",
+            ],
+        )?;
+        output
+            .split(|c: char| !c.is_ascii_digit())
+            .filter(|s| !s.is_empty())
+            .nth(1)
+            .and_then(|ms| ms.parse::<u64>().ok())
+            .map(Duration::from_millis)
+            .ok_or_else(|| format!("unexpected GetIdletime reply: {}", output.trim()))
+    }
+}
+
+// KDE Plasma and other compositors implementing the freedesktop screensaver interface.
+#[cfg(target_os = "linux")]
+pub struct FreedesktopScreenSaver;
+
+#[cfg(target_os = "linux")]
+impl IdleProvider for FreedesktopScreenSaver {
+    fn name(&self) -> &'static str {
+        "wayland-screensaver"
+    }
+
+    fn idle_time(&self) -> Result<Duration, String> {
+        let output = run(
+            "gdbus",
+            &[
+                "call",
+                "--session",
+                "--dest",
+                "org.freedesktop.ScreenSaver",
+                "--object-path",
+                "/org/freedesktop/ScreenSaver",
+                "--method",
+                "org.freedesktop.ScreenSaver.GetSessionIdleTime",
+            ],
+        )?;
+        output
+            .split(|c: char| !c.is_ascii_digit())
+            .find(|s| !s.is_empty())
+            .and_then(|ms| ms.parse::<u64>().ok())
+            .map(Duration::from_millis)
+            .ok_or_else(|| format!("unexpected GetSessionIdleTime reply: {}", output.trim()))
+    }
+}
+
+// logind's IdleHint is set by the compositor after its own idle delay, so it is coarse,
+// but it is available on nearly every systemd-based session.
+#[cfg(target_os = "linux")]
+pub struct Logind;
+
+#[cfg(target_os = "linux")]
+impl IdleProvider for Logind {
+    fn name(&self) -> &'static str {
+        "logind"
+    }
+
+    fn idle_time(&self) -> Result<Duration, String> {
+        let session = std::env::var("XDG_SESSION_ID").unwrap_or_else(|_| "self".to_string());
+        let output = run(
+            "loginctl",
+            &[
+                "show-session",
+                &session,
+                "-p",
+                "IdleHint",
+                "-p",
+                "IdleSinceHint",
+            ],
+        )?;
+        let value = |key: &str| {
+            output
+                .lines()
+                .find_map(|line| line.strip_prefix(key)?.strip_prefix('='))
+                .map(str::trim)
+        };
+        if value("IdleHint") != Some("yes") {
+            return Ok(Duration::ZERO);
+        }
+        let since_us = value("IdleSinceHint")
+            .and_then(|v| v.parse::<u64>().ok())
+            .ok_or_else(|| "IdleSinceHint missing".to_string())?;
+        let now_us = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_err(|e| e.to_string())?
+            .as_micros() as u64;
+        Ok(Duration::from_micros(now_us.saturating_sub(since_us)))
+    }
+}
@@ -0,0 +1,30 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use super::IdleProvider;
+
+// Deterministic idle source for integration tests and QA builds. Idle time grows from the
+// moment `set_idle` was called, just like a real provider would after input stops.
+#[derive(Default)]
+pub struct SimulatedIdleProvider {
+    idle_since: Mutex<Option<Instant>>,
+}
+
+impl SimulatedIdleProvider {
+    pub fn set_idle(&self, seconds: u64) {
+        if let Ok(mut idle_since) = self.idle_since.lock() {
+            *idle_since = Instant::now().checked_sub(Duration::from_secs(seconds));
+        }
+    }
+}
+
+impl IdleProvider for std::sync::Arc<SimulatedIdleProvider> {
+    fn name(&self) -> &'static str {
+        "simulated"
+    }
+
+    fn idle_time(&self) -> Result<Duration, String> {
+        let idle_since = self.idle_since.lock().map_err(|e| e.to_string())?;
+        Ok(idle_since.map(|t| t.elapsed()).unwrap_or(Duration::ZERO))
+    }
+}
@@ -0,0 +1,358 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::VecDeque;
+
+use super::IdleSettings;
+
+// Transitions kept for get_idle_state_machine
+const HISTORY_LEN: usize = 20;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IdleState {
+    Active,
+    // Past the threshold but still within the grace period
+    IdleGrace,
+    Idle,
+    // Idle for longer than `away_secs`
+    Away,
+    Locked,
+}
+
+impl IdleState {
+    // States in which the timer is treated as idle
+    pub fn is_idle(self) -> bool {
+        matches!(self, IdleState::Idle | IdleState::Away | IdleState::Locked)
+    }
+}
+
+// Why a transition happened
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IdleEvent {
+    ThresholdReached,
+    GraceExpired,
+    AwayTimeout,
+    // Input during the grace period, or `resume_secs` of continuous input while idle
+    InputResumed,
+    ScreenLocked,
+    ScreenUnlocked,
+    MonitoringPaused,
+}
+
+// What the monitor saw at one poll
+#[derive(Clone, Copy)]
+pub struct Observation {
+    pub idle_secs: u64,
+    // Time since the previous poll
+    pub interval_secs: u64,
+    pub locked: bool,
+}
+
+#[derive(Clone, Serialize)]
+pub struct Transition {
+    pub from: IdleState,
+    pub to: IdleState,
+    pub event: IdleEvent,
+    pub at: DateTime<Utc>,
+    pub idle_seconds: u64,
+}
+
+#[derive(Clone, Serialize)]
+pub struct IdleStateSnapshot {
+    pub state: IdleState,
+    pub entered_at: DateTime<Utc>,
+    pub seconds_in_state: i64,
+    // Oldest first
+    pub history: Vec<Transition>,
+}
+
+pub struct IdleStateMachine {
+    state: IdleState,
+    entered_at: DateTime<Utc>,
+    // Seconds of polling so far; only differences are meaningful
+    uptime: u64,
+    // Start of the current unbroken run of input while idle
+    active_since: Option<u64>,
+    history: VecDeque<Transition>,
+}
+
+impl Default for IdleStateMachine {
+    fn default() -> Self {
+        Self {
+            state: IdleState::Active,
+            entered_at: Utc::now(),
+            uptime: 0,
+            active_since: None,
+            history: VecDeque::new(),
+        }
+    }
+}
+
+// The transition out of `state` for this observation, if any. `resumed` says whether
+// input has been continuous for long enough to leave an idle state.
+fn next(
+    state: IdleState,
+    obs: &Observation,
+    settings: &IdleSettings,
+    resumed: bool,
+) -> Option<(IdleState, IdleEvent)> {
+    use IdleEvent::*;
+    use IdleState::*;
+    let idle_at = settings.threshold_secs + settings.grace_secs;
+    match state {
+        Locked if obs.locked => None,
+        Locked => Some((Active, ScreenUnlocked)),
+        _ if obs.locked => Some((Locked, ScreenLocked)),
+        Active if obs.idle_secs >= settings.threshold_secs => Some((IdleGrace, ThresholdReached)),
+        Active => None,
+        IdleGrace if obs.idle_secs < settings.threshold_secs => Some((Active, InputResumed)),
+        IdleGrace if obs.idle_secs >= idle_at => Some((Idle, GraceExpired)),
+        IdleGrace => None,
+        Idle | Away if resumed => Some((Active, InputResumed)),
+        Idle if settings.away_secs > 0 && obs.idle_secs >= settings.away_secs => {
+            Some((Away, AwayTimeout))
+        }
+        Idle | Away => None,
+    }
+}
+
+impl IdleStateMachine {
+    pub fn state(&self) -> IdleState {
+        self.state
+    }
+
+    // Applies one observation. A poll can cross several states at once (e.g. straight
+    // from Active to Idle after a long sleep); every transition taken is returned.
+    pub fn step(&mut self, obs: Observation, settings: &IdleSettings) -> Vec<Transition> {
+        self.uptime += obs.interval_secs;
+        // Input since the last poll extends the run; a gap resets it, so a single mouse
+        // nudge doesn't end an idle period.
+        let resumed = if obs.idle_secs <= obs.interval_secs {
+            let since = *self
+                .active_since
+                .get_or_insert(self.uptime.saturating_sub(obs.idle_secs));
+            self.uptime - since >= settings.resume_secs
+        } else {
+            self.active_since = None;
+            false
+        };
+
+        let mut transitions = Vec::new();
+        // Bounded by the longest chain, Active -> IdleGrace -> Idle -> Away
+        for _ in 0..4 {
+            let Some((to, event)) = next(self.state, &obs, settings, resumed) else {
+                break;
+            };
+            let transition = Transition {
+                from: self.state,
+                to,
+                event,
+                at: Utc::now(),
+                idle_seconds: obs.idle_secs,
+            };
+            self.state = to;
+            self.entered_at = transition.at;
+            self.active_since = None;
+            if self.history.len() == HISTORY_LEN {
+                self.history.pop_front();
+            }
+            self.history.push_back(transition.clone());
+            transitions.push(transition);
+            if to == IdleState::Active {
+                break;
+            }
+        }
+        transitions
+    }
+
+    // Returns to Active, as when monitoring is paused. The transition is returned unless
+    // the machine was already active.
+    pub fn reset(&mut self) -> Option<Transition> {
+        self.active_since = None;
+        if self.state == IdleState::Active {
+            return None;
+        }
+        let transition = Transition {
+            from: self.state,
+            to: IdleState::Active,
+            event: IdleEvent::MonitoringPaused,
+            at: Utc::now(),
+            idle_seconds: 0,
+        };
+        self.state = IdleState::Active;
+        self.entered_at = transition.at;
+        if self.history.len() == HISTORY_LEN {
+            self.history.pop_front();
+        }
+        self.history.push_back(transition.clone());
+        Some(transition)
+    }
+
+    pub fn snapshot(&self) -> IdleStateSnapshot {
+        IdleStateSnapshot {
+            state: self.state,
+            entered_at: self.entered_at,
+            seconds_in_state: (Utc::now() - self.entered_at).num_seconds(),
+            history: self.history.iter().cloned().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings() -> IdleSettings {
+        IdleSettings {
+            threshold_secs: 60,
+            grace_secs: 10,
+            resume_secs: 5,
+            away_secs: 300,
+            adaptive_polling: false,
+        }
+    }
+
+    fn obs(idle_secs: u64) -> Observation {
+        Observation {
+            idle_secs,
+            interval_secs: 5,
+            locked: false,
+        }
+    }
+
+    fn events(transitions: &[Transition]) -> Vec<(IdleState, IdleEvent)> {
+        transitions.iter().map(|t| (t.to, t.event)).collect()
+    }
+
+    #[test]
+    fn threshold_boundaries() {
+        let s = settings();
+        assert_eq!(next(IdleState::Active, &obs(59), &s, false), None);
+        assert_eq!(
+            next(IdleState::Active, &obs(60), &s, false),
+            Some((IdleState::IdleGrace, IdleEvent::ThresholdReached))
+        );
+        assert_eq!(next(IdleState::IdleGrace, &obs(69), &s, false), None);
+        assert_eq!(
+            next(IdleState::IdleGrace, &obs(70), &s, false),
+            Some((IdleState::Idle, IdleEvent::GraceExpired))
+        );
+        assert_eq!(
+            next(IdleState::IdleGrace, &obs(59), &s, false),
+            Some((IdleState::Active, IdleEvent::InputResumed))
+        );
+        assert_eq!(next(IdleState::Idle, &obs(299), &s, false), None);
+        assert_eq!(
+            next(IdleState::Idle, &obs(300), &s, false),
+            Some((IdleState::Away, IdleEvent::AwayTimeout))
+        );
+    }
+
+    #[test]
+    fn away_disabled() {
+        let s = IdleSettings {
+            away_secs: 0,
+            ..settings()
+        };
+        assert_eq!(next(IdleState::Idle, &obs(100_000), &s, false), None);
+    }
+
+    #[test]
+    fn active_idle_away_active() {
+        let s = settings();
+        let mut machine = IdleStateMachine::default();
+        assert!(machine.step(obs(30), &s).is_empty());
+
+        let t = machine.step(obs(60), &s);
+        assert_eq!(
+            events(&t),
+            vec![(IdleState::IdleGrace, IdleEvent::ThresholdReached)]
+        );
+        let t = machine.step(obs(70), &s);
+        assert_eq!(events(&t), vec![(IdleState::Idle, IdleEvent::GraceExpired)]);
+        let t = machine.step(obs(300), &s);
+        assert_eq!(events(&t), vec![(IdleState::Away, IdleEvent::AwayTimeout)]);
+
+        // One nudge isn't enough; input has to last `resume_secs`
+        assert!(machine.step(obs(0), &s).is_empty());
+        assert_eq!(machine.state(), IdleState::Away);
+        let t = machine.step(obs(0), &s);
+        assert_eq!(
+            events(&t),
+            vec![(IdleState::Active, IdleEvent::InputResumed)]
+        );
+        assert_eq!(machine.snapshot().history.len(), 4);
+    }
+
+    #[test]
+    fn gap_in_input_restarts_resume() {
+        let s = settings();
+        let mut machine = IdleStateMachine::default();
+        machine.step(obs(70), &s);
+        assert_eq!(machine.state(), IdleState::Idle);
+        assert!(machine.step(obs(0), &s).is_empty());
+        // Idle again for longer than the poll interval, so the run is broken
+        assert!(machine.step(obs(20), &s).is_empty());
+        assert!(machine.step(obs(0), &s).is_empty());
+        assert_eq!(machine.state(), IdleState::Idle);
+    }
+
+    #[test]
+    fn long_sleep_crosses_several_states() {
+        let s = settings();
+        let mut machine = IdleStateMachine::default();
+        let t = machine.step(obs(1000), &s);
+        assert_eq!(
+            events(&t),
+            vec![
+                (IdleState::IdleGrace, IdleEvent::ThresholdReached),
+                (IdleState::Idle, IdleEvent::GraceExpired),
+                (IdleState::Away, IdleEvent::AwayTimeout),
+            ]
+        );
+    }
+
+    #[test]
+    fn lock_and_unlock() {
+        let s = settings();
+        let mut machine = IdleStateMachine::default();
+        let locked = Observation {
+            locked: true,
+            ..obs(0)
+        };
+        let t = machine.step(locked, &s);
+        assert_eq!(
+            events(&t),
+            vec![(IdleState::Locked, IdleEvent::ScreenLocked)]
+        );
+        assert!(machine.state().is_idle());
+        assert!(machine.step(locked, &s).is_empty());
+        let t = machine.step(obs(0), &s);
+        assert_eq!(
+            events(&t),
+            vec![(IdleState::Active, IdleEvent::ScreenUnlocked)]
+        );
+    }
+
+    #[test]
+    fn pause_and_resume() {
+        let s = settings();
+        let mut machine = IdleStateMachine::default();
+        assert!(machine.reset().is_none());
+        machine.step(obs(70), &s);
+        assert_eq!(machine.state(), IdleState::Idle);
+
+        let t = machine.reset().unwrap();
+        assert_eq!((t.from, t.to), (IdleState::Idle, IdleState::Active));
+        assert_eq!(t.event, IdleEvent::MonitoringPaused);
+
+        // After resuming, polling starts over from Active
+        assert!(machine.step(obs(30), &s).is_empty());
+        let t = machine.step(obs(60), &s);
+        assert_eq!(
+            events(&t),
+            vec![(IdleState::IdleGrace, IdleEvent::ThresholdReached)]
+        );
+    }
+}
@@ -0,0 +1,206 @@
+use chrono::{DateTime, DurationRound, Local, NaiveDate, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+
+use crate::permissions::{self, PermissionKind};
+use crate::sessions::DateRange;
+use crate::settings::SettingsStore;
+use crate::storage;
+use crate::timer::TimerManager;
+
+const BUCKET: Duration = Duration::from_secs(60);
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct InputMinute {
+    pub minute: DateTime<Utc>,
+    // Task of the primary timer during this minute, if one was running
+    pub task_id: Option<u64>,
+    pub keystrokes: u32,
+    pub clicks: u32,
+}
+
+// Totals kept after the per-minute buckets of a day are pruned
+#[derive(Clone, Serialize, Deserialize)]
+pub struct InputDay {
+    pub date: NaiveDate,
+    pub keystrokes: u64,
+    pub clicks: u64,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct InputStatsData {
+    minutes: Vec<InputMinute>,
+    #[serde(default)]
+    days: Vec<InputDay>,
+}
+
+// Counts key presses and mouse clicks only; which key or where the pointer was is never
+// read or stored.
+pub struct InputStats {
+    path: PathBuf,
+    data: Mutex<InputStatsData>,
+    keystrokes: AtomicU32,
+    clicks: AtomicU32,
+    enabled: AtomicBool,
+    listening: AtomicBool,
+}
+
+impl InputStats {
+    pub fn load(app: &AppHandle) -> Result<Self, String> {
+        let path = storage::data_file(app, "input_stats.json")?;
+        let data = storage::load_json(&path)?;
+        Ok(Self {
+            path,
+            data: Mutex::new(data),
+            keystrokes: AtomicU32::new(0),
+            clicks: AtomicU32::new(0),
+            enabled: AtomicBool::new(false),
+            listening: AtomicBool::new(false),
+        })
+    }
+
+    fn record(&self, event: &rdev::EventType) {
+        if !self.enabled.load(Ordering::Relaxed) {
+            return;
+        }
+        match event {
+            rdev::EventType::KeyPress(_) => {
+                self.keystrokes.fetch_add(1, Ordering::Relaxed);
+            }
+            rdev::EventType::ButtonPress(_) => {
+                self.clicks.fetch_add(1, Ordering::Relaxed);
+            }
+            _ => {}
+        }
+    }
+
+    fn flush(&self, minute: DateTime<Utc>, task_id: Option<u64>) -> Result<(), String> {
+        let keystrokes = self.keystrokes.swap(0, Ordering::Relaxed);
+        let clicks = self.clicks.swap(0, Ordering::Relaxed);
+        if keystrokes == 0 && clicks == 0 {
+            return Ok(());
+        }
+        let mut data = self.data.lock().map_err(|e| e.to_string())?;
+        data.minutes.push(InputMinute {
+            minute,
+            task_id,
+            keystrokes,
+            clicks,
+        });
+        storage::save_json(&self.path, &*data)
+    }
+
+    // Folds minutes older than `before` into daily totals and drops them. Returns the
+    // number of minutes removed and of new daily summaries.
+    pub fn prune(&self, before: DateTime<Utc>) -> Result<(usize, usize), String> {
+        let mut data = self.data.lock().map_err(|e| e.to_string())?;
+        let (old, kept): (Vec<_>, Vec<_>) = data.minutes.drain(..).partition(|m| m.minute < before);
+        data.minutes = kept;
+        let mut added = 0;
+        for minute in &old {
+            let date = minute.minute.with_timezone(&Local).date_naive();
+            match data.days.iter_mut().find(|d| d.date == date) {
+                Some(day) => {
+                    day.keystrokes += minute.keystrokes as u64;
+                    day.clicks += minute.clicks as u64;
+                }
+                None => {
+                    added += 1;
+                    data.days.push(InputDay {
+                        date,
+                        keystrokes: minute.keystrokes as u64,
+                        clicks: minute.clicks as u64,
+                    });
+                }
+            }
+        }
+        if !old.is_empty() {
+            storage::save_json(&self.path, &*data)?;
+        }
+        Ok((old.len(), added))
+    }
+
+    pub fn in_range(&self, range: DateRange) -> Result<Vec<InputMinute>, String> {
+        let data = self.data.lock().map_err(|e| e.to_string())?;
+        Ok(data
+            .minutes
+            .iter()
+            .filter(|m| m.minute >= range.start && m.minute < range.end)
+            .cloned()
+            .collect())
+    }
+}
+
+// The OS hook can't be removed once installed, so it is started the first time collection
+// is enabled and afterwards only gated by the `enabled` flag.
+fn start_listener(app: &AppHandle) {
+    if !permissions::is_granted(PermissionKind::InputMonitoring) {
+        log::warn!("input statistics need the Input Monitoring permission");
+        return;
+    }
+    let stats = app.state::<InputStats>();
+    if stats.listening.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let listener_app = app.clone();
+    std::thread::spawn(move || {
+        let result = rdev::listen(move |event| {
+            listener_app.state::<InputStats>().record(&event.event_type);
+        });
+        if let Err(e) = result {
+            log::error!("input statistics listener failed: {:?}", e);
+        }
+    });
+
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        std::thread::sleep(BUCKET);
+        let minute = Utc::now()
+            .duration_trunc(TimeDelta::minutes(1))
+            .unwrap_or_else(|_| Utc::now())
+            - TimeDelta::minutes(1);
+        let task_id = app.state::<TimerManager>().active().map(|t| t.task_id);
+        if let Err(e) = app.state::<InputStats>().flush(minute, task_id) {
+            log::warn!("failed to store input statistics: {}", e);
+        }
+    });
+}
+
+pub fn init(app: &AppHandle) -> Result<(), String> {
+    app.manage(InputStats::load(app)?);
+    if app.state::<SettingsStore>().get()?.input_stats {
+        app.state::<InputStats>()
+            .enabled
+            .store(true, Ordering::Relaxed);
+        start_listener(app);
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_input_stats(app: AppHandle, enabled: bool) -> Result<(), String> {
+    if enabled && !permissions::is_granted(PermissionKind::InputMonitoring) {
+        return Err("Grant the Input Monitoring permission first".to_string());
+    }
+    app.state::<SettingsStore>()
+        .update(|s| s.input_stats = enabled)?;
+    app.state::<InputStats>()
+        .enabled
+        .store(enabled, Ordering::Relaxed);
+    if enabled {
+        start_listener(&app);
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_input_stats(
+    stats: State<'_, InputStats>,
+    range: DateRange,
+) -> Result<Vec<InputMinute>, String> {
+    stats.in_range(range)
+}
@@ -0,0 +1,164 @@
+use chrono::Utc;
+use discord_rich_presence::activity::{Activity, Timestamps};
+use discord_rich_presence::{DiscordIpc, DiscordIpcClient};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Listener, Manager, State};
+
+use crate::background;
+use crate::flags::{self, FeatureFlags};
+use crate::settings::SettingsStore;
+use crate::timer::TimerManager;
+
+// Discord rate-limits presence updates to one every 15 seconds
+const REFRESH_INTERVAL: Duration = Duration::from_secs(15);
+// Application registered on the Discord developer portal, baked in at build time
+const BUILD_APPLICATION_ID: Option<&str> = option_env!("FTT_DISCORD_APP_ID");
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DiscordSettings {
+    pub enabled: bool,
+    // Shows "Tracking time" instead of the task name
+    pub hide_project: bool,
+    // Overrides the built-in application, e.g. for custom artwork
+    pub application_id: Option<String>,
+}
+
+// The IPC client is None while Discord isn't running; `shown` is the activity last
+// sent, so unchanged text isn't resent every tick.
+#[derive(Default)]
+pub struct DiscordPresence {
+    client: Mutex<Option<DiscordIpcClient>>,
+    shown: Mutex<Option<String>>,
+}
+
+fn elapsed(seconds: i64) -> String {
+    let (hours, minutes) = (seconds / 3600, (seconds % 3600) / 60);
+    if hours > 0 {
+        format!("{} h {} m", hours, minutes)
+    } else {
+        format!("{} m", minutes)
+    }
+}
+
+impl DiscordPresence {
+    fn disconnect(&self) {
+        if let Ok(mut client) = self.client.lock() {
+            if let Some(mut client) = client.take() {
+                let _ = client.clear_activity();
+                let _ = client.close();
+            }
+        }
+        if let Ok(mut shown) = self.shown.lock() {
+            *shown = None;
+        }
+    }
+
+    // Brings the presence in line with the running timer.
+    fn refresh(&self, app: &AppHandle) -> Result<(), String> {
+        let settings = app.state::<SettingsStore>().get()?.discord;
+        let application_id = settings
+            .application_id
+            .clone()
+            .or(BUILD_APPLICATION_ID.map(str::to_string));
+        let (true, Some(application_id)) = (
+            settings.enabled && app.state::<FeatureFlags>().is_enabled(flags::INTEGRATIONS),
+            application_id,
+        ) else {
+            self.disconnect();
+            return Ok(());
+        };
+
+        let mut client = self.client.lock().map_err(|e| e.to_string())?;
+        let mut shown = self.shown.lock().map_err(|e| e.to_string())?;
+        if client.is_none() {
+            let mut connecting =
+                DiscordIpcClient::new(&application_id).map_err(|e| e.to_string())?;
+            // Discord not running; try again next tick
+            if connecting.connect().is_err() {
+                return Ok(());
+            }
+            log::info!("connected to Discord");
+            *client = Some(connecting);
+            *shown = None;
+        }
+        let Some(ipc) = client.as_mut() else {
+            return Ok(());
+        };
+
+        let result = match app.state::<TimerManager>().active() {
+            Some(timer) => {
+                let project = match (&timer.title, settings.hide_project) {
+                    (Some(title), false) => title.clone(),
+                    _ => "Tracking time".to_string(),
+                };
+                let details = format!(
+                    "Tracking: {} \u{2013} {}",
+                    project,
+                    elapsed((Utc::now() - timer.started_at).num_seconds())
+                );
+                if shown.as_deref() == Some(details.as_str()) {
+                    return Ok(());
+                }
+                let activity = Activity::new()
+                    .details(&details)
+                    .timestamps(Timestamps::new().start(timer.started_at.timestamp()));
+                ipc.set_activity(activity).map(|_| Some(details))
+            }
+            None if shown.is_some() => ipc.clear_activity().map(|_| None),
+            None => return Ok(()),
+        };
+        match result {
+            Ok(details) => {
+                *shown = details;
+                Ok(())
+            }
+            Err(e) => {
+                // Discord closed or restarted; reconnect on the next refresh
+                *client = None;
+                *shown = None;
+                Err(format!("Discord presence update failed: {}", e))
+            }
+        }
+    }
+}
+
+fn spawn_refresh(app: &AppHandle) {
+    let handle = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        if let Err(e) = handle.state::<DiscordPresence>().refresh(&handle) {
+            log::warn!("{}", e);
+        }
+    });
+}
+
+pub fn register(app: &AppHandle) {
+    app.manage(DiscordPresence::default());
+    // Start and stop show up immediately; the ticker keeps the elapsed time current and
+    // reconnects after Discord restarts
+    for event in ["timer-started", "timer-stopped"] {
+        let handle = app.clone();
+        app.listen(event, move |_| spawn_refresh(&handle));
+    }
+    background::spawn_thread(app, "discord_presence", |app, task| {
+        while task.sleep_blocking(REFRESH_INTERVAL) {
+            if let Err(e) = app.state::<DiscordPresence>().refresh(&app) {
+                log::debug!("{}", e);
+            }
+        }
+        app.state::<DiscordPresence>().disconnect();
+    });
+}
+
+#[tauri::command]
+pub fn set_discord_settings(
+    app: AppHandle,
+    settings: State<'_, SettingsStore>,
+    discord: DiscordSettings,
+) -> Result<(), String> {
+    settings.update(|s| s.discord = discord)?;
+    spawn_refresh(&app);
+    Ok(())
+}
@@ -0,0 +1,226 @@
+use midir::{Ignore, MidiInput};
+use serde::{Deserialize, Serialize};
+use std::sync::mpsc::{self, Sender};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::settings::SettingsStore;
+use crate::timer::TimerManager;
+
+const CLIENT_NAME: &str = "ftt-triggers";
+
+// A physical button: a MIDI note or controller, or a named trigger posted to the local
+// API (used by the Stream Deck plugin)
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "source", rename_all = "snake_case")]
+pub enum Trigger {
+    Midi {
+        // 0-15; None matches any channel
+        #[serde(default)]
+        channel: Option<u8>,
+        // Note number for note-on messages, controller number for CC messages
+        note: u8,
+    },
+    Named {
+        name: String,
+    },
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum TriggerAction {
+    Start {
+        task_id: u64,
+        #[serde(default)]
+        title: Option<String>,
+    },
+    Stop,
+    // Stops the task if it is the primary timer, otherwise starts it
+    Toggle {
+        task_id: u64,
+        #[serde(default)]
+        title: Option<String>,
+    },
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct TriggerBinding {
+    pub trigger: Trigger,
+    pub action: TriggerAction,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HardwareSettings {
+    pub midi_enabled: bool,
+    // Input port name from list_midi_ports; None uses the first port
+    pub midi_port: Option<String>,
+    pub bindings: Vec<TriggerBinding>,
+}
+
+// Stop signal for the thread holding the MIDI connection; midir connections aren't
+// Send on every backend, so they never leave that thread.
+#[derive(Default)]
+pub struct HardwareTriggers {
+    midi_stop: Mutex<Option<Sender<()>>>,
+}
+
+fn run_action(app: &AppHandle, action: &TriggerAction) -> Result<(), String> {
+    let timers = app.state::<TimerManager>();
+    match action {
+        TriggerAction::Start { task_id, title } => {
+            timers.start(app, *task_id, title.clone())?;
+        }
+        TriggerAction::Stop => {
+            timers.stop(app)?;
+        }
+        TriggerAction::Toggle { task_id, title } => {
+            if timers.active().is_some_and(|t| t.task_id == *task_id) {
+                timers.stop(app)?;
+            } else {
+                timers.start(app, *task_id, title.clone())?;
+            }
+        }
+    }
+    Ok(())
+}
+
+// Runs every binding for the trigger; returns whether any matched.
+pub fn fire(app: &AppHandle, trigger: &Trigger) -> Result<bool, String> {
+    let bindings = app.state::<SettingsStore>().get()?.hardware.bindings;
+    let mut matched = false;
+    for binding in bindings
+        .iter()
+        .filter(|b| binding_matches(&b.trigger, trigger))
+    {
+        matched = true;
+        run_action(app, &binding.action)?;
+    }
+    let _ = app.emit("hardware-trigger", trigger);
+    Ok(matched)
+}
+
+fn binding_matches(bound: &Trigger, fired: &Trigger) -> bool {
+    match (bound, fired) {
+        (
+            Trigger::Midi { channel, note },
+            Trigger::Midi {
+                channel: fired_channel,
+                note: fired_note,
+            },
+        ) => note == fired_note && (channel.is_none() || channel == fired_channel),
+        (bound, fired) => bound == fired,
+    }
+}
+
+// Button presses arrive as note-on with non-zero velocity or CC with a non-zero value
+fn parse_midi(message: &[u8]) -> Option<Trigger> {
+    let &[status, note, value, ..] = message else {
+        return None;
+    };
+    let pressed = matches!(status & 0xF0, 0x90 | 0xB0) && value > 0;
+    pressed.then_some(Trigger::Midi {
+        channel: Some(status & 0x0F),
+        note,
+    })
+}
+
+fn midi_input() -> Result<MidiInput, String> {
+    let mut input = MidiInput::new(CLIENT_NAME).map_err(|e| e.to_string())?;
+    input.ignore(Ignore::All);
+    Ok(input)
+}
+
+fn start_midi(app: &AppHandle, port_name: Option<String>) -> Result<Sender<()>, String> {
+    let (tx, rx) = mpsc::channel();
+    let (ready_tx, ready_rx) = mpsc::channel();
+    let handle = app.clone();
+    std::thread::spawn(move || {
+        let connect = || -> Result<_, String> {
+            let input = midi_input()?;
+            let ports = input.ports();
+            let port = match &port_name {
+                Some(name) => ports
+                    .iter()
+                    .find(|p| input.port_name(p).is_ok_and(|n| &n == name))
+                    .ok_or_else(|| format!("MIDI port not found: {}", name))?,
+                None => ports.first().ok_or("No MIDI input ports")?,
+            }
+            .clone();
+            input
+                .connect(
+                    &port,
+                    CLIENT_NAME,
+                    move |_, message, _| {
+                        if let Some(trigger) = parse_midi(message) {
+                            if let Err(e) = fire(&handle, &trigger) {
+                                log::warn!("MIDI trigger failed: {}", e);
+                            }
+                        }
+                    },
+                    (),
+                )
+                .map_err(|e| e.to_string())
+        };
+        match connect() {
+            Ok(connection) => {
+                let _ = ready_tx.send(Ok(()));
+                // Held until asked to stop or the sender is dropped
+                let _ = rx.recv();
+                connection.close();
+            }
+            Err(e) => {
+                let _ = ready_tx.send(Err(e));
+            }
+        }
+    });
+    ready_rx
+        .recv()
+        .map_err(|_| "MIDI listener exited".to_string())??;
+    Ok(tx)
+}
+
+impl HardwareTriggers {
+    // Reconnects MIDI to match the settings.
+    pub fn apply(&self, app: &AppHandle, settings: &HardwareSettings) -> Result<(), String> {
+        let mut stop = self.midi_stop.lock().map_err(|e| e.to_string())?;
+        if let Some(previous) = stop.take() {
+            let _ = previous.send(());
+        }
+        if settings.midi_enabled {
+            *stop = Some(start_midi(app, settings.midi_port.clone())?);
+        }
+        Ok(())
+    }
+}
+
+pub fn init(app: &AppHandle) {
+    let triggers = HardwareTriggers::default();
+    if let Ok(settings) = app.state::<SettingsStore>().get() {
+        if let Err(e) = triggers.apply(app, &settings.hardware) {
+            log::warn!("hardware triggers unavailable: {}", e);
+        }
+    }
+    app.manage(triggers);
+}
+
+#[tauri::command]
+pub fn list_midi_ports() -> Result<Vec<String>, String> {
+    let input = midi_input()?;
+    Ok(input
+        .ports()
+        .iter()
+        .filter_map(|p| input.port_name(p).ok())
+        .collect())
+}
+
+#[tauri::command]
+pub fn set_hardware_triggers(
+    app: AppHandle,
+    triggers: State<'_, HardwareTriggers>,
+    settings: State<'_, SettingsStore>,
+    hardware: HardwareSettings,
+) -> Result<(), String> {
+    let updated = settings.update(|s| s.hardware = hardware)?;
+    triggers.apply(&app, &updated.hardware)
+}
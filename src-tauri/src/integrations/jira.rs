@@ -0,0 +1,273 @@
+use futures_util::FutureExt;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+
+use crate::background;
+use crate::flags::{self, FeatureFlags};
+use crate::secrets;
+use crate::sessions::{Session, SessionStore};
+use crate::settings::SettingsStore;
+use crate::storage;
+
+pub(crate) const TOKEN_KEY: &str = "jira-token";
+const RETRY_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JiraSettings {
+    pub base_url: Option<String>,
+    // Jira Cloud authenticates with email + API token; Server/Data Center uses a bearer PAT
+    pub email: Option<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PendingWorklog {
+    pub session_id: u64,
+    pub issue_key: String,
+    pub attempts: u32,
+    pub last_error: Option<String>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct JiraData {
+    pending: Vec<PendingWorklog>,
+    // Session id -> Jira worklog id, so a session is never logged twice
+    pushed: HashMap<u64, String>,
+}
+
+pub struct JiraQueue {
+    path: PathBuf,
+    data: Mutex<JiraData>,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum PushResult {
+    Pushed { worklog_id: String },
+    Queued { reason: String },
+}
+
+enum PushError {
+    Retryable(String),
+    Fatal(String),
+}
+
+impl JiraQueue {
+    pub fn load(app: &AppHandle) -> Result<Self, String> {
+        let path = storage::data_file(app, "jira.json")?;
+        let data = storage::load_json(&path)?;
+        Ok(Self {
+            path,
+            data: Mutex::new(data),
+        })
+    }
+
+    pub fn pending(&self) -> Result<Vec<PendingWorklog>, String> {
+        self.update(|data| data.pending.clone())
+    }
+
+    fn update<T>(&self, f: impl FnOnce(&mut JiraData) -> T) -> Result<T, String> {
+        let mut data = self.data.lock().map_err(|e| e.to_string())?;
+        let result = f(&mut data);
+        storage::save_json(&self.path, &*data)?;
+        Ok(result)
+    }
+}
+
+// Finds the first Jira-style issue key (e.g. "FTT-123") in free text.
+pub fn parse_issue_key(text: &str) -> Option<String> {
+    text.split(|c: char| !(c.is_ascii_alphanumeric() || c == '-'))
+        .find_map(|word| {
+            let (project, number) = word.split_once('-')?;
+            let valid_project = project.len() >= 2
+                && project.starts_with(|c: char| c.is_ascii_uppercase())
+                && project
+                    .chars()
+                    .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit());
+            let valid_number = !number.is_empty() && number.chars().all(|c| c.is_ascii_digit());
+            (valid_project && valid_number).then(|| word.to_string())
+        })
+}
+
+async fn send(app: &AppHandle, session: &Session, issue_key: &str) -> Result<String, PushError> {
+    let settings = app
+        .state::<SettingsStore>()
+        .get()
+        .map_err(PushError::Fatal)?
+        .jira;
+    let base_url = settings
+        .base_url
+        .ok_or_else(|| PushError::Fatal("Jira is not configured".to_string()))?;
+    let token = secrets::get(TOKEN_KEY)
+        .map_err(PushError::Fatal)?
+        .ok_or_else(|| PushError::Fatal("Jira token is missing".to_string()))?;
+
+    let body = serde_json::json!({
+        "started": session.start.format("%Y-%m-%dT%H:%M:%S%.3f%z").to_string(),
+        "timeSpentSeconds": (session.end - session.start).num_seconds().max(60),
+        "comment": session.note.clone().or_else(|| session.title.clone()).unwrap_or_default(),
+    });
+    let url = format!(
+        "{}/rest/api/2/issue/{}/worklog",
+        base_url.trim_end_matches('/'),
+        issue_key
+    );
+    let request = reqwest::Client::new().post(url).json(&body);
+    let request = match settings.email {
+        Some(email) => request.basic_auth(email, Some(token)),
+        None => request.bearer_auth(token),
+    };
+
+    let response = request
+        .send()
+        .await
+        .map_err(|e| PushError::Retryable(e.to_string()))?;
+    let status = response.status();
+    if status.is_server_error() || status.as_u16() == 429 {
+        return Err(PushError::Retryable(format!("Jira responded {}", status)));
+    }
+    if !status.is_success() {
+        let text = response.text().await.unwrap_or_default();
+        return Err(PushError::Fatal(format!(
+            "Jira responded {}: {}",
+            status, text
+        )));
+    }
+    let created: serde_json::Value = response
+        .json()
+        .await
+        .map_err(|e| PushError::Fatal(e.to_string()))?;
+    Ok(created["id"].as_str().unwrap_or_default().to_string())
+}
+
+async fn push(app: &AppHandle, session_id: u64, issue_key: &str) -> Result<String, PushError> {
+    let session = app
+        .state::<SessionStore>()
+        .get(session_id)
+        .map_err(PushError::Fatal)?;
+    let worklog_id = send(app, &session, issue_key).await?;
+    let _ = app.state::<JiraQueue>().update(|data| {
+        data.pending.retain(|p| p.session_id != session_id);
+        data.pushed.insert(session_id, worklog_id.clone());
+    });
+    Ok(worklog_id)
+}
+
+// Retry every queued worklog once, dropping the ones Jira rejects outright.
+pub async fn flush_pending(app: &AppHandle) {
+    if !app.state::<FeatureFlags>().is_enabled(flags::INTEGRATIONS) {
+        return;
+    }
+    let pending = app
+        .state::<JiraQueue>()
+        .update(|data| data.pending.clone())
+        .unwrap_or_default();
+    for item in pending {
+        let error = match push(app, item.session_id, &item.issue_key).await {
+            Ok(_) => continue,
+            Err(PushError::Retryable(e)) => e,
+            Err(PushError::Fatal(e)) => {
+                log::error!(
+                    "dropping Jira worklog for session {}: {}",
+                    item.session_id,
+                    e
+                );
+                let _ = app
+                    .state::<JiraQueue>()
+                    .update(|data| data.pending.retain(|p| p.session_id != item.session_id));
+                continue;
+            }
+        };
+        let _ = app.state::<JiraQueue>().update(|data| {
+            if let Some(p) = data
+                .pending
+                .iter_mut()
+                .find(|p| p.session_id == item.session_id)
+            {
+                p.attempts += 1;
+                p.last_error = Some(error);
+            }
+        });
+    }
+}
+
+pub fn start_retry_loop(app: AppHandle) {
+    background::spawn_async(&app, "jira_retry", |app, task| {
+        async move {
+            while task.sleep(RETRY_INTERVAL).await {
+                flush_pending(&app).await;
+            }
+        }
+        .boxed()
+    });
+}
+
+#[tauri::command]
+pub async fn push_worklog(
+    app: AppHandle,
+    session_id: u64,
+    issue_key: Option<String>,
+) -> Result<PushResult, String> {
+    if !app.state::<FeatureFlags>().is_enabled(flags::INTEGRATIONS) {
+        return Err("Integrations are disabled".to_string());
+    }
+    let queue = app.state::<JiraQueue>();
+    if let Some(worklog_id) = queue.update(|data| data.pushed.get(&session_id).cloned())? {
+        return Ok(PushResult::Pushed { worklog_id });
+    }
+
+    let issue_key = match issue_key {
+        Some(key) => key,
+        None => {
+            let session = app.state::<SessionStore>().get(session_id)?;
+            session
+                .title
+                .as_deref()
+                .and_then(parse_issue_key)
+                .ok_or_else(|| "No Jira issue key found in the task name".to_string())?
+        }
+    };
+
+    match push(&app, session_id, &issue_key).await {
+        Ok(worklog_id) => Ok(PushResult::Pushed { worklog_id }),
+        Err(PushError::Fatal(e)) => Err(e),
+        Err(PushError::Retryable(reason)) => {
+            queue.update(|data| {
+                if !data.pending.iter().any(|p| p.session_id == session_id) {
+                    data.pending.push(PendingWorklog {
+                        session_id,
+                        issue_key,
+                        attempts: 1,
+                        last_error: Some(reason.clone()),
+                    });
+                }
+            })?;
+            Ok(PushResult::Queued { reason })
+        }
+    }
+}
+
+#[tauri::command]
+pub fn get_pending_worklogs(queue: State<'_, JiraQueue>) -> Result<Vec<PendingWorklog>, String> {
+    queue.pending()
+}
+
+#[tauri::command]
+pub fn set_jira_config(
+    settings: State<'_, SettingsStore>,
+    base_url: Option<String>,
+    email: Option<String>,
+    token: Option<String>,
+) -> Result<(), String> {
+    match token.as_deref() {
+        Some("") => secrets::delete(TOKEN_KEY)?,
+        Some(token) => secrets::set(TOKEN_KEY, token)?,
+        None => {}
+    }
+    settings.update(|s| s.jira = JiraSettings { base_url, email })?;
+    Ok(())
+}
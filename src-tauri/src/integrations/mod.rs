@@ -0,0 +1,5 @@
+pub mod discord;
+pub mod hardware;
+pub mod jira;
+pub mod mqtt;
+pub mod slack;
@@ -0,0 +1,294 @@
+use chrono::{DateTime, Utc};
+use rumqttc::{Client, Event, LastWill, MqttOptions, Packet, QoS, Transport};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Listener, Manager, State};
+
+use crate::idle::IdleMonitor;
+use crate::secrets;
+use crate::settings::SettingsStore;
+use crate::timer::TimerManager;
+
+pub(crate) const PASSWORD_KEY: &str = "mqtt_password";
+const KEEP_ALIVE: Duration = Duration::from_secs(30);
+const RECONNECT_DELAY: Duration = Duration::from_secs(10);
+const STATE_EVENTS: [&str; 4] = ["timer-started", "timer-stopped", "user-idle", "user-active"];
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MqttConfig {
+    pub enabled: bool,
+    pub host: String,
+    pub port: u16,
+    pub tls: bool,
+    // PEM bundle for brokers with a private CA; None trusts the system roots
+    pub ca_cert: Option<PathBuf>,
+    pub username: Option<String>,
+    pub client_id: String,
+    // Retained JSON with the timer and idle state
+    pub status_topic: String,
+    // Retained plain "on"/"off" and "idle"/"active", for automations that
+    // can't parse JSON
+    pub timer_topic: Option<String>,
+    pub idle_topic: Option<String>,
+    // Leaves the task name out of published state
+    pub hide_task: bool,
+}
+
+impl Default for MqttConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            host: "localhost".to_string(),
+            port: 1883,
+            tls: false,
+            ca_cert: None,
+            username: None,
+            client_id: "ftt-desktop".to_string(),
+            status_topic: "ftt/status".to_string(),
+            timer_topic: None,
+            idle_topic: None,
+            hide_task: false,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MqttState {
+    #[default]
+    Disabled,
+    Connecting,
+    Connected,
+    Disconnected,
+}
+
+#[derive(Clone, Default, Serialize)]
+pub struct MqttStatus {
+    pub state: MqttState,
+    pub connected_since: Option<DateTime<Utc>>,
+    pub last_published: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+}
+
+#[derive(Serialize)]
+struct PresencePayload {
+    online: bool,
+    timer_running: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    task: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    started_at: Option<DateTime<Utc>>,
+    idle: bool,
+}
+
+// The connection runs on its own thread; `stopped` tells it to exit once the
+// client is disconnected.
+struct Connection {
+    client: Client,
+    config: MqttConfig,
+    stopped: Arc<AtomicBool>,
+}
+
+#[derive(Default)]
+pub struct Mqtt {
+    connection: Mutex<Option<Connection>>,
+    status: Mutex<MqttStatus>,
+}
+
+impl Mqtt {
+    fn update(&self, f: impl FnOnce(&mut MqttStatus)) {
+        if let Ok(mut status) = self.status.lock() {
+            f(&mut status);
+        }
+    }
+
+    pub fn status(&self) -> Result<MqttStatus, String> {
+        self.status
+            .lock()
+            .map(|s| s.clone())
+            .map_err(|e| e.to_string())
+    }
+
+    // Reconnects to match the config.
+    pub fn apply(&self, app: &AppHandle, config: &MqttConfig) -> Result<(), String> {
+        let mut connection = self.connection.lock().map_err(|e| e.to_string())?;
+        if let Some(previous) = connection.take() {
+            previous.stopped.store(true, Ordering::SeqCst);
+            let _ = previous.client.try_disconnect();
+        }
+        self.update(|s| *s = MqttStatus::default());
+        if config.enabled {
+            *connection = Some(connect(app, config)?);
+        }
+        Ok(())
+    }
+
+    // Publishes the current timer and idle state to the configured topics.
+    fn publish_state(&self, app: &AppHandle) {
+        let Ok(connection) = self.connection.lock() else {
+            return;
+        };
+        let Some(connection) = connection.as_ref() else {
+            return;
+        };
+        let config = &connection.config;
+        let timer = app.state::<TimerManager>().active();
+        // An early ConnAck can arrive before the idle monitor is managed
+        let idle = app
+            .try_state::<IdleMonitor>()
+            .is_some_and(|monitor| monitor.state().is_idle());
+        let payload = PresencePayload {
+            online: true,
+            timer_running: timer.is_some(),
+            task: timer
+                .as_ref()
+                .filter(|_| !config.hide_task)
+                .and_then(|t| t.title.clone()),
+            started_at: timer.as_ref().map(|t| t.started_at),
+            idle,
+        };
+        let mut messages = vec![(
+            config.status_topic.clone(),
+            serde_json::to_string(&payload).unwrap_or_default(),
+        )];
+        if let Some(topic) = &config.timer_topic {
+            let value = if timer.is_some() { "on" } else { "off" };
+            messages.push((topic.clone(), value.to_string()));
+        }
+        if let Some(topic) = &config.idle_topic {
+            let value = if idle { "idle" } else { "active" };
+            messages.push((topic.clone(), value.to_string()));
+        }
+        for (topic, message) in messages {
+            // Queued for the connection thread; dropped if the queue is full
+            if let Err(e) = connection
+                .client
+                .try_publish(topic, QoS::AtLeastOnce, true, message)
+            {
+                log::warn!("failed to queue MQTT publish: {}", e);
+                return;
+            }
+        }
+        self.update(|s| s.last_published = Some(Utc::now()));
+    }
+}
+
+fn options(config: &MqttConfig) -> Result<MqttOptions, String> {
+    let mut options = MqttOptions::new(&config.client_id, &config.host, config.port);
+    options.set_keep_alive(KEEP_ALIVE);
+    if let Some(username) = &config.username {
+        let password = secrets::get(PASSWORD_KEY)?.unwrap_or_default();
+        options.set_credentials(username, password);
+    }
+    if config.tls {
+        let transport = match &config.ca_cert {
+            Some(path) => {
+                let ca = std::fs::read(path)
+                    .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+                Transport::tls(ca, None, None)
+            }
+            None => Transport::tls_with_default_config(),
+        };
+        options.set_transport(transport);
+    }
+    // The broker marks us offline if the app dies without disconnecting
+    let offline = serde_json::json!({ "online": false }).to_string();
+    options.set_last_will(LastWill::new(
+        &config.status_topic,
+        offline,
+        QoS::AtLeastOnce,
+        true,
+    ));
+    Ok(options)
+}
+
+fn connect(app: &AppHandle, config: &MqttConfig) -> Result<Connection, String> {
+    let (client, mut connection) = Client::new(options(config)?, 16);
+    let stopped = Arc::new(AtomicBool::new(false));
+    let handle = app.clone();
+    let stop = stopped.clone();
+    app.state::<Mqtt>()
+        .update(|s| s.state = MqttState::Connecting);
+    std::thread::spawn(move || {
+        let mqtt = handle.state::<Mqtt>();
+        // Polling the connection drives it; rumqttc reconnects on the next poll after an error
+        for event in connection.iter() {
+            if stop.load(Ordering::SeqCst) {
+                break;
+            }
+            match event {
+                Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                    mqtt.update(|s| {
+                        s.state = MqttState::Connected;
+                        s.connected_since = Some(Utc::now());
+                        s.last_error = None;
+                    });
+                    mqtt.publish_state(&handle);
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    log::warn!("MQTT connection failed: {}", e);
+                    mqtt.update(|s| {
+                        s.state = MqttState::Disconnected;
+                        s.connected_since = None;
+                        s.last_error = Some(e.to_string());
+                    });
+                    std::thread::sleep(RECONNECT_DELAY);
+                    if stop.load(Ordering::SeqCst) {
+                        break;
+                    }
+                }
+            }
+        }
+    });
+    Ok(Connection {
+        client,
+        config: config.clone(),
+        stopped,
+    })
+}
+
+pub fn init(app: &AppHandle) {
+    app.manage(Mqtt::default());
+    if let Ok(settings) = app.state::<SettingsStore>().get() {
+        if let Err(e) = app.state::<Mqtt>().apply(app, &settings.mqtt) {
+            log::warn!("MQTT unavailable: {}", e);
+        }
+    }
+    for event in STATE_EVENTS {
+        let handle = app.clone();
+        app.listen(event, move |_| {
+            handle.state::<Mqtt>().publish_state(&handle)
+        });
+    }
+}
+
+// `password`: None keeps the stored one, an empty string removes it.
+#[tauri::command]
+pub fn set_mqtt_config(
+    app: AppHandle,
+    mqtt: State<'_, Mqtt>,
+    settings: State<'_, SettingsStore>,
+    config: MqttConfig,
+    password: Option<String>,
+) -> Result<(), String> {
+    if config.enabled && (config.host.trim().is_empty() || config.status_topic.is_empty()) {
+        return Err("A broker host and status topic are required".to_string());
+    }
+    match password.as_deref() {
+        Some("") => secrets::delete(PASSWORD_KEY)?,
+        Some(password) => secrets::set(PASSWORD_KEY, password)?,
+        None => {}
+    }
+    let updated = settings.update(|s| s.mqtt = config)?;
+    mqtt.apply(&app, &updated.mqtt)
+}
+
+#[tauri::command]
+pub fn get_mqtt_status(mqtt: State<'_, Mqtt>) -> Result<MqttStatus, String> {
+    mqtt.status()
+}
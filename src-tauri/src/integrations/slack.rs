@@ -0,0 +1,144 @@
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Listener, Manager, State};
+
+use crate::flags::{self, FeatureFlags};
+use crate::secrets;
+use crate::settings::SettingsStore;
+
+pub(crate) const TOKEN_KEY: &str = "slack-token";
+const API: &str = "https://slack.com/api";
+// Slack caps snooze length; the status is cleared explicitly on stop anyway
+const DND_MINUTES: u32 = 8 * 60;
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SlackSettings {
+    pub connected: bool,
+    pub set_dnd: bool,
+}
+
+#[derive(Serialize)]
+pub struct SlackConnection {
+    pub team: String,
+    pub user: String,
+}
+
+async fn call(
+    token: &str,
+    method: &str,
+    body: serde_json::Value,
+) -> Result<serde_json::Value, String> {
+    let response: serde_json::Value = reqwest::Client::new()
+        .post(format!("{}/{}", API, method))
+        .bearer_auth(token)
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?
+        .json()
+        .await
+        .map_err(|e| e.to_string())?;
+    if response["ok"].as_bool() != Some(true) {
+        return Err(format!(
+            "Slack {} failed: {}",
+            method,
+            response["error"].as_str().unwrap_or("unknown error")
+        ));
+    }
+    Ok(response)
+}
+
+async fn set_status(app: &AppHandle, title: Option<String>) -> Result<(), String> {
+    let settings = app.state::<SettingsStore>().get()?.slack;
+    if !settings.connected {
+        return Ok(());
+    }
+    let Some(token) = secrets::get(TOKEN_KEY)? else {
+        return Ok(());
+    };
+
+    let profile = match &title {
+        Some(title) => serde_json::json!({
+            "status_text": format!("Focusing on {}", title),
+            "status_emoji": ":red_circle:",
+            "status_expiration": 0,
+        }),
+        None => serde_json::json!({ "status_text": "", "status_emoji": "" }),
+    };
+    call(
+        &token,
+        "users.profile.set",
+        serde_json::json!({ "profile": profile }),
+    )
+    .await?;
+
+    if settings.set_dnd {
+        match title {
+            Some(_) => {
+                call(
+                    &token,
+                    "dnd.setSnooze",
+                    serde_json::json!({ "num_minutes": DND_MINUTES }),
+                )
+                .await?
+            }
+            None => call(&token, "dnd.endSnooze", serde_json::json!({})).await?,
+        };
+    }
+    Ok(())
+}
+
+fn spawn_status_update(app: AppHandle, title: Option<String>) {
+    if !app.state::<FeatureFlags>().is_enabled(flags::INTEGRATIONS) {
+        return;
+    }
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = set_status(&app, title).await {
+            log::warn!("{}", e);
+        }
+    });
+}
+
+// Mirror the timer into the Slack status: set on start, clear on stop or idle.
+pub fn register(app: &AppHandle) {
+    let handle = app.clone();
+    app.listen("timer-started", move |event| {
+        let title = serde_json::from_str::<serde_json::Value>(event.payload())
+            .ok()
+            .and_then(|payload| payload["title"].as_str().map(str::to_string))
+            .unwrap_or_else(|| "a task".to_string());
+        spawn_status_update(handle.clone(), Some(title));
+    });
+    for event in ["timer-stopped", "user-idle"] {
+        let handle = app.clone();
+        app.listen(event, move |_| spawn_status_update(handle.clone(), None));
+    }
+}
+
+#[tauri::command]
+pub async fn connect_slack(app: AppHandle, token: String) -> Result<SlackConnection, String> {
+    let identity = call(&token, "auth.test", serde_json::json!({})).await?;
+    secrets::set(TOKEN_KEY, &token)?;
+    app.state::<SettingsStore>()
+        .update(|s| s.slack.connected = true)?;
+    Ok(SlackConnection {
+        team: identity["team"].as_str().unwrap_or_default().to_string(),
+        user: identity["user"].as_str().unwrap_or_default().to_string(),
+    })
+}
+
+#[tauri::command]
+pub async fn disconnect_slack(app: AppHandle) -> Result<(), String> {
+    // Leave the user without a stale "Focusing on" status
+    let _ = set_status(&app, None).await;
+    secrets::delete(TOKEN_KEY)?;
+    app.state::<SettingsStore>()
+        .update(|s| s.slack.connected = false)?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_slack_dnd(settings: State<'_, SettingsStore>, enabled: bool) -> Result<(), String> {
+    settings.update(|s| s.slack.set_dnd = enabled)?;
+    Ok(())
+}
@@ -0,0 +1,184 @@
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use tauri::State;
+
+use crate::csv;
+use crate::org_policy::{self, OrgPolicies};
+use crate::sessions::{DateRange, NewSession, Session, SessionStore};
+
+// Toggl Track "Detailed report" CSV columns. Times are in the exporting user's local zone.
+// A trailing "Notes" column carries session notes; Toggl ignores columns it doesn't know.
+const TOGGL_HEADER: [&str; 11] = [
+    "User",
+    "Email",
+    "Project",
+    "Description",
+    "Start date",
+    "Start time",
+    "End date",
+    "End time",
+    "Duration",
+    "Tags",
+    "Notes",
+];
+
+#[derive(Serialize)]
+pub struct ImportSummary {
+    pub imported: usize,
+    // Row number (1-based, excluding the header) and reason for each row not imported
+    pub skipped: Vec<(usize, String)>,
+}
+
+fn parse_local(date: &str, time: &str) -> Result<DateTime<Utc>, String> {
+    let naive = NaiveDateTime::parse_from_str(&format!("{} {}", date, time), "%Y-%m-%d %H:%M:%S")
+        .map_err(|e| format!("invalid date/time '{} {}': {}", date, time, e))?;
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|dt| dt.with_timezone(&Utc))
+        .ok_or_else(|| format!("'{} {}' does not exist in the local timezone", date, time))
+}
+
+fn parse_task_id(project: &str, project_map: &HashMap<String, u64>) -> Option<u64> {
+    project_map.get(project).copied().or_else(|| {
+        // Projects exported by us are named "Task #<id>" when no name is known
+        project.strip_prefix("Task #")?.parse().ok()
+    })
+}
+
+fn import_row(
+    row: &[String],
+    columns: &HashMap<&str, usize>,
+    project_map: &HashMap<String, u64>,
+) -> Result<NewSession, String> {
+    let field = |name: &str| {
+        columns
+            .get(name)
+            .and_then(|&i| row.get(i))
+            .map(|s| s.trim())
+            .ok_or_else(|| format!("missing column '{}'", name))
+    };
+
+    let project = field("Project")?;
+    let task_id = parse_task_id(project, project_map)
+        .ok_or_else(|| format!("no task mapped for project '{}'", project))?;
+    let description = field("Description")?;
+    let tags = field("Tags")
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .map(str::to_string)
+        .collect();
+
+    Ok(NewSession {
+        task_id,
+        title: (!description.is_empty()).then(|| description.to_string()),
+        start: parse_local(field("Start date")?, field("Start time")?)?,
+        end: parse_local(field("End date")?, field("End time")?)?,
+        note: field("Notes")
+            .ok()
+            .filter(|n| !n.is_empty())
+            .map(str::to_string),
+        tags,
+        manual: true,
+        focus_breaches: Vec::new(),
+    })
+}
+
+pub fn import_toggl(
+    store: &SessionStore,
+    text: &str,
+    project_map: &HashMap<String, u64>,
+) -> Result<ImportSummary, String> {
+    let mut rows = csv::parse(text).into_iter();
+    let header = rows.next().ok_or_else(|| "CSV file is empty".to_string())?;
+    let columns: HashMap<&str, usize> = header
+        .iter()
+        .enumerate()
+        .map(|(i, name)| (name.trim(), i))
+        .collect();
+
+    let mut summary = ImportSummary {
+        imported: 0,
+        skipped: Vec::new(),
+    };
+    for (index, row) in rows.enumerate() {
+        let result = import_row(&row, &columns, project_map).and_then(|new| store.insert(new));
+        match result {
+            Ok(_) => summary.imported += 1,
+            Err(e) => summary.skipped.push((index + 1, e)),
+        }
+    }
+    Ok(summary)
+}
+
+fn format_duration(seconds: i64) -> String {
+    format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600,
+        (seconds % 3600) / 60,
+        seconds % 60
+    )
+}
+
+pub fn export_toggl(sessions: &[Session], project_names: &HashMap<u64, String>) -> String {
+    let mut out = String::new();
+    csv::push_row(&mut out, &TOGGL_HEADER);
+    for session in sessions {
+        let start = session.start.with_timezone(&Local);
+        let end = session.end.with_timezone(&Local);
+        let project = project_names
+            .get(&session.task_id)
+            .cloned()
+            .unwrap_or_else(|| format!("Task #{}", session.task_id));
+        csv::push_row(
+            &mut out,
+            &[
+                String::new(),
+                String::new(),
+                project,
+                session.title.clone().unwrap_or_default(),
+                start.format("%Y-%m-%d").to_string(),
+                start.format("%H:%M:%S").to_string(),
+                end.format("%Y-%m-%d").to_string(),
+                end.format("%H:%M:%S").to_string(),
+                format_duration((session.end - session.start).num_seconds()),
+                session.tags.join(", "),
+                session.note.clone().unwrap_or_default(),
+            ],
+        );
+    }
+    out
+}
+
+#[tauri::command]
+pub fn import_toggl_csv(
+    store: State<'_, SessionStore>,
+    path: PathBuf,
+    project_map: Option<HashMap<String, u64>>,
+) -> Result<ImportSummary, String> {
+    let text = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    import_toggl(&store, &text, &project_map.unwrap_or_default())
+}
+
+#[tauri::command]
+pub fn export_toggl_csv(
+    store: State<'_, SessionStore>,
+    policy: State<'_, OrgPolicies>,
+    path: PathBuf,
+    range: DateRange,
+    project_map: Option<HashMap<String, u64>>,
+) -> Result<usize, String> {
+    policy.check_export(org_policy::EXPORT_CSV)?;
+    let sessions = store.in_range(range)?;
+    let project_names = project_map
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(name, id)| (id, name))
+        .collect();
+    std::fs::write(&path, export_toggl(&sessions, &project_names)).map_err(|e| e.to_string())?;
+    Ok(sessions.len())
+}
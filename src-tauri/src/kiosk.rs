@@ -0,0 +1,342 @@
+use chrono::{DateTime, Utc};
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use tauri::ipc::Invoke;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::app_lock::{self, AppLock};
+use crate::secrets;
+use crate::sessions::DateRange;
+use crate::settings::SettingsStore;
+use crate::storage;
+
+const MIN_BADGE_LEN: usize = 4;
+
+// The only commands the kiosk screen can reach
+const KIOSK_COMMANDS: &[&str] = &[
+    "punch_in",
+    "punch_out",
+    "get_kiosk_state",
+    "exit_kiosk_mode",
+    // Metrics from the frontend's invoke wrapper
+    "record_command_result",
+];
+
+// Kept in settings so a restarted terminal comes back as a kiosk
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KioskSettings {
+    pub enabled: bool,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct KioskUser {
+    id: u64,
+    name: String,
+    // Salted SHA-256 of the badge code; codes themselves are never stored
+    badge_hash: String,
+}
+
+#[derive(Clone, Serialize)]
+pub struct KioskMember {
+    pub id: u64,
+    pub name: String,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Punch {
+    pub id: u64,
+    pub user_id: u64,
+    pub start: DateTime<Utc>,
+    pub end: Option<DateTime<Utc>>,
+}
+
+// Kiosk users and their punches, apart from the machine owner's sessions
+#[derive(Default, Serialize, Deserialize)]
+struct KioskData {
+    salt: String,
+    next_id: u64,
+    users: Vec<KioskUser>,
+    punches: Vec<Punch>,
+}
+
+#[derive(Clone, Serialize)]
+pub struct ClockedIn {
+    pub name: String,
+    pub since: DateTime<Utc>,
+}
+
+#[derive(Clone, Serialize)]
+pub struct KioskState {
+    pub active: bool,
+    pub clocked_in: Vec<ClockedIn>,
+}
+
+#[derive(Clone, Serialize)]
+pub struct PunchResult {
+    pub name: String,
+    pub punch: Punch,
+}
+
+pub struct Kiosk {
+    active: AtomicBool,
+    path: PathBuf,
+    data: Mutex<KioskData>,
+}
+
+impl Kiosk {
+    fn write<T>(&self, f: impl FnOnce(&mut KioskData) -> Result<T, String>) -> Result<T, String> {
+        let mut data = self.data.lock().map_err(|e| e.to_string())?;
+        let result = f(&mut data)?;
+        storage::save_json(&self.path, &*data)?;
+        Ok(result)
+    }
+}
+
+fn hash_badge(salt: &str, code: &str) -> String {
+    Sha256::new()
+        .chain_update(salt)
+        .chain_update(code.trim())
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+fn find_user(data: &KioskData, code: &str) -> Result<KioskUser, String> {
+    let hash = hash_badge(&data.salt, code);
+    data.users
+        .iter()
+        .find(|u| u.badge_hash == hash)
+        .cloned()
+        .ok_or_else(|| "Unknown badge".to_string())
+}
+
+pub fn is_active(app: &AppHandle) -> bool {
+    app.try_state::<Kiosk>()
+        .is_some_and(|k| k.active.load(Ordering::SeqCst))
+}
+
+// Fullscreen, on top and without window controls while active
+fn apply_window(app: &AppHandle, active: bool) {
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+    let _ = window.set_decorations(!active);
+    let _ = window.set_always_on_top(active);
+    let _ = window.set_closable(!active);
+    let _ = window.set_minimizable(!active);
+    let _ = window.set_fullscreen(active);
+    if active {
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+fn set_active(app: &AppHandle, active: bool) -> Result<(), String> {
+    app.state::<SettingsStore>()
+        .update(|s| s.kiosk.enabled = active)?;
+    app.state::<Kiosk>().active.store(active, Ordering::SeqCst);
+    apply_window(app, active);
+    let _ = app.emit("kiosk-mode-changed", active);
+    log::info!("kiosk mode {}", if active { "on" } else { "off" });
+    Ok(())
+}
+
+pub fn init(app: &AppHandle) -> Result<(), String> {
+    let path = storage::data_file(app, "kiosk.json")?;
+    let mut data: KioskData = storage::load_json(&path)?;
+    if data.salt.is_empty() {
+        data.salt = rand::thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(32)
+            .map(char::from)
+            .collect();
+    }
+    let active = app.state::<SettingsStore>().get()?.kiosk.enabled;
+    app.manage(Kiosk {
+        active: AtomicBool::new(active),
+        path,
+        data: Mutex::new(data),
+    });
+    if active {
+        apply_window(app, true);
+    }
+    Ok(())
+}
+
+// Wraps the command handler so the kiosk screen can't reach anything but punching.
+pub fn guarded(
+    handler: impl Fn(Invoke) -> bool + Send + Sync + 'static,
+) -> impl Fn(Invoke) -> bool + Send + Sync + 'static {
+    move |invoke: Invoke| {
+        let command = invoke.message.command();
+        let active = is_active(invoke.message.webview().app_handle());
+        if active && !KIOSK_COMMANDS.contains(&command) {
+            log::debug!(target: "commands", "{} refused in kiosk mode", command);
+            invoke.resolver.reject("Not available in kiosk mode");
+            return true;
+        }
+        handler(invoke)
+    }
+}
+
+#[tauri::command]
+pub fn punch_in(kiosk: State<'_, Kiosk>, code: String) -> Result<PunchResult, String> {
+    kiosk.write(|data| {
+        let user = find_user(data, &code)?;
+        if data
+            .punches
+            .iter()
+            .any(|p| p.user_id == user.id && p.end.is_none())
+        {
+            return Err(format!("{} is already clocked in", user.name));
+        }
+        data.next_id += 1;
+        let punch = Punch {
+            id: data.next_id,
+            user_id: user.id,
+            start: Utc::now(),
+            end: None,
+        };
+        data.punches.push(punch.clone());
+        Ok(PunchResult {
+            name: user.name,
+            punch,
+        })
+    })
+}
+
+#[tauri::command]
+pub fn punch_out(kiosk: State<'_, Kiosk>, code: String) -> Result<PunchResult, String> {
+    kiosk.write(|data| {
+        let user = find_user(data, &code)?;
+        let punch = data
+            .punches
+            .iter_mut()
+            .find(|p| p.user_id == user.id && p.end.is_none())
+            .ok_or_else(|| format!("{} is not clocked in", user.name))?;
+        punch.end = Some(Utc::now());
+        Ok(PunchResult {
+            name: user.name,
+            punch: punch.clone(),
+        })
+    })
+}
+
+#[tauri::command]
+pub fn get_kiosk_state(app: AppHandle, kiosk: State<'_, Kiosk>) -> Result<KioskState, String> {
+    let data = kiosk.data.lock().map_err(|e| e.to_string())?;
+    let clocked_in = data
+        .punches
+        .iter()
+        .filter(|p| p.end.is_none())
+        .filter_map(|p| {
+            let user = data.users.iter().find(|u| u.id == p.user_id)?;
+            Some(ClockedIn {
+                name: user.name.clone(),
+                since: p.start,
+            })
+        })
+        .collect();
+    Ok(KioskState {
+        active: is_active(&app),
+        clocked_in,
+    })
+}
+
+// Entering needs an app lock PIN, since that PIN is what gets the terminal back out.
+#[tauri::command]
+pub fn enter_kiosk_mode(app: AppHandle) -> Result<(), String> {
+    if secrets::get(app_lock::PIN_KEY)?.is_none() {
+        return Err("Set an app lock PIN before entering kiosk mode".to_string());
+    }
+    set_active(&app, true)
+}
+
+#[tauri::command]
+pub fn exit_kiosk_mode(
+    app: AppHandle,
+    lock: State<'_, AppLock>,
+    pin: String,
+) -> Result<(), String> {
+    if !lock.check_pin(&pin)? {
+        return Err("Incorrect PIN".to_string());
+    }
+    set_active(&app, false)
+}
+
+#[tauri::command]
+pub fn add_kiosk_user(
+    kiosk: State<'_, Kiosk>,
+    name: String,
+    code: String,
+) -> Result<KioskMember, String> {
+    if code.trim().chars().count() < MIN_BADGE_LEN {
+        return Err(format!(
+            "Badge codes must be at least {} characters",
+            MIN_BADGE_LEN
+        ));
+    }
+    kiosk.write(|data| {
+        let badge_hash = hash_badge(&data.salt, &code);
+        if data.users.iter().any(|u| u.badge_hash == badge_hash) {
+            return Err("That badge is already assigned".to_string());
+        }
+        data.next_id += 1;
+        data.users.push(KioskUser {
+            id: data.next_id,
+            name: name.clone(),
+            badge_hash,
+        });
+        Ok(KioskMember {
+            id: data.next_id,
+            name,
+        })
+    })
+}
+
+// Removes the user and their punches.
+#[tauri::command]
+pub fn remove_kiosk_user(kiosk: State<'_, Kiosk>, id: u64) -> Result<(), String> {
+    kiosk.write(|data| {
+        data.users.retain(|u| u.id != id);
+        data.punches.retain(|p| p.user_id != id);
+        Ok(())
+    })
+}
+
+#[tauri::command]
+pub fn list_kiosk_users(kiosk: State<'_, Kiosk>) -> Result<Vec<KioskMember>, String> {
+    let data = kiosk.data.lock().map_err(|e| e.to_string())?;
+    Ok(data
+        .users
+        .iter()
+        .map(|u| KioskMember {
+            id: u.id,
+            name: u.name.clone(),
+        })
+        .collect())
+}
+
+// Punches overlapping `range`, optionally for one user
+#[tauri::command]
+pub fn get_kiosk_punches(
+    kiosk: State<'_, Kiosk>,
+    user_id: Option<u64>,
+    range: DateRange,
+) -> Result<Vec<Punch>, String> {
+    let data = kiosk.data.lock().map_err(|e| e.to_string())?;
+    Ok(data
+        .punches
+        .iter()
+        .filter(|p| user_id.map_or(true, |id| p.user_id == id))
+        .filter(|p| p.start < range.end && p.end.map_or(true, |end| end > range.start))
+        .cloned()
+        .collect())
+}
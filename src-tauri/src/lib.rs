@@ -1,43 +1,102 @@
+mod active_window;
+mod activity;
+mod app_lock;
+mod backend;
+mod background;
+mod backup;
+mod badge;
+mod billing;
+mod browser;
+mod calendar;
+mod cli;
+mod clipboard;
+mod coding;
 mod commands;
+mod compliance;
+mod csv;
+mod deep_link;
+mod devices;
+mod diagnostics;
+mod display;
+mod e2ee;
+mod email;
+mod encryption;
+mod expenses;
+mod flags;
+mod focus;
+mod git_activity;
+mod goals;
+mod health;
+mod heuristics;
+mod holidays;
+mod idle;
+mod input_stats;
+mod integrations;
+mod interop;
+mod kiosk;
+mod lifecycle;
+mod local_api;
+mod maintenance;
+mod meeting_detection;
+mod metrics;
+mod migrations;
+mod mini_timer;
+mod native_sound;
+mod network;
+mod notifications;
+mod org_policy;
+mod pdf;
+mod permissions;
+mod presence;
+mod privacy;
+mod profiles;
+mod project_policy;
+mod reading_mode;
+mod realtime;
+mod reports;
+mod rounding;
+mod rpc;
+mod rules;
+mod screenshots;
+mod secrets;
+mod sessions;
+mod settings;
+mod sound;
+mod speech;
+mod standup;
+mod storage;
+mod sync;
+mod tasks_remote;
+mod time;
+mod timeline;
+mod timer;
+mod tray;
+mod tray_icon;
+mod undo;
+mod updater;
+mod webhooks;
+mod windows;
+mod wipe;
+mod work_context;
 use commands::*;
 
-use tauri::menu::{Menu, MenuItem};
-use tauri::tray::TrayIconBuilder;
 use tauri::Manager;
 use tauri::Emitter;
+use tauri_plugin_deep_link::DeepLinkExt;
 
-
-fn create_tray(app: &tauri::AppHandle) {
-    // Create menu
-    let show_i = MenuItem::with_id(app, "show", "Show Window", true, None::<&str>).unwrap();
-    let quit_i = MenuItem::with_id(app, "quit", "Exit", true, None::<&str>).unwrap();
-    let menu = Menu::with_items(app, &[&show_i, &quit_i]).unwrap();
-
-    // Create tray
-    let tray = TrayIconBuilder::with_id("main-tray")
-        .icon(app.default_window_icon().unwrap().clone())
-        .menu(&menu)
-        .on_menu_event(|app, event| {
-             match event.id.as_ref() {
-                 "show" => {
-                     if let Some(window) = app.get_webview_window("main") {
-                         let _ = window.unminimize();
-                         let _ = window.show();
-                         let _ = window.set_focus();
-                     } else {
-                     }
-                 }
-                 "quit" => {
-                     app.exit(0);
-                 }
-                 _ => {
-                 }
-             }
-         })
-        .build(app).unwrap();
-
-    // Store tray
-    app.manage(tray);
+pub(crate) fn show_main_window(app: &tauri::AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        // The frontend renders the lock screen before the window appears
+        if app
+            .try_state::<app_lock::AppLock>()
+            .is_some_and(|lock| lock.is_locked())
+        {
+            let _ = app.emit("app-locked", ());
+        }
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
 }
 
 #[derive(Clone, serde::Serialize)]
@@ -48,37 +107,369 @@ struct Payload {
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    tauri::Builder::default()
-        .plugin(tauri_plugin_single_instance::init(|app, argv, cwd| {
-            // Focus the existing window when another instance is launched
-            if let Some(window) = app.get_webview_window("main") {
-                let _ = window.unminimize();
-                let _ = window.show();
-                let _ = window.set_focus();
+    // Started by the browser as the extension's native-messaging host, not as the app
+    if browser::is_native_host_launch(&std::env::args().collect::<Vec<_>>()) {
+        browser::run_native_host();
+        return;
+    }
+    // `ftt start|stop|status` talks to the running app and exits without a window
+    if let Some(invocation) = cli::parse(&std::env::args().collect::<Vec<_>>()) {
+        std::process::exit(cli::run(invocation));
+    }
+    // `--rpc` serves JSON-RPC on stdio with no window, and must not share the data files
+    // with a running app
+    let rpc_mode = rpc::is_rpc_launch(&std::env::args().collect::<Vec<_>>());
+    if rpc_mode && cli::is_app_running() {
+        eprintln!("Time Tracker is already running; quit it before using --rpc");
+        std::process::exit(1);
+    }
+    let mut context = tauri::generate_context!();
+    if rpc_mode {
+        context.config_mut().app.windows.clear();
+    }
+    let mut builder = tauri::Builder::default();
+    if !rpc_mode {
+        builder = builder.plugin(tauri_plugin_single_instance::init(|app, argv, cwd| {
+            // CLI invocations that reached the app this way run without focusing it
+            if let Some(Ok(invocation)) = cli::parse(&argv) {
+                if let Err(e) = cli::execute(app, &invocation.command) {
+                    log::warn!("CLI command failed: {}", e);
+                }
+                return;
             }
+            // Focus the existing window when another instance is launched
+            show_main_window(app);
+            // Secondary launches carry ftt:// links in argv on Windows and Linux
+            deep_link::handle_args(app, &argv);
             app.emit("single-instance", Payload { args: argv, cwd }).unwrap();
-        }))
+        }));
+    }
+    builder
         .plugin(tauri_plugin_store::Builder::new().build())
         .plugin(tauri_plugin_os::init())
         .plugin(tauri_plugin_autostart::Builder::new().build())
-        .setup(|app| {
+        .plugin(tauri_plugin_updater::Builder::new().build())
+        .plugin(tauri_plugin_deep_link::init())
+        .plugin(tauri_plugin_notification::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .setup(move |app| {
              if cfg!(debug_assertions) {
-                 app.handle().plugin(
-                     tauri_plugin_log::Builder::default()
-                         .level(log::LevelFilter::Info)
-                         .build(),
-                 )?;
+                 let mut logger = tauri_plugin_log::Builder::default()
+                     .level(log::LevelFilter::Info)
+                     // Command calls are logged at debug
+                     .level_for("commands", log::LevelFilter::Debug);
+                 // Stdout carries the JSON-RPC responses
+                 if rpc_mode {
+                     logger = logger
+                         .clear_targets()
+                         .target(tauri_plugin_log::Target::new(tauri_plugin_log::TargetKind::Stderr));
+                 }
+                 app.handle().plugin(logger.build())?;
+             }
+
+             // Load persisted settings and the session store
+             profiles::init(app.handle())?;
+             encryption::init()?;
+             e2ee::init()?;
+             migrations::run(app.handle())?;
+             app.manage(metrics::CommandMetrics::default());
+             app.manage(background::TaskRegistry::default());
+             app.manage(settings::SettingsStore::load(app.handle())?);
+             app.manage(windows::WindowStore::load(app.handle())?);
+             app_lock::init(app.handle());
+             kiosk::init(app.handle())?;
+             app.manage(flags::FeatureFlags::load(
+                 &app.state::<settings::SettingsStore>().get()?,
+             ));
+             flags::start_refresh(app.handle().clone());
+             app.manage(org_policy::OrgPolicies::load(app.handle())?);
+             app.manage(sessions::SessionStore::load(app.handle())?);
+             app.manage(timer::TimerManager::load(app.handle())?);
+             app.manage(sound::SoundManager::load(app.handle())?);
+             app.manage(speech::Speech::start());
+             notifications::init(app.handle())?;
+             app.manage(billing::BillingStore::load(app.handle())?);
+             app.manage(expenses::ExpenseStore::load(app.handle())?);
+             app.manage(integrations::jira::JiraQueue::load(app.handle())?);
+             app.manage(tasks_remote::RemoteTasks::load(app.handle())?);
+             tasks_remote::start_refresh(app.handle().clone());
+             app.manage(sync::SyncStore::load(app.handle())?);
+             sync::start_scheduler(app.handle().clone());
+             app.manage(realtime::Realtime::default());
+             realtime::start(app.handle().clone());
+             presence::start_publisher(app.handle().clone());
+             compliance::start_monitor(app.handle().clone());
+             app.manage(coding::CodingStore::load(app.handle())?);
+             local_api::init(app.handle());
+             if !rpc_mode {
+                 if let Err(e) = cli::start_server(app.handle()) {
+                     log::warn!("CLI control channel unavailable: {}", e);
+                 }
              }
+             integrations::hardware::init(app.handle());
+             integrations::jira::start_retry_loop(app.handle().clone());
+             integrations::slack::register(app.handle());
+             webhooks::register(app.handle());
+             integrations::mqtt::init(app.handle());
+             integrations::discord::register(app.handle());
+             calendar::start_export_scheduler(app.handle().clone());
+             email::start_scheduler(app.handle().clone());
+             app.manage(calendar::MeetingCache::default());
+             calendar::start_meeting_watcher(app.handle().clone());
+             meeting_detection::start_detector(app.handle().clone());
+             work_context::start_detector(app.handle().clone());
+             reading_mode::start_detector(app.handle().clone());
+             project_policy::register(app.handle());
+             org_policy::start_refresh(app.handle().clone());
+             app.manage(screenshots::ScreenshotStore::load(app.handle())?);
+             screenshots::start(app.handle().clone());
+             app.manage(activity::ActivityLog::default());
+             display::start_watcher(app.handle().clone());
+             app.manage(heuristics::ActivityHeuristics::load(app.handle())?);
+             idle::init(app.handle());
+             input_stats::init(app.handle())?;
+             maintenance::start_scheduler(app.handle().clone());
+             app.manage(goals::GoalStore::load(app.handle())?);
+             goals::start_evaluator(app.handle().clone());
+             app.manage(rules::RuleStore::load(app.handle())?);
+             app.manage(wipe::WipeConfirmation::default());
+             rules::start_evaluator(app.handle().clone());
+             browser::init(app.handle());
+             app.manage(timeline::TimelineStore::load(app.handle())?);
+             timeline::start_sampler(app.handle().clone());
+             app.manage(git_activity::GitActivityStore::load(app.handle())?);
+             git_activity::start_watcher(app.handle().clone());
+             focus::start_focus_watcher(app.handle().clone());
+             timer::start_ticker(app.handle().clone());
+             health::check(app.handle());
 
-             // Create tray
-             create_tray(&app.handle());
+             // Handle ftt:// links from the launching argv and, on macOS, from open-url events
+             #[cfg(any(windows, target_os = "linux"))]
+             app.deep_link().register_all()?;
+             let handle = app.handle().clone();
+             app.deep_link().on_open_url(move |event| {
+                 for url in event.urls() {
+                     if let Some(action) = deep_link::parse(url.as_str()) {
+                         deep_link::dispatch(&handle, action);
+                     }
+                 }
+             });
+             deep_link::handle_args(app.handle(), &std::env::args().collect::<Vec<_>>());
+
+             updater::spawn_periodic_checks(app.handle().clone());
+
+             if rpc_mode {
+                 rpc::serve_stdio(app.handle().clone());
+             } else {
+                 // Create tray
+                 tray::create_tray(app.handle());
+             }
 
              Ok(())
          })
-        .invoke_handler(tauri::generate_handler![greet, get_timer_state, stop_timer, get_processes, toggle_devtools])
-        .on_window_event(|_window, _event| {
-            // Close is handled in frontend
-        })
-        .run(tauri::generate_context!())
+        .invoke_handler(metrics::instrumented(kiosk::guarded(app_lock::guarded(tauri::generate_handler![
+            greet,
+            get_timer_state,
+            start_timer,
+            stop_timer,
+            get_processes,
+            toggle_devtools,
+            sessions::add_manual_entry,
+            sessions::edit_entry,
+            sessions::delete_entry,
+            sessions::discard_idle_span,
+            sessions::set_session_tags,
+            sessions::list_tags,
+            sessions::get_entry_history,
+            settings::get_settings,
+            settings::get_settings_version,
+            migrations::get_db_info,
+            backup::create_backup,
+            backup::restore_backup,
+            maintenance::run_maintenance_now,
+            maintenance::set_retention,
+            notifications::set_notification_policy,
+            notifications::get_notifications,
+            notifications::mark_read,
+            notifications::clear_notifications,
+            goals::get_goal_progress,
+            goals::set_goals,
+            focus::set_focus_blocklist,
+            focus::set_focus_mode,
+            sound::set_sound_enabled,
+            sound::list_available_sounds,
+            sound::preview_sound,
+            sound::set_channel_sound,
+            sound::get_sound_metadata,
+            sound::import_sound,
+            sound::list_audio_devices,
+            sound::set_audio_device,
+            sound::set_sound_ducking,
+            speech::speak,
+            speech::list_voices,
+            speech::set_speech_settings,
+            time::set_report_timezone,
+            tray::set_menu_bar_display,
+            tray_icon::set_tray_icon_style,
+            mini_timer::show_mini_timer,
+            mini_timer::hide_mini_timer,
+            mini_timer::set_mini_timer_click_through,
+            windows::open_window,
+            windows::close_window,
+            windows::list_open_windows,
+            windows::reset_window_geometry,
+            idle::get_idle_provider_info,
+            idle::simulate_idle,
+            idle::set_idle_hysteresis,
+            idle::get_idle_monitor_config,
+            idle::get_idle_state_machine,
+            idle::set_adaptive_idle_polling,
+            idle::pause_idle_monitoring,
+            idle::resume_idle_monitoring,
+            idle::get_idle_pause,
+            activity::get_recent_activity,
+            billing::get_billing_config,
+            billing::set_billing_config,
+            billing::generate_invoice_data,
+            billing::get_earnings,
+            expenses::add_expense,
+            expenses::delete_expense,
+            expenses::list_expenses,
+            expenses::get_receipt_path,
+            rounding::preview_rounding,
+            interop::import_toggl_csv,
+            interop::export_toggl_csv,
+            integrations::jira::push_worklog,
+            integrations::jira::get_pending_worklogs,
+            integrations::jira::set_jira_config,
+            integrations::slack::connect_slack,
+            integrations::slack::disconnect_slack,
+            integrations::slack::set_slack_dnd,
+            calendar::export_ics,
+            calendar::set_ics_schedule,
+            calendar::get_upcoming_meetings,
+            calendar::set_calendar_sources,
+            timer::list_timers,
+            timer::set_primary_timer,
+            timer::append_session_note,
+            reports::get_report,
+            standup::generate_standup,
+            clipboard::copy_report_markdown,
+            clipboard::copy_invoice_csv,
+            clipboard::copy_session_link,
+            lifecycle::hide_to_tray,
+            lifecycle::quit_app,
+            lifecycle::set_close_behavior,
+            heuristics::set_activity_heuristics,
+            heuristics::get_low_confidence_segments,
+            input_stats::set_input_stats,
+            input_stats::get_input_stats,
+            updater::check_for_updates,
+            updater::download_and_install,
+            updater::set_release_channel,
+            metrics::get_command_metrics,
+            metrics::record_command_result,
+            diagnostics::generate_diagnostics,
+            flags::get_feature_flags,
+            flags::set_feature_flag,
+            profiles::list_profiles,
+            profiles::create_profile,
+            profiles::switch_profile,
+            backend::set_backend_config,
+            tasks_remote::get_assigned_tasks,
+            tasks_remote::refresh_tasks,
+            realtime::get_realtime_status,
+            local_api::get_local_api_info,
+            local_api::set_local_api,
+            local_api::regenerate_local_api_token,
+            integrations::hardware::list_midi_ports,
+            integrations::hardware::set_hardware_triggers,
+            rules::list_rules,
+            rules::save_rule,
+            rules::delete_rule,
+            background::restart_task,
+            background::get_task_status,
+            health::get_startup_health,
+            permissions::get_permission_status,
+            permissions::request_permission,
+            permissions::open_permission_settings,
+            app_lock::get_app_lock_status,
+            app_lock::set_app_lock,
+            app_lock::unlock,
+            app_lock::unlock_with_biometric,
+            app_lock::lock_app,
+            encryption::set_encryption,
+            privacy::set_privacy_rules,
+            privacy::preview_privacy_rules,
+            wipe::request_data_wipe,
+            wipe::delete_all_my_data,
+            timeline::get_timeline,
+            timeline::set_timeline_settings,
+            reports::get_productivity_heatmap,
+            pdf::render_report_pdf,
+            pdf::set_report_branding,
+            email::set_email_settings,
+            email::send_report_now,
+            webhooks::set_webhooks,
+            webhooks::set_webhook_secret,
+            webhooks::get_webhook_deliveries,
+            integrations::mqtt::set_mqtt_config,
+            integrations::mqtt::get_mqtt_status,
+            integrations::discord::set_discord_settings,
+            git_activity::set_git_repositories,
+            git_activity::get_session_git_activity,
+            git_activity::suggest_task_from_branch,
+            coding::get_coding_activity,
+            browser::get_native_host_manifest,
+            browser::set_browser_bridge,
+            browser::get_browser_tab,
+            meeting_detection::get_meeting_state,
+            meeting_detection::set_meeting_detection,
+            work_context::get_current_context,
+            work_context::set_context_rules,
+            reading_mode::set_reading_mode,
+            reading_mode::get_reading_mode,
+            reading_mode::set_reading_mode_settings,
+            display::get_display_info,
+            project_policy::set_project_policies,
+            project_policy::get_effective_policy,
+            undo::undo_last,
+            undo::get_undo_history,
+            sync::sync_now,
+            sync::get_sync_status,
+            sync::get_sync_conflicts,
+            sync::resolve_conflict,
+            sync::set_sync_settings,
+            e2ee::enable_e2ee,
+            e2ee::disable_e2ee,
+            e2ee::generate_recovery_phrase,
+            e2ee::export_e2ee_key,
+            e2ee::import_e2ee_key,
+            e2ee::get_e2ee_status,
+            presence::get_team_presence,
+            presence::get_presence,
+            presence::set_presence_settings,
+            kiosk::punch_in,
+            kiosk::punch_out,
+            kiosk::get_kiosk_state,
+            kiosk::enter_kiosk_mode,
+            kiosk::exit_kiosk_mode,
+            kiosk::add_kiosk_user,
+            kiosk::remove_kiosk_user,
+            kiosk::list_kiosk_users,
+            kiosk::get_kiosk_punches,
+            compliance::get_compliance_breaches,
+            compliance::set_compliance_settings,
+            holidays::get_days_off,
+            holidays::get_holiday_countries,
+            holidays::set_holiday_settings,
+            screenshots::list_screenshots,
+            screenshots::delete_screenshot,
+            screenshots::set_screenshot_settings,
+            screenshots::list_monitors,
+            screenshots::get_screenshot_upload_status,
+        ]))))
+        .on_window_event(lifecycle::on_window_event)
+        .run(context)
         .expect("error while running tauri application");
 }
@@ -0,0 +1,180 @@
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State, Window, WindowEvent};
+
+use crate::app_lock;
+use crate::idle::IdleMonitor;
+use crate::integrations::jira;
+use crate::kiosk;
+use crate::mini_timer;
+use crate::settings::SettingsStore;
+use crate::sync;
+use crate::timer::TimerManager;
+use crate::windows;
+
+const SYNC_FLUSH_TIMEOUT: Duration = Duration::from_secs(5);
+
+static SHUTTING_DOWN: AtomicBool = AtomicBool::new(false);
+
+#[derive(Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CloseBehavior {
+    // Hide the main window and keep tracking from the tray
+    #[default]
+    Tray,
+    // Ask the frontend, which answers with `hide_to_tray` or `quit_app`
+    Prompt,
+    Exit,
+}
+
+// Stop every running timer so its time is saved as a session before the process ends.
+fn finalize_timers(app: &AppHandle) -> Result<(), String> {
+    app.state::<TimerManager>().stop_all(app)
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShutdownStage {
+    StoppingMonitors,
+    SavingTimers,
+    FlushingSync,
+    Exiting,
+}
+
+fn report(app: &AppHandle, stage: ShutdownStage) {
+    let _ = app.emit("shutting-down", stage);
+}
+
+// Runs once the stores are saved, so files it writes aren't overwritten on the way out
+type BeforeRestart = Box<dyn FnOnce() -> Result<(), String> + Send>;
+
+// What happens once the shutdown steps are done
+enum AfterShutdown {
+    Exit,
+    // Relaunch, e.g. into a freshly installed update
+    Restart(Option<BeforeRestart>),
+}
+
+// Runs the shutdown steps in order and exits or restarts. With `discard` unset, a failure
+// to save the running timers aborts the shutdown and is returned to the caller.
+async fn shutdown(app: &AppHandle, discard: bool, then: AfterShutdown) -> Result<(), String> {
+    if SHUTTING_DOWN.swap(true, Ordering::SeqCst) {
+        return Ok(());
+    }
+
+    report(app, ShutdownStage::StoppingMonitors);
+    app.state::<IdleMonitor>().stop();
+
+    report(app, ShutdownStage::SavingTimers);
+    if let Err(e) = finalize_timers(app) {
+        if !discard {
+            SHUTTING_DOWN.store(false, Ordering::SeqCst);
+            return Err(e);
+        }
+        log::error!("discarding running timers on exit: {}", e);
+    }
+
+    report(app, ShutdownStage::FlushingSync);
+    let flush = async {
+        jira::flush_pending(app).await;
+        sync::flush(app).await;
+    };
+    if tokio::time::timeout(SYNC_FLUSH_TIMEOUT, flush)
+        .await
+        .is_err()
+    {
+        log::warn!("sync flush timed out; pending items stay queued for next launch");
+    }
+
+    report(app, ShutdownStage::Exiting);
+    mini_timer::on_exit(app);
+    windows::on_exit(app);
+    match then {
+        AfterShutdown::Exit => app.exit(0),
+        AfterShutdown::Restart(before) => {
+            if let Err(e) = before.map_or(Ok(()), |before| before()) {
+                log::error!("failed before restarting: {}", e);
+            }
+            app.restart()
+        }
+    }
+    Ok(())
+}
+
+// Saves everything the way quitting does, then relaunches the app.
+pub async fn restart(app: &AppHandle) -> Result<(), String> {
+    shutdown(app, true, AfterShutdown::Restart(None)).await
+}
+
+// Like `restart`, with `replace` run between saving the stores and relaunching.
+pub async fn restart_with(
+    app: &AppHandle,
+    replace: impl FnOnce() -> Result<(), String> + Send + 'static,
+) -> Result<(), String> {
+    shutdown(app, true, AfterShutdown::Restart(Some(Box::new(replace)))).await
+}
+
+pub fn quit(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let _ = shutdown(&app, true, AfterShutdown::Exit).await;
+    });
+}
+
+pub fn on_window_event(window: &Window, event: &WindowEvent) {
+    let WindowEvent::CloseRequested { api, .. } = event else {
+        return;
+    };
+    // Auxiliary windows close normally
+    if window.label() != "main" {
+        return;
+    }
+    api.prevent_close();
+
+    let app = window.app_handle();
+    // A kiosk only leaves through exit_kiosk_mode
+    if kiosk::is_active(app) {
+        return;
+    }
+    let behavior = app
+        .state::<SettingsStore>()
+        .get()
+        .map(|s| s.close_behavior)
+        .unwrap_or_default();
+    match behavior {
+        CloseBehavior::Tray => {
+            let _ = window.hide();
+            app_lock::on_hide(app);
+        }
+        CloseBehavior::Prompt => {
+            let _ = window.emit("close-requested", ());
+        }
+        CloseBehavior::Exit => quit(app),
+    }
+}
+
+#[tauri::command]
+pub fn hide_to_tray(app: AppHandle) -> Result<(), String> {
+    if let Some(window) = app.get_webview_window("main") {
+        window.hide().map_err(|e| e.to_string())?;
+    }
+    app_lock::on_hide(&app);
+    Ok(())
+}
+
+// Exits after saving running timers. If they can't be saved the error is returned so the
+// UI can ask the user; calling again with `confirm_discard` exits regardless.
+#[tauri::command]
+pub async fn quit_app(app: AppHandle, confirm_discard: bool) -> Result<(), String> {
+    shutdown(&app, confirm_discard, AfterShutdown::Exit).await
+}
+
+#[tauri::command]
+pub fn set_close_behavior(
+    settings: State<'_, SettingsStore>,
+    behavior: CloseBehavior,
+) -> Result<(), String> {
+    settings.update(|s| s.close_behavior = behavior)?;
+    Ok(())
+}
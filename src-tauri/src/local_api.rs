@@ -0,0 +1,363 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::io::Read;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use crate::app_lock;
+use crate::coding::{CodingStore, IncomingHeartbeat};
+use crate::integrations::hardware::{self, Trigger};
+use crate::secrets;
+use crate::settings::SettingsStore;
+use crate::timer::{Countdown, TimerManager};
+
+pub(crate) const TOKEN_KEY: &str = "local_api_token";
+const MAX_BODY_BYTES: u64 = 64 * 1024;
+const BIND_RETRY: Duration = Duration::from_millis(50);
+// WakaTime-compatible heartbeat endpoints; editor plugins point `api_url` at
+// http://127.0.0.1:<port>/api/v1 and use the local API token as `api_key`
+const HEARTBEATS_PATH: &str = "/api/v1/users/current/heartbeats";
+const HEARTBEATS_BULK_PATH: &str = "/api/v1/users/current/heartbeats.bulk";
+
+// Opt-in HTTP control surface on 127.0.0.1 for scripts, editors and hardware buttons
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LocalApiSettings {
+    pub enabled: bool,
+    pub port: u16,
+}
+
+impl Default for LocalApiSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 47611,
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+pub struct LocalApiInfo {
+    pub enabled: bool,
+    pub running: bool,
+    pub port: u16,
+    pub token: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct StartRequest {
+    task_id: u64,
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    countdown: Option<Countdown>,
+}
+
+#[derive(Default, Deserialize)]
+struct StopRequest {
+    #[serde(default)]
+    id: Option<String>,
+}
+
+#[derive(Default)]
+pub struct LocalApi {
+    server: Mutex<Option<Arc<Server>>>,
+}
+
+fn generate_token() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(32)
+        .map(char::from)
+        .collect()
+}
+
+fn token() -> Result<String, String> {
+    match secrets::get(TOKEN_KEY)? {
+        Some(token) => Ok(token),
+        None => {
+            let token = generate_token();
+            secrets::set(TOKEN_KEY, &token)?;
+            Ok(token)
+        }
+    }
+}
+
+fn header<'a>(request: &'a Request, name: &'static str) -> Option<&'a str> {
+    request
+        .headers()
+        .iter()
+        .find(|h| h.field.equiv(name))
+        .map(|h| h.value.as_str())
+}
+
+// Doesn't return at the first differing byte, so response timing can't be used to guess
+// the token
+fn same_token(given: &str, token: &str) -> bool {
+    given.len() == token.len()
+        && given
+            .bytes()
+            .zip(token.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+// Rejects other hosts so a web page can't reach the API through DNS rebinding.
+fn authorized(request: &Request, port: u16, token: &str) -> bool {
+    let host_ok = header(request, "Host").is_some_and(|host| {
+        host == format!("127.0.0.1:{}", port) || host == format!("localhost:{}", port)
+    });
+    let token_ok = header(request, "Authorization").is_some_and(|value| {
+        if let Some(given) = value.strip_prefix("Bearer ") {
+            return same_token(given, token);
+        }
+        // WakaTime clients send the API key as Basic credentials
+        value
+            .strip_prefix("Basic ")
+            .and_then(|encoded| STANDARD.decode(encoded.trim()).ok())
+            .and_then(|decoded| String::from_utf8(decoded).ok())
+            .is_some_and(|given| same_token(given.trim_end_matches(':'), token))
+    });
+    host_ok && token_ok
+}
+
+fn read_body<T: for<'de> Deserialize<'de> + Default>(request: &mut Request) -> Result<T, String> {
+    let mut body = String::new();
+    request
+        .as_reader()
+        .take(MAX_BODY_BYTES)
+        .read_to_string(&mut body)
+        .map_err(|e| e.to_string())?;
+    if body.trim().is_empty() {
+        return Ok(T::default());
+    }
+    serde_json::from_str(&body).map_err(|e| e.to_string())
+}
+
+// Answers the way the WakaTime API does: 201 for one heartbeat, 202 with a result per
+// heartbeat for a bulk upload.
+fn heartbeats(
+    app: &AppHandle,
+    request: &mut Request,
+    bulk: bool,
+) -> Result<(u16, Value), (u16, String)> {
+    if let Some(reason) = app_lock::refusal(app) {
+        return Err((423, reason.to_string()));
+    }
+    let bad_request = |e: String| (400, e);
+    let incoming: Vec<IncomingHeartbeat> = if bulk {
+        read_body::<Option<Vec<IncomingHeartbeat>>>(request)
+            .map_err(bad_request)?
+            .unwrap_or_default()
+    } else {
+        read_body::<Option<IncomingHeartbeat>>(request)
+            .map_err(bad_request)?
+            .into_iter()
+            .collect()
+    };
+    let count = incoming.len();
+    let accepted = app
+        .state::<CodingStore>()
+        .record(app, incoming)
+        .map_err(|e| (500, e))?;
+    log::debug!("recorded {} of {} heartbeats", accepted, count);
+    if bulk {
+        let responses: Vec<Value> = (0..count).map(|_| json!([{ "data": {} }, 201])).collect();
+        Ok((202, json!({ "responses": responses })))
+    } else {
+        Ok((201, json!({ "data": {} })))
+    }
+}
+
+fn route(app: &AppHandle, request: &mut Request) -> Result<Value, (u16, String)> {
+    if let Some(reason) = app_lock::refusal(app) {
+        return Err((423, reason.to_string()));
+    }
+    let timers = app.state::<TimerManager>();
+    let bad_request = |e: String| (400, e);
+    let failed = |e: String| (500, e);
+    // Query parameters aren't part of the route
+    let url = request
+        .url()
+        .split('?')
+        .next()
+        .unwrap_or_default()
+        .to_string();
+    let method = request.method().clone();
+    if let (Method::Post, Some(name)) = (&method, url.strip_prefix("/trigger/")) {
+        let trigger = Trigger::Named {
+            name: name.to_string(),
+        };
+        let matched = hardware::fire(app, &trigger).map_err(failed)?;
+        return Ok(json!({ "matched": matched }));
+    }
+    match (method, url.as_str()) {
+        (Method::Get, "/status") => Ok(json!({
+            "active": timers.active(),
+            "timers": timers.list(),
+        })),
+        (Method::Post, "/timer/start") => {
+            let body: Option<StartRequest> = read_body(request).map_err(bad_request)?;
+            let body = body.ok_or_else(|| (400, "task_id is required".to_string()))?;
+            let timer = timers
+                .start_with(app, body.task_id, body.title, body.countdown)
+                .map_err(failed)?;
+            Ok(json!({ "timer": timer }))
+        }
+        (Method::Post, "/timer/stop") => {
+            let body: StopRequest = read_body(request).map_err(bad_request)?;
+            let session = match body.id {
+                Some(id) => timers.stop_named(app, &id),
+                None => timers.stop(app),
+            }
+            .map_err(failed)?;
+            Ok(json!({ "session": session }))
+        }
+        _ => Err((404, "Not found".to_string())),
+    }
+}
+
+fn respond(request: Request, status: u16, body: Value) {
+    let content_type =
+        Header::from_bytes("Content-Type", "application/json").expect("static header is valid");
+    let response = Response::from_string(body.to_string())
+        .with_status_code(status)
+        .with_header(content_type);
+    if let Err(e) = request.respond(response) {
+        log::debug!("local API response failed: {}", e);
+    }
+}
+
+// The previous server releases the port once its thread notices the unblock, so
+// rebinding the same port retries briefly.
+fn bind(port: u16) -> Result<Server, String> {
+    let mut attempts = 0;
+    loop {
+        match Server::http(("127.0.0.1", port)) {
+            Ok(server) => return Ok(server),
+            Err(e) if attempts >= 10 => return Err(e.to_string()),
+            Err(_) => {
+                attempts += 1;
+                std::thread::sleep(BIND_RETRY);
+            }
+        }
+    }
+}
+
+fn serve(app: AppHandle, server: Arc<Server>, port: u16, token: String) {
+    for mut request in server.incoming_requests() {
+        if !authorized(&request, port, &token) {
+            respond(request, 401, json!({ "error": "Unauthorized" }));
+            continue;
+        }
+        log::debug!("local API {} {}", request.method(), request.url());
+        // Plugins may append query parameters
+        let path = request
+            .url()
+            .split('?')
+            .next()
+            .unwrap_or_default()
+            .to_string();
+        if *request.method() == Method::Post
+            && (path == HEARTBEATS_PATH || path == HEARTBEATS_BULK_PATH)
+        {
+            match heartbeats(&app, &mut request, path == HEARTBEATS_BULK_PATH) {
+                Ok((status, body)) => respond(request, status, body),
+                Err((status, error)) => respond(request, status, json!({ "error": error })),
+            }
+            continue;
+        }
+        match route(&app, &mut request) {
+            Ok(body) => respond(request, 200, body),
+            Err((status, error)) => respond(request, status, json!({ "error": error })),
+        }
+    }
+}
+
+impl LocalApi {
+    // (Re)starts the server to match the settings; stops it when disabled.
+    pub fn apply(&self, app: &AppHandle, settings: &LocalApiSettings) -> Result<(), String> {
+        let mut server = self.server.lock().map_err(|e| e.to_string())?;
+        if let Some(running) = server.take() {
+            running.unblock();
+        }
+        if !settings.enabled {
+            return Ok(());
+        }
+        let token = token()?;
+        let bound = Arc::new(bind(settings.port)?);
+        let (handle, thread_server, port) = (app.clone(), bound.clone(), settings.port);
+        std::thread::spawn(move || serve(handle, thread_server, port, token));
+        log::info!("local API listening on 127.0.0.1:{}", settings.port);
+        *server = Some(bound);
+        Ok(())
+    }
+
+    fn is_running(&self) -> bool {
+        self.server.lock().is_ok_and(|s| s.is_some())
+    }
+}
+
+// Spawned from setup; failures only disable the API.
+pub fn init(app: &AppHandle) {
+    let api = LocalApi::default();
+    match app.state::<SettingsStore>().get() {
+        Ok(settings) => {
+            if let Err(e) = api.apply(app, &settings.local_api) {
+                log::warn!("local API failed to start: {}", e);
+            }
+        }
+        Err(e) => log::warn!("local API settings unavailable: {}", e),
+    }
+    app.manage(api);
+}
+
+#[tauri::command]
+pub fn get_local_api_info(
+    api: State<'_, LocalApi>,
+    settings: State<'_, SettingsStore>,
+) -> Result<LocalApiInfo, String> {
+    let local_api = settings.get()?.local_api;
+    Ok(LocalApiInfo {
+        enabled: local_api.enabled,
+        running: api.is_running(),
+        port: local_api.port,
+        token: local_api.enabled.then(token).transpose()?,
+    })
+}
+
+#[tauri::command]
+pub fn set_local_api(
+    app: AppHandle,
+    api: State<'_, LocalApi>,
+    settings: State<'_, SettingsStore>,
+    enabled: bool,
+    port: Option<u16>,
+) -> Result<(), String> {
+    let updated = settings.update(|s| {
+        s.local_api.enabled = enabled;
+        if let Some(port) = port {
+            s.local_api.port = port;
+        }
+    })?;
+    api.apply(&app, &updated.local_api)
+}
+
+// Invalidates the old token; a running server picks up the new one.
+#[tauri::command]
+pub fn regenerate_local_api_token(
+    app: AppHandle,
+    api: State<'_, LocalApi>,
+    settings: State<'_, SettingsStore>,
+) -> Result<String, String> {
+    let token = generate_token();
+    secrets::set(TOKEN_KEY, &token)?;
+    api.apply(&app, &settings.get()?.local_api)?;
+    Ok(token)
+}
@@ -0,0 +1,171 @@
+use chrono::{DateTime, Duration as ChronoDuration, Local, NaiveDateTime, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::background;
+use crate::heuristics::ActivityHeuristics;
+use crate::input_stats::InputStats;
+use crate::sessions::SessionStore;
+use crate::settings::SettingsStore;
+use crate::storage;
+use crate::timeline::TimelineStore;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+// Local hour at which the nightly run happens
+const MAINTENANCE_HOUR: u32 = 3;
+// As written into snapshot names by backup and migrations
+const SNAPSHOT_TIMESTAMP: &str = "%Y%m%dT%H%M%SZ";
+const TIMESTAMP_LEN: usize = 16;
+
+// A value of 0 keeps the data forever. Tracked sessions and the daily input summaries are
+// never pruned.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetentionSettings {
+    pub input_stats_days: u32,
+    pub low_confidence_days: u32,
+    pub audit_days: u32,
+    // Per-minute timeline rows
+    pub timeline_days: u32,
+    // Migration and pre-restore snapshots kept in the backups directory
+    pub max_backups: usize,
+    pub last_run: Option<DateTime<Utc>>,
+}
+
+impl Default for RetentionSettings {
+    fn default() -> Self {
+        Self {
+            input_stats_days: 90,
+            low_confidence_days: 90,
+            audit_days: 365,
+            timeline_days: 90,
+            max_backups: 10,
+            last_run: None,
+        }
+    }
+}
+
+#[derive(Default, Serialize)]
+pub struct MaintenanceReport {
+    pub input_minutes_pruned: usize,
+    pub daily_summaries_added: usize,
+    pub low_confidence_pruned: usize,
+    pub audit_records_pruned: usize,
+    pub timeline_days_pruned: usize,
+    pub backups_removed: usize,
+}
+
+fn cutoff(days: u32) -> Option<DateTime<Utc>> {
+    (days > 0).then(|| Utc::now() - ChronoDuration::days(days as i64))
+}
+
+// Snapshot names embed when they were taken, after a prefix that differs between kinds
+// (pre-migration-v3-..., pre-restore-...); the file's mtime stands in when there's none.
+fn taken_at(entry: &fs::DirEntry) -> DateTime<Utc> {
+    let name = entry.file_name().to_string_lossy().into_owned();
+    let embedded = name
+        .char_indices()
+        .filter_map(|(i, _)| name.get(i..i + TIMESTAMP_LEN))
+        .find_map(|s| NaiveDateTime::parse_from_str(s, SNAPSHOT_TIMESTAMP).ok());
+    match embedded {
+        Some(at) => at.and_utc(),
+        None => entry
+            .metadata()
+            .and_then(|m| m.modified())
+            .map_or(DateTime::<Utc>::MIN_UTC, DateTime::from),
+    }
+}
+
+fn prune_backups(app: &AppHandle, keep: usize) -> Result<usize, String> {
+    let dir = storage::data_file(app, "backups")?;
+    if !dir.exists() {
+        return Ok(0);
+    }
+    let mut entries: Vec<_> = fs::read_dir(&dir)
+        .map_err(|e| e.to_string())?
+        .filter_map(Result::ok)
+        .collect();
+    // Newest last
+    entries.sort_by_cached_key(taken_at);
+    let excess = entries.len().saturating_sub(keep);
+    for entry in &entries[..excess] {
+        let path = entry.path();
+        let result = if path.is_dir() {
+            fs::remove_dir_all(&path)
+        } else {
+            fs::remove_file(&path)
+        };
+        result.map_err(|e| format!("{}: {}", path.display(), e))?;
+    }
+    Ok(excess)
+}
+
+pub fn run(app: &AppHandle) -> Result<MaintenanceReport, String> {
+    let retention = app.state::<SettingsStore>().get()?.retention;
+    let mut report = MaintenanceReport::default();
+
+    if let Some(before) = cutoff(retention.input_stats_days) {
+        let (pruned, summaries) = app.state::<InputStats>().prune(before)?;
+        report.input_minutes_pruned = pruned;
+        report.daily_summaries_added = summaries;
+    }
+    if let Some(before) = cutoff(retention.low_confidence_days) {
+        report.low_confidence_pruned = app.state::<ActivityHeuristics>().prune(before)?;
+    }
+    if let Some(before) = cutoff(retention.audit_days) {
+        report.audit_records_pruned = app.state::<SessionStore>().write(|data| {
+            let len = data.audit.len();
+            data.audit.retain(|record| record.at >= before);
+            Ok(len - data.audit.len())
+        })?;
+    }
+    if let Some(before) = cutoff(retention.timeline_days) {
+        report.timeline_days_pruned = app.state::<TimelineStore>().prune(before)?;
+    }
+    if retention.max_backups > 0 {
+        report.backups_removed = prune_backups(app, retention.max_backups)?;
+    }
+
+    app.state::<SettingsStore>()
+        .update(|s| s.retention.last_run = Some(Utc::now()))?;
+    Ok(report)
+}
+
+pub fn start_scheduler(app: AppHandle) {
+    background::spawn_thread(&app, "maintenance", |app, task| {
+        while task.sleep_blocking(CHECK_INTERVAL) {
+            let Ok(settings) = app.state::<SettingsStore>().get() else {
+                continue;
+            };
+            let now = Local::now();
+            let ran_today = settings
+                .retention
+                .last_run
+                .is_some_and(|t| t.with_timezone(&Local).date_naive() == now.date_naive());
+            if now.hour() < MAINTENANCE_HOUR || ran_today {
+                continue;
+            }
+            if let Err(e) = run(&app) {
+                log::error!("nightly maintenance failed: {}", e);
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub fn run_maintenance_now(app: AppHandle) -> Result<MaintenanceReport, String> {
+    run(&app)
+}
+
+#[tauri::command]
+pub fn set_retention(app: AppHandle, retention: RetentionSettings) -> Result<(), String> {
+    app.state::<SettingsStore>().update(|s| {
+        s.retention = RetentionSettings {
+            last_run: s.retention.last_run,
+            ..retention
+        }
+    })?;
+    Ok(())
+}
@@ -0,0 +1,179 @@
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use sysinfo::System;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::background;
+use crate::devices;
+use crate::settings::SettingsStore;
+use crate::timer::TimerManager;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(20);
+// Concurrent timer id used for auto-started meeting time
+const MEETING_TIMER_ID: &str = "meeting";
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct MeetingTimer {
+    pub task_id: u64,
+    #[serde(default)]
+    pub title: Option<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MeetingDetectionSettings {
+    pub enabled: bool,
+    // Process names, matched case-insensitively as substrings; a meeting needs one of
+    // these running while the microphone or camera is in use
+    pub conferencing_apps: Vec<String>,
+    // Started as a concurrent timer when a meeting begins and stopped when it ends
+    pub auto_start: Option<MeetingTimer>,
+}
+
+impl Default for MeetingDetectionSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            conferencing_apps: [
+                "zoom",
+                "teams",
+                "webex",
+                "skype",
+                "slack",
+                "discord",
+                "facetime",
+                "gotomeeting",
+            ]
+            .iter()
+            .map(|s| s.to_string())
+            .collect(),
+            auto_start: None,
+        }
+    }
+}
+
+#[derive(Clone, Default, Serialize)]
+pub struct MeetingState {
+    pub in_meeting: bool,
+    pub microphone: bool,
+    pub camera: bool,
+    // The conferencing process that was found
+    pub app: Option<String>,
+}
+
+#[derive(Default)]
+pub struct MeetingDetector {
+    in_meeting: AtomicBool,
+    state: Mutex<MeetingState>,
+}
+
+// True while a meeting is detected. Suppresses idle pauses and routine notifications.
+pub fn in_meeting(app: &AppHandle) -> bool {
+    app.try_state::<MeetingDetector>()
+        .is_some_and(|d| d.in_meeting.load(Ordering::Relaxed))
+}
+
+fn conferencing_app(apps: &[String]) -> Option<String> {
+    let mut sys = System::new();
+    sys.refresh_processes();
+    let apps: Vec<String> = apps.iter().map(|a| a.to_lowercase()).collect();
+    sys.processes()
+        .values()
+        .map(|p| p.name().to_string())
+        .find(|name| {
+            let name = name.to_lowercase();
+            apps.iter().any(|a| name.contains(a.as_str()))
+        })
+}
+
+fn detect(settings: &MeetingDetectionSettings) -> MeetingState {
+    let (microphone, camera) = (devices::microphone(), devices::camera());
+    // Only look for a conferencing app when a device is busy; listing processes is the
+    // expensive part
+    let app = if microphone || camera {
+        conferencing_app(&settings.conferencing_apps)
+    } else {
+        None
+    };
+    MeetingState {
+        in_meeting: app.is_some(),
+        microphone,
+        camera,
+        app,
+    }
+}
+
+fn on_change(app: &AppHandle, state: &MeetingState, auto_start: Option<MeetingTimer>) {
+    log::info!(
+        "meeting {} ({:?})",
+        if state.in_meeting { "started" } else { "ended" },
+        state.app
+    );
+    let _ = app.emit("meeting-state-changed", state);
+    let Some(timer) = auto_start else {
+        return;
+    };
+    let timers = app.state::<TimerManager>();
+    let result = if state.in_meeting {
+        timers
+            .start_named(
+                app,
+                MEETING_TIMER_ID.to_string(),
+                timer.task_id,
+                timer.title,
+                true,
+                None,
+            )
+            .map(|_| ())
+    } else {
+        timers.stop_named(app, MEETING_TIMER_ID).map(|_| ())
+    };
+    if let Err(e) = result {
+        log::warn!("failed to update the meeting timer: {}", e);
+    }
+}
+
+fn poll(app: &AppHandle) -> Result<(), String> {
+    let settings = app.state::<SettingsStore>().get()?.meeting_detection;
+    let state = if settings.enabled {
+        detect(&settings)
+    } else {
+        MeetingState::default()
+    };
+    let detector = app.state::<MeetingDetector>();
+    let was = detector
+        .in_meeting
+        .swap(state.in_meeting, Ordering::Relaxed);
+    *detector.state.lock().map_err(|e| e.to_string())? = state.clone();
+    if was != state.in_meeting {
+        on_change(app, &state, settings.auto_start);
+    }
+    Ok(())
+}
+
+pub fn start_detector(app: AppHandle) {
+    app.manage(MeetingDetector::default());
+    background::spawn_thread(&app, "meeting_detection", |app, task| {
+        while task.sleep_blocking(POLL_INTERVAL) {
+            if let Err(e) = poll(&app) {
+                log::warn!("meeting detection failed: {}", e);
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub fn get_meeting_state(detector: State<'_, MeetingDetector>) -> Result<MeetingState, String> {
+    Ok(detector.state.lock().map_err(|e| e.to_string())?.clone())
+}
+
+#[tauri::command]
+pub fn set_meeting_detection(
+    settings: State<'_, SettingsStore>,
+    meeting_detection: MeetingDetectionSettings,
+) -> Result<(), String> {
+    settings.update(|s| s.meeting_detection = meeting_detection)?;
+    Ok(())
+}
@@ -0,0 +1,161 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::ipc::{Invoke, InvokeBody};
+use tauri::State;
+
+// Latest samples kept per command for the percentiles
+const SAMPLE_WINDOW: usize = 200;
+// Sent by the frontend's invoke wrapper after every call; not logged itself
+const REPORT_COMMAND: &str = "record_command_result";
+// Argument names whose values are never logged
+const SECRET_KEYS: &[&str] = &[
+    "token",
+    "password",
+    "passphrase",
+    "secret",
+    "api_key",
+    "apikey",
+    "webhook",
+    "credential",
+    // App lock PINs and kiosk badge codes
+    "pin",
+    "code",
+];
+
+#[derive(Default)]
+struct Samples {
+    calls: u64,
+    errors: u64,
+    durations: VecDeque<Duration>,
+}
+
+#[derive(Default)]
+pub struct CommandMetrics {
+    commands: Mutex<HashMap<String, Samples>>,
+}
+
+#[derive(Clone, Serialize)]
+pub struct CommandStats {
+    pub command: String,
+    pub calls: u64,
+    pub errors: u64,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub max_ms: f64,
+}
+
+impl CommandMetrics {
+    fn record(&self, command: &str, ok: bool, elapsed: Duration) {
+        let Ok(mut commands) = self.commands.lock() else {
+            return;
+        };
+        let samples = commands.entry(command.to_string()).or_default();
+        samples.calls += 1;
+        if !ok {
+            samples.errors += 1;
+        }
+        samples.durations.push_back(elapsed);
+        if samples.durations.len() > SAMPLE_WINDOW {
+            samples.durations.pop_front();
+        }
+    }
+
+    pub fn stats(&self) -> Result<Vec<CommandStats>, String> {
+        let commands = self.commands.lock().map_err(|e| e.to_string())?;
+        let mut stats: Vec<CommandStats> = commands
+            .iter()
+            .map(|(command, samples)| {
+                let mut sorted: Vec<Duration> = samples.durations.iter().copied().collect();
+                sorted.sort();
+                let percentile = |p: f64| {
+                    let index = ((sorted.len() as f64 - 1.0) * p).round() as usize;
+                    sorted.get(index).map_or(0.0, |d| d.as_secs_f64() * 1000.0)
+                };
+                CommandStats {
+                    command: command.clone(),
+                    calls: samples.calls,
+                    errors: samples.errors,
+                    p50_ms: percentile(0.5),
+                    p95_ms: percentile(0.95),
+                    max_ms: percentile(1.0),
+                }
+            })
+            .collect();
+        stats.sort_by(|a, b| a.command.cmp(&b.command));
+        Ok(stats)
+    }
+}
+
+fn is_secret(key: &str) -> bool {
+    let key = key.to_lowercase();
+    SECRET_KEYS.iter().any(|s| key.contains(s))
+}
+
+pub fn redact(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(k, v)| {
+                    let v = if is_secret(k) {
+                        Value::String("[redacted]".to_string())
+                    } else {
+                        redact(v)
+                    };
+                    (k.clone(), v)
+                })
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.iter().map(redact).collect()),
+        other => other.clone(),
+    }
+}
+
+// Wraps the generated command handler to log each call. The handler can't see a
+// command's result or when an async one finishes, so timings and outcomes come from the
+// frontend through record_command_result.
+pub fn instrumented(
+    handler: impl Fn(Invoke) -> bool + Send + Sync + 'static,
+) -> impl Fn(Invoke) -> bool + Send + Sync + 'static {
+    move |invoke: Invoke| {
+        let command = invoke.message.command().to_string();
+        if command != REPORT_COMMAND {
+            let args = match invoke.message.payload() {
+                InvokeBody::Json(value) => redact(value).to_string(),
+                InvokeBody::Raw(bytes) => format!("<{} bytes>", bytes.len()),
+            };
+            log::debug!(target: "commands", "{} {}", command, args);
+        }
+        let handled = handler(invoke);
+        if !handled {
+            log::warn!(target: "commands", "unknown command {}", command);
+        }
+        handled
+    }
+}
+
+// Called by the frontend once a command has resolved or failed, with the round trip time
+#[tauri::command]
+pub fn record_command_result(
+    metrics: State<'_, CommandMetrics>,
+    command: String,
+    ok: bool,
+    duration_ms: f64,
+) {
+    let elapsed = Duration::try_from_secs_f64(duration_ms / 1000.0).unwrap_or_default();
+    if ok {
+        log::debug!(target: "commands", "{} finished in {:?}", command, elapsed);
+    } else {
+        log::debug!(target: "commands", "{} failed after {:?}", command, elapsed);
+    }
+    metrics.record(&command, ok, elapsed);
+}
+
+#[tauri::command]
+pub fn get_command_metrics(
+    metrics: State<'_, CommandMetrics>,
+) -> Result<Vec<CommandStats>, String> {
+    metrics.stats()
+}
@@ -0,0 +1,199 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager, State};
+
+use crate::encryption;
+use crate::storage;
+
+// Schema version of the JSON data files as a whole. settings.json is versioned
+// separately by the settings loader.
+pub const STORE_VERSION: u32 = 2;
+
+const META_FILE: &str = "store.json";
+
+// MIGRATIONS[n] upgrades the store from version n + 1 to n + 2.
+const MIGRATIONS: &[fn(&Path) -> Result<(), String>] = &[v1_to_v2];
+
+#[derive(Default, Serialize, Deserialize)]
+struct StoreMeta {
+    version: u32,
+}
+
+#[derive(Clone, Serialize)]
+pub struct DataFileInfo {
+    pub name: String,
+    pub bytes: u64,
+    // Length of every list or map at the top level of the file, keyed by field name
+    pub records: BTreeMap<String, usize>,
+}
+
+#[derive(Clone, Serialize)]
+pub struct StoreInfo {
+    pub version: u32,
+    pub directory: PathBuf,
+    pub files: Vec<DataFileInfo>,
+    pub last_backup: Option<PathBuf>,
+    pub encrypted: bool,
+}
+
+pub struct MigrationState {
+    pub last_backup: Option<PathBuf>,
+    // No data existed before this launch
+    pub fresh_install: bool,
+}
+
+fn read_value(path: &Path) -> Result<Option<Value>, String> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let contents = storage::read_text(path)?;
+    serde_json::from_str(&contents)
+        .map(Some)
+        .map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+// v2 stores concurrent timers: timer.json changes from a single optional timer to
+// { timers: [...], primary }.
+fn v1_to_v2(dir: &Path) -> Result<(), String> {
+    let path = dir.join("timer.json");
+    let timer = match read_value(&path)? {
+        Some(Value::Object(map)) if map.contains_key("timers") => return Ok(()),
+        Some(Value::Object(mut timer)) => {
+            timer
+                .entry("id")
+                .or_insert_with(|| Value::String("main".to_string()));
+            Some(Value::Object(timer))
+        }
+        _ => None,
+    };
+    let mut set = Map::new();
+    set.insert(
+        "primary".to_string(),
+        timer
+            .as_ref()
+            .and_then(|t| t.get("id").cloned())
+            .unwrap_or(Value::Null),
+    );
+    set.insert(
+        "timers".to_string(),
+        Value::Array(timer.into_iter().collect()),
+    );
+    storage::save_json(&path, &Value::Object(set))
+}
+
+fn backup(dir: &Path, version: u32) -> Result<PathBuf, String> {
+    let target = dir.join("backups").join(format!(
+        "pre-migration-v{}-{}",
+        version,
+        Utc::now().format("%Y%m%dT%H%M%SZ")
+    ));
+    fs::create_dir_all(&target).map_err(|e| e.to_string())?;
+    for name in storage::DATA_FILES {
+        let source = dir.join(name);
+        if source.exists() {
+            fs::copy(&source, target.join(name)).map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(target)
+}
+
+// Runs before any store is loaded. Existing installs without store.json are treated as
+// version 1; a fresh data directory starts at the current version.
+pub fn run(app: &AppHandle) -> Result<(), String> {
+    let meta_path = storage::data_file(app, META_FILE)?;
+    let dir = meta_path
+        .parent()
+        .ok_or_else(|| "invalid data directory".to_string())?
+        .to_path_buf();
+    let mut meta: StoreMeta = storage::load_json(&meta_path)?;
+    let mut fresh_install = false;
+    if meta.version == 0 {
+        let existing = storage::DATA_FILES
+            .iter()
+            .any(|name| dir.join(name).exists());
+        meta.version = if existing { 1 } else { STORE_VERSION };
+        fresh_install = !existing;
+    }
+
+    let mut last_backup = None;
+    if meta.version < STORE_VERSION {
+        let path = backup(&dir, meta.version)?;
+        log::info!(
+            "migrating data store from v{} to v{} (backup in {})",
+            meta.version,
+            STORE_VERSION,
+            path.display()
+        );
+        for (i, step) in MIGRATIONS
+            .iter()
+            .enumerate()
+            .skip(meta.version as usize - 1)
+        {
+            step(&dir)?;
+            meta.version = i as u32 + 2;
+            storage::save_json(&meta_path, &meta)?;
+        }
+        last_backup = Some(path);
+    } else if meta.version > STORE_VERSION {
+        return Err(format!(
+            "data store version {} is newer than this build supports ({})",
+            meta.version, STORE_VERSION
+        ));
+    }
+    storage::save_json(&meta_path, &meta)?;
+    app.manage(MigrationState {
+        last_backup,
+        fresh_install,
+    });
+    Ok(())
+}
+
+fn file_info(dir: &Path, name: &str) -> Result<Option<DataFileInfo>, String> {
+    let path = dir.join(name);
+    let Some(value) = read_value(&path)? else {
+        return Ok(None);
+    };
+    let mut records = BTreeMap::new();
+    if let Value::Object(map) = &value {
+        for (key, field) in map {
+            match field {
+                Value::Array(items) => records.insert(key.clone(), items.len()),
+                Value::Object(items) => records.insert(key.clone(), items.len()),
+                _ => None,
+            };
+        }
+    }
+    let bytes = fs::metadata(&path).map_err(|e| e.to_string())?.len();
+    Ok(Some(DataFileInfo {
+        name: name.to_string(),
+        bytes,
+        records,
+    }))
+}
+
+#[tauri::command]
+pub fn get_db_info(app: AppHandle, state: State<'_, MigrationState>) -> Result<StoreInfo, String> {
+    let meta_path = storage::data_file(&app, META_FILE)?;
+    let directory = meta_path
+        .parent()
+        .ok_or_else(|| "invalid data directory".to_string())?
+        .to_path_buf();
+    let meta: StoreMeta = storage::load_json(&meta_path)?;
+    let mut files = Vec::new();
+    for name in storage::DATA_FILES {
+        if let Some(info) = file_info(&directory, name)? {
+            files.push(info);
+        }
+    }
+    Ok(StoreInfo {
+        version: meta.version,
+        directory,
+        files,
+        last_backup: state.last_backup.clone(),
+        encrypted: encryption::is_enabled(),
+    })
+}
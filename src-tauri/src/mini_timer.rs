@@ -0,0 +1,117 @@
+// A small frameless window that floats above everything else and shows the running timer.
+// It renders the frontend's /mini route and listens to the same `timer-tick` events as the
+// main window.
+use serde::{Deserialize, Serialize};
+use tauri::{
+    AppHandle, LogicalPosition, Manager, State, WebviewUrl, WebviewWindow, WebviewWindowBuilder,
+    WindowEvent,
+};
+
+use crate::settings::SettingsStore;
+use crate::windows;
+
+const LABEL: &str = "mini-timer";
+const WIDTH: f64 = 240.0;
+const HEIGHT: f64 = 64.0;
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct WindowPosition {
+    pub x: f64,
+    pub y: f64,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MiniTimerSettings {
+    // Logical position of the top-left corner; None lets the OS place it
+    pub position: Option<WindowPosition>,
+    // Clicks pass through to the window below, so it can sit over other apps' content
+    pub click_through: bool,
+}
+
+fn save_position(window: &WebviewWindow) {
+    let (Ok(position), Ok(scale)) = (window.outer_position(), window.scale_factor()) else {
+        return;
+    };
+    let position: LogicalPosition<f64> = position.to_logical(scale);
+    let result = window.state::<SettingsStore>().update(|s| {
+        s.mini_timer.position = Some(WindowPosition {
+            x: position.x,
+            y: position.y,
+        })
+    });
+    if let Err(e) = result {
+        log::warn!("failed to save mini timer position: {}", e);
+    }
+}
+
+fn build(app: &AppHandle) -> Result<WebviewWindow, String> {
+    let settings = app.state::<SettingsStore>().get()?.mini_timer;
+    let mut builder = WebviewWindowBuilder::new(app, LABEL, WebviewUrl::App("mini".into()))
+        .title("Timer")
+        .inner_size(WIDTH, HEIGHT)
+        .resizable(false)
+        .decorations(false)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .visible_on_all_workspaces(true)
+        .focused(false);
+    if let Some(position) = settings
+        .position
+        .filter(|p| windows::on_screen(app, p.x, p.y))
+    {
+        builder = builder.position(position.x, position.y);
+    }
+    let window = builder.build().map_err(|e| e.to_string())?;
+    window
+        .set_ignore_cursor_events(settings.click_through)
+        .map_err(|e| e.to_string())?;
+
+    // Moves arrive continuously while dragging; the position is saved once it's closed
+    let handle = window.clone();
+    window.on_window_event(move |event| {
+        if let WindowEvent::CloseRequested { .. } = event {
+            save_position(&handle);
+        }
+    });
+    Ok(window)
+}
+
+// app.exit skips CloseRequested, so the position is saved here on the way out
+pub fn on_exit(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window(LABEL) {
+        save_position(&window);
+    }
+}
+
+#[tauri::command]
+pub fn show_mini_timer(app: AppHandle) -> Result<(), String> {
+    let window = match app.get_webview_window(LABEL) {
+        Some(window) => window,
+        None => build(&app)?,
+    };
+    window.show().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn hide_mini_timer(app: AppHandle) -> Result<(), String> {
+    let Some(window) = app.get_webview_window(LABEL) else {
+        return Ok(());
+    };
+    window.close().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn set_mini_timer_click_through(
+    app: AppHandle,
+    settings: State<'_, SettingsStore>,
+    enabled: bool,
+) -> Result<(), String> {
+    settings.update(|s| s.mini_timer.click_through = enabled)?;
+    if let Some(window) = app.get_webview_window(LABEL) {
+        window
+            .set_ignore_cursor_events(enabled)
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
@@ -0,0 +1,173 @@
+// The platform's own notification sounds, played when the user hasn't picked a file.
+// Each notification kind maps to the closest system event.
+
+use std::path::PathBuf;
+
+use crate::notifications::NotificationKind;
+
+#[cfg(windows)]
+mod platform {
+    use std::path::PathBuf;
+    use winreg::enums::HKEY_CURRENT_USER;
+    use winreg::RegKey;
+
+    use crate::notifications::NotificationKind;
+
+    const SCHEME: &str = r"AppEvents\Schemes\Apps\.Default";
+
+    fn event(kind: NotificationKind) -> &'static str {
+        match kind {
+            NotificationKind::Goal => "Notification.Reminder",
+            NotificationKind::Team => "Notification.IM",
+            NotificationKind::Timer => "Notification.Looping.Alarm2",
+            NotificationKind::Focus | NotificationKind::Compliance => "SystemExclamation",
+            NotificationKind::System => "Notification.Default",
+        }
+    }
+
+    fn expand(path: &str) -> String {
+        ["SystemRoot", "WINDIR", "SystemDrive"]
+            .iter()
+            .fold(path.to_string(), |path, var| match std::env::var(var) {
+                Ok(value) => path.replace(&format!("%{}%", var), &value),
+                Err(_) => path,
+            })
+    }
+
+    // The wav file the active sound scheme assigns to the event; the scheme can leave
+    // events silent, in which case there is none
+    pub fn find(kind: NotificationKind) -> Option<PathBuf> {
+        [event(kind), "Notification.Default"]
+            .iter()
+            .find_map(|event| {
+                let path: String = RegKey::predef(HKEY_CURRENT_USER)
+                    .open_subkey(format!(r"{}\{}\.Current", SCHEME, event))
+                    .and_then(|key| key.get_value(""))
+                    .ok()?;
+                let path = PathBuf::from(expand(path.trim()));
+                path.is_file().then_some(path)
+            })
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::path::PathBuf;
+
+    use crate::notifications::NotificationKind;
+
+    // NSSound names, looked up where NSSound looks for them
+    fn name(kind: NotificationKind) -> &'static str {
+        match kind {
+            NotificationKind::Goal => "Glass",
+            NotificationKind::Team => "Ping",
+            NotificationKind::Timer => "Hero",
+            NotificationKind::Focus | NotificationKind::Compliance => "Funk",
+            NotificationKind::System => "Pop",
+        }
+    }
+
+    pub fn find(kind: NotificationKind) -> Option<PathBuf> {
+        let file = format!("{}.aiff", name(kind));
+        let mut dirs: Vec<PathBuf> = dirs::home_dir()
+            .map(|home| home.join("Library/Sounds"))
+            .into_iter()
+            .collect();
+        dirs.push(PathBuf::from("/Library/Sounds"));
+        dirs.push(PathBuf::from("/System/Library/Sounds"));
+        dirs.into_iter()
+            .map(|d| d.join(&file))
+            .find(|p| p.is_file())
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use std::path::PathBuf;
+
+    use crate::notifications::NotificationKind;
+
+    const THEME: &str = "freedesktop";
+    const EXTENSIONS: &[&str] = &["oga", "ogg", "wav"];
+
+    // Names from the freedesktop sound naming spec, with a generic fallback
+    fn names(kind: NotificationKind) -> [&'static str; 2] {
+        let name = match kind {
+            NotificationKind::Goal => "complete",
+            NotificationKind::Focus | NotificationKind::Compliance => "dialog-warning",
+            NotificationKind::Timer => "alarm-clock-elapsed",
+            NotificationKind::Team => "message-new-instant",
+            NotificationKind::System => "dialog-information",
+        };
+        [name, "bell"]
+    }
+
+    fn data_dirs() -> Vec<PathBuf> {
+        let mut dirs: Vec<PathBuf> = dirs::data_dir().into_iter().collect();
+        let system = std::env::var("XDG_DATA_DIRS")
+            .ok()
+            .filter(|d| !d.is_empty())
+            .unwrap_or_else(|| "/usr/local/share:/usr/share".to_string());
+        dirs.extend(system.split(':').map(PathBuf::from));
+        dirs
+    }
+
+    pub fn find(kind: NotificationKind) -> Option<PathBuf> {
+        let dirs = data_dirs();
+        names(kind).iter().find_map(|name| {
+            dirs.iter().find_map(|dir| {
+                EXTENSIONS
+                    .iter()
+                    .map(|ext| {
+                        dir.join("sounds")
+                            .join(THEME)
+                            .join("stereo")
+                            .join(format!("{}.{}", name, ext))
+                    })
+                    .find(|p| p.is_file())
+            })
+        })
+    }
+}
+
+#[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+mod platform {
+    use std::path::PathBuf;
+
+    use crate::notifications::NotificationKind;
+
+    pub fn find(_kind: NotificationKind) -> Option<PathBuf> {
+        None
+    }
+}
+
+// The system sound file for the kind, if the platform has one installed
+pub fn find(kind: NotificationKind) -> Option<PathBuf> {
+    platform::find(kind)
+}
+
+// AIFF, which rodio can't decode, is played with the system player instead
+#[cfg(target_os = "macos")]
+pub fn play(
+    path: &std::path::Path,
+    volume: f32,
+    interrupted: impl Fn() -> bool,
+) -> Result<(), String> {
+    let mut child = std::process::Command::new("afplay")
+        .arg("-v")
+        .arg(volume.clamp(0.0, 1.0).to_string())
+        .arg(path)
+        .spawn()
+        .map_err(|e| format!("afplay: {}", e))?;
+    loop {
+        if child.try_wait().map_err(|e| e.to_string())?.is_some() {
+            return Ok(());
+        }
+        if interrupted() {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Ok(());
+        }
+        std::thread::sleep(std::time::Duration::from_millis(20));
+    }
+}
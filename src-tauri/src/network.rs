@@ -0,0 +1,83 @@
+use serde::Serialize;
+
+// What the OS reports about the connection to the internet. None where the platform
+// can't tell.
+#[derive(Clone, Copy, Default, Serialize)]
+pub struct NetworkStatus {
+    pub wifi: Option<bool>,
+    // Metered or otherwise costed, e.g. a phone hotspot or a data plan
+    pub metered: Option<bool>,
+}
+
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+fn run(program: &str, args: &[&str]) -> Option<String> {
+    let output = std::process::Command::new(program)
+        .args(args)
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(windows)]
+pub fn status() -> NetworkStatus {
+    use windows::Networking::Connectivity::{NetworkCostType, NetworkInformation};
+    let Ok(profile) = NetworkInformation::GetInternetConnectionProfile() else {
+        return NetworkStatus::default();
+    };
+    let metered = profile
+        .GetConnectionCost()
+        .and_then(|cost| cost.NetworkCostType())
+        .ok()
+        .filter(|cost| *cost != NetworkCostType::Unknown)
+        .map(|cost| cost != NetworkCostType::Unrestricted);
+    NetworkStatus {
+        wifi: profile.IsWlanConnectionProfile().ok(),
+        metered,
+    }
+}
+
+// NetworkManager; the first connected device is taken as the route to the internet
+#[cfg(target_os = "linux")]
+pub fn status() -> NetworkStatus {
+    let devices = run("nmcli", &["-t", "-f", "DEVICE,TYPE,STATE", "device"]).unwrap_or_default();
+    let Some((device, kind)) = devices.lines().find_map(|line| {
+        let mut fields = line.split(':');
+        let (device, kind, state) = (fields.next()?, fields.next()?, fields.next()?);
+        (state == "connected" && kind != "loopback").then(|| (device.to_string(), kind.to_string()))
+    }) else {
+        return NetworkStatus::default();
+    };
+    // "yes", "no", or either with " (guessed)"; "unknown" otherwise
+    let metered = run(
+        "nmcli",
+        &["-g", "GENERAL.METERED", "device", "show", &device],
+    )
+    .and_then(|value| match value.trim().split(' ').next() {
+        Some("yes") => Some(true),
+        Some("no") => Some(false),
+        _ => None,
+    });
+    NetworkStatus {
+        wifi: Some(kind == "wifi"),
+        metered,
+    }
+}
+
+// macOS doesn't expose the metered (Low Data Mode) flag outside the Network framework
+#[cfg(target_os = "macos")]
+pub fn status() -> NetworkStatus {
+    let wifi = run("networksetup", &["-getairportnetwork", "en0"])
+        .map(|out| out.starts_with("Current Wi-Fi Network"));
+    NetworkStatus {
+        wifi,
+        metered: None,
+    }
+}
+
+#[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+pub fn status() -> NetworkStatus {
+    NetworkStatus::default()
+}
@@ -0,0 +1,151 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+use zbus::blocking::{Connection, Proxy};
+use zbus::zvariant::Value;
+
+use super::{NotificationImportance, NotificationKind};
+use crate::timer::TimerManager;
+
+const DESTINATION: &str = "org.freedesktop.Notifications";
+const PATH: &str = "/org/freedesktop/Notifications";
+
+pub enum Sound<'a> {
+    Silent,
+    // Name from the freedesktop sound naming spec, resolved by the notification server
+    Named(&'static str),
+    File(&'a Path),
+}
+
+// Notification ids handed out by the server -> kind, so actions can be routed
+#[derive(Default)]
+pub struct SentNotifications(Mutex<HashMap<u32, NotificationKind>>);
+
+#[derive(Clone, serde::Serialize)]
+pub struct NotificationAction {
+    pub kind: NotificationKind,
+    pub action: String,
+}
+
+pub fn sound_name(kind: NotificationKind) -> &'static str {
+    match kind {
+        NotificationKind::Goal => "complete",
+        NotificationKind::Focus => "dialog-warning",
+        NotificationKind::Timer => "alarm-clock-elapsed",
+        NotificationKind::Team => "message-new-instant",
+        NotificationKind::System => "dialog-information",
+        NotificationKind::Compliance => "dialog-warning",
+    }
+}
+
+fn urgency(importance: NotificationImportance) -> u8 {
+    match importance {
+        NotificationImportance::Low => 0,
+        NotificationImportance::Normal => 1,
+        NotificationImportance::High => 2,
+    }
+}
+
+// Pairs of action key and label; "default" is invoked by clicking the notification body.
+fn actions(kind: NotificationKind) -> Vec<&'static str> {
+    match kind {
+        NotificationKind::Timer => vec!["default", "Open", "stop", "Stop timer"],
+        _ => vec!["default", "Open"],
+    }
+}
+
+pub fn show(
+    app: &AppHandle,
+    kind: NotificationKind,
+    importance: NotificationImportance,
+    title: &str,
+    body: &str,
+    sound: Sound<'_>,
+) -> Result<(), String> {
+    let conn = Connection::session().map_err(|e| e.to_string())?;
+    let mut hints: HashMap<&str, Value> = HashMap::new();
+    hints.insert("urgency", Value::U8(urgency(importance)));
+    match sound {
+        Sound::Silent => {
+            hints.insert("suppress-sound", Value::Bool(true));
+        }
+        Sound::Named(name) => {
+            hints.insert("sound-name", Value::from(name));
+        }
+        Sound::File(path) => {
+            hints.insert(
+                "sound-file",
+                Value::from(path.to_string_lossy().into_owned()),
+            );
+        }
+    }
+
+    let app_name = app.package_info().name.clone();
+    let reply = conn
+        .call_method(
+            Some(DESTINATION),
+            PATH,
+            Some(DESTINATION),
+            "Notify",
+            &(
+                app_name.as_str(),
+                0u32,
+                "",
+                title,
+                body,
+                actions(kind),
+                hints,
+                -1i32,
+            ),
+        )
+        .map_err(|e| e.to_string())?;
+    let id: u32 = reply.body().deserialize().map_err(|e| e.to_string())?;
+    if let Ok(mut sent) = app.state::<SentNotifications>().0.lock() {
+        sent.insert(id, kind);
+    }
+    Ok(())
+}
+
+fn on_action(app: &AppHandle, id: u32, action: String) {
+    let Some(kind) = app
+        .state::<SentNotifications>()
+        .0
+        .lock()
+        .ok()
+        .and_then(|mut sent| sent.remove(&id))
+    else {
+        return;
+    };
+    match action.as_str() {
+        "stop" => {
+            if let Err(e) = app.state::<TimerManager>().stop(app) {
+                log::error!("failed to stop timer from notification: {}", e);
+            }
+        }
+        _ => crate::show_main_window(app),
+    }
+    let _ = app.emit("notification-action", NotificationAction { kind, action });
+}
+
+// Routes ActionInvoked signals for our notifications. Without a session bus this does
+// nothing and notifications fall back to the plugin.
+pub fn listen(app: &AppHandle) {
+    app.manage(SentNotifications::default());
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let listen = || -> zbus::Result<()> {
+            let conn = Connection::session()?;
+            let proxy = Proxy::new(&conn, DESTINATION, PATH, DESTINATION)?;
+            for message in proxy.receive_signal("ActionInvoked")? {
+                if let Ok((id, action)) = message.body().deserialize::<(u32, String)>() {
+                    on_action(&app, id, action);
+                }
+            }
+            Ok(())
+        };
+        if let Err(e) = listen() {
+            log::info!("notification actions unavailable: {}", e);
+        }
+    });
+}
@@ -0,0 +1,114 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::AppHandle;
+
+use super::{NotificationImportance, NotificationKind};
+use crate::storage;
+
+const MAX_HISTORY: usize = 500;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct NotificationRecord {
+    pub id: u64,
+    pub kind: NotificationKind,
+    pub importance: NotificationImportance,
+    pub title: String,
+    pub body: String,
+    pub at: DateTime<Utc>,
+    // False when the policy suppressed it or the OS call failed
+    pub shown: bool,
+    pub read: bool,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+pub struct NotificationFilter {
+    pub unread_only: bool,
+    pub kind: Option<NotificationKind>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct HistoryData {
+    next_id: u64,
+    records: Vec<NotificationRecord>,
+}
+
+// Every notification, shown or not, so alerts missed while away can be reviewed.
+pub struct NotificationHistory {
+    path: PathBuf,
+    data: Mutex<HistoryData>,
+}
+
+impl NotificationHistory {
+    pub fn load(app: &AppHandle) -> Result<Self, String> {
+        let path = storage::data_file(app, "notifications.json")?;
+        let data = storage::load_json(&path)?;
+        Ok(Self {
+            path,
+            data: Mutex::new(data),
+        })
+    }
+
+    fn update<T>(&self, f: impl FnOnce(&mut HistoryData) -> T) -> Result<T, String> {
+        let mut data = self.data.lock().map_err(|e| e.to_string())?;
+        let result = f(&mut data);
+        storage::save_json(&self.path, &*data)?;
+        Ok(result)
+    }
+
+    pub fn record(
+        &self,
+        kind: NotificationKind,
+        importance: NotificationImportance,
+        title: &str,
+        body: &str,
+        shown: bool,
+    ) -> Result<NotificationRecord, String> {
+        self.update(|data| {
+            data.next_id += 1;
+            let record = NotificationRecord {
+                id: data.next_id,
+                kind,
+                importance,
+                title: title.to_string(),
+                body: body.to_string(),
+                at: Utc::now(),
+                shown,
+                read: false,
+            };
+            data.records.push(record.clone());
+            let excess = data.records.len().saturating_sub(MAX_HISTORY);
+            data.records.drain(..excess);
+            record
+        })
+    }
+
+    // Newest first
+    pub fn list(&self, filter: &NotificationFilter) -> Result<Vec<NotificationRecord>, String> {
+        let data = self.data.lock().map_err(|e| e.to_string())?;
+        Ok(data
+            .records
+            .iter()
+            .rev()
+            .filter(|r| !filter.unread_only || !r.read)
+            .filter(|r| filter.kind.map_or(true, |kind| r.kind == kind))
+            .take(filter.limit.unwrap_or(MAX_HISTORY))
+            .cloned()
+            .collect())
+    }
+
+    pub fn mark_read(&self, ids: &[u64]) -> Result<(), String> {
+        self.update(|data| {
+            for record in data.records.iter_mut().filter(|r| ids.contains(&r.id)) {
+                record.read = true;
+            }
+        })
+    }
+
+    pub fn clear(&self) -> Result<(), String> {
+        self.update(|data| data.records.clear())
+    }
+}
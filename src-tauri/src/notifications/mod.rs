@@ -0,0 +1,291 @@
+#[cfg(target_os = "linux")]
+mod dbus;
+mod history;
+mod throttle;
+#[cfg(windows)]
+mod toast;
+
+use chrono::{Local, Timelike};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, State};
+#[cfg(not(windows))]
+use tauri_plugin_notification::NotificationExt;
+
+use crate::meeting_detection;
+use crate::settings::SettingsStore;
+#[cfg(any(windows, target_os = "linux"))]
+use crate::sound::SoundManager;
+pub use history::{NotificationFilter, NotificationHistory, NotificationRecord};
+use throttle::{Decision, Throttle};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationKind {
+    Goal,
+    Focus,
+    Timer,
+    // Pushed by the team backend
+    Team,
+    // About the app itself, e.g. features disabled at startup
+    System,
+    // Working-time rules
+    Compliance,
+}
+
+impl NotificationKind {
+    // Matches the serialized name; used as the sound channel key in config.toml
+    pub fn channel_id(self) -> &'static str {
+        match self {
+            NotificationKind::Goal => "goal",
+            NotificationKind::Focus => "focus",
+            NotificationKind::Timer => "timer",
+            NotificationKind::Team => "team",
+            NotificationKind::System => "system",
+            NotificationKind::Compliance => "compliance",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationImportance {
+    Low,
+    Normal,
+    // Shown even during quiet hours
+    High,
+}
+
+// Local hours [start, end); may wrap past midnight, e.g. 22..7
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct QuietHours {
+    pub start_hour: u32,
+    pub end_hour: u32,
+}
+
+impl QuietHours {
+    fn contains(&self, hour: u32) -> bool {
+        if self.start_hour <= self.end_hour {
+            hour >= self.start_hour && hour < self.end_hour
+        } else {
+            hour >= self.start_hour || hour < self.end_hour
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationPolicy {
+    pub enabled: bool,
+    pub muted: Vec<NotificationKind>,
+    pub quiet_hours: Option<QuietHours>,
+    // Identical notifications within this many seconds are dropped
+    pub dedup_window_secs: u64,
+    // Maximum notifications of one kind per five minutes; 0 disables the limit
+    pub default_rate_limit: u32,
+    pub rate_limits: HashMap<NotificationKind, u32>,
+}
+
+impl Default for NotificationPolicy {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            muted: Vec::new(),
+            quiet_hours: None,
+            dedup_window_secs: 60,
+            default_rate_limit: 3,
+            rate_limits: HashMap::new(),
+        }
+    }
+}
+
+impl NotificationPolicy {
+    fn allows(&self, kind: NotificationKind, importance: NotificationImportance) -> bool {
+        if !self.enabled || self.muted.contains(&kind) {
+            return false;
+        }
+        let quiet = self
+            .quiet_hours
+            .is_some_and(|q| q.contains(Local::now().hour()));
+        !quiet || importance == NotificationImportance::High
+    }
+}
+
+// Every native notification goes through here so the user's policy is applied in one
+// place and the notification lands in the history. Returns whether it was shown.
+pub fn notify(
+    app: &AppHandle,
+    kind: NotificationKind,
+    importance: NotificationImportance,
+    title: &str,
+    body: &str,
+) -> bool {
+    let policy = match app.state::<SettingsStore>().get() {
+        Ok(settings) => settings.notifications,
+        Err(_) => NotificationPolicy::default(),
+    };
+    // Meetings get the same treatment as quiet hours
+    let in_meeting =
+        meeting_detection::in_meeting(app) && importance != NotificationImportance::High;
+    if !policy.allows(kind, importance) || in_meeting {
+        record(app, kind, importance, title, body, false);
+        return false;
+    }
+    let decision = match app.state::<Mutex<Throttle>>().lock() {
+        Ok(mut throttle) => throttle.check(&policy, kind, title, body),
+        Err(_) => Decision::Show { coalesced: 0 },
+    };
+    let body = match decision {
+        Decision::Duplicate => return false,
+        Decision::RateLimited => {
+            record(app, kind, importance, title, body, false);
+            return false;
+        }
+        Decision::Show { coalesced: 0 } => body.to_string(),
+        Decision::Show { coalesced } => format!(
+            "{}\n(+{} similar in the last {} minutes)",
+            body,
+            coalesced,
+            throttle::RATE_WINDOW.as_secs() / 60
+        ),
+    };
+
+    let shown = match show(app, kind, importance, title, &body) {
+        Ok(()) => true,
+        Err(e) => {
+            log::warn!("failed to show {:?} notification: {}", kind, e);
+            false
+        }
+    };
+    record(app, kind, importance, title, &body, shown);
+    shown
+}
+
+// Windows toasts carry their own per-channel audio, so the notification plugin (which
+// can't set sounds there) is only used on other platforms.
+#[cfg(windows)]
+fn show(
+    app: &AppHandle,
+    kind: NotificationKind,
+    importance: NotificationImportance,
+    title: &str,
+    body: &str,
+) -> Result<(), String> {
+    let sound = app.state::<SoundManager>();
+    let enabled = sound.config().map(|c| c.enabled).unwrap_or(true);
+    // A user-picked channel sound replaces the toast's own audio
+    let custom = sound.channel_sound(kind);
+    if enabled && custom.is_some() {
+        sound.play(custom.clone(), kind, importance.into(), false);
+    }
+    toast::show(
+        app,
+        kind,
+        importance,
+        title,
+        body,
+        !enabled || custom.is_some(),
+    )
+}
+
+// Talks to the notification server directly for actions, urgency and sound hints,
+// falling back to the plugin when there is no session bus.
+#[cfg(target_os = "linux")]
+fn show(
+    app: &AppHandle,
+    kind: NotificationKind,
+    importance: NotificationImportance,
+    title: &str,
+    body: &str,
+) -> Result<(), String> {
+    let manager = app.state::<SoundManager>();
+    let enabled = manager.config().map(|c| c.enabled).unwrap_or(true);
+    let custom = manager.channel_sound(kind);
+    let sound = match &custom {
+        _ if !enabled => dbus::Sound::Silent,
+        Some(path) => dbus::Sound::File(path),
+        None => dbus::Sound::Named(dbus::sound_name(kind)),
+    };
+    dbus::show(app, kind, importance, title, body, sound).or_else(|e| {
+        log::debug!("D-Bus notification failed, using plugin: {}", e);
+        show_with_plugin(app, title, body)
+    })
+}
+
+#[cfg(not(any(windows, target_os = "linux")))]
+fn show(
+    app: &AppHandle,
+    _kind: NotificationKind,
+    _importance: NotificationImportance,
+    title: &str,
+    body: &str,
+) -> Result<(), String> {
+    show_with_plugin(app, title, body)
+}
+
+#[cfg(not(windows))]
+fn show_with_plugin(app: &AppHandle, title: &str, body: &str) -> Result<(), String> {
+    app.notification()
+        .builder()
+        .title(title)
+        .body(body)
+        .show()
+        .map_err(|e| e.to_string())
+}
+
+fn record(
+    app: &AppHandle,
+    kind: NotificationKind,
+    importance: NotificationImportance,
+    title: &str,
+    body: &str,
+    shown: bool,
+) {
+    match app
+        .state::<NotificationHistory>()
+        .record(kind, importance, title, body, shown)
+    {
+        Ok(record) => {
+            let _ = app.emit("notification-recorded", &record);
+        }
+        Err(e) => log::warn!("failed to record notification: {}", e),
+    }
+}
+
+pub fn init(app: &AppHandle) -> Result<(), String> {
+    #[cfg(windows)]
+    toast::register(app);
+    #[cfg(target_os = "linux")]
+    dbus::listen(app);
+    app.manage(NotificationHistory::load(app)?);
+    app.manage(Mutex::new(Throttle::default()));
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_notification_policy(
+    settings: State<'_, SettingsStore>,
+    policy: NotificationPolicy,
+) -> Result<(), String> {
+    settings.update(|s| s.notifications = policy)?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_notifications(
+    history: State<'_, NotificationHistory>,
+    filter: Option<NotificationFilter>,
+) -> Result<Vec<NotificationRecord>, String> {
+    history.list(&filter.unwrap_or_default())
+}
+
+#[tauri::command]
+pub fn mark_read(history: State<'_, NotificationHistory>, ids: Vec<u64>) -> Result<(), String> {
+    history.mark_read(&ids)
+}
+
+#[tauri::command]
+pub fn clear_notifications(history: State<'_, NotificationHistory>) -> Result<(), String> {
+    history.clear()
+}
@@ -0,0 +1,84 @@
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use super::{NotificationKind, NotificationPolicy};
+
+pub const RATE_WINDOW: Duration = Duration::from_secs(5 * 60);
+
+pub enum Decision {
+    // Show it; `coalesced` similar notifications were held back since the last one shown
+    Show { coalesced: u32 },
+    // Same kind, title and body as one shown within the dedup window
+    Duplicate,
+    RateLimited,
+}
+
+struct Sent {
+    at: Instant,
+    kind: NotificationKind,
+    title: String,
+    body: String,
+}
+
+// In-memory record of recently shown notifications, consulted before anything reaches
+// the OS so flapping state (idle/resume, focus) can't flood the user.
+#[derive(Default)]
+pub struct Throttle {
+    sent: VecDeque<Sent>,
+    held_back: HashMap<NotificationKind, u32>,
+}
+
+impl Throttle {
+    pub fn check(
+        &mut self,
+        policy: &NotificationPolicy,
+        kind: NotificationKind,
+        title: &str,
+        body: &str,
+    ) -> Decision {
+        let now = Instant::now();
+        let keep = RATE_WINDOW.max(Duration::from_secs(policy.dedup_window_secs));
+        while self
+            .sent
+            .front()
+            .is_some_and(|s| now.duration_since(s.at) > keep)
+        {
+            self.sent.pop_front();
+        }
+
+        let dedup = Duration::from_secs(policy.dedup_window_secs);
+        if self.sent.iter().any(|s| {
+            now.duration_since(s.at) <= dedup
+                && s.kind == kind
+                && s.title == title
+                && s.body == body
+        }) {
+            return Decision::Duplicate;
+        }
+
+        let limit = policy
+            .rate_limits
+            .get(&kind)
+            .copied()
+            .unwrap_or(policy.default_rate_limit);
+        let recent = self
+            .sent
+            .iter()
+            .filter(|s| s.kind == kind && now.duration_since(s.at) <= RATE_WINDOW)
+            .count();
+        if limit > 0 && recent >= limit as usize {
+            *self.held_back.entry(kind).or_default() += 1;
+            return Decision::RateLimited;
+        }
+
+        self.sent.push_back(Sent {
+            at: now,
+            kind,
+            title: title.to_string(),
+            body: body.to_string(),
+        });
+        Decision::Show {
+            coalesced: self.held_back.remove(&kind).unwrap_or(0),
+        }
+    }
+}
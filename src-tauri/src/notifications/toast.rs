@@ -0,0 +1,102 @@
+use tauri::AppHandle;
+use windows::core::HSTRING;
+use windows::Data::Xml::Dom::XmlDocument;
+use windows::Win32::UI::Shell::SetCurrentProcessExplicitAppUserModelID;
+use windows::UI::Notifications::{
+    ToastNotification, ToastNotificationManager, ToastNotificationPriority,
+};
+
+use super::{NotificationImportance, NotificationKind};
+
+// The installer's Start Menu shortcut carries the bundle identifier as its AppUserModelID;
+// the process must claim the same id for toasts to be attributed to the app.
+fn aumid(app: &AppHandle) -> HSTRING {
+    HSTRING::from(app.config().identifier.as_str())
+}
+
+pub fn register(app: &AppHandle) {
+    // SAFETY: sets a process-wide property from a valid, owned wide string
+    if let Err(e) = unsafe { SetCurrentProcessExplicitAppUserModelID(&aumid(app)) } {
+        log::warn!("failed to register AppUserModelID: {}", e);
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+// Each channel gets its own system toast sound
+fn audio(kind: NotificationKind) -> &'static str {
+    match kind {
+        NotificationKind::Goal => "ms-winsoundevent:Notification.Reminder",
+        NotificationKind::Focus | NotificationKind::Compliance => {
+            "ms-winsoundevent:Notification.IM"
+        }
+        NotificationKind::Timer => "ms-winsoundevent:Notification.Looping.Alarm2",
+        NotificationKind::Team | NotificationKind::System => {
+            "ms-winsoundevent:Notification.Default"
+        }
+    }
+}
+
+fn toast_xml(
+    kind: NotificationKind,
+    importance: NotificationImportance,
+    title: &str,
+    body: &str,
+    silent: bool,
+) -> String {
+    // Alarm audio only loops when the toast uses the alarm scenario
+    let scenario = match (kind, importance) {
+        (NotificationKind::Timer, _) => " scenario=\"alarm\"",
+        (_, NotificationImportance::High) => " scenario=\"reminder\"",
+        _ => "",
+    };
+    let audio = if silent {
+        "<audio silent=\"true\"/>".to_string()
+    } else {
+        format!("<audio src=\"{}\"/>", audio(kind))
+    };
+    let actions = if scenario.is_empty() {
+        ""
+    } else {
+        // Reminder and alarm toasts stay on screen until dismissed
+        "<actions><action activationType=\"system\" arguments=\"dismiss\" content=\"\"/></actions>"
+    };
+    format!(
+        "<toast{}><visual><binding template=\"ToastGeneric\"><text>{}</text><text>{}</text></binding></visual>{}{}</toast>",
+        scenario,
+        escape(title),
+        escape(body),
+        audio,
+        actions
+    )
+}
+
+pub fn show(
+    app: &AppHandle,
+    kind: NotificationKind,
+    importance: NotificationImportance,
+    title: &str,
+    body: &str,
+    silent: bool,
+) -> Result<(), String> {
+    let build = || -> windows::core::Result<()> {
+        let doc = XmlDocument::new()?;
+        doc.LoadXml(&HSTRING::from(toast_xml(
+            kind, importance, title, body, silent,
+        )))?;
+        let toast = ToastNotification::CreateToastNotification(&doc)?;
+        toast.SetPriority(if importance == NotificationImportance::High {
+            ToastNotificationPriority::High
+        } else {
+            ToastNotificationPriority::Default
+        })?;
+        ToastNotificationManager::CreateToastNotifierWithId(&aumid(app))?.Show(&toast)
+    };
+    build().map_err(|e| e.to_string())
+}
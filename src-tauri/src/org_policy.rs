@@ -0,0 +1,222 @@
+use futures_util::FutureExt;
+use reqwest::{Method, StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::backend;
+use crate::background;
+use crate::flags::{self, FeatureFlags};
+use crate::project_policy::{self, EffectivePolicy, ScreenshotPolicy};
+use crate::settings::AppSettings;
+use crate::storage;
+
+const POLICY_PATH: &str = "/api/org/policy";
+const REFRESH_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+pub const EXPORT_ICS: &str = "ics";
+pub const EXPORT_CSV: &str = "csv";
+pub const EXPORT_PDF: &str = "pdf";
+pub const EXPORT_CLIPBOARD: &str = "clipboard";
+
+// Rules set by the organization's admins on the backend; they win over local and
+// project settings
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OrgPolicy {
+    pub min_idle_threshold_secs: Option<u64>,
+    pub screenshots_required: bool,
+    // EXPORT_* names
+    pub blocked_exports: Vec<String>,
+}
+
+// A local setting the organization policy replaces
+#[derive(Clone, PartialEq, Serialize)]
+pub struct PolicyOverride {
+    pub setting: String,
+    pub local: Value,
+    pub enforced: Value,
+}
+
+pub struct OrgPolicies {
+    path: PathBuf,
+    // None until the backend has sent a policy, or when the organization has none
+    policy: Mutex<Option<OrgPolicy>>,
+    // Last reported, so each override is announced once
+    overrides: Mutex<Vec<PolicyOverride>>,
+}
+
+impl OrgPolicies {
+    // Uses the policy cached at the last fetch, so it's enforced offline too.
+    pub fn load(app: &AppHandle) -> Result<Self, String> {
+        let path = storage::data_file(app, "org_policy.json")?;
+        let policy: Option<OrgPolicy> = storage::load_json(&path)?;
+        app.state::<FeatureFlags>()
+            .set_policy(flag_policy(policy.as_ref()))?;
+        Ok(Self {
+            path,
+            policy: Mutex::new(policy),
+            overrides: Mutex::default(),
+        })
+    }
+
+    pub fn get(&self) -> Result<Option<OrgPolicy>, String> {
+        Ok(self.policy.lock().map_err(|e| e.to_string())?.clone())
+    }
+
+    pub fn check_export(&self, kind: &str) -> Result<(), String> {
+        let blocked = self
+            .get()?
+            .is_some_and(|p| p.blocked_exports.iter().any(|b| b == kind));
+        if blocked {
+            return Err(format!(
+                "{} exports are disabled by your organization's policy",
+                kind.to_uppercase()
+            ));
+        }
+        Ok(())
+    }
+}
+
+fn flag_policy(policy: Option<&OrgPolicy>) -> BTreeMap<String, bool> {
+    let mut forced = BTreeMap::new();
+    if policy.is_some_and(|p| p.screenshots_required) {
+        forced.insert(flags::SCREENSHOTS.to_string(), true);
+    }
+    forced
+}
+
+fn overrides(settings: &AppSettings, policy: &OrgPolicy) -> Vec<PolicyOverride> {
+    let mut overrides = Vec::new();
+    if let Some(min) = policy.min_idle_threshold_secs {
+        if settings.idle.threshold_secs < min {
+            overrides.push(PolicyOverride {
+                setting: "idle.threshold_secs".to_string(),
+                local: json!(settings.idle.threshold_secs),
+                enforced: json!(min),
+            });
+        }
+    }
+    let screenshots_off = settings
+        .feature_flags
+        .overrides
+        .get(flags::SCREENSHOTS)
+        .is_some_and(|enabled| !enabled);
+    if policy.screenshots_required && screenshots_off {
+        overrides.push(PolicyOverride {
+            setting: "feature_flags.screenshots".to_string(),
+            local: json!(false),
+            enforced: json!(true),
+        });
+    }
+    let ics_blocked = policy.blocked_exports.iter().any(|b| b == EXPORT_ICS);
+    if ics_blocked && settings.ics_schedule.is_some() {
+        overrides.push(PolicyOverride {
+            setting: "ics_schedule".to_string(),
+            local: json!(true),
+            enforced: json!(false),
+        });
+    }
+    overrides
+}
+
+// Layers the organization policy over a project's effective policy and emits
+// `policy-override` for each local setting it newly overrides.
+pub fn apply_to(app: &AppHandle, settings: &AppSettings, effective: &mut EffectivePolicy) {
+    let Some(policies) = app.try_state::<OrgPolicies>() else {
+        return;
+    };
+    let Ok(Some(policy)) = policies.get() else {
+        return;
+    };
+    if let Some(min) = policy.min_idle_threshold_secs {
+        let threshold = effective
+            .idle_threshold_secs
+            .unwrap_or(settings.idle.threshold_secs);
+        if threshold < min {
+            effective.idle_threshold_secs = Some(min);
+        }
+    }
+    if policy.screenshots_required {
+        effective.screenshots = Some(ScreenshotPolicy {
+            enabled: true,
+            interval_secs: effective.screenshots.as_ref().and_then(|s| s.interval_secs),
+        });
+    }
+    effective.blocked_exports = policy.blocked_exports.clone();
+    effective.overrides = overrides(settings, &policy);
+
+    let Ok(mut reported) = policies.overrides.lock() else {
+        return;
+    };
+    for item in &effective.overrides {
+        if !reported.contains(item) {
+            log::info!("organization policy overrides {}", item.setting);
+            let _ = app.emit("policy-override", item);
+        }
+    }
+    *reported = effective.overrides.clone();
+}
+
+fn apply(app: &AppHandle, policy: Option<OrgPolicy>) -> Result<(), String> {
+    let policies = app.state::<OrgPolicies>();
+    let changed = {
+        let mut current = policies.policy.lock().map_err(|e| e.to_string())?;
+        let changed = *current != policy;
+        if changed {
+            storage::save_json(&policies.path, &policy)?;
+            *current = policy.clone();
+        }
+        changed
+    };
+    if changed {
+        let flags = app.state::<FeatureFlags>();
+        flags.set_policy(flag_policy(policy.as_ref()))?;
+        let _ = app.emit("feature-flags-changed", flags.all());
+        let _ = app.emit("org-policy-changed", &policy);
+    }
+    project_policy::refresh(app)?;
+    Ok(())
+}
+
+// Fetches the organization policy; a 404 means the organization has none.
+pub async fn refresh(app: &AppHandle) -> Result<(), String> {
+    if !backend::is_configured(app) {
+        return Ok(());
+    }
+    let response = backend::request(app, Method::GET, POLICY_PATH)?
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    let policy = if response.status() == StatusCode::NOT_FOUND {
+        None
+    } else {
+        response
+            .error_for_status()
+            .map_err(|e| e.to_string())?
+            .json()
+            .await
+            .map_err(|e| e.to_string())?
+    };
+    apply(app, policy)
+}
+
+pub fn start_refresh(app: AppHandle) {
+    background::spawn_async(&app, "org_policy", |app, task| {
+        async move {
+            loop {
+                if let Err(e) = refresh(&app).await {
+                    log::warn!("organization policy refresh failed: {}", e);
+                }
+                if !task.sleep(REFRESH_INTERVAL).await {
+                    break;
+                }
+            }
+        }
+        .boxed()
+    });
+}
@@ -0,0 +1,410 @@
+use chrono::{Datelike, NaiveDate, TimeDelta, Utc};
+use printpdf::{
+    BuiltinFont, Color, IndirectFontRef, Line, Mm, PdfDocument, PdfDocumentReference,
+    PdfLayerReference, Point, Rgb,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use tauri::State;
+
+use crate::billing::InvoiceData;
+use crate::org_policy::{self, OrgPolicies};
+use crate::reports;
+use crate::sessions::{DateRange, Session, SessionStore};
+use crate::settings::SettingsStore;
+use crate::time::ReportZone;
+
+// A4 portrait
+const PAGE_WIDTH: f32 = 210.0;
+const PAGE_HEIGHT: f32 = 297.0;
+const MARGIN: f32 = 18.0;
+const ROW_HEIGHT: f32 = 5.5;
+// Columns of a project table: date, time, duration, note
+const COLUMNS: [f32; 4] = [MARGIN, MARGIN + 28.0, MARGIN + 60.0, MARGIN + 82.0];
+// Columns of an invoice: description, hours, rate, amount
+const INVOICE_COLUMNS: [f32; 4] = [MARGIN, MARGIN + 104.0, MARGIN + 124.0, MARGIN + 146.0];
+// Longest note printed before it is cut; the built-in fonts have no metrics to wrap with
+const NOTE_CHARS: usize = 70;
+const DESCRIPTION_CHARS: usize = 55;
+
+// Shown on generated timesheets
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReportBranding {
+    pub company_name: Option<String>,
+    pub footer: Option<String>,
+    // Heading and rule colour
+    pub accent: [u8; 3],
+}
+
+impl Default for ReportBranding {
+    fn default() -> Self {
+        Self {
+            company_name: None,
+            footer: None,
+            accent: [37, 99, 235],
+        }
+    }
+}
+
+// Lays text out top to bottom, starting a new page when the current one is full.
+struct Writer {
+    doc: PdfDocumentReference,
+    layer: PdfLayerReference,
+    regular: IndirectFontRef,
+    bold: IndirectFontRef,
+    accent: Color,
+    footer: String,
+    page: usize,
+    // Distance of the next baseline from the bottom of the page
+    y: f32,
+}
+
+impl Writer {
+    fn new(title: &str, branding: &ReportBranding) -> Result<Self, String> {
+        let (doc, page, layer) =
+            PdfDocument::new(title, Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "content");
+        let regular = doc
+            .add_builtin_font(BuiltinFont::Helvetica)
+            .map_err(|e| e.to_string())?;
+        let bold = doc
+            .add_builtin_font(BuiltinFont::HelveticaBold)
+            .map_err(|e| e.to_string())?;
+        let layer = doc.get_page(page).get_layer(layer);
+        let [r, g, b] = branding.accent;
+        let mut writer = Self {
+            doc,
+            layer,
+            regular,
+            bold,
+            accent: Color::Rgb(Rgb::new(
+                r as f32 / 255.0,
+                g as f32 / 255.0,
+                b as f32 / 255.0,
+                None,
+            )),
+            footer: branding.footer.clone().unwrap_or_default(),
+            page: 1,
+            y: PAGE_HEIGHT - MARGIN,
+        };
+        writer.draw_footer();
+        Ok(writer)
+    }
+
+    fn draw_footer(&mut self) {
+        let text = match self.footer.as_str() {
+            "" => format!("Page {}", self.page),
+            footer => format!("{}  -  Page {}", footer, self.page),
+        };
+        self.layer
+            .use_text(text, 8.0, Mm(MARGIN), Mm(MARGIN / 2.0), &self.regular);
+    }
+
+    fn ensure(&mut self, height: f32) {
+        if self.y - height >= MARGIN {
+            return;
+        }
+        let (page, layer) = self
+            .doc
+            .add_page(Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "content");
+        self.layer = self.doc.get_page(page).get_layer(layer);
+        self.page += 1;
+        self.y = PAGE_HEIGHT - MARGIN;
+        self.draw_footer();
+    }
+
+    fn text(&mut self, x: f32, size: f32, bold: bool, text: &str) {
+        let font = if bold { &self.bold } else { &self.regular };
+        self.layer.use_text(text, size, Mm(x), Mm(self.y), font);
+    }
+
+    fn heading(&mut self, size: f32, text: &str) {
+        self.ensure(size * 0.6 + ROW_HEIGHT);
+        self.layer.set_fill_color(self.accent.clone());
+        self.text(MARGIN, size, true, text);
+        self.layer
+            .set_fill_color(Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None)));
+        self.y -= size * 0.5;
+    }
+
+    fn row(&mut self, bold: bool, cells: &[(f32, &str)]) {
+        self.ensure(ROW_HEIGHT);
+        for (x, cell) in cells {
+            self.text(*x, 9.0, bold, cell);
+        }
+        self.y -= ROW_HEIGHT;
+    }
+
+    fn rule(&mut self) {
+        self.layer.set_outline_color(self.accent.clone());
+        self.layer.set_outline_thickness(0.5);
+        self.layer.add_line(Line {
+            points: vec![
+                (Point::new(Mm(MARGIN), Mm(self.y + 3.5)), false),
+                (Point::new(Mm(PAGE_WIDTH - MARGIN), Mm(self.y + 3.5)), false),
+            ],
+            is_closed: false,
+        });
+    }
+
+    fn gap(&mut self) {
+        self.y -= ROW_HEIGHT;
+    }
+
+    fn finish(self) -> Result<Vec<u8>, String> {
+        self.doc.save_to_bytes().map_err(|e| e.to_string())
+    }
+}
+
+fn hours(seconds: i64) -> String {
+    format!("{}:{:02}", seconds / 3600, (seconds % 3600) / 60)
+}
+
+fn truncate(text: &str, chars: usize) -> String {
+    match text.char_indices().nth(chars) {
+        Some((cut, _)) => format!("{}...", &text[..cut]),
+        None => text.to_string(),
+    }
+}
+
+fn task_name(session: &Session) -> String {
+    session
+        .title
+        .clone()
+        .unwrap_or_else(|| format!("Task #{}", session.task_id))
+}
+
+// Monday to Monday in the report zone, around `date`
+pub fn week_range(date: NaiveDate, zone: ReportZone) -> DateRange {
+    let monday = date - TimeDelta::days(date.weekday().num_days_from_monday() as i64);
+    DateRange {
+        start: zone.start_of_day(monday),
+        end: zone.start_of_day(monday + TimeDelta::days(7)),
+    }
+}
+
+pub fn render_timesheet(
+    sessions: &[Session],
+    range: DateRange,
+    zone: ReportZone,
+    branding: &ReportBranding,
+) -> Result<Vec<u8>, String> {
+    let report = reports::build_report(sessions, &[], range, zone, None);
+    let first = zone.date_of(range.start);
+    let last = zone.date_of(range.end - TimeDelta::seconds(1));
+    let period = format!("{} to {}", first, last);
+
+    let mut writer = Writer::new(&format!("Timesheet {}", period), branding)?;
+    if let Some(company) = &branding.company_name {
+        writer.heading(11.0, company);
+    }
+    writer.heading(18.0, "Timesheet");
+    writer.row(false, &[(MARGIN, &period)]);
+    writer.row(
+        false,
+        &[(MARGIN, &format!("Times shown in {} time", zone.name()))],
+    );
+    writer.gap();
+
+    let mut by_task: BTreeMap<u64, Vec<&Session>> = BTreeMap::new();
+    for session in sessions.iter().filter(|s| range.contains(s)) {
+        by_task.entry(session.task_id).or_default().push(session);
+    }
+    // Largest projects first, matching the report
+    for total in &report.tasks {
+        let Some(entries) = by_task.get(&total.task_id) else {
+            continue;
+        };
+        writer.heading(12.0, &task_name(entries[0]));
+        writer.row(
+            true,
+            &[
+                (COLUMNS[0], "Date"),
+                (COLUMNS[1], "Time"),
+                (COLUMNS[2], "Duration"),
+                (COLUMNS[3], "Note"),
+            ],
+        );
+        writer.rule();
+        for session in entries {
+            let start = session.start.max(range.start);
+            let end = session.end.min(range.end);
+            let local_start = zone.local_time(start);
+            let time = format!(
+                "{}-{}",
+                local_start.format("%H:%M"),
+                zone.local_time(end).format("%H:%M")
+            );
+            writer.row(
+                false,
+                &[
+                    (COLUMNS[0], &local_start.format("%a %d %b").to_string()),
+                    (COLUMNS[1], &time),
+                    (COLUMNS[2], &hours((end - start).num_seconds())),
+                    (
+                        COLUMNS[3],
+                        &truncate(session.note.as_deref().unwrap_or_default(), NOTE_CHARS),
+                    ),
+                ],
+            );
+        }
+        writer.rule();
+        writer.row(
+            true,
+            &[(COLUMNS[0], "Total"), (COLUMNS[2], &hours(total.seconds))],
+        );
+        writer.gap();
+    }
+
+    writer.heading(12.0, "Summary");
+    for day in &report.days {
+        writer.row(
+            false,
+            &[
+                (COLUMNS[0], &day.date.format("%a %d %b").to_string()),
+                (COLUMNS[2], &hours(day.seconds)),
+            ],
+        );
+    }
+    writer.rule();
+    writer.row(
+        true,
+        &[
+            (COLUMNS[0], "Total"),
+            (COLUMNS[2], &hours(report.total_seconds)),
+        ],
+    );
+    writer.finish()
+}
+
+fn money(amount: f64, currency: &str) -> String {
+    format!("{:.2} {}", amount, currency)
+}
+
+pub fn render_invoice(
+    invoice: &InvoiceData,
+    zone: ReportZone,
+    branding: &ReportBranding,
+) -> Result<Vec<u8>, String> {
+    let first = zone.date_of(invoice.range.start);
+    let last = zone.date_of(invoice.range.end - TimeDelta::seconds(1));
+    let period = format!("{} to {}", first, last);
+
+    let mut writer = Writer::new(
+        &format!("Invoice {} {}", invoice.client.name, period),
+        branding,
+    )?;
+    if let Some(company) = &branding.company_name {
+        writer.heading(11.0, company);
+    }
+    writer.heading(18.0, "Invoice");
+    writer.row(false, &[(MARGIN, &invoice.client.name)]);
+    writer.row(false, &[(MARGIN, &period)]);
+    writer.gap();
+
+    writer.row(
+        true,
+        &[
+            (INVOICE_COLUMNS[0], "Description"),
+            (INVOICE_COLUMNS[1], "Hours"),
+            (INVOICE_COLUMNS[2], "Rate"),
+            (INVOICE_COLUMNS[3], "Amount"),
+        ],
+    );
+    writer.rule();
+    for line in &invoice.lines {
+        writer.row(
+            false,
+            &[
+                (
+                    INVOICE_COLUMNS[0],
+                    &truncate(&line.description, DESCRIPTION_CHARS),
+                ),
+                (INVOICE_COLUMNS[1], &format!("{:.2}", line.hours)),
+                (INVOICE_COLUMNS[2], &format!("{:.2}", line.hourly_rate)),
+                (INVOICE_COLUMNS[3], &money(line.amount, &line.currency)),
+            ],
+        );
+    }
+    for expense in &invoice.expenses {
+        let description = format!("{} {}", expense.date, expense.description);
+        writer.row(
+            false,
+            &[
+                (
+                    INVOICE_COLUMNS[0],
+                    &truncate(&description, DESCRIPTION_CHARS),
+                ),
+                (
+                    INVOICE_COLUMNS[3],
+                    &money(expense.amount, &expense.currency),
+                ),
+            ],
+        );
+    }
+    writer.rule();
+    writer.row(
+        false,
+        &[
+            (INVOICE_COLUMNS[2], "Subtotal"),
+            (
+                INVOICE_COLUMNS[3],
+                &money(invoice.subtotal, &invoice.currency),
+            ),
+        ],
+    );
+    writer.row(
+        false,
+        &[
+            (
+                INVOICE_COLUMNS[1],
+                &format!("Tax {}%", invoice.client.tax_rate_percent),
+            ),
+            (INVOICE_COLUMNS[3], &money(invoice.tax, &invoice.currency)),
+        ],
+    );
+    writer.row(
+        true,
+        &[
+            (INVOICE_COLUMNS[2], "Total"),
+            (INVOICE_COLUMNS[3], &money(invoice.total, &invoice.currency)),
+        ],
+    );
+    writer.finish()
+}
+
+// Renders a timesheet for `range`, or for the week containing `week_of` (this week when
+// neither is given). The built-in PDF fonts only cover Latin-1 text.
+#[tauri::command]
+pub fn render_report_pdf(
+    store: State<'_, SessionStore>,
+    settings: State<'_, SettingsStore>,
+    policy: State<'_, OrgPolicies>,
+    range: Option<DateRange>,
+    week_of: Option<NaiveDate>,
+    path: PathBuf,
+) -> Result<(), String> {
+    policy.check_export(org_policy::EXPORT_PDF)?;
+    let settings = settings.get()?;
+    let zone = ReportZone::from_settings(&settings);
+    let range = range
+        .unwrap_or_else(|| week_range(week_of.unwrap_or_else(|| zone.date_of(Utc::now())), zone));
+    if range.end <= range.start {
+        return Err("The report range is empty".to_string());
+    }
+    let sessions = store.in_range(range)?;
+    let pdf = render_timesheet(&sessions, range, zone, &settings.report_branding)?;
+    std::fs::write(&path, pdf).map_err(|e| e.to_string())?;
+    log::info!("rendered timesheet to {}", path.display());
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_report_branding(
+    settings: State<'_, SettingsStore>,
+    branding: ReportBranding,
+) -> Result<(), String> {
+    settings.update(|s| s.report_branding = branding)?;
+    Ok(())
+}
@@ -0,0 +1,275 @@
+use serde::{Deserialize, Serialize};
+use tauri::plugin::PermissionState as PluginPermission;
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_notification::NotificationExt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PermissionKind {
+    // Window titles (macOS)
+    Accessibility,
+    // Keyboard and mouse statistics (macOS)
+    InputMonitoring,
+    // Screenshots, and window titles of other apps on newer macOS
+    ScreenRecording,
+    Notifications,
+}
+
+const ALL: [PermissionKind; 4] = [
+    PermissionKind::Accessibility,
+    PermissionKind::InputMonitoring,
+    PermissionKind::ScreenRecording,
+    PermissionKind::Notifications,
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PermissionState {
+    Granted,
+    Denied,
+    // The user hasn't been asked yet
+    NotDetermined,
+    // This platform doesn't gate the feature
+    NotRequired,
+}
+
+#[derive(Clone, Serialize)]
+pub struct PermissionStatus {
+    pub kind: PermissionKind,
+    pub state: PermissionState,
+    pub required_for: Vec<String>,
+    // Whether the app can show the system prompt itself; otherwise the user has to
+    // grant it in system settings
+    pub can_request: bool,
+}
+
+impl PermissionKind {
+    fn required_for(self) -> &'static [&'static str] {
+        match self {
+            PermissionKind::Accessibility => &["window titles", "focus mode", "rules"],
+            PermissionKind::InputMonitoring => &["input statistics"],
+            PermissionKind::ScreenRecording => &["screenshots"],
+            PermissionKind::Notifications => &["reminders", "alerts"],
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use std::ffi::c_void;
+
+    // kIOHIDRequestTypeListenEvent
+    pub const LISTEN_EVENT: u32 = 1;
+    // IOHIDAccessType
+    pub const ACCESS_GRANTED: u32 = 0;
+    pub const ACCESS_DENIED: u32 = 1;
+
+    #[link(name = "ApplicationServices", kind = "framework")]
+    extern "C" {
+        pub static kAXTrustedCheckOptionPrompt: *const c_void;
+        pub fn AXIsProcessTrusted() -> bool;
+        pub fn AXIsProcessTrustedWithOptions(options: *const c_void) -> bool;
+    }
+
+    #[link(name = "CoreFoundation", kind = "framework")]
+    extern "C" {
+        pub static kCFBooleanTrue: *const c_void;
+        pub static kCFTypeDictionaryKeyCallBacks: c_void;
+        pub static kCFTypeDictionaryValueCallBacks: c_void;
+        pub fn CFDictionaryCreate(
+            allocator: *const c_void,
+            keys: *const *const c_void,
+            values: *const *const c_void,
+            count: isize,
+            key_callbacks: *const c_void,
+            value_callbacks: *const c_void,
+        ) -> *const c_void;
+        pub fn CFRelease(cf: *const c_void);
+    }
+
+    #[link(name = "IOKit", kind = "framework")]
+    extern "C" {
+        pub fn IOHIDCheckAccess(request: u32) -> u32;
+        pub fn IOHIDRequestAccess(request: u32) -> bool;
+    }
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        pub fn CGPreflightScreenCaptureAccess() -> bool;
+        pub fn CGRequestScreenCaptureAccess() -> bool;
+    }
+
+    // Shows the system prompt that points the user to the Accessibility pane
+    pub fn prompt_accessibility() -> bool {
+        // SAFETY: builds a one-entry CFDictionary from framework constants and releases it
+        unsafe {
+            let keys = [kAXTrustedCheckOptionPrompt];
+            let values = [kCFBooleanTrue];
+            let options = CFDictionaryCreate(
+                std::ptr::null(),
+                keys.as_ptr(),
+                values.as_ptr(),
+                1,
+                &kCFTypeDictionaryKeyCallBacks as *const c_void,
+                &kCFTypeDictionaryValueCallBacks as *const c_void,
+            );
+            let trusted = AXIsProcessTrustedWithOptions(options);
+            if !options.is_null() {
+                CFRelease(options);
+            }
+            trusted
+        }
+    }
+}
+
+fn notification_state(app: &AppHandle) -> PermissionState {
+    match app.notification().permission_state() {
+        Ok(PluginPermission::Granted) => PermissionState::Granted,
+        Ok(PluginPermission::Denied) => PermissionState::Denied,
+        Ok(_) => PermissionState::NotDetermined,
+        Err(e) => {
+            log::warn!("notification permission check failed: {}", e);
+            PermissionState::NotDetermined
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn system_state(kind: PermissionKind) -> PermissionState {
+    let granted = |ok: bool| {
+        if ok {
+            PermissionState::Granted
+        } else {
+            PermissionState::Denied
+        }
+    };
+    // SAFETY: side-effect-free permission queries
+    unsafe {
+        match kind {
+            PermissionKind::Accessibility => granted(macos::AXIsProcessTrusted()),
+            PermissionKind::InputMonitoring => match macos::IOHIDCheckAccess(macos::LISTEN_EVENT) {
+                macos::ACCESS_GRANTED => PermissionState::Granted,
+                macos::ACCESS_DENIED => PermissionState::Denied,
+                _ => PermissionState::NotDetermined,
+            },
+            PermissionKind::ScreenRecording => granted(macos::CGPreflightScreenCaptureAccess()),
+            PermissionKind::Notifications => PermissionState::NotRequired,
+        }
+    }
+}
+
+// Windows and Linux don't gate these behind user consent
+#[cfg(not(target_os = "macos"))]
+fn system_state(_kind: PermissionKind) -> PermissionState {
+    PermissionState::NotRequired
+}
+
+pub fn state(app: &AppHandle, kind: PermissionKind) -> PermissionState {
+    match kind {
+        PermissionKind::Notifications => notification_state(app),
+        _ => system_state(kind),
+    }
+}
+
+// For gating a subsystem without an app handle; notifications are never gated here.
+pub fn is_granted(kind: PermissionKind) -> bool {
+    matches!(
+        system_state(kind),
+        PermissionState::Granted | PermissionState::NotRequired
+    )
+}
+
+fn can_request(kind: PermissionKind) -> bool {
+    cfg!(target_os = "macos") || kind == PermissionKind::Notifications
+}
+
+pub fn status(app: &AppHandle) -> Vec<PermissionStatus> {
+    ALL.iter()
+        .map(|&kind| PermissionStatus {
+            kind,
+            state: state(app, kind),
+            required_for: kind.required_for().iter().map(|s| s.to_string()).collect(),
+            can_request: can_request(kind),
+        })
+        .collect()
+}
+
+#[cfg(target_os = "macos")]
+fn settings_url(kind: PermissionKind) -> Option<&'static str> {
+    Some(match kind {
+        PermissionKind::Accessibility => {
+            "x-apple.systempreferences:com.apple.preference.security?Privacy_Accessibility"
+        }
+        PermissionKind::InputMonitoring => {
+            "x-apple.systempreferences:com.apple.preference.security?Privacy_ListenEvent"
+        }
+        PermissionKind::ScreenRecording => {
+            "x-apple.systempreferences:com.apple.preference.security?Privacy_ScreenCapture"
+        }
+        PermissionKind::Notifications => {
+            "x-apple.systempreferences:com.apple.preference.notifications"
+        }
+    })
+}
+
+#[cfg(windows)]
+fn settings_url(kind: PermissionKind) -> Option<&'static str> {
+    (kind == PermissionKind::Notifications).then_some("ms-settings:notifications")
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+fn settings_url(_kind: PermissionKind) -> Option<&'static str> {
+    None
+}
+
+fn open_url(url: &str) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    let mut command = std::process::Command::new("open");
+    #[cfg(windows)]
+    let mut command = std::process::Command::new("explorer");
+    #[cfg(not(any(windows, target_os = "macos")))]
+    let mut command = std::process::Command::new("xdg-open");
+    command.arg(url).spawn().map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_permission_status(app: AppHandle) -> Vec<PermissionStatus> {
+    status(&app)
+}
+
+// Shows the system prompt where the platform has one. macOS only prompts once per
+// permission; after that the user has to use open_permission_settings.
+#[tauri::command]
+pub fn request_permission(app: AppHandle, kind: PermissionKind) -> Result<PermissionState, String> {
+    if kind == PermissionKind::Notifications {
+        app.notification()
+            .request_permission()
+            .map_err(|e| e.to_string())?;
+    }
+    #[cfg(target_os = "macos")]
+    // SAFETY: these calls only show the system consent prompt
+    unsafe {
+        match kind {
+            PermissionKind::Accessibility => {
+                macos::prompt_accessibility();
+            }
+            PermissionKind::InputMonitoring => {
+                macos::IOHIDRequestAccess(macos::LISTEN_EVENT);
+            }
+            PermissionKind::ScreenRecording => {
+                macos::CGRequestScreenCaptureAccess();
+            }
+            PermissionKind::Notifications => {}
+        }
+    }
+    let _ = app.emit("permissions-changed", status(&app));
+    Ok(state(&app, kind))
+}
+
+#[tauri::command]
+pub fn open_permission_settings(kind: PermissionKind) -> Result<(), String> {
+    let url = settings_url(kind)
+        .ok_or_else(|| format!("{:?} has no settings page on this platform", kind))?;
+    open_url(url)
+}
@@ -0,0 +1,197 @@
+use chrono::{DateTime, TimeDelta, Utc};
+use futures_util::FutureExt;
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+
+use crate::app_lock::AppLock;
+use crate::backend;
+use crate::background;
+use crate::idle::IdleMonitor;
+use crate::meeting_detection;
+use crate::settings::SettingsStore;
+use crate::timer::TimerManager;
+
+const PRESENCE_PATH: &str = "/api/presence";
+const TEAM_PRESENCE_PATH: &str = "/api/team/presence";
+const POLL_INTERVAL: Duration = Duration::from_secs(15);
+// Re-sent even when nothing changed, so the backend can tell a closed app from a quiet one
+const HEARTBEAT: TimeDelta = TimeDelta::minutes(1);
+// Teammates not heard from for this long are shown offline
+const STALE_AFTER: TimeDelta = TimeDelta::minutes(3);
+
+// Off by default; nothing is published until the user opts in
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PresenceSettings {
+    pub enabled: bool,
+    // Also share the running timer's title; window titles and apps are never shared
+    pub share_task: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PresenceState {
+    Working,
+    InMeeting,
+    Away,
+    Offline,
+}
+
+// Everything that leaves the machine
+#[derive(Clone, PartialEq, Serialize)]
+pub struct Presence {
+    pub state: PresenceState,
+    pub task: Option<String>,
+    pub since: DateTime<Utc>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct TeammatePresence {
+    pub user_id: String,
+    pub name: String,
+    pub state: PresenceState,
+    #[serde(default)]
+    pub task: Option<String>,
+    #[serde(default)]
+    pub since: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Default)]
+pub struct PresencePublisher {
+    // Last state sent and when
+    published: Mutex<Option<(Presence, DateTime<Utc>)>>,
+}
+
+fn current_state(app: &AppHandle) -> (PresenceState, Option<String>) {
+    let locked = app.try_state::<AppLock>().is_some_and(|l| l.is_locked());
+    let idle = app.try_state::<IdleMonitor>().is_some_and(|m| m.is_idle());
+    let timer = app.state::<TimerManager>().active();
+    if meeting_detection::in_meeting(app) {
+        (PresenceState::InMeeting, None)
+    } else if locked || idle {
+        (PresenceState::Away, None)
+    } else if let Some(timer) = timer {
+        (PresenceState::Working, timer.title)
+    } else {
+        (PresenceState::Away, None)
+    }
+}
+
+async fn send(app: &AppHandle, presence: &Presence) -> Result<(), String> {
+    backend::request(app, Method::PUT, PRESENCE_PATH)?
+        .json(presence)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?
+        .error_for_status()
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+async fn publish(app: &AppHandle, settings: &PresenceSettings) -> Result<(), String> {
+    let (state, task) = if settings.enabled {
+        current_state(app)
+    } else {
+        (PresenceState::Offline, None)
+    };
+    let task = task.filter(|_| settings.share_task);
+    let now = Utc::now();
+    let presence = {
+        let published = app
+            .state::<PresencePublisher>()
+            .published
+            .lock()
+            .map_err(|e| e.to_string())?
+            .clone();
+        match published {
+            // Opted out and already said so
+            None if !settings.enabled => return Ok(()),
+            Some((last, _)) if !settings.enabled && last.state == PresenceState::Offline => {
+                return Ok(())
+            }
+            Some((last, at)) if last.state == state && last.task == task => {
+                if now - at < HEARTBEAT {
+                    return Ok(());
+                }
+                last
+            }
+            _ => Presence {
+                state,
+                task,
+                since: now,
+            },
+        }
+    };
+    send(app, &presence).await?;
+    *app.state::<PresencePublisher>()
+        .published
+        .lock()
+        .map_err(|e| e.to_string())? = Some((presence, now));
+    Ok(())
+}
+
+pub fn start_publisher(app: AppHandle) {
+    app.manage(PresencePublisher::default());
+    background::spawn_async(&app, "presence", |app, task| {
+        async move {
+            loop {
+                let settings = app.state::<SettingsStore>().get().map(|s| s.presence);
+                if let (true, Ok(settings)) = (backend::is_configured(&app), settings) {
+                    if let Err(e) = publish(&app, &settings).await {
+                        log::debug!("presence publish failed: {}", e);
+                    }
+                }
+                if !task.sleep(POLL_INTERVAL).await {
+                    break;
+                }
+            }
+        }
+        .boxed()
+    });
+}
+
+#[tauri::command]
+pub async fn get_team_presence(app: AppHandle) -> Result<Vec<TeammatePresence>, String> {
+    let mut team: Vec<TeammatePresence> = backend::request(&app, Method::GET, TEAM_PRESENCE_PATH)?
+        .send()
+        .await
+        .map_err(|e| e.to_string())?
+        .error_for_status()
+        .map_err(|e| e.to_string())?
+        .json()
+        .await
+        .map_err(|e| e.to_string())?;
+    let cutoff = Utc::now() - STALE_AFTER;
+    for mate in &mut team {
+        if mate.updated_at.is_some_and(|at| at < cutoff) {
+            mate.state = PresenceState::Offline;
+            mate.task = None;
+        }
+    }
+    Ok(team)
+}
+
+// The state last published for this user, if any
+#[tauri::command]
+pub fn get_presence(publisher: State<'_, PresencePublisher>) -> Result<Option<Presence>, String> {
+    Ok(publisher
+        .published
+        .lock()
+        .map_err(|e| e.to_string())?
+        .as_ref()
+        .map(|(presence, _)| presence.clone()))
+}
+
+#[tauri::command]
+pub fn set_presence_settings(
+    settings: State<'_, SettingsStore>,
+    presence: PresenceSettings,
+) -> Result<(), String> {
+    settings.update(|s| s.presence = presence)?;
+    Ok(())
+}
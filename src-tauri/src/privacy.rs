@@ -0,0 +1,151 @@
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Manager, State};
+
+use crate::active_window::ActiveWindow;
+use crate::secrets;
+use crate::settings::SettingsStore;
+
+// Salt mixed into hashed titles so they can't be looked up from a list of guesses
+pub(crate) const SALT_KEY: &str = "privacy_hash_salt";
+// Hex digits kept from a hashed title; enough to tell titles apart in reports
+const HASH_LEN: usize = 12;
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum PrivacyAction {
+    // Regex replace within the title; `replacement` may use $1-style groups
+    Replace {
+        pattern: String,
+        replacement: String,
+    },
+    // Keeps a stable salted hash so equal titles still group together
+    Hash,
+    // Keeps only the app name
+    DropTitle,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PrivacyRule {
+    // App name contains this (case-insensitive); None applies to every app
+    #[serde(default)]
+    pub app: Option<String>,
+    #[serde(flatten)]
+    pub action: PrivacyAction,
+}
+
+// Applied in order to every window title before it is stored or synced
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PrivacySettings {
+    pub rules: Vec<PrivacyRule>,
+}
+
+fn salt() -> Result<String, String> {
+    match secrets::get(SALT_KEY)? {
+        Some(salt) => Ok(salt),
+        None => {
+            let salt: String = rand::thread_rng()
+                .sample_iter(&Alphanumeric)
+                .take(32)
+                .map(char::from)
+                .collect();
+            secrets::set(SALT_KEY, &salt)?;
+            Ok(salt)
+        }
+    }
+}
+
+fn hash_title(title: &str) -> Result<String, String> {
+    let digest = Sha256::new()
+        .chain_update(salt()?)
+        .chain_update(title)
+        .finalize();
+    let hex: String = digest.iter().map(|b| format!("{:02x}", b)).collect();
+    Ok(format!("#{}", &hex[..HASH_LEN]))
+}
+
+fn applies(rule: &PrivacyRule, app: &str) -> bool {
+    rule.app.as_ref().map_or(true, |needle| {
+        app.to_lowercase().contains(&needle.to_lowercase())
+    })
+}
+
+fn validate(rules: &[PrivacyRule]) -> Result<(), String> {
+    for rule in rules {
+        if let PrivacyAction::Replace { pattern, .. } = &rule.action {
+            Regex::new(pattern).map_err(|e| format!("{}: {}", pattern, e))?;
+        }
+    }
+    Ok(())
+}
+
+fn apply(rules: &[PrivacyRule], mut window: ActiveWindow) -> Result<ActiveWindow, String> {
+    for rule in rules.iter().filter(|r| applies(r, &window.app)) {
+        let Some(title) = window.title.take() else {
+            break;
+        };
+        window.title = match &rule.action {
+            PrivacyAction::Replace {
+                pattern,
+                replacement,
+            } => {
+                let regex = Regex::new(pattern).map_err(|e| format!("{}: {}", pattern, e))?;
+                Some(regex.replace_all(&title, replacement.as_str()).into_owned())
+            }
+            PrivacyAction::Hash => Some(hash_title(&title)?),
+            PrivacyAction::DropTitle => None,
+        };
+    }
+    // A rule that blanks the title is the same as dropping it
+    window.title = window.title.filter(|t| !t.trim().is_empty());
+    Ok(window)
+}
+
+// The form of `window` that may be persisted or synced. If the rules can't be applied
+// the title is dropped rather than stored unscrubbed.
+pub fn scrub(app: &AppHandle, window: ActiveWindow) -> ActiveWindow {
+    let rules = app
+        .state::<SettingsStore>()
+        .get()
+        .map(|s| s.privacy.rules)
+        .unwrap_or_default();
+    let app_name = window.app.clone();
+    apply(&rules, window).unwrap_or_else(|e| {
+        log::warn!("privacy rules failed, dropping title: {}", e);
+        ActiveWindow {
+            app: app_name,
+            title: None,
+        }
+    })
+}
+
+#[tauri::command]
+pub fn set_privacy_rules(
+    settings: State<'_, SettingsStore>,
+    rules: Vec<PrivacyRule>,
+) -> Result<(), String> {
+    validate(&rules)?;
+    settings.update(|s| s.privacy.rules = rules)?;
+    Ok(())
+}
+
+// Shows how a sample window would be stored, using `rules` when given (e.g. unsaved
+// edits) and the saved rules otherwise.
+#[tauri::command]
+pub fn preview_privacy_rules(
+    settings: State<'_, SettingsStore>,
+    app: String,
+    title: Option<String>,
+    rules: Option<Vec<PrivacyRule>>,
+) -> Result<ActiveWindow, String> {
+    let rules = match rules {
+        Some(rules) => rules,
+        None => settings.get()?.privacy.rules,
+    };
+    validate(&rules)?;
+    apply(&rules, ActiveWindow { app, title })
+}
@@ -0,0 +1,190 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use tauri::{AppHandle, Manager, State};
+
+use crate::lifecycle;
+use crate::storage;
+use crate::timer::TimerManager;
+use crate::tray;
+
+// Registry of profiles, kept in the shared app data directory
+const PROFILES_FILE: &str = "profiles.json";
+const PROFILES_DIR: &str = "profiles";
+// The profile that existed before profiles did; its data stays at the top level
+pub const DEFAULT_PROFILE: &str = "default";
+
+// Active profile for this run, when it isn't the default
+static ACTIVE: OnceLock<String> = OnceLock::new();
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Profile {
+    pub id: String,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+struct ProfileData {
+    profiles: Vec<Profile>,
+    active: String,
+}
+
+impl Default for ProfileData {
+    fn default() -> Self {
+        Self {
+            profiles: Vec::new(),
+            active: DEFAULT_PROFILE.to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+pub struct ProfileInfo {
+    pub id: String,
+    pub name: String,
+    pub active: bool,
+}
+
+pub struct ProfileStore {
+    path: PathBuf,
+    data: Mutex<ProfileData>,
+}
+
+// None for the default profile.
+pub fn active_id() -> Option<&'static str> {
+    ACTIVE.get().map(String::as_str)
+}
+
+fn profile_dir(id: &str) -> PathBuf {
+    Path::new(PROFILES_DIR).join(id)
+}
+
+// Lowercase alphanumerics and dashes, derived from the display name
+fn slug(name: &str) -> String {
+    let slug: String = name
+        .trim()
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    slug.split('-')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+impl ProfileStore {
+    fn load(app: &AppHandle) -> Result<Self, String> {
+        let path = storage::root_file(app, PROFILES_FILE)?;
+        let data = storage::load_json(&path)?;
+        Ok(Self {
+            path,
+            data: Mutex::new(data),
+        })
+    }
+
+    pub fn list(&self) -> Result<Vec<ProfileInfo>, String> {
+        let data = self.data.lock().map_err(|e| e.to_string())?;
+        let active = active_id().unwrap_or(DEFAULT_PROFILE);
+        let mut profiles = vec![ProfileInfo {
+            id: DEFAULT_PROFILE.to_string(),
+            name: "Default".to_string(),
+            active: active == DEFAULT_PROFILE,
+        }];
+        profiles.extend(data.profiles.iter().map(|p| ProfileInfo {
+            id: p.id.clone(),
+            name: p.name.clone(),
+            active: p.id == active,
+        }));
+        Ok(profiles)
+    }
+
+    // Name of the running profile, or None while only the default profile exists
+    pub fn active_name(&self) -> Option<String> {
+        let profiles = self.list().ok()?;
+        if profiles.len() < 2 {
+            return None;
+        }
+        profiles.into_iter().find(|p| p.active).map(|p| p.name)
+    }
+
+    fn create(&self, name: &str) -> Result<Profile, String> {
+        let name = name.trim();
+        let base = slug(name);
+        if base.is_empty() {
+            return Err("Profile name must contain letters or digits".to_string());
+        }
+        let mut data = self.data.lock().map_err(|e| e.to_string())?;
+        let taken = |id: &str| id == DEFAULT_PROFILE || data.profiles.iter().any(|p| p.id == id);
+        let mut id = base.clone();
+        let mut n = 2;
+        while taken(&id) {
+            id = format!("{}-{}", base, n);
+            n += 1;
+        }
+        let profile = Profile {
+            id,
+            name: name.to_string(),
+            created_at: Utc::now(),
+        };
+        data.profiles.push(profile.clone());
+        storage::save_json(&self.path, &*data)?;
+        Ok(profile)
+    }
+
+    fn set_active(&self, id: &str) -> Result<(), String> {
+        let mut data = self.data.lock().map_err(|e| e.to_string())?;
+        if id != DEFAULT_PROFILE && !data.profiles.iter().any(|p| p.id == id) {
+            return Err(format!("Unknown profile: {}", id));
+        }
+        data.active = id.to_string();
+        storage::save_json(&self.path, &*data)
+    }
+}
+
+// Must run before any store is loaded so they all resolve into the active profile.
+pub fn init(app: &AppHandle) -> Result<(), String> {
+    let store = ProfileStore::load(app)?;
+    let active = store.data.lock().map_err(|e| e.to_string())?.active.clone();
+    if active != DEFAULT_PROFILE {
+        storage::set_profile_dir(profile_dir(&active));
+        let _ = ACTIVE.set(active);
+    }
+    app.manage(store);
+    Ok(())
+}
+
+// Stops running timers so they are saved to the current profile, then restarts into
+// the new one; every store is loaded once at startup.
+pub async fn switch(app: &AppHandle, id: &str) -> Result<(), String> {
+    if active_id().unwrap_or(DEFAULT_PROFILE) == id {
+        return Ok(());
+    }
+    app.state::<TimerManager>().stop_all(app)?;
+    app.state::<ProfileStore>().set_active(id)?;
+    lifecycle::restart(app).await
+}
+
+#[tauri::command]
+pub fn list_profiles(store: State<'_, ProfileStore>) -> Result<Vec<ProfileInfo>, String> {
+    store.list()
+}
+
+#[tauri::command]
+pub fn create_profile(
+    app: AppHandle,
+    store: State<'_, ProfileStore>,
+    name: String,
+) -> Result<Profile, String> {
+    let profile = store.create(&name)?;
+    tray::refresh_menu(&app);
+    Ok(profile)
+}
+
+#[tauri::command]
+pub async fn switch_profile(app: AppHandle, id: String) -> Result<(), String> {
+    switch(&app, &id).await
+}
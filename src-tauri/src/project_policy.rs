@@ -0,0 +1,171 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Listener, Manager, State};
+
+use crate::compliance::ComplianceSettings;
+use crate::idle::IdleSettings;
+use crate::org_policy::{self, PolicyOverride};
+use crate::settings::SettingsStore;
+use crate::tasks_remote::RemoteTasks;
+use crate::timer::TimerManager;
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScreenshotPolicy {
+    pub enabled: bool,
+    #[serde(default)]
+    pub interval_secs: Option<u64>,
+}
+
+// Overrides for time tracked on a project's tasks; unset fields keep the global setting
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ProjectPolicy {
+    // Project name as assigned by the backend
+    pub project: String,
+    #[serde(default)]
+    pub idle_threshold_secs: Option<u64>,
+    #[serde(default)]
+    pub screenshots: Option<ScreenshotPolicy>,
+    // Work between breaks, replacing ComplianceSettings::break_after_hours
+    #[serde(default)]
+    pub break_interval_mins: Option<u64>,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProjectPolicySettings {
+    pub policies: Vec<ProjectPolicy>,
+}
+
+// The policy in force for the primary timer's project, with the organization policy
+// applied on top
+#[derive(Clone, Default, PartialEq, Serialize)]
+pub struct EffectivePolicy {
+    pub project: Option<String>,
+    pub idle_threshold_secs: Option<u64>,
+    pub screenshots: Option<ScreenshotPolicy>,
+    pub break_interval_mins: Option<u64>,
+    pub blocked_exports: Vec<String>,
+    // Local settings the organization policy replaces
+    pub overrides: Vec<PolicyOverride>,
+}
+
+#[derive(Default)]
+pub struct ProjectPolicies {
+    current: Mutex<EffectivePolicy>,
+}
+
+fn active_project(app: &AppHandle) -> Option<String> {
+    let task_id = app.state::<TimerManager>().active()?.task_id;
+    app.state::<RemoteTasks>()
+        .get()
+        .ok()?
+        .tasks
+        .into_iter()
+        .find(|t| t.id == task_id)?
+        .project
+}
+
+fn resolve(app: &AppHandle) -> Result<EffectivePolicy, String> {
+    let settings = app.state::<SettingsStore>().get()?;
+    let project = active_project(app);
+    let policy = project.as_ref().and_then(|project| {
+        settings
+            .project_policies
+            .policies
+            .iter()
+            .find(|p| p.project == *project)
+            .cloned()
+    });
+    let mut effective = match policy {
+        Some(policy) => EffectivePolicy {
+            project,
+            idle_threshold_secs: policy.idle_threshold_secs,
+            screenshots: policy.screenshots,
+            break_interval_mins: policy.break_interval_mins,
+            ..Default::default()
+        },
+        None => EffectivePolicy {
+            project,
+            ..Default::default()
+        },
+    };
+    org_policy::apply_to(app, &settings, &mut effective);
+    Ok(effective)
+}
+
+// Re-reads the policy for the active project and emits `project-policy-changed` when it
+// differs from the last one.
+pub fn refresh(app: &AppHandle) -> Result<EffectivePolicy, String> {
+    let policy = resolve(app)?;
+    let changed = {
+        let mut current = app
+            .state::<ProjectPolicies>()
+            .current
+            .lock()
+            .map_err(|e| e.to_string())?;
+        let changed = *current != policy;
+        *current = policy.clone();
+        changed
+    };
+    if changed {
+        let _ = app.emit("project-policy-changed", &policy);
+    }
+    Ok(policy)
+}
+
+// Applies the effective idle threshold on top of the saved settings.
+pub fn adjust_idle(app: &AppHandle, idle: &mut IdleSettings) {
+    if app.try_state::<ProjectPolicies>().is_none() {
+        return;
+    }
+    match refresh(app) {
+        Ok(policy) => {
+            if let Some(threshold) = policy.idle_threshold_secs {
+                idle.threshold_secs = threshold;
+            }
+        }
+        Err(e) => log::warn!("project policy unavailable: {}", e),
+    }
+}
+
+// Applies the effective break interval to the break reminders.
+pub fn adjust_compliance(app: &AppHandle, compliance: &mut ComplianceSettings) {
+    if app.try_state::<ProjectPolicies>().is_none() {
+        return;
+    }
+    match refresh(app) {
+        Ok(policy) => {
+            if let Some(mins) = policy.break_interval_mins.filter(|m| *m > 0) {
+                compliance.break_after_hours = mins as f64 / 60.0;
+            }
+        }
+        Err(e) => log::warn!("project policy unavailable: {}", e),
+    }
+}
+
+pub fn register(app: &AppHandle) {
+    app.manage(ProjectPolicies::default());
+    for event in ["timer-started", "timer-stopped"] {
+        let handle = app.clone();
+        app.listen(event, move |_| {
+            if let Err(e) = refresh(&handle) {
+                log::warn!("project policy unavailable: {}", e);
+            }
+        });
+    }
+}
+
+#[tauri::command]
+pub fn set_project_policies(
+    app: AppHandle,
+    settings: State<'_, SettingsStore>,
+    policies: Vec<ProjectPolicy>,
+) -> Result<EffectivePolicy, String> {
+    settings.update(|s| s.project_policies.policies = policies)?;
+    refresh(&app)
+}
+
+#[tauri::command]
+pub fn get_effective_policy(app: AppHandle) -> Result<EffectivePolicy, String> {
+    refresh(&app)
+}
@@ -0,0 +1,176 @@
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::active_window;
+use crate::background;
+use crate::devices;
+use crate::settings::SettingsStore;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+// An application whose passive use counts as active time while it has focus. With
+// neither signal required, having it focused is enough (e.g. a documentation reader).
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PassiveApp {
+    // Matched case-insensitively as a substring of the focused app's name
+    pub app: String,
+    #[serde(default)]
+    pub fullscreen: bool,
+    #[serde(default)]
+    pub audio: bool,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReadingModeSettings {
+    // Automatic detection; the manual toggle works regardless
+    pub enabled: bool,
+    pub apps: Vec<PassiveApp>,
+}
+
+impl Default for ReadingModeSettings {
+    fn default() -> Self {
+        let player = |app: &str| PassiveApp {
+            app: app.to_string(),
+            fullscreen: true,
+            audio: true,
+        };
+        Self {
+            enabled: false,
+            apps: vec![
+                player("vlc"),
+                player("mpv"),
+                player("chrome"),
+                player("firefox"),
+                player("msedge"),
+                player("safari"),
+            ],
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PassiveReason {
+    Manual,
+    Fullscreen,
+    Audio,
+    // Focused app configured without signals
+    App,
+}
+
+#[derive(Clone, Default, PartialEq, Serialize)]
+pub struct ReadingState {
+    pub active: bool,
+    pub reason: Option<PassiveReason>,
+    // The focused app that matched, for detected passive time
+    pub app: Option<String>,
+}
+
+#[derive(Default)]
+pub struct ReadingMode {
+    manual: AtomicBool,
+    active: AtomicBool,
+    state: Mutex<ReadingState>,
+}
+
+// True while passive time counts as active. Suppresses idle detection.
+pub fn is_active(app: &AppHandle) -> bool {
+    app.try_state::<ReadingMode>()
+        .is_some_and(|mode| mode.active.load(Ordering::Relaxed))
+}
+
+fn detect(settings: &ReadingModeSettings) -> ReadingState {
+    let Ok(focused) = active_window::current() else {
+        return ReadingState::default();
+    };
+    let name = focused.app.to_lowercase();
+    let Some(matched) = settings
+        .apps
+        .iter()
+        .find(|p| name.contains(&p.app.to_lowercase()))
+    else {
+        return ReadingState::default();
+    };
+    let reason = if !matched.fullscreen && !matched.audio {
+        Some(PassiveReason::App)
+    } else if matched.fullscreen && active_window::is_fullscreen().unwrap_or(false) {
+        Some(PassiveReason::Fullscreen)
+    } else if matched.audio && devices::speaker() {
+        Some(PassiveReason::Audio)
+    } else {
+        None
+    };
+    ReadingState {
+        active: reason.is_some(),
+        app: reason.map(|_| focused.app),
+        reason,
+    }
+}
+
+fn poll(app: &AppHandle) -> Result<(), String> {
+    let mode = app.state::<ReadingMode>();
+    let state = if mode.manual.load(Ordering::Relaxed) {
+        ReadingState {
+            active: true,
+            reason: Some(PassiveReason::Manual),
+            app: None,
+        }
+    } else {
+        let settings = app.state::<SettingsStore>().get()?.reading_mode;
+        if settings.enabled {
+            detect(&settings)
+        } else {
+            ReadingState::default()
+        }
+    };
+    mode.active.store(state.active, Ordering::Relaxed);
+    let changed = {
+        let mut current = mode.state.lock().map_err(|e| e.to_string())?;
+        let changed = *current != state;
+        *current = state.clone();
+        changed
+    };
+    if changed {
+        let _ = app.emit("reading-mode-changed", &state);
+    }
+    Ok(())
+}
+
+pub fn start_detector(app: AppHandle) {
+    app.manage(ReadingMode::default());
+    background::spawn_thread(&app, "reading_mode", |app, task| {
+        while task.sleep_blocking(POLL_INTERVAL) {
+            if let Err(e) = poll(&app) {
+                log::warn!("reading mode detection failed: {}", e);
+            }
+        }
+    });
+}
+
+// The manual toggle, e.g. for reading documentation without fullscreen or sound.
+#[tauri::command]
+pub fn set_reading_mode(app: AppHandle, active: bool) -> Result<ReadingState, String> {
+    app.state::<ReadingMode>()
+        .manual
+        .store(active, Ordering::Relaxed);
+    poll(&app)?;
+    get_reading_mode(app.state::<ReadingMode>())
+}
+
+#[tauri::command]
+pub fn get_reading_mode(mode: State<'_, ReadingMode>) -> Result<ReadingState, String> {
+    Ok(mode.state.lock().map_err(|e| e.to_string())?.clone())
+}
+
+#[tauri::command]
+pub fn set_reading_mode_settings(
+    settings: State<'_, SettingsStore>,
+    reading_mode: ReadingModeSettings,
+) -> Result<(), String> {
+    settings.update(|s| s.reading_mode = reading_mode)?;
+    Ok(())
+}
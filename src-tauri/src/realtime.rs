@@ -0,0 +1,223 @@
+use chrono::{DateTime, Utc};
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::header::AUTHORIZATION;
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::backend;
+use crate::notifications::{self, NotificationImportance, NotificationKind};
+use crate::tasks_remote::{self, AssignedTask};
+
+const REALTIME_PATH: &str = "/api/realtime";
+const MIN_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+// How often to check whether the backend has been configured
+const CONFIG_POLL: Duration = Duration::from_secs(30);
+
+#[derive(Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConnectionState {
+    NotConfigured,
+    Connecting,
+    Connected,
+    Disconnected,
+}
+
+#[derive(Clone, Serialize)]
+pub struct RealtimeStatus {
+    pub state: ConnectionState,
+    pub connected_since: Option<DateTime<Utc>>,
+    pub last_event_at: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+    // Failed attempts since the last successful connection
+    pub reconnect_attempts: u32,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ServerEvent {
+    TaskAssigned {
+        task: AssignedTask,
+    },
+    CheckIn {
+        #[serde(default)]
+        message: Option<String>,
+    },
+    Announcement {
+        title: String,
+        body: String,
+        #[serde(default)]
+        importance: Option<NotificationImportance>,
+    },
+}
+
+pub struct Realtime {
+    status: Mutex<RealtimeStatus>,
+}
+
+impl Default for Realtime {
+    fn default() -> Self {
+        Self {
+            status: Mutex::new(RealtimeStatus {
+                state: ConnectionState::NotConfigured,
+                connected_since: None,
+                last_event_at: None,
+                last_error: None,
+                reconnect_attempts: 0,
+            }),
+        }
+    }
+}
+
+impl Realtime {
+    pub fn status(&self) -> Result<RealtimeStatus, String> {
+        Ok(self.status.lock().map_err(|e| e.to_string())?.clone())
+    }
+}
+
+// Applies `f` to the status and emits `realtime-status` when the state changes.
+fn update(app: &AppHandle, f: impl FnOnce(&mut RealtimeStatus)) {
+    let realtime = app.state::<Realtime>();
+    let Ok(mut status) = realtime.status.lock() else {
+        return;
+    };
+    let before = status.state;
+    f(&mut status);
+    if status.state != before {
+        let _ = app.emit("realtime-status", status.clone());
+    }
+}
+
+fn ws_url(base: &str) -> Result<String, String> {
+    let url = if let Some(rest) = base.strip_prefix("https://") {
+        format!("wss://{}", rest)
+    } else if let Some(rest) = base.strip_prefix("http://") {
+        format!("ws://{}", rest)
+    } else {
+        return Err(format!("Unsupported backend URL: {}", base));
+    };
+    Ok(format!("{}{}", url, REALTIME_PATH))
+}
+
+async fn handle(app: &AppHandle, text: &str) {
+    let event: ServerEvent = match serde_json::from_str(text) {
+        Ok(event) => event,
+        Err(e) => {
+            log::warn!("ignoring realtime message: {}", e);
+            return;
+        }
+    };
+    update(app, |s| s.last_event_at = Some(Utc::now()));
+    match event {
+        ServerEvent::TaskAssigned { task } => {
+            if let Err(e) = tasks_remote::refresh(app).await {
+                log::warn!("failed to refresh assigned tasks: {}", e);
+            }
+            notifications::notify(
+                app,
+                NotificationKind::Team,
+                NotificationImportance::Normal,
+                "New task assigned",
+                &task.title,
+            );
+        }
+        ServerEvent::CheckIn { message } => {
+            let _ = app.emit("check-in-requested", &message);
+            notifications::notify(
+                app,
+                NotificationKind::Team,
+                NotificationImportance::High,
+                "Check-in requested",
+                message
+                    .as_deref()
+                    .unwrap_or("Your team asked you to check in."),
+            );
+        }
+        ServerEvent::Announcement {
+            title,
+            body,
+            importance,
+        } => {
+            notifications::notify(
+                app,
+                NotificationKind::Team,
+                importance.unwrap_or(NotificationImportance::Normal),
+                &title,
+                &body,
+            );
+        }
+    }
+}
+
+// Runs one connection until the server closes it or it fails.
+async fn connect_once(app: &AppHandle) -> Result<(), String> {
+    let mut request = ws_url(&backend::base_url(app)?)?
+        .into_client_request()
+        .map_err(|e| e.to_string())?;
+    let auth = format!("Bearer {}", backend::token()?)
+        .parse()
+        .map_err(|_| "Invalid backend token".to_string())?;
+    request.headers_mut().insert(AUTHORIZATION, auth);
+
+    update(app, |s| s.state = ConnectionState::Connecting);
+    let (mut socket, _) = connect_async(request).await.map_err(|e| e.to_string())?;
+    update(app, |s| {
+        s.state = ConnectionState::Connected;
+        s.connected_since = Some(Utc::now());
+        s.last_error = None;
+        s.reconnect_attempts = 0;
+    });
+
+    while let Some(message) = socket.next().await {
+        match message.map_err(|e| e.to_string())? {
+            Message::Text(text) => handle(app, &text).await,
+            Message::Close(_) => break,
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+// Keeps a connection open while the backend is configured, reconnecting with
+// exponential backoff.
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut backoff = MIN_BACKOFF;
+        loop {
+            if !backend::is_configured(&app) {
+                update(&app, |s| s.state = ConnectionState::NotConfigured);
+                tokio::time::sleep(CONFIG_POLL).await;
+                continue;
+            }
+            let result = connect_once(&app).await;
+            let was_connected = app
+                .state::<Realtime>()
+                .status()
+                .is_ok_and(|s| s.state == ConnectionState::Connected);
+            if was_connected {
+                backoff = MIN_BACKOFF;
+            }
+            update(&app, |s| {
+                s.state = ConnectionState::Disconnected;
+                s.connected_since = None;
+                if let Err(e) = result {
+                    log::warn!("realtime connection failed: {}", e);
+                    s.last_error = Some(e);
+                }
+                s.reconnect_attempts += 1;
+            });
+            tokio::time::sleep(backoff).await;
+            backoff = (backoff * 2).min(MAX_BACKOFF);
+        }
+    });
+}
+
+#[tauri::command]
+pub fn get_realtime_status(realtime: State<'_, Realtime>) -> Result<RealtimeStatus, String> {
+    realtime.status()
+}
@@ -0,0 +1,339 @@
+use chrono::{DateTime, Datelike, NaiveDate, Timelike, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use tauri::State;
+
+use crate::billing::BillingStore;
+use crate::coding::{CodingStore, CodingSummary};
+use crate::compliance::{self, ComplianceSummary};
+use crate::heuristics::{ActivityHeuristics, LowConfidenceSegment};
+use crate::holidays::{self, DayOff};
+use crate::rounding;
+use crate::sessions::{DateRange, Session, SessionStore};
+use crate::settings::SettingsStore;
+use crate::time::ReportZone;
+
+#[derive(Serialize)]
+pub struct TaskTotal {
+    pub task_id: u64,
+    pub title: Option<String>,
+    pub seconds: i64,
+}
+
+// Sessions with several tags count towards each of them
+#[derive(Serialize)]
+pub struct TagTotal {
+    pub tag: String,
+    pub seconds: i64,
+}
+
+#[derive(Serialize)]
+pub struct DayTotal {
+    pub date: NaiveDate,
+    pub seconds: i64,
+}
+
+#[derive(Serialize)]
+pub struct Report {
+    pub range: DateRange,
+    pub timezone: String,
+    // Only sessions carrying this tag were included
+    pub tag: Option<String>,
+    // Sum of all session durations; exceeds tracked_seconds when timers ran concurrently
+    pub total_seconds: i64,
+    // Wall-clock time covered by at least one session
+    pub tracked_seconds: i64,
+    pub overlapping_seconds: i64,
+    pub tasks: Vec<TaskTotal>,
+    pub tags: Vec<TagTotal>,
+    pub untagged_seconds: i64,
+    pub overlapping_session_ids: Vec<u64>,
+    // Session time that overlaps segments flagged by the activity heuristics
+    pub low_confidence_seconds: i64,
+    pub low_confidence_session_ids: Vec<u64>,
+    pub days: Vec<DayTotal>,
+    // Editor heartbeats over the range, whatever the tag filter; only set by get_report
+    pub coding: Option<CodingSummary>,
+    // Working-time rule checks, when enabled; only set by get_report
+    pub compliance: Option<ComplianceSummary>,
+    // Holidays and leave in the range, and past workdays with nothing tracked; only set by
+    // get_report
+    pub days_off: Vec<DayOff>,
+    pub missing_days: Vec<NaiveDate>,
+    // total_seconds with billing rounding applied to each session, when rounding is
+    // configured; only set by get_report
+    pub rounded_seconds: Option<i64>,
+}
+
+// Tracked minutes by local weekday and hour
+#[derive(Serialize)]
+pub struct Heatmap {
+    pub range: DateRange,
+    pub timezone: String,
+    pub tag: Option<String>,
+    // minutes[weekday][hour], Monday first
+    pub minutes: Vec<Vec<i64>>,
+    pub max_minutes: i64,
+    pub total_minutes: i64,
+}
+
+fn clip(session: &Session, range: DateRange) -> (DateTime<Utc>, DateTime<Utc>) {
+    (session.start.max(range.start), session.end.min(range.end))
+}
+
+// Split each span at the zone's midnights so time is credited to the day it happened.
+fn day_totals(spans: &[(DateTime<Utc>, DateTime<Utc>, u64)], zone: ReportZone) -> Vec<DayTotal> {
+    let mut days: BTreeMap<NaiveDate, i64> = BTreeMap::new();
+    for &(start, end, _) in spans {
+        let mut cursor = start;
+        while cursor < end {
+            let date = zone.date_of(cursor);
+            let next_day = date.succ_opt().map(|d| zone.start_of_day(d)).unwrap_or(end);
+            let until = end.min(next_day.max(cursor + chrono::Duration::seconds(1)));
+            *days.entry(date).or_default() += (until - cursor).num_seconds();
+            cursor = until;
+        }
+    }
+    days.into_iter()
+        .map(|(date, seconds)| DayTotal { date, seconds })
+        .collect()
+}
+
+fn has_tag(session: &Session, tag: &str) -> bool {
+    session.tags.iter().any(|t| t.eq_ignore_ascii_case(tag))
+}
+
+pub fn build_report(
+    sessions: &[Session],
+    segments: &[LowConfidenceSegment],
+    range: DateRange,
+    zone: ReportZone,
+    tag: Option<&str>,
+) -> Report {
+    let filtered: Vec<Session>;
+    let sessions = match tag {
+        Some(tag) => {
+            filtered = sessions
+                .iter()
+                .filter(|s| has_tag(s, tag))
+                .cloned()
+                .collect();
+            &filtered[..]
+        }
+        None => sessions,
+    };
+    let mut spans: Vec<(DateTime<Utc>, DateTime<Utc>, u64)> = sessions
+        .iter()
+        .filter(|s| range.contains(s))
+        .map(|s| {
+            let (start, end) = clip(s, range);
+            (start, end, s.id)
+        })
+        .collect();
+    spans.sort();
+
+    let mut tasks: BTreeMap<u64, TaskTotal> = BTreeMap::new();
+    for session in sessions.iter().filter(|s| range.contains(s)) {
+        let (start, end) = clip(session, range);
+        let total = tasks.entry(session.task_id).or_insert_with(|| TaskTotal {
+            task_id: session.task_id,
+            title: session.title.clone(),
+            seconds: 0,
+        });
+        total.seconds += (end - start).num_seconds();
+    }
+
+    // Keyed case-insensitively, labelled with the first spelling seen
+    let mut tags: BTreeMap<String, TagTotal> = BTreeMap::new();
+    let mut untagged_seconds = 0;
+    for session in sessions.iter().filter(|s| range.contains(s)) {
+        let (start, end) = clip(session, range);
+        let seconds = (end - start).num_seconds();
+        if session.tags.is_empty() {
+            untagged_seconds += seconds;
+        }
+        for tag in &session.tags {
+            tags.entry(tag.to_lowercase())
+                .or_insert_with(|| TagTotal {
+                    tag: tag.clone(),
+                    seconds: 0,
+                })
+                .seconds += seconds;
+        }
+    }
+
+    // Sweep in start order, tracking the furthest end seen so far
+    let mut tracked_seconds = 0;
+    let mut overlapping = Vec::new();
+    let mut current: Option<(DateTime<Utc>, DateTime<Utc>, u64)> = None;
+    for &(start, end, id) in &spans {
+        match current {
+            Some((cur_start, cur_end, cur_id)) if start < cur_end => {
+                overlapping.push(cur_id);
+                overlapping.push(id);
+                if end > cur_end {
+                    current = Some((cur_start, end, id));
+                }
+            }
+            _ => {
+                if let Some((cur_start, cur_end, _)) = current {
+                    tracked_seconds += (cur_end - cur_start).num_seconds();
+                }
+                current = Some((start, end, id));
+            }
+        }
+    }
+    if let Some((cur_start, cur_end, _)) = current {
+        tracked_seconds += (cur_end - cur_start).num_seconds();
+    }
+    overlapping.sort_unstable();
+    overlapping.dedup();
+
+    let mut low_confidence_seconds = 0;
+    let mut low_confidence_ids = Vec::new();
+    for &(start, end, id) in &spans {
+        for segment in segments {
+            let overlap = (end.min(segment.end) - start.max(segment.start)).num_seconds();
+            if overlap > 0 {
+                low_confidence_seconds += overlap;
+                low_confidence_ids.push(id);
+            }
+        }
+    }
+    low_confidence_ids.dedup();
+
+    let total_seconds = spans.iter().map(|(s, e, _)| (*e - *s).num_seconds()).sum();
+    let mut tasks: Vec<TaskTotal> = tasks.into_values().collect();
+    tasks.sort_by(|a, b| b.seconds.cmp(&a.seconds));
+    let mut tags: Vec<TagTotal> = tags.into_values().collect();
+    tags.sort_by(|a, b| b.seconds.cmp(&a.seconds));
+    Report {
+        range,
+        timezone: zone.name(),
+        tag: tag.map(str::to_string),
+        days: day_totals(&spans, zone),
+        total_seconds,
+        tracked_seconds,
+        overlapping_seconds: total_seconds - tracked_seconds,
+        tasks,
+        tags,
+        untagged_seconds,
+        overlapping_session_ids: overlapping,
+        low_confidence_seconds,
+        low_confidence_session_ids: low_confidence_ids,
+        coding: None,
+        compliance: None,
+        days_off: Vec::new(),
+        missing_days: Vec::new(),
+        rounded_seconds: None,
+    }
+}
+
+// Buckets session time into local hour-of-week cells, splitting spans at each local
+// hour boundary.
+pub fn build_heatmap(
+    sessions: &[Session],
+    range: DateRange,
+    zone: ReportZone,
+    tag: Option<&str>,
+) -> Heatmap {
+    let mut seconds = [[0i64; 24]; 7];
+    let included = sessions
+        .iter()
+        .filter(|s| range.contains(s) && tag.map_or(true, |tag| has_tag(s, tag)));
+    for session in included {
+        let (mut cursor, end) = clip(session, range);
+        while cursor < end {
+            let local = zone.local_time(cursor);
+            let into_hour = (local.minute() * 60 + local.second()) as i64;
+            let until = end.min(cursor + chrono::Duration::seconds(3600 - into_hour));
+            let weekday = local.weekday().num_days_from_monday() as usize;
+            seconds[weekday][local.hour() as usize] += (until - cursor).num_seconds();
+            cursor = until;
+        }
+    }
+    let minutes: Vec<Vec<i64>> = seconds
+        .iter()
+        .map(|day| day.iter().map(|s| (s + 30) / 60).collect())
+        .collect();
+    let max_minutes = minutes.iter().flatten().copied().max().unwrap_or(0);
+    let total_minutes = seconds.iter().flatten().sum::<i64>() / 60;
+    Heatmap {
+        range,
+        timezone: zone.name(),
+        tag: tag.map(str::to_string),
+        minutes,
+        max_minutes,
+        total_minutes,
+    }
+}
+
+#[tauri::command]
+pub fn get_report(
+    store: State<'_, SessionStore>,
+    heuristics: State<'_, ActivityHeuristics>,
+    coding: State<'_, CodingStore>,
+    billing: State<'_, BillingStore>,
+    settings: State<'_, SettingsStore>,
+    range: DateRange,
+    tag: Option<String>,
+) -> Result<Report, String> {
+    let settings = settings.get()?;
+    let zone = ReportZone::from_settings(&settings);
+    let sessions = store.in_range(range)?;
+    let tag = tag.as_deref().map(str::trim).filter(|t| !t.is_empty());
+    let mut report = build_report(&sessions, &heuristics.in_range(range)?, range, zone, tag);
+    let billing = billing.get()?;
+    let rounds = !billing.rounding.is_off()
+        || billing
+            .clients
+            .iter()
+            .any(|c| c.rounding.is_some_and(|p| !p.is_off()));
+    if rounds {
+        report.rounded_seconds = Some(
+            sessions
+                .iter()
+                .filter(|s| range.contains(s) && tag.map_or(true, |tag| has_tag(s, tag)))
+                .map(|s| {
+                    let (start, end) = clip(s, range);
+                    rounding::for_task(&billing, s.task_id).apply((end - start).num_seconds())
+                })
+                .sum(),
+        );
+    }
+    report.coding = Some(coding.summary(range)?);
+    if settings.compliance.enabled {
+        report.compliance = Some(compliance::summarize(
+            &sessions,
+            &[],
+            range,
+            zone,
+            &settings.compliance,
+        ));
+    }
+    // A day off with nothing tracked is expected; a workday with nothing tracked is missing data
+    let first = zone.date_of(range.start);
+    let last = zone.date_of(range.end - chrono::Duration::seconds(1));
+    let today = zone.date_of(Utc::now());
+    report.days_off = holidays::days_off(&settings.holidays, first, last);
+    report.missing_days = first
+        .iter_days()
+        .take_while(|date| *date <= last && *date < today)
+        .filter(|date| holidays::is_workday(&settings.holidays, *date))
+        .filter(|date| !report.days.iter().any(|d| d.date == *date && d.seconds > 0))
+        .collect();
+    Ok(report)
+}
+
+// Aggregated in the backend so long histories never cross to the webview as raw sessions.
+#[tauri::command]
+pub fn get_productivity_heatmap(
+    store: State<'_, SessionStore>,
+    settings: State<'_, SettingsStore>,
+    range: DateRange,
+    tag: Option<String>,
+) -> Result<Heatmap, String> {
+    let zone = ReportZone::from_settings(&settings.get()?);
+    let tag = tag.as_deref().map(str::trim).filter(|t| !t.is_empty());
+    store.read(|data| build_heatmap(&data.sessions, range, zone, tag))
+}
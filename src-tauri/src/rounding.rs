@@ -0,0 +1,118 @@
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::billing::{BillingConfig, BillingStore};
+use crate::sessions::SessionStore;
+
+const INCREMENTS_MINS: &[u32] = &[1, 5, 6, 10, 15, 30, 60];
+
+#[derive(Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoundingMode {
+    #[default]
+    Off,
+    Nearest,
+    Up,
+    Down,
+}
+
+// Applied to each session when reports and invoices are generated; stored sessions keep
+// their exact seconds.
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RoundingPolicy {
+    pub mode: RoundingMode,
+    pub increment_mins: u32,
+}
+
+impl Default for RoundingPolicy {
+    fn default() -> Self {
+        Self {
+            mode: RoundingMode::Off,
+            increment_mins: 15,
+        }
+    }
+}
+
+impl RoundingPolicy {
+    pub fn is_off(&self) -> bool {
+        self.mode == RoundingMode::Off || self.increment_mins == 0
+    }
+
+    pub fn apply(&self, seconds: i64) -> i64 {
+        let step = self.increment_mins as i64 * 60;
+        if step == 0 || seconds <= 0 {
+            return seconds.max(0);
+        }
+        let steps = match self.mode {
+            RoundingMode::Off => return seconds,
+            RoundingMode::Nearest => (seconds + step / 2) / step,
+            RoundingMode::Up => (seconds + step - 1) / step,
+            RoundingMode::Down => seconds / step,
+        };
+        steps * step
+    }
+}
+
+pub fn validate(policy: &RoundingPolicy) -> Result<(), String> {
+    if !policy.is_off() && !INCREMENTS_MINS.contains(&policy.increment_mins) {
+        return Err(format!(
+            "Rounding increment must be one of {:?} minutes",
+            INCREMENTS_MINS
+        ));
+    }
+    Ok(())
+}
+
+// The client's override if it has one, else the default policy
+pub fn for_client(config: &BillingConfig, client_id: u64) -> RoundingPolicy {
+    config
+        .clients
+        .iter()
+        .find(|c| c.id == client_id)
+        .and_then(|c| c.rounding)
+        .unwrap_or(config.rounding)
+}
+
+// The policy that applies to a task's sessions when they're billed
+pub fn for_task(config: &BillingConfig, task_id: u64) -> RoundingPolicy {
+    config
+        .rates
+        .iter()
+        .find(|r| r.task_id == task_id)
+        .map(|r| for_client(config, r.client_id))
+        .unwrap_or(config.rounding)
+}
+
+#[derive(Serialize)]
+pub struct RoundingPreview {
+    pub session_id: u64,
+    pub policy: RoundingPolicy,
+    pub raw_seconds: i64,
+    pub rounded_seconds: i64,
+}
+
+// Without a policy, previews whatever would apply on the session's invoice.
+#[tauri::command]
+pub fn preview_rounding(
+    sessions: State<'_, SessionStore>,
+    billing: State<'_, BillingStore>,
+    session_id: u64,
+    policy: Option<RoundingPolicy>,
+) -> Result<RoundingPreview, String> {
+    let session = sessions.get(session_id)?;
+    let policy = match policy {
+        Some(policy) => {
+            validate(&policy)?;
+            policy
+        }
+        None => for_task(&billing.get()?, session.task_id),
+    };
+    let raw_seconds = (session.end - session.start).num_seconds();
+    Ok(RoundingPreview {
+        session_id,
+        policy,
+        raw_seconds,
+        rounded_seconds: policy.apply(raw_seconds),
+    })
+}
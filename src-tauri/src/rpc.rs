@@ -0,0 +1,261 @@
+use chrono::NaiveDate;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+
+use crate::app_lock;
+use crate::commands;
+use crate::sessions::DateRange;
+use crate::timer::{Countdown, TimerManager};
+use crate::{calendar, interop, pdf, reports};
+
+// JSON-RPC 2.0 error codes
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+// Reserved range for application errors; the command's message is passed through
+const COMMAND_FAILED: i64 = -32000;
+
+#[derive(Deserialize)]
+struct Request {
+    jsonrpc: String,
+    method: String,
+    #[serde(default)]
+    params: Value,
+    // Absent for notifications, which get no response
+    #[serde(default)]
+    id: Option<Value>,
+}
+
+#[derive(Deserialize)]
+struct StartParams {
+    task_id: u64,
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    countdown: Option<Countdown>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct StopParams {
+    id: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ReportParams {
+    range: DateRange,
+    #[serde(default)]
+    tag: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct IcsParams {
+    range: DateRange,
+    path: PathBuf,
+}
+
+#[derive(Deserialize)]
+struct TogglParams {
+    range: DateRange,
+    path: PathBuf,
+    #[serde(default)]
+    project_map: Option<HashMap<String, u64>>,
+}
+
+#[derive(Deserialize)]
+struct PdfParams {
+    #[serde(default)]
+    range: Option<DateRange>,
+    #[serde(default)]
+    week_of: Option<NaiveDate>,
+    path: PathBuf,
+}
+
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<String> for RpcError {
+    fn from(message: String) -> Self {
+        Self::new(COMMAND_FAILED, message)
+    }
+}
+
+// A missing `params` is treated as an empty object, so methods without required
+// parameters can omit it.
+fn params<T: DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    let params = if params.is_null() { json!({}) } else { params };
+    serde_json::from_value(params).map_err(|e| RpcError::new(INVALID_PARAMS, e.to_string()))
+}
+
+fn to_value(value: impl serde::Serialize) -> Result<Value, RpcError> {
+    serde_json::to_value(value).map_err(|e| RpcError::from(e.to_string()))
+}
+
+// The same commands the frontend invokes, under dotted method names.
+fn call(app: &AppHandle, method: &str, raw: Value) -> Result<Value, RpcError> {
+    if let Some(reason) = app_lock::refusal(app) {
+        return Err(RpcError::from(reason.to_string()));
+    }
+    let timers = app.state::<TimerManager>();
+    match method {
+        "timer.status" => to_value(commands::get_timer_state(app.state())),
+        "timer.list" => to_value(timers.list()),
+        "timer.start" => {
+            let p: StartParams = params(raw)?;
+            to_value(timers.start_with(app, p.task_id, p.title, p.countdown)?)
+        }
+        "timer.stop" => {
+            let p: StopParams = params(raw)?;
+            let session = match p.id {
+                Some(id) => timers.stop_named(app, &id)?,
+                None => timers.stop(app)?,
+            };
+            to_value(session)
+        }
+        "report.get" => {
+            let p: ReportParams = params(raw)?;
+            to_value(reports::get_report(
+                app.state(),
+                app.state(),
+                app.state(),
+                app.state(),
+                app.state(),
+                p.range,
+                p.tag,
+            )?)
+        }
+        "report.heatmap" => {
+            let p: ReportParams = params(raw)?;
+            to_value(reports::get_productivity_heatmap(
+                app.state(),
+                app.state(),
+                p.range,
+                p.tag,
+            )?)
+        }
+        "export.ics" => {
+            let p: IcsParams = params(raw)?;
+            to_value(calendar::export_ics(
+                app.state(),
+                app.state(),
+                p.range,
+                p.path,
+            )?)
+        }
+        "export.toggl_csv" => {
+            let p: TogglParams = params(raw)?;
+            to_value(interop::export_toggl_csv(
+                app.state(),
+                app.state(),
+                p.path,
+                p.range,
+                p.project_map,
+            )?)
+        }
+        "export.pdf" => {
+            let p: PdfParams = params(raw)?;
+            to_value(pdf::render_report_pdf(
+                app.state(),
+                app.state(),
+                app.state(),
+                p.range,
+                p.week_of,
+                p.path,
+            )?)
+        }
+        _ => Err(RpcError::new(
+            METHOD_NOT_FOUND,
+            format!("Unknown method: {}", method),
+        )),
+    }
+}
+
+fn error_response(id: Value, error: RpcError) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": error.code, "message": error.message },
+    })
+}
+
+// None for notifications.
+fn handle_line(app: &AppHandle, line: &str) -> Option<Value> {
+    let value: Value = match serde_json::from_str(line) {
+        Ok(value) => value,
+        Err(e) => {
+            return Some(error_response(
+                Value::Null,
+                RpcError::new(PARSE_ERROR, e.to_string()),
+            ))
+        }
+    };
+    let id = value.get("id").cloned().unwrap_or(Value::Null);
+    let request = match serde_json::from_value::<Request>(value) {
+        Ok(request) if request.jsonrpc == "2.0" => request,
+        Ok(_) => {
+            return Some(error_response(
+                id,
+                RpcError::new(INVALID_REQUEST, "jsonrpc must be \"2.0\""),
+            ))
+        }
+        Err(e) => {
+            return Some(error_response(
+                id,
+                RpcError::new(INVALID_REQUEST, e.to_string()),
+            ))
+        }
+    };
+    let result = call(app, &request.method, request.params);
+    let id = request.id?;
+    Some(match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(error) => error_response(id, error),
+    })
+}
+
+pub fn is_rpc_launch(args: &[String]) -> bool {
+    args.iter().skip(1).any(|arg| arg == "--rpc")
+}
+
+// Reads one request per line from stdin and writes one response per line to stdout.
+// The app exits when stdin closes.
+pub fn serve_stdio(app: AppHandle) {
+    std::thread::spawn(move || {
+        let stdin = std::io::stdin();
+        for line in stdin.lock().lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+            if let Some(response) = handle_line(&app, &line) {
+                let mut stdout = std::io::stdout().lock();
+                if writeln!(stdout, "{}", response)
+                    .and_then(|_| stdout.flush())
+                    .is_err()
+                {
+                    break;
+                }
+            }
+        }
+        app.exit(0);
+    });
+}
@@ -0,0 +1,478 @@
+use chrono::{Duration as ChronoDuration, Local, NaiveDate, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::active_window;
+use crate::background;
+use crate::holidays;
+use crate::idle::IdleMonitor;
+use crate::migrations::MigrationState;
+use crate::notifications::{self, NotificationImportance, NotificationKind};
+use crate::reports;
+use crate::sessions::{DateRange, SessionStore};
+use crate::settings::SettingsStore;
+use crate::storage;
+use crate::time::{self, ReportZone};
+use crate::timer::TimerManager;
+
+const EVALUATE_INTERVAL: Duration = Duration::from_secs(15);
+// Idle for less than this counts as being at the machine
+const ACTIVE_IDLE: Duration = Duration::from_secs(60);
+const DEFAULT_END_OF_DAY: (u32, u32) = (17, 30);
+
+// All conditions of a rule must hold for it to fire
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Condition {
+    // Focused app name or window title contains `app` (case-insensitive) for at least
+    // `min_secs` without interruption
+    AppFocused { app: String, min_secs: u64 },
+    Idle { min_secs: u64 },
+    TimerRunning { running: bool },
+    // Local wall-clock time
+    After { time: NaiveTime },
+    Before { time: NaiveTime },
+    // Not a weekend, public holiday or leave day
+    Workday,
+    // The first check of the day that finds the user at the machine
+    FirstActivity,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RuleAction {
+    // Asks the user instead of starting the timer directly
+    PromptStart {
+        task_id: u64,
+        #[serde(default)]
+        title: Option<String>,
+    },
+    StartTimer {
+        task_id: u64,
+        #[serde(default)]
+        title: Option<String>,
+    },
+    StopTimer,
+    Notify {
+        title: String,
+        body: String,
+    },
+    // Offers to start tracking, with how long the user worked the day before
+    PromptStartDay,
+    // Offers to stop the running timer and review the day
+    PromptEndDay,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Rule {
+    // 0 when creating a rule
+    #[serde(default)]
+    pub id: u64,
+    pub name: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    pub conditions: Vec<Condition>,
+    pub action: RuleAction,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Clone, Serialize)]
+pub struct RulePrompt {
+    pub rule_id: u64,
+    pub rule: String,
+    pub task_id: u64,
+    pub title: Option<String>,
+}
+
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DayPromptKind {
+    Start,
+    End,
+}
+
+#[derive(Clone, Serialize)]
+pub struct DayPrompt {
+    pub rule_id: u64,
+    pub kind: DayPromptKind,
+    // The day the worked time was counted for: yesterday for start, today for end
+    pub date: NaiveDate,
+    pub worked_seconds: i64,
+    pub timer_running: bool,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct RuleData {
+    next_id: u64,
+    rules: Vec<Rule>,
+    // Kept across restarts, so relaunching the app doesn't count as the day's first activity
+    #[serde(default)]
+    active_day: Option<NaiveDate>,
+    #[serde(default)]
+    day_prompts_added: bool,
+    // Day on which each start or end of day prompt last fired, so a restart doesn't
+    // prompt again
+    #[serde(default)]
+    day_prompted: HashMap<u64, NaiveDate>,
+}
+
+// The start and end of day prompts, added once so they can be edited or disabled like
+// any other rule. Only new installs get them enabled; existing users opt in.
+fn day_prompt_rules() -> [(String, Vec<Condition>, RuleAction); 2] {
+    let (hour, minute) = DEFAULT_END_OF_DAY;
+    [
+        (
+            "Start of day".to_string(),
+            vec![
+                Condition::Workday,
+                Condition::FirstActivity,
+                Condition::TimerRunning { running: false },
+            ],
+            RuleAction::PromptStartDay,
+        ),
+        (
+            "End of day".to_string(),
+            vec![
+                Condition::Workday,
+                Condition::After {
+                    time: NaiveTime::from_hms_opt(hour, minute, 0).unwrap_or_default(),
+                },
+            ],
+            RuleAction::PromptEndDay,
+        ),
+    ]
+}
+
+// What the evaluator knows about the machine at one tick
+struct Snapshot {
+    window: Option<active_window::ActiveWindow>,
+    focused_for: Duration,
+    idle: Duration,
+    timer_running: bool,
+    now: NaiveTime,
+    workday: bool,
+    first_activity: bool,
+}
+
+#[derive(Default)]
+struct EvalState {
+    // Focused app and when it gained focus
+    focus: Option<(String, Instant)>,
+    // Rules whose conditions held at the last tick; a rule fires again only after its
+    // conditions stop holding
+    firing: HashSet<u64>,
+}
+
+pub struct RuleStore {
+    path: PathBuf,
+    data: Mutex<RuleData>,
+    state: Mutex<EvalState>,
+}
+
+impl RuleStore {
+    pub fn load(app: &AppHandle) -> Result<Self, String> {
+        let path = storage::data_file(app, "rules.json")?;
+        let fresh = app
+            .try_state::<MigrationState>()
+            .is_some_and(|m| m.fresh_install);
+        let mut data: RuleData = storage::load_json(&path)?;
+        if !data.day_prompts_added {
+            for (name, conditions, action) in day_prompt_rules() {
+                data.next_id += 1;
+                data.rules.push(Rule {
+                    id: data.next_id,
+                    name,
+                    enabled: fresh,
+                    conditions,
+                    action,
+                });
+            }
+            data.day_prompts_added = true;
+            storage::save_json(&path, &data)?;
+        }
+        Ok(Self {
+            path,
+            data: Mutex::new(data),
+            state: Mutex::default(),
+        })
+    }
+
+    pub fn list(&self) -> Result<Vec<Rule>, String> {
+        Ok(self.data.lock().map_err(|e| e.to_string())?.rules.clone())
+    }
+
+    pub fn save(&self, mut rule: Rule) -> Result<Rule, String> {
+        let mut data = self.data.lock().map_err(|e| e.to_string())?;
+        if rule.id == 0 {
+            data.next_id += 1;
+            rule.id = data.next_id;
+            data.rules.push(rule.clone());
+        } else {
+            let existing = data
+                .rules
+                .iter_mut()
+                .find(|r| r.id == rule.id)
+                .ok_or_else(|| format!("Rule {} not found", rule.id))?;
+            *existing = rule.clone();
+        }
+        storage::save_json(&self.path, &*data)?;
+        Ok(rule)
+    }
+
+    pub fn delete(&self, id: u64) -> Result<(), String> {
+        let mut data = self.data.lock().map_err(|e| e.to_string())?;
+        data.rules.retain(|r| r.id != id);
+        storage::save_json(&self.path, &*data)
+    }
+
+    // Records activity on `today`; true the first time for that day
+    fn mark_active(&self, today: NaiveDate) -> Result<bool, String> {
+        let mut data = self.data.lock().map_err(|e| e.to_string())?;
+        if data.active_day == Some(today) {
+            return Ok(false);
+        }
+        data.active_day = Some(today);
+        storage::save_json(&self.path, &*data)?;
+        Ok(true)
+    }
+
+    // Records that a day prompt fired on `today`; true the first time for that day
+    fn mark_prompted(&self, id: u64, today: NaiveDate) -> Result<bool, String> {
+        let mut data = self.data.lock().map_err(|e| e.to_string())?;
+        if data.day_prompted.get(&id) == Some(&today) {
+            return Ok(false);
+        }
+        data.day_prompted.insert(id, today);
+        storage::save_json(&self.path, &*data)?;
+        Ok(true)
+    }
+}
+
+fn holds(condition: &Condition, snapshot: &Snapshot) -> bool {
+    match condition {
+        Condition::AppFocused { app, min_secs } => {
+            let needle = app.to_lowercase();
+            let focused = snapshot.window.as_ref().is_some_and(|w| {
+                w.app.to_lowercase().contains(&needle)
+                    || w.title
+                        .as_deref()
+                        .is_some_and(|t| t.to_lowercase().contains(&needle))
+            });
+            focused && snapshot.focused_for.as_secs() >= *min_secs
+        }
+        Condition::Idle { min_secs } => snapshot.idle.as_secs() >= *min_secs,
+        Condition::TimerRunning { running } => snapshot.timer_running == *running,
+        Condition::After { time } => snapshot.now >= *time,
+        Condition::Before { time } => snapshot.now < *time,
+        Condition::Workday => snapshot.workday,
+        Condition::FirstActivity => snapshot.first_activity,
+    }
+}
+
+// Time tracked on `date` in the report zone, counting running timers up to now
+fn worked_on(app: &AppHandle, zone: ReportZone, date: NaiveDate) -> Result<i64, String> {
+    let range = DateRange {
+        start: zone.start_of_day(date),
+        end: zone.start_of_day(date + ChronoDuration::days(1)),
+    };
+    let sessions = app.state::<SessionStore>().in_range(range)?;
+    let mut total = reports::build_report(&sessions, &[], range, zone, None).total_seconds;
+    let now = Utc::now().min(range.end);
+    for running in app.state::<TimerManager>().list() {
+        total += (now - running.timer.started_at.max(range.start))
+            .num_seconds()
+            .max(0);
+    }
+    Ok(total)
+}
+
+fn prompt_day(app: &AppHandle, rule: &Rule, kind: DayPromptKind) -> Result<(), String> {
+    let zone = ReportZone::from_settings(&app.state::<SettingsStore>().get()?);
+    let today = zone.date_of(Utc::now());
+    let date = match kind {
+        DayPromptKind::Start => today - ChronoDuration::days(1),
+        DayPromptKind::End => today,
+    };
+    let worked_seconds = worked_on(app, zone, date)?;
+    let timer_running = app.state::<TimerManager>().active().is_some();
+    let worked = time::format_hours_minutes(worked_seconds);
+    let (title, body) = match kind {
+        DayPromptKind::Start if worked_seconds > 0 => (
+            "Start tracking?",
+            format!("Yesterday you worked {}.", worked),
+        ),
+        DayPromptKind::Start => ("Start tracking?", "Good morning.".to_string()),
+        DayPromptKind::End if timer_running => (
+            "End of the workday",
+            format!(
+                "You've worked {} today. Stop the timer and review your day?",
+                worked
+            ),
+        ),
+        DayPromptKind::End => (
+            "End of the workday",
+            format!("You've worked {} today. Review your day?", worked),
+        ),
+    };
+    let _ = app.emit(
+        "day-prompt",
+        DayPrompt {
+            rule_id: rule.id,
+            kind,
+            date,
+            worked_seconds,
+            timer_running,
+        },
+    );
+    notifications::notify(
+        app,
+        NotificationKind::Timer,
+        NotificationImportance::Normal,
+        title,
+        &body,
+    );
+    Ok(())
+}
+
+fn run_action(app: &AppHandle, rule: &Rule) -> Result<(), String> {
+    let timers = app.state::<TimerManager>();
+    match &rule.action {
+        RuleAction::PromptStart { task_id, title } => {
+            let name = title
+                .clone()
+                .unwrap_or_else(|| format!("Task #{}", task_id));
+            let _ = app.emit(
+                "rule-prompt",
+                RulePrompt {
+                    rule_id: rule.id,
+                    rule: rule.name.clone(),
+                    task_id: *task_id,
+                    title: title.clone(),
+                },
+            );
+            notifications::notify(
+                app,
+                NotificationKind::Timer,
+                NotificationImportance::Normal,
+                "Start tracking?",
+                &format!("{}: start a timer for {}?", rule.name, name),
+            );
+        }
+        RuleAction::StartTimer { task_id, title } => {
+            timers.start(app, *task_id, title.clone())?;
+        }
+        RuleAction::StopTimer => {
+            timers.stop(app)?;
+        }
+        RuleAction::Notify { title, body } => {
+            notifications::notify(
+                app,
+                NotificationKind::Timer,
+                NotificationImportance::Normal,
+                title,
+                body,
+            );
+        }
+        RuleAction::PromptStartDay => prompt_day(app, rule, DayPromptKind::Start)?,
+        RuleAction::PromptEndDay => prompt_day(app, rule, DayPromptKind::End)?,
+    }
+    Ok(())
+}
+
+fn evaluate(app: &AppHandle) -> Result<(), String> {
+    let store = app.state::<RuleStore>();
+    let rules = store.list()?;
+    if rules.iter().all(|r| !r.enabled) {
+        return Ok(());
+    }
+
+    let idle = app.state::<IdleMonitor>().idle_time();
+    let today = Local::now().date_naive();
+    let first_activity = idle < ACTIVE_IDLE && store.mark_active(today)?;
+    let workday = holidays::is_workday(&app.state::<SettingsStore>().get()?.holidays, today);
+
+    let window = active_window::current().ok();
+    let mut state = store.state.lock().map_err(|e| e.to_string())?;
+    let same_app = matches!(
+        (&window, &state.focus),
+        (Some(w), Some((focused, _))) if *focused == w.app
+    );
+    if !same_app {
+        state.focus = window.as_ref().map(|w| (w.app.clone(), Instant::now()));
+    }
+    let focused_for = state
+        .focus
+        .as_ref()
+        .map_or(Duration::ZERO, |(_, since)| since.elapsed());
+    let snapshot = Snapshot {
+        window,
+        focused_for,
+        idle,
+        timer_running: app.state::<TimerManager>().active().is_some(),
+        now: Local::now().time(),
+        workday,
+        first_activity,
+    };
+
+    let mut to_run = Vec::new();
+    for rule in rules.iter().filter(|r| r.enabled) {
+        let matched =
+            !rule.conditions.is_empty() && rule.conditions.iter().all(|c| holds(c, &snapshot));
+        if !matched {
+            state.firing.remove(&rule.id);
+            continue;
+        }
+        if !state.firing.insert(rule.id) {
+            continue;
+        }
+        let day_prompt = matches!(
+            rule.action,
+            RuleAction::PromptStartDay | RuleAction::PromptEndDay
+        );
+        if !day_prompt || store.mark_prompted(rule.id, today)? {
+            to_run.push(rule);
+        }
+    }
+    drop(state);
+
+    for rule in to_run {
+        log::info!("rule \"{}\" fired", rule.name);
+        if let Err(e) = run_action(app, rule) {
+            log::warn!("rule \"{}\" failed: {}", rule.name, e);
+        }
+    }
+    Ok(())
+}
+
+pub fn start_evaluator(app: AppHandle) {
+    background::spawn_thread(&app, "rules", |app, task| {
+        while task.sleep_blocking(EVALUATE_INTERVAL) {
+            if let Err(e) = evaluate(&app) {
+                log::warn!("rule evaluation failed: {}", e);
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub fn list_rules(store: State<'_, RuleStore>) -> Result<Vec<Rule>, String> {
+    store.list()
+}
+
+// Creates the rule when its id is 0, otherwise replaces the rule with that id.
+#[tauri::command]
+pub fn save_rule(store: State<'_, RuleStore>, rule: Rule) -> Result<Rule, String> {
+    store.save(rule)
+}
+
+#[tauri::command]
+pub fn delete_rule(store: State<'_, RuleStore>, id: u64) -> Result<(), String> {
+    store.delete(id)
+}
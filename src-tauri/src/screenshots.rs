@@ -0,0 +1,606 @@
+use chrono::{DateTime, Utc};
+use image::{imageops, ImageFormat, Rgba, RgbaImage};
+use reqwest::header::CONTENT_TYPE;
+use reqwest::{Method, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Cursor;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::active_window;
+use crate::app_lock::AppLock;
+use crate::backend;
+use crate::background;
+use crate::flags::{self, FeatureFlags};
+use crate::project_policy;
+use crate::sessions::DateRange;
+use crate::settings::SettingsStore;
+use crate::storage;
+use crate::timer::TimerManager;
+
+const SCREENSHOTS_DIR: &str = "screenshots";
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+const UPLOADS_PATH: &str = "/api/screenshots/uploads";
+const THUMBNAIL_WIDTH: u32 = 320;
+const PART_SIZE: usize = 512 * 1024;
+// Original bytes sent per sync run, so a backlog of screenshots trickles out instead of
+// saturating the connection
+const UPLOAD_BUDGET: usize = 8 * 1024 * 1024;
+// Deleted screenshots remembered so an upload already in flight is withdrawn
+const MAX_TOMBSTONES: usize = 100;
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScreenshotSettings {
+    // Used unless the project policy sets an interval
+    pub interval_secs: u64,
+    // No capture at all while the focused app name or window title contains one of these
+    // (case-insensitive), e.g. a password manager or a bank's site in the browser
+    pub excluded_apps: Vec<String>,
+    // Monitors captured as a black image, by name
+    pub masked_monitors: Vec<String>,
+}
+
+impl Default for ScreenshotSettings {
+    fn default() -> Self {
+        Self {
+            interval_secs: 10 * 60,
+            excluded_apps: [
+                "1password",
+                "bitwarden",
+                "keepass",
+                "lastpass",
+                "dashlane",
+                "keychain access",
+            ]
+            .map(str::to_string)
+            .to_vec(),
+            masked_monitors: Vec::new(),
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Screenshot {
+    pub id: u64,
+    pub taken_at: DateTime<Utc>,
+    pub task_id: u64,
+    pub monitor: String,
+    pub masked: bool,
+    // File names inside the screenshots directory
+    pub file: String,
+    #[serde(default)]
+    pub thumbnail: Option<String>,
+    #[serde(default)]
+    pub upload: UploadProgress,
+}
+
+// Where a screenshot's resumable upload stands
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UploadProgress {
+    pub upload_id: Option<String>,
+    pub thumbnail_sent: bool,
+    // Bytes of the original the backend has acknowledged
+    pub sent_bytes: u64,
+    pub done: bool,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct ScreenshotData {
+    next_id: u64,
+    screenshots: Vec<Screenshot>,
+    #[serde(default)]
+    last_upload_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    last_upload_error: Option<String>,
+    // Ids deleted while their upload was unfinished
+    #[serde(default)]
+    deleted: Vec<u64>,
+}
+
+#[derive(Serialize)]
+pub struct ScreenshotUploadStatus {
+    pub queued: usize,
+    // Started but not finished
+    pub in_progress: usize,
+    pub uploaded: usize,
+    // Original bytes still to send
+    pub pending_bytes: u64,
+    pub last_upload_at: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+}
+
+#[derive(Serialize)]
+struct StartUpload<'a> {
+    taken_at: DateTime<Utc>,
+    task_id: u64,
+    monitor: &'a str,
+    masked: bool,
+    size: u64,
+    part_size: usize,
+}
+
+#[derive(Deserialize)]
+struct UploadCreated {
+    upload_id: String,
+}
+
+#[derive(Deserialize)]
+struct UploadState {
+    received: u64,
+}
+
+pub struct ScreenshotStore {
+    path: PathBuf,
+    dir: PathBuf,
+    data: Mutex<ScreenshotData>,
+    last_capture: Mutex<Option<DateTime<Utc>>>,
+}
+
+impl ScreenshotStore {
+    pub fn load(app: &AppHandle) -> Result<Self, String> {
+        let path = storage::data_file(app, "screenshots.json")?;
+        let dir = storage::data_file(app, SCREENSHOTS_DIR)?;
+        let data = storage::load_json(&path)?;
+        Ok(Self {
+            path,
+            dir,
+            data: Mutex::new(data),
+            last_capture: Mutex::default(),
+        })
+    }
+
+    fn write<T>(
+        &self,
+        f: impl FnOnce(&mut ScreenshotData) -> Result<T, String>,
+    ) -> Result<T, String> {
+        let mut data = self.data.lock().map_err(|e| e.to_string())?;
+        let result = f(&mut data)?;
+        storage::save_json(&self.path, &*data)?;
+        Ok(result)
+    }
+
+    pub fn file_path(&self, screenshot: &Screenshot) -> PathBuf {
+        self.dir.join(&screenshot.file)
+    }
+
+    fn is_deleted(&self, id: u64) -> bool {
+        self.data
+            .lock()
+            .map_or(true, |data| data.deleted.contains(&id))
+    }
+
+    fn set_upload(&self, id: u64, upload: UploadProgress) -> Result<(), String> {
+        self.write(|data| {
+            if let Some(s) = data.screenshots.iter_mut().find(|s| s.id == id) {
+                s.upload = upload;
+            }
+            Ok(())
+        })
+    }
+}
+
+// Why a due capture was skipped. When there are exclusions but the focused window can't
+// be read, nothing is captured, since it could be one of them.
+fn excluded(settings: &ScreenshotSettings) -> Option<String> {
+    if settings.excluded_apps.iter().all(|e| e.trim().is_empty()) {
+        return None;
+    }
+    let window = match active_window::current() {
+        Ok(window) => window,
+        Err(e) => return Some(format!("unknown focused window ({})", e)),
+    };
+    let app = window.app.to_lowercase();
+    let title = window.title.unwrap_or_default().to_lowercase();
+    settings
+        .excluded_apps
+        .iter()
+        .map(|e| e.trim().to_lowercase())
+        .find(|e| !e.is_empty() && (app.contains(e) || title.contains(e)))
+}
+
+// Screenshots are only taken while a timer runs and the app is unlocked, when the
+// feature flag or the project policy turns them on.
+fn interval(app: &AppHandle, settings: &ScreenshotSettings) -> Option<u64> {
+    if app.try_state::<AppLock>().is_some_and(|l| l.is_locked()) {
+        return None;
+    }
+    app.state::<TimerManager>().active()?;
+    let policy = project_policy::refresh(app).ok()?.screenshots;
+    let enabled = policy.as_ref().map_or_else(
+        || app.state::<FeatureFlags>().is_enabled(flags::SCREENSHOTS),
+        |p| p.enabled,
+    );
+    if !enabled {
+        return None;
+    }
+    Some(
+        policy
+            .and_then(|p| p.interval_secs)
+            .unwrap_or(settings.interval_secs)
+            .max(60),
+    )
+}
+
+// Encoded in memory so the file can be sealed like the stores when encryption is on
+fn encode(image: &RgbaImage, format: ImageFormat) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut bytes), format)
+        .map_err(|e| e.to_string())?;
+    Ok(bytes)
+}
+
+fn capture(app: &AppHandle, settings: &ScreenshotSettings) -> Result<Vec<Screenshot>, String> {
+    let store = app.state::<ScreenshotStore>();
+    let task_id = app
+        .state::<TimerManager>()
+        .active()
+        .map(|t| t.task_id)
+        .ok_or_else(|| "No timer running".to_string())?;
+    fs::create_dir_all(&store.dir).map_err(|e| e.to_string())?;
+    let taken_at = Utc::now();
+    let mut images = Vec::new();
+    for monitor in xcap::Monitor::all().map_err(|e| e.to_string())? {
+        let name = monitor.name().to_string();
+        // Masked monitors are never read from
+        let masked = settings.masked_monitors.contains(&name);
+        let image = if masked {
+            RgbaImage::from_pixel(monitor.width(), monitor.height(), Rgba([0, 0, 0, 255]))
+        } else {
+            monitor.capture_image().map_err(|e| e.to_string())?
+        };
+        images.push((name, masked, image));
+    }
+    store.write(|data| {
+        let mut taken = Vec::new();
+        for (name, masked, image) in images {
+            data.next_id += 1;
+            let file = format!("{}.png", data.next_id);
+            storage::write_bytes(&store.dir.join(&file), encode(&image, ImageFormat::Png)?)?;
+            // Shown in lists and uploaded ahead of the original
+            let height = image.height() * THUMBNAIL_WIDTH / image.width().max(1);
+            let thumbnail = format!("{}.thumb.webp", data.next_id);
+            let small = imageops::thumbnail(&image, THUMBNAIL_WIDTH, height.max(1));
+            storage::write_bytes(
+                &store.dir.join(&thumbnail),
+                encode(&small, ImageFormat::WebP)?,
+            )?;
+            let screenshot = Screenshot {
+                id: data.next_id,
+                taken_at,
+                task_id,
+                monitor: name,
+                masked,
+                file,
+                thumbnail: Some(thumbnail),
+                upload: UploadProgress::default(),
+            };
+            data.screenshots.push(screenshot.clone());
+            taken.push(screenshot);
+        }
+        Ok(taken)
+    })
+}
+
+fn tick(app: &AppHandle) -> Result<(), String> {
+    let settings = app.state::<SettingsStore>().get()?.screenshots;
+    let Some(interval) = interval(app, &settings) else {
+        return Ok(());
+    };
+    let store = app.state::<ScreenshotStore>();
+    let now = Utc::now();
+    let mut last = store.last_capture.lock().map_err(|e| e.to_string())?;
+    if last.is_some_and(|at| (now - at).num_seconds() < interval as i64) {
+        return Ok(());
+    }
+    // Checked right before capturing, so nothing is read from the screen while excluded;
+    // a skipped capture is retried at the next poll
+    if let Some(rule) = excluded(&settings) {
+        log::debug!("screenshot skipped, excluded by {:?}", rule);
+        let _ = app.emit("screenshot-skipped", rule);
+        return Ok(());
+    }
+    *last = Some(now);
+    drop(last);
+    let taken = capture(app, &settings)?;
+    let _ = app.emit("screenshots-taken", &taken);
+    Ok(())
+}
+
+async fn send(request: reqwest::RequestBuilder) -> Result<reqwest::Response, String> {
+    request
+        .send()
+        .await
+        .map_err(|e| e.to_string())?
+        .error_for_status()
+        .map_err(|e| e.to_string())
+}
+
+// Picks up an interrupted upload where the backend left it, or starts a new one when the
+// backend no longer knows it.
+async fn resume(
+    app: &AppHandle,
+    screenshot: &Screenshot,
+    size: u64,
+) -> Result<UploadProgress, String> {
+    let mut upload = screenshot.upload.clone();
+    if let Some(upload_id) = &upload.upload_id {
+        let response =
+            backend::request(app, Method::GET, &format!("{}/{}", UPLOADS_PATH, upload_id))?
+                .send()
+                .await
+                .map_err(|e| e.to_string())?;
+        if response.status() != StatusCode::NOT_FOUND {
+            let state: UploadState = response
+                .error_for_status()
+                .map_err(|e| e.to_string())?
+                .json()
+                .await
+                .map_err(|e| e.to_string())?;
+            upload.sent_bytes = state.received;
+            return Ok(upload);
+        }
+    }
+    let created: UploadCreated = send(backend::request(app, Method::POST, UPLOADS_PATH)?.json(
+        &StartUpload {
+            taken_at: screenshot.taken_at,
+            task_id: screenshot.task_id,
+            monitor: &screenshot.monitor,
+            masked: screenshot.masked,
+            size,
+            part_size: PART_SIZE,
+        },
+    ))
+    .await?
+    .json()
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(UploadProgress {
+        upload_id: Some(created.upload_id),
+        ..Default::default()
+    })
+}
+
+// Sends one screenshot, thumbnail first, in parts until the budget runs out. Progress is
+// saved after every part. Returns the original bytes sent.
+async fn upload_one(
+    app: &AppHandle,
+    screenshot: &Screenshot,
+    budget: usize,
+) -> Result<usize, String> {
+    let store = app.state::<ScreenshotStore>();
+    if store.is_deleted(screenshot.id) {
+        return Ok(0);
+    }
+    let original = storage::read_bytes(&store.file_path(screenshot))?;
+    let mut upload = resume(app, screenshot, original.len() as u64).await?;
+    store.set_upload(screenshot.id, upload.clone())?;
+    let Some(upload_id) = upload.upload_id.clone() else {
+        return Ok(0);
+    };
+    let path = format!("{}/{}", UPLOADS_PATH, upload_id);
+
+    if let (false, Some(thumbnail)) = (upload.thumbnail_sent, &screenshot.thumbnail) {
+        let bytes = storage::read_bytes(&store.dir.join(thumbnail))?;
+        send(
+            backend::request(app, Method::PUT, &format!("{}/thumbnail", path))?
+                .header(CONTENT_TYPE, "image/webp")
+                .body(bytes),
+        )
+        .await?;
+        upload.thumbnail_sent = true;
+        store.set_upload(screenshot.id, upload.clone())?;
+    }
+
+    let mut sent = 0;
+    while (upload.sent_bytes as usize) < original.len() && sent < budget {
+        if store.is_deleted(screenshot.id) {
+            break;
+        }
+        let offset = upload.sent_bytes as usize;
+        let part = &original[offset..(offset + PART_SIZE).min(original.len())];
+        send(
+            backend::request(
+                app,
+                Method::PUT,
+                &format!("{}/parts?offset={}", path, offset),
+            )?
+            .header(CONTENT_TYPE, "application/octet-stream")
+            .body(part.to_vec()),
+        )
+        .await?;
+        upload.sent_bytes += part.len() as u64;
+        sent += part.len();
+        store.set_upload(screenshot.id, upload.clone())?;
+    }
+
+    // Deleted while uploading; the backend copy goes too
+    if store.is_deleted(screenshot.id) {
+        withdraw(app, &upload_id).await;
+        return Ok(sent);
+    }
+    if upload.sent_bytes as usize >= original.len() {
+        send(backend::request(
+            app,
+            Method::POST,
+            &format!("{}/complete", path),
+        )?)
+        .await?;
+        upload.done = true;
+        store.set_upload(screenshot.id, upload)?;
+    }
+    Ok(sent)
+}
+
+async fn withdraw(app: &AppHandle, upload_id: &str) {
+    let path = format!("{}/{}", UPLOADS_PATH, upload_id);
+    if let Ok(request) = backend::request(app, Method::DELETE, &path) {
+        if let Err(e) = send(request).await {
+            log::warn!("failed to delete uploaded screenshot: {}", e);
+        }
+    }
+}
+
+// Uploads queued screenshots, oldest first, within UPLOAD_BUDGET. Runs after the session
+// sync so screenshots never hold up entries.
+pub async fn upload_pending(app: &AppHandle) -> Result<(), String> {
+    let Some(store) = app.try_state::<ScreenshotStore>() else {
+        return Ok(());
+    };
+    let queue: Vec<Screenshot> = store
+        .data
+        .lock()
+        .map_err(|e| e.to_string())?
+        .screenshots
+        .iter()
+        .filter(|s| !s.upload.done)
+        .cloned()
+        .collect();
+    if queue.is_empty() {
+        return Ok(());
+    }
+    let mut budget = UPLOAD_BUDGET;
+    let mut result = Ok(());
+    let mut handled = Vec::new();
+    for screenshot in queue {
+        if budget == 0 {
+            break;
+        }
+        handled.push(screenshot.id);
+        match upload_one(app, &screenshot, budget).await {
+            Ok(sent) => budget = budget.saturating_sub(sent),
+            Err(e) => {
+                result = Err(e);
+                break;
+            }
+        }
+    }
+    store.write(|data| {
+        data.deleted.retain(|id| !handled.contains(id));
+        data.last_upload_error = result.as_ref().err().cloned();
+        if result.is_ok() {
+            data.last_upload_at = Some(Utc::now());
+        }
+        Ok(())
+    })?;
+    result
+}
+
+pub fn start(app: AppHandle) {
+    background::spawn_thread(&app, "screenshots", |app, task| {
+        while task.sleep_blocking(POLL_INTERVAL) {
+            if let Err(e) = tick(&app) {
+                log::warn!("screenshot failed: {}", e);
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub fn list_screenshots(
+    store: State<'_, ScreenshotStore>,
+    range: DateRange,
+) -> Result<Vec<Screenshot>, String> {
+    let data = store.data.lock().map_err(|e| e.to_string())?;
+    Ok(data
+        .screenshots
+        .iter()
+        .filter(|s| range.start <= s.taken_at && s.taken_at < range.end)
+        .cloned()
+        .collect())
+}
+
+// Removes the record and the image file straight away.
+#[tauri::command]
+pub fn delete_screenshot(
+    app: AppHandle,
+    store: State<'_, ScreenshotStore>,
+    id: u64,
+) -> Result<(), String> {
+    let removed = store.write(|data| {
+        let index = data
+            .screenshots
+            .iter()
+            .position(|s| s.id == id)
+            .ok_or_else(|| format!("Screenshot {} not found", id))?;
+        let removed = data.screenshots.remove(index);
+        if !removed.upload.done {
+            data.deleted.push(id);
+            if data.deleted.len() > MAX_TOMBSTONES {
+                data.deleted.remove(0);
+            }
+        }
+        Ok(removed)
+    })?;
+    let path = store.file_path(&removed);
+    if path.exists() {
+        fs::remove_file(path).map_err(|e| e.to_string())?;
+    }
+    if let Some(thumbnail) = &removed.thumbnail {
+        let _ = fs::remove_file(store.dir.join(thumbnail));
+    }
+    // Also withdraw it from the backend, best effort. An upload still in flight sees the
+    // tombstone and withdraws itself.
+    if let Some(upload_id) = removed.upload.upload_id {
+        let handle = app.clone();
+        tauri::async_runtime::spawn(async move { withdraw(&handle, &upload_id).await });
+    }
+    let _ = app.emit("screenshot-deleted", id);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_screenshot_settings(
+    settings: State<'_, SettingsStore>,
+    screenshots: ScreenshotSettings,
+) -> Result<(), String> {
+    if screenshots.interval_secs < 60 {
+        return Err("Screenshots can't be taken more than once a minute".to_string());
+    }
+    settings.update(|s| s.screenshots = screenshots)?;
+    Ok(())
+}
+
+// Names to pick masked monitors from
+#[tauri::command]
+pub fn list_monitors() -> Result<Vec<String>, String> {
+    Ok(xcap::Monitor::all()
+        .map_err(|e| e.to_string())?
+        .iter()
+        .map(|m| m.name().to_string())
+        .collect())
+}
+
+#[tauri::command]
+pub fn get_screenshot_upload_status(
+    store: State<'_, ScreenshotStore>,
+) -> Result<ScreenshotUploadStatus, String> {
+    let data = store.data.lock().map_err(|e| e.to_string())?;
+    let mut status = ScreenshotUploadStatus {
+        queued: 0,
+        in_progress: 0,
+        uploaded: 0,
+        pending_bytes: 0,
+        last_upload_at: data.last_upload_at,
+        last_error: data.last_upload_error.clone(),
+    };
+    for screenshot in &data.screenshots {
+        if screenshot.upload.done {
+            status.uploaded += 1;
+            continue;
+        }
+        if screenshot.upload.upload_id.is_some() {
+            status.in_progress += 1;
+        } else {
+            status.queued += 1;
+        }
+        let size = fs::metadata(store.file_path(screenshot)).map_or(0, |m| m.len());
+        status.pending_bytes += size.saturating_sub(screenshot.upload.sent_bytes);
+    }
+    Ok(status)
+}
@@ -0,0 +1,51 @@
+use keyring::Entry;
+
+use crate::profiles;
+
+// Credentials live in the OS keyring (Keychain, Credential Manager, Secret Service)
+// rather than in any of the JSON stores.
+const SERVICE: &str = "com.time-tracker.dev";
+
+// Profiles other than the default keep their own credentials under a prefixed key.
+fn account(key: &str) -> String {
+    match profiles::active_id() {
+        Some(profile) => format!("{}:{}", profile, key),
+        None => key.to_string(),
+    }
+}
+
+// Removes `key` for a given profile rather than the active one; None is the default
+// profile.
+pub fn delete_for(profile: Option<&str>, key: &str) -> Result<(), String> {
+    let account = match profile {
+        Some(profile) => format!("{}:{}", profile, key),
+        None => key.to_string(),
+    };
+    let entry = Entry::new(SERVICE, &account).map_err(|e| e.to_string())?;
+    match entry.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+pub fn get(key: &str) -> Result<Option<String>, String> {
+    let entry = Entry::new(SERVICE, &account(key)).map_err(|e| e.to_string())?;
+    match entry.get_password() {
+        Ok(secret) => Ok(Some(secret)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+pub fn set(key: &str, secret: &str) -> Result<(), String> {
+    let entry = Entry::new(SERVICE, &account(key)).map_err(|e| e.to_string())?;
+    entry.set_password(secret).map_err(|e| e.to_string())
+}
+
+pub fn delete(key: &str) -> Result<(), String> {
+    let entry = Entry::new(SERVICE, &account(key)).map_err(|e| e.to_string())?;
+    match entry.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
@@ -0,0 +1,453 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::State;
+
+use crate::focus::FocusBreach;
+use crate::storage;
+use crate::time;
+use crate::undo::{self, UndoEntry, UndoKind};
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct Session {
+    pub id: u64,
+    pub task_id: u64,
+    #[serde(default)]
+    pub title: Option<String>,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub note: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub manual: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub focus_breaches: Vec<FocusBreach>,
+    // Local UTC offset when the session started; absent on entries recorded before it was
+    // captured
+    #[serde(default)]
+    pub utc_offset_secs: Option<i32>,
+}
+
+pub struct NewSession {
+    pub task_id: u64,
+    pub title: Option<String>,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub note: Option<String>,
+    pub tags: Vec<String>,
+    pub manual: bool,
+    pub focus_breaches: Vec<FocusBreach>,
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct DateRange {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+impl DateRange {
+    pub fn contains(&self, session: &Session) -> bool {
+        session.start < self.end && self.start < session.end
+    }
+}
+
+#[derive(Deserialize)]
+pub struct EntryPatch {
+    pub task_id: Option<u64>,
+    pub start: Option<DateTime<Utc>>,
+    pub end: Option<DateTime<Utc>>,
+    // Description shown in place of the task name; empty clears it
+    pub title: Option<String>,
+    pub note: Option<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    Created,
+    Edited,
+    Deleted,
+    // Put back by undo_last
+    Restored,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct AuditRecord {
+    pub entry_id: u64,
+    pub action: AuditAction,
+    pub at: DateTime<Utc>,
+    pub before: Option<Session>,
+    pub after: Option<Session>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+pub struct SessionData {
+    pub next_id: u64,
+    pub sessions: Vec<Session>,
+    pub audit: Vec<AuditRecord>,
+    #[serde(default)]
+    pub undo: Vec<UndoEntry>,
+}
+
+pub struct SessionStore {
+    path: PathBuf,
+    data: Mutex<SessionData>,
+}
+
+impl SessionStore {
+    pub fn load(app: &tauri::AppHandle) -> Result<Self, String> {
+        let path = storage::data_file(app, "sessions.json")?;
+        let data = storage::load_json(&path)?;
+        Ok(Self {
+            path,
+            data: Mutex::new(data),
+        })
+    }
+
+    // Run `f` against the store and persist the result if it succeeded.
+    pub fn write<T>(
+        &self,
+        f: impl FnOnce(&mut SessionData) -> Result<T, String>,
+    ) -> Result<T, String> {
+        let mut data = self.data.lock().map_err(|e| e.to_string())?;
+        let result = f(&mut data)?;
+        storage::save_json(&self.path, &*data)?;
+        Ok(result)
+    }
+
+    pub fn read<T>(&self, f: impl FnOnce(&SessionData) -> T) -> Result<T, String> {
+        let data = self.data.lock().map_err(|e| e.to_string())?;
+        Ok(f(&data))
+    }
+
+    pub fn insert(&self, new: NewSession) -> Result<Session, String> {
+        self.write(|data| {
+            // Concurrent timers legitimately overlap; reports flag that time instead
+            if new.manual {
+                check_overlap(data, new.start, new.end, None)?;
+            }
+            data.next_id += 1;
+            let session = Session {
+                id: data.next_id,
+                task_id: new.task_id,
+                title: new.title,
+                start: new.start,
+                end: new.end,
+                note: new.note,
+                tags: new.tags,
+                manual: new.manual,
+                focus_breaches: new.focus_breaches,
+                utc_offset_secs: Some(time::local_offset_secs(new.start)),
+            };
+            data.sessions.push(session.clone());
+            data.audit.push(AuditRecord {
+                entry_id: session.id,
+                action: AuditAction::Created,
+                at: Utc::now(),
+                before: None,
+                after: Some(session.clone()),
+            });
+            Ok(session)
+        })
+    }
+
+    pub fn get(&self, id: u64) -> Result<Session, String> {
+        self.read(|data| data.sessions.iter().find(|s| s.id == id).cloned())?
+            .ok_or_else(|| format!("Entry {} not found", id))
+    }
+
+    pub fn in_range(&self, range: DateRange) -> Result<Vec<Session>, String> {
+        self.read(|data| {
+            let mut sessions: Vec<Session> = data
+                .sessions
+                .iter()
+                .filter(|s| range.contains(s))
+                .cloned()
+                .collect();
+            sessions.sort_by_key(|s| s.start);
+            sessions
+        })
+    }
+
+    // Distinct tasks ordered by when they were last worked on, newest first
+    pub fn recent_tasks(&self, limit: usize) -> Result<Vec<(u64, Option<String>)>, String> {
+        self.read(|data| {
+            let mut sessions: Vec<&Session> = data.sessions.iter().collect();
+            sessions.sort_by(|a, b| b.end.cmp(&a.end));
+            let mut tasks: Vec<(u64, Option<String>)> = Vec::new();
+            for session in sessions {
+                if tasks.len() == limit {
+                    break;
+                }
+                if !tasks.iter().any(|(id, _)| *id == session.task_id) {
+                    tasks.push((session.task_id, session.title.clone()));
+                }
+            }
+            tasks
+        })
+    }
+}
+
+// Trims tags and drops empty and case-insensitive duplicates, keeping the first spelling
+pub fn normalize_tags(tags: Vec<String>) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.trim();
+        if !tag.is_empty() && !normalized.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+            normalized.push(tag.to_string());
+        }
+    }
+    normalized
+}
+
+fn check_order(start: DateTime<Utc>, end: DateTime<Utc>) -> Result<(), String> {
+    if start >= end {
+        return Err("Entry must end after it starts".to_string());
+    }
+    Ok(())
+}
+
+fn check_overlap(
+    data: &SessionData,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    exclude: Option<u64>,
+) -> Result<(), String> {
+    check_order(start, end)?;
+    let clash = data
+        .sessions
+        .iter()
+        .filter(|s| Some(s.id) != exclude)
+        .find(|s| s.start < end && start < s.end);
+    match clash {
+        Some(s) => Err(format!("Entry overlaps existing entry {}", s.id)),
+        None => Ok(()),
+    }
+}
+
+#[tauri::command]
+pub fn add_manual_entry(
+    store: State<'_, SessionStore>,
+    task_id: u64,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    note: Option<String>,
+) -> Result<Session, String> {
+    store.insert(NewSession {
+        task_id,
+        title: None,
+        start,
+        end,
+        note,
+        tags: Vec::new(),
+        manual: true,
+        focus_breaches: Vec::new(),
+    })
+}
+
+#[tauri::command]
+pub fn edit_entry(
+    store: State<'_, SessionStore>,
+    id: u64,
+    patch: EntryPatch,
+) -> Result<Session, String> {
+    store.write(|data| {
+        let index = data
+            .sessions
+            .iter()
+            .position(|s| s.id == id)
+            .ok_or_else(|| format!("Entry {} not found", id))?;
+        let before = data.sessions[index].clone();
+        let mut after = before.clone();
+        if let Some(task_id) = patch.task_id {
+            after.task_id = task_id;
+        }
+        if let Some(start) = patch.start {
+            after.start = start;
+        }
+        if let Some(end) = patch.end {
+            after.end = end;
+        }
+        if let Some(title) = patch.title {
+            let title = title.trim();
+            after.title = (!title.is_empty()).then(|| title.to_string());
+        }
+        if let Some(note) = patch.note {
+            after.note = if note.is_empty() { None } else { Some(note) };
+        }
+        if after.manual {
+            check_overlap(data, after.start, after.end, Some(id))?;
+        } else {
+            check_order(after.start, after.end)?;
+        }
+
+        data.sessions[index] = after.clone();
+        undo::record(data, UndoKind::Edit, vec![before.clone()], Vec::new());
+        data.audit.push(AuditRecord {
+            entry_id: id,
+            action: AuditAction::Edited,
+            at: Utc::now(),
+            before: Some(before),
+            after: Some(after.clone()),
+        });
+        Ok(after)
+    })
+}
+
+#[tauri::command]
+pub fn set_session_tags(
+    store: State<'_, SessionStore>,
+    id: u64,
+    tags: Vec<String>,
+) -> Result<Session, String> {
+    store.write(|data| {
+        let session = data
+            .sessions
+            .iter_mut()
+            .find(|s| s.id == id)
+            .ok_or_else(|| format!("Entry {} not found", id))?;
+        let before = session.clone();
+        session.tags = normalize_tags(tags);
+        let after = session.clone();
+        undo::record(data, UndoKind::Edit, vec![before.clone()], Vec::new());
+        data.audit.push(AuditRecord {
+            entry_id: id,
+            action: AuditAction::Edited,
+            at: Utc::now(),
+            before: Some(before),
+            after: Some(after.clone()),
+        });
+        Ok(after)
+    })
+}
+
+// Tags in use for autocomplete, most used first; `prefix` matches case-insensitively
+#[tauri::command]
+pub fn list_tags(
+    store: State<'_, SessionStore>,
+    prefix: Option<String>,
+) -> Result<Vec<String>, String> {
+    let prefix = prefix.unwrap_or_default().to_lowercase();
+    store.read(|data| {
+        let mut counts: HashMap<String, (String, usize)> = HashMap::new();
+        for tag in data.sessions.iter().flat_map(|s| &s.tags) {
+            let key = tag.to_lowercase();
+            if key.starts_with(&prefix) {
+                counts.entry(key).or_insert_with(|| (tag.clone(), 0)).1 += 1;
+            }
+        }
+        let mut tags: Vec<(String, usize)> = counts.into_values().collect();
+        tags.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        tags.into_iter().map(|(tag, _)| tag).collect()
+    })
+}
+
+#[tauri::command]
+pub fn delete_entry(store: State<'_, SessionStore>, id: u64) -> Result<(), String> {
+    store.write(|data| {
+        let index = data
+            .sessions
+            .iter()
+            .position(|s| s.id == id)
+            .ok_or_else(|| format!("Entry {} not found", id))?;
+        let removed = data.sessions.remove(index);
+        undo::record(data, UndoKind::Delete, vec![removed.clone()], Vec::new());
+        data.audit.push(AuditRecord {
+            entry_id: id,
+            action: AuditAction::Deleted,
+            at: Utc::now(),
+            before: Some(removed),
+            after: None,
+        });
+        Ok(())
+    })
+}
+
+// Removes an idle span from an entry: trims it when the span touches either end, splits
+// it in two when the span is in the middle, and deletes it when the span covers it.
+// Returns what remains of the entry.
+#[tauri::command]
+pub fn discard_idle_span(
+    store: State<'_, SessionStore>,
+    id: u64,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<Vec<Session>, String> {
+    check_order(start, end)?;
+    store.write(|data| {
+        let index = data
+            .sessions
+            .iter()
+            .position(|s| s.id == id)
+            .ok_or_else(|| format!("Entry {} not found", id))?;
+        let before = data.sessions[index].clone();
+        if end <= before.start || start >= before.end {
+            return Err("The idle span is outside the entry".to_string());
+        }
+        let mut remaining = Vec::new();
+        let mut created = Vec::new();
+        if start > before.start {
+            let mut head = before.clone();
+            head.end = start;
+            head.focus_breaches.retain(|b| b.at < start);
+            remaining.push(head);
+        }
+        if end < before.end {
+            let mut tail = before.clone();
+            tail.start = end;
+            tail.focus_breaches.retain(|b| b.at >= end);
+            // The head keeps the original id; a separate tail is a new entry
+            if !remaining.is_empty() {
+                data.next_id += 1;
+                tail.id = data.next_id;
+                created.push(tail.id);
+            }
+            remaining.push(tail);
+        }
+
+        data.sessions.remove(index);
+        undo::record(data, UndoKind::DiscardIdle, vec![before.clone()], created);
+        for session in &remaining {
+            let original = session.id == id;
+            data.sessions.push(session.clone());
+            data.audit.push(AuditRecord {
+                entry_id: session.id,
+                action: if original {
+                    AuditAction::Edited
+                } else {
+                    AuditAction::Created
+                },
+                at: Utc::now(),
+                before: original.then(|| before.clone()),
+                after: Some(session.clone()),
+            });
+        }
+        if remaining.is_empty() {
+            data.audit.push(AuditRecord {
+                entry_id: id,
+                action: AuditAction::Deleted,
+                at: Utc::now(),
+                before: Some(before),
+                after: None,
+            });
+        }
+        Ok(remaining)
+    })
+}
+
+#[tauri::command]
+pub fn get_entry_history(
+    store: State<'_, SessionStore>,
+    id: u64,
+) -> Result<Vec<AuditRecord>, String> {
+    store.read(|data| {
+        data.audit
+            .iter()
+            .filter(|a| a.entry_id == id)
+            .cloned()
+            .collect()
+    })
+}
@@ -0,0 +1,327 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::State;
+
+use crate::app_lock::AppLockSettings;
+use crate::backend::BackendSettings;
+use crate::browser::BrowserSettings;
+use crate::calendar::IcsExportSchedule;
+use crate::compliance::ComplianceSettings;
+use crate::email::EmailSettings;
+use crate::flags::FeatureFlagSettings;
+use crate::focus::FocusSettings;
+use crate::git_activity::GitSettings;
+use crate::holidays::HolidaySettings;
+use crate::idle::IdleSettings;
+use crate::integrations::discord::DiscordSettings;
+use crate::integrations::hardware::HardwareSettings;
+use crate::integrations::jira::JiraSettings;
+use crate::integrations::mqtt::MqttConfig;
+use crate::integrations::slack::SlackSettings;
+use crate::kiosk::KioskSettings;
+use crate::lifecycle::CloseBehavior;
+use crate::local_api::LocalApiSettings;
+use crate::maintenance::RetentionSettings;
+use crate::meeting_detection::MeetingDetectionSettings;
+use crate::mini_timer::MiniTimerSettings;
+use crate::notifications::NotificationPolicy;
+use crate::pdf::ReportBranding;
+use crate::presence::PresenceSettings;
+use crate::privacy::PrivacySettings;
+use crate::project_policy::ProjectPolicySettings;
+use crate::reading_mode::ReadingModeSettings;
+use crate::screenshots::ScreenshotSettings;
+use crate::storage;
+use crate::sync::SyncSettings;
+use crate::timeline::TimelineSettings;
+use crate::tray::MenuBarSettings;
+use crate::tray_icon::TrayIconSettings;
+use crate::webhooks::WebhookSettings;
+use crate::work_context::ContextSettings;
+
+#[derive(Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReleaseChannel {
+    #[default]
+    Stable,
+    Beta,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppSettings {
+    pub version: u32,
+    pub release_channel: ReleaseChannel,
+    pub idle: IdleSettings,
+    pub jira: JiraSettings,
+    pub slack: SlackSettings,
+    pub ics_schedule: Option<IcsExportSchedule>,
+    // ICS feed URLs or local .ics files to read meetings from
+    pub calendar_sources: Vec<String>,
+    pub close_behavior: CloseBehavior,
+    // Flag machine-like input rhythms (e.g. mouse jigglers) as low-confidence time
+    pub activity_heuristics: bool,
+    // Opt-in per-minute keystroke and click counts
+    pub input_stats: bool,
+    pub retention: RetentionSettings,
+    pub notifications: NotificationPolicy,
+    pub focus: FocusSettings,
+    // IANA zone used to group reports by day; None uses the system zone
+    pub report_timezone: Option<String>,
+    pub menu_bar: MenuBarSettings,
+    pub feature_flags: FeatureFlagSettings,
+    pub backend: BackendSettings,
+    pub local_api: LocalApiSettings,
+    pub hardware: HardwareSettings,
+    pub app_lock: AppLockSettings,
+    pub privacy: PrivacySettings,
+    pub timeline: TimelineSettings,
+    pub report_branding: ReportBranding,
+    pub email: EmailSettings,
+    pub webhooks: WebhookSettings,
+    pub mqtt: MqttConfig,
+    pub discord: DiscordSettings,
+    pub git: GitSettings,
+    pub browser: BrowserSettings,
+    pub meeting_detection: MeetingDetectionSettings,
+    pub context: ContextSettings,
+    pub reading_mode: ReadingModeSettings,
+    pub project_policies: ProjectPolicySettings,
+    pub sync: SyncSettings,
+    pub presence: PresenceSettings,
+    pub kiosk: KioskSettings,
+    pub compliance: ComplianceSettings,
+    pub holidays: HolidaySettings,
+    pub screenshots: ScreenshotSettings,
+    pub tray_icon: TrayIconSettings,
+    pub mini_timer: MiniTimerSettings,
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        Self {
+            version: SETTINGS_VERSION,
+            release_channel: ReleaseChannel::default(),
+            idle: IdleSettings::default(),
+            jira: JiraSettings::default(),
+            slack: SlackSettings::default(),
+            ics_schedule: None,
+            calendar_sources: Vec::new(),
+            close_behavior: CloseBehavior::default(),
+            activity_heuristics: false,
+            input_stats: false,
+            retention: RetentionSettings::default(),
+            notifications: NotificationPolicy::default(),
+            focus: FocusSettings::default(),
+            report_timezone: None,
+            menu_bar: MenuBarSettings::default(),
+            feature_flags: FeatureFlagSettings::default(),
+            backend: BackendSettings::default(),
+            local_api: LocalApiSettings::default(),
+            hardware: HardwareSettings::default(),
+            app_lock: AppLockSettings::default(),
+            privacy: PrivacySettings::default(),
+            timeline: TimelineSettings::default(),
+            report_branding: ReportBranding::default(),
+            email: EmailSettings::default(),
+            webhooks: WebhookSettings::default(),
+            mqtt: MqttConfig::default(),
+            discord: DiscordSettings::default(),
+            git: GitSettings::default(),
+            browser: BrowserSettings::default(),
+            meeting_detection: MeetingDetectionSettings::default(),
+            context: ContextSettings::default(),
+            reading_mode: ReadingModeSettings::default(),
+            project_policies: ProjectPolicySettings::default(),
+            sync: SyncSettings::default(),
+            presence: PresenceSettings::default(),
+            kiosk: KioskSettings::default(),
+            compliance: ComplianceSettings::default(),
+            holidays: HolidaySettings::default(),
+            screenshots: ScreenshotSettings::default(),
+            tray_icon: TrayIconSettings::default(),
+            mini_timer: MiniTimerSettings::default(),
+        }
+    }
+}
+
+pub const SETTINGS_VERSION: u32 = 2;
+
+// MIGRATIONS[n] upgrades a version n + 1 file to version n + 2. Files written before
+// versioning have no `version` key and are treated as version 1.
+const MIGRATIONS: &[fn(&mut Map<String, Value>)] = &[v1_to_v2];
+
+// v2 groups the flat idle_* keys under `idle`.
+fn v1_to_v2(settings: &mut Map<String, Value>) {
+    let mut idle = Map::new();
+    for (old, new) in [
+        ("idle_threshold_secs", "threshold_secs"),
+        ("idle_grace_secs", "grace_secs"),
+        ("idle_resume_secs", "resume_secs"),
+    ] {
+        if let Some(value) = settings.remove(old) {
+            idle.insert(new.to_string(), value);
+        }
+    }
+    settings.insert("idle".to_string(), Value::Object(idle));
+}
+
+#[derive(Clone, Serialize)]
+pub struct SettingsVersion {
+    pub version: u32,
+    // Version found on disk at startup when a migration ran
+    pub migrated_from: Option<u32>,
+    pub backup: Option<PathBuf>,
+}
+
+// A key in the settings file this build doesn't know, e.g. written by a newer version,
+// kept so saving doesn't drop it. `path` runs from the top level down to the key.
+struct UnknownKey {
+    path: Vec<String>,
+    value: Value,
+}
+
+// Collects what's in `stored` but missing from `known`, the same file after a round trip
+// through `AppSettings`.
+fn find_unknown(
+    stored: &Value,
+    known: &Value,
+    path: &mut Vec<String>,
+    found: &mut Vec<UnknownKey>,
+) {
+    let (Value::Object(stored), Value::Object(known)) = (stored, known) else {
+        return;
+    };
+    for (key, value) in stored {
+        path.push(key.clone());
+        match known.get(key) {
+            Some(known) => find_unknown(value, known, path, found),
+            None => found.push(UnknownKey {
+                path: path.clone(),
+                value: value.clone(),
+            }),
+        }
+        path.pop();
+    }
+}
+
+// Puts unknown keys back, skipping any whose section no longer exists.
+fn save(path: &Path, settings: &AppSettings, unknown: &[UnknownKey]) -> Result<(), String> {
+    let mut value = serde_json::to_value(settings).map_err(|e| e.to_string())?;
+    for key in unknown {
+        let Some((name, sections)) = key.path.split_last() else {
+            continue;
+        };
+        let section = sections
+            .iter()
+            .try_fold(&mut value, |value, section| value.get_mut(section.as_str()));
+        if let Some(Value::Object(section)) = section {
+            section
+                .entry(name.clone())
+                .or_insert_with(|| key.value.clone());
+        }
+    }
+    storage::save_json(path, &value)
+}
+
+// Loads the settings file, upgrading it in place after copying the original next to it
+// as settings.v<N>.bak.json.
+fn load_versioned(path: &Path) -> Result<(AppSettings, Vec<UnknownKey>, SettingsVersion), String> {
+    let mut info = SettingsVersion {
+        version: SETTINGS_VERSION,
+        migrated_from: None,
+        backup: None,
+    };
+    if !path.exists() {
+        return Ok((AppSettings::default(), Vec::new(), info));
+    }
+    let contents = storage::read_text(path)?;
+    let mut value: Value =
+        serde_json::from_str(&contents).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let map = value
+        .as_object_mut()
+        .ok_or_else(|| format!("{}: expected an object", path.display()))?;
+    let stored = map
+        .get("version")
+        .and_then(Value::as_u64)
+        .map_or(1, |v| v as u32);
+
+    if stored > SETTINGS_VERSION {
+        log::warn!(
+            "settings version {} is newer than this build ({}); unknown keys are kept as-is",
+            stored,
+            SETTINGS_VERSION
+        );
+    } else if stored < SETTINGS_VERSION {
+        let backup = path.with_file_name(format!("settings.v{}.bak.json", stored));
+        fs::copy(path, &backup).map_err(|e| e.to_string())?;
+        for step in &MIGRATIONS[(stored.max(1) - 1) as usize..] {
+            step(map);
+        }
+        map.insert("version".to_string(), SETTINGS_VERSION.into());
+        log::info!(
+            "migrated settings from v{} to v{}",
+            stored,
+            SETTINGS_VERSION
+        );
+        info.migrated_from = Some(stored);
+        info.backup = Some(backup);
+    }
+
+    let mut settings: AppSettings =
+        serde_json::from_value(value.clone()).map_err(|e| format!("{}: {}", path.display(), e))?;
+    // A newer file keeps its version, so that build doesn't migrate it a second time
+    settings.version = stored.max(SETTINGS_VERSION);
+    let known = serde_json::to_value(&settings).map_err(|e| e.to_string())?;
+    let mut unknown = Vec::new();
+    find_unknown(&value, &known, &mut Vec::new(), &mut unknown);
+    if info.migrated_from.is_some() {
+        save(path, &settings, &unknown)?;
+    }
+    Ok((settings, unknown, info))
+}
+
+pub struct SettingsStore {
+    path: PathBuf,
+    settings: Mutex<AppSettings>,
+    unknown: Vec<UnknownKey>,
+    version: SettingsVersion,
+}
+
+impl SettingsStore {
+    pub fn load(app: &tauri::AppHandle) -> Result<Self, String> {
+        let path = storage::data_file(app, "settings.json")?;
+        let (settings, unknown, version) = load_versioned(&path)?;
+        Ok(Self {
+            path,
+            settings: Mutex::new(settings),
+            unknown,
+            version,
+        })
+    }
+
+    pub fn get(&self) -> Result<AppSettings, String> {
+        let settings = self.settings.lock().map_err(|e| e.to_string())?;
+        Ok(settings.clone())
+    }
+
+    pub fn update(&self, f: impl FnOnce(&mut AppSettings)) -> Result<AppSettings, String> {
+        let mut settings = self.settings.lock().map_err(|e| e.to_string())?;
+        f(&mut settings);
+        save(&self.path, &settings, &self.unknown)?;
+        Ok(settings.clone())
+    }
+}
+
+#[tauri::command]
+pub fn get_settings(store: State<'_, SettingsStore>) -> Result<AppSettings, String> {
+    store.get()
+}
+
+#[tauri::command]
+pub fn get_settings_version(store: State<'_, SettingsStore>) -> SettingsVersion {
+    store.version.clone()
+}
@@ -0,0 +1,760 @@
+use rodio::cpal::traits::{DeviceTrait, HostTrait};
+use rodio::source::{SineWave, Source};
+use rodio::{cpal, Decoder, OutputStream, OutputStreamHandle, Sample, Sink};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+use sysinfo::System;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::native_sound;
+use crate::notifications::{NotificationImportance, NotificationKind};
+use crate::speech::SpeechConfig;
+use crate::storage;
+
+pub const CONFIG_FILE: &str = "config.toml";
+// The system notification sound, or a generated tone where the platform has none
+pub const BEEP: &str = "beep";
+pub const SOUND_EXTENSIONS: &[&str] = &["wav", "mp3", "ogg", "flac"];
+
+// Imported sounds live in the app data directory under this name
+pub const SOUNDS_DIR: &str = "sounds";
+const MAX_IMPORT_BYTES: u64 = 2 * 1024 * 1024;
+const MAX_IMPORT_DURATION: Duration = Duration::from_secs(5);
+// Points in the waveform returned by get_sound_metadata
+const ENVELOPE_POINTS: usize = 100;
+// Samples at or above this fraction of full scale count as clipped
+const CLIP_LEVEL: f32 = 0.999;
+// Decoding stops here; anything longer is flagged anyway
+const MAX_ANALYZED: Duration = Duration::from_secs(30);
+
+// Process name fragments of meeting apps that trigger ducking
+const MEETING_APPS: &[&str] = &["zoom", "teams"];
+
+const BEEP_HZ: f32 = 800.0;
+const BEEP_LENGTH: Duration = Duration::from_millis(250);
+// How often a playing sound checks whether a critical one wants to cut in
+const INTERRUPT_POLL: Duration = Duration::from_millis(20);
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SoundPriority {
+    // Coalesced: dropped while another info sound is queued or playing
+    Info,
+    Normal,
+    // Cuts off whatever is playing and ignores the rate cap
+    Critical,
+}
+
+impl From<NotificationImportance> for SoundPriority {
+    fn from(importance: NotificationImportance) -> Self {
+        match importance {
+            NotificationImportance::Low => SoundPriority::Info,
+            NotificationImportance::Normal => SoundPriority::Normal,
+            NotificationImportance::High => SoundPriority::Critical,
+        }
+    }
+}
+
+// The [queue] table in config.toml
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SoundQueueConfig {
+    // Non-critical sounds beyond this in a rolling minute are dropped; 0 means no cap
+    pub max_per_minute: u32,
+    pub coalesce_info: bool,
+}
+
+impl Default for SoundQueueConfig {
+    fn default() -> Self {
+        Self {
+            max_per_minute: 6,
+            coalesce_info: true,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SoundConfig {
+    pub enabled: bool,
+    // 0.0 - 1.0
+    pub volume: f32,
+    // Custom audio file for notifications; the platform default is used when unset
+    pub notification_sound: Option<PathBuf>,
+    // Channel id (notification kind) -> sound name; overrides notification_sound
+    pub channels: BTreeMap<String, String>,
+    pub speech: SpeechConfig,
+    // Output device name from list_audio_devices; None follows the system default
+    pub output_device: Option<String>,
+    // Lower playback volume while a meeting app is running
+    pub duck_during_meetings: bool,
+    // Fraction of the normal volume used while ducked
+    pub duck_volume: f32,
+    pub queue: SoundQueueConfig,
+}
+
+impl Default for SoundConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            volume: 0.6,
+            notification_sound: None,
+            channels: BTreeMap::new(),
+            speech: SpeechConfig::default(),
+            output_device: None,
+            duck_during_meetings: true,
+            duck_volume: 0.3,
+            queue: SoundQueueConfig::default(),
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SoundSource {
+    Builtin,
+    User,
+    Bundled,
+}
+
+#[derive(Clone, Serialize)]
+pub struct SoundInfo {
+    pub name: String,
+    pub source: SoundSource,
+    pub path: Option<PathBuf>,
+}
+
+#[derive(Clone, Serialize)]
+pub struct SoundMetadata {
+    pub name: String,
+    pub duration_ms: u64,
+    pub sample_rate: u32,
+    pub channels: u16,
+    // Peak of each slice of the sound, 0.0 - 1.0
+    pub envelope: Vec<f32>,
+    pub peak: f32,
+    pub clipped_samples: u64,
+    // Too long for a notification, or clipping
+    pub warnings: Vec<String>,
+}
+
+#[derive(Clone, Serialize)]
+pub struct AudioDevice {
+    // cpal has no stable device ids, so the device name doubles as one
+    pub id: String,
+    pub is_default: bool,
+    pub selected: bool,
+}
+
+#[derive(Clone, Serialize)]
+pub struct ChannelSound {
+    pub channel_id: NotificationKind,
+    pub sound: Option<String>,
+}
+
+fn load_config(path: &Path) -> Result<SoundConfig, String> {
+    if !path.exists() {
+        return Ok(SoundConfig::default());
+    }
+    let contents = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    toml::from_str(&contents).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+fn save_config(path: &Path, config: &SoundConfig) -> Result<(), String> {
+    let contents = toml::to_string_pretty(config).map_err(|e| e.to_string())?;
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, contents).map_err(|e| e.to_string())?;
+    fs::rename(&tmp, path).map_err(|e| e.to_string())
+}
+
+// Audio files in `dir`, keyed by file stem
+fn scan(dir: &Path) -> Vec<(String, PathBuf)> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut sounds: Vec<(String, PathBuf)> = entries
+        .filter_map(Result::ok)
+        .map(|e| e.path())
+        .filter(|p| {
+            p.extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| SOUND_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+        })
+        .filter_map(|p| Some((p.file_stem()?.to_str()?.to_string(), p.clone())))
+        .collect();
+    sounds.sort();
+    sounds
+}
+
+struct QueuedSound {
+    path: Option<PathBuf>,
+    // Picks the system sound when there is no path
+    kind: NotificationKind,
+    priority: SoundPriority,
+    config: SoundConfig,
+}
+
+#[derive(Default)]
+struct SoundQueue {
+    pending: VecDeque<QueuedSound>,
+    playing: Option<SoundPriority>,
+    // Set when a critical sound should cut off the one playing
+    interrupt: bool,
+    // Start times of sounds played in the last minute
+    played: VecDeque<Instant>,
+}
+
+impl SoundQueue {
+    // Whether the sound was queued; `force` skips the cap and coalescing
+    fn push(&mut self, sound: QueuedSound, force: bool) -> bool {
+        let limits = &sound.config.queue;
+        if sound.priority == SoundPriority::Critical {
+            if self.playing.is_some_and(|p| p != SoundPriority::Critical) {
+                self.interrupt = true;
+            }
+            // Ahead of everything but earlier critical sounds
+            let at = self
+                .pending
+                .iter()
+                .position(|s| s.priority != SoundPriority::Critical)
+                .unwrap_or(self.pending.len());
+            self.pending.insert(at, sound);
+            return true;
+        }
+        if !force {
+            let info_queued = self.playing == Some(SoundPriority::Info)
+                || self
+                    .pending
+                    .iter()
+                    .any(|s| s.priority == SoundPriority::Info);
+            if sound.priority == SoundPriority::Info && limits.coalesce_info && info_queued {
+                return false;
+            }
+            // None within a minute of boot, when nothing played can be older than that
+            if let Some(minute_ago) = Instant::now().checked_sub(Duration::from_secs(60)) {
+                while self.played.front().is_some_and(|at| *at < minute_ago) {
+                    self.played.pop_front();
+                }
+            }
+            let max = limits.max_per_minute as usize;
+            if max > 0 && self.played.len() + self.pending.len() >= max {
+                return false;
+            }
+        }
+        let at = self
+            .pending
+            .iter()
+            .position(|s| s.priority < sound.priority)
+            .unwrap_or(self.pending.len());
+        self.pending.insert(at, sound);
+        true
+    }
+}
+
+// Sound settings live in config.toml so they can be hand-edited. Sounds play one at a
+// time on a dedicated thread, since rodio's output stream can't be shared across threads.
+pub struct SoundManager {
+    path: PathBuf,
+    config: Mutex<SoundConfig>,
+    // Imported by the user; takes precedence over bundled sounds with the same name
+    user_dir: PathBuf,
+    // Sounds shipped in the app bundle's resources/sounds
+    bundled_dir: Option<PathBuf>,
+    queue: Arc<(Mutex<SoundQueue>, Condvar)>,
+}
+
+impl SoundManager {
+    pub fn load(app: &AppHandle) -> Result<Self, String> {
+        let path = storage::data_file(app, CONFIG_FILE)?;
+        let config = load_config(&path)?;
+        let user_dir = storage::data_file(app, SOUNDS_DIR)?;
+        let bundled_dir = app.path().resource_dir().ok().map(|d| d.join(SOUNDS_DIR));
+        let queue = Arc::new((Mutex::new(SoundQueue::default()), Condvar::new()));
+        let player = queue.clone();
+        std::thread::Builder::new()
+            .name("sound".to_string())
+            .spawn(move || run_player(&player))
+            .map_err(|e| e.to_string())?;
+        Ok(Self {
+            path,
+            config: Mutex::new(config),
+            user_dir,
+            bundled_dir,
+            queue,
+        })
+    }
+
+    pub fn config(&self) -> Result<SoundConfig, String> {
+        Ok(self.config.lock().map_err(|e| e.to_string())?.clone())
+    }
+
+    pub fn update(&self, f: impl FnOnce(&mut SoundConfig)) -> Result<SoundConfig, String> {
+        let mut config = self.config.lock().map_err(|e| e.to_string())?;
+        let mut updated = config.clone();
+        f(&mut updated);
+        save_config(&self.path, &updated)?;
+        *config = updated.clone();
+        Ok(updated)
+    }
+
+    pub fn available(&self) -> Vec<SoundInfo> {
+        let mut sounds = vec![SoundInfo {
+            name: BEEP.to_string(),
+            source: SoundSource::Builtin,
+            path: None,
+        }];
+        let dirs = [
+            (Some(&self.user_dir), SoundSource::User),
+            (self.bundled_dir.as_ref(), SoundSource::Bundled),
+        ];
+        for (dir, source) in dirs {
+            let Some(dir) = dir else { continue };
+            for (name, path) in scan(dir) {
+                if sounds.iter().all(|s| s.name != name) {
+                    sounds.push(SoundInfo {
+                        name,
+                        source: source.clone(),
+                        path: Some(path),
+                    });
+                }
+            }
+        }
+        sounds
+    }
+
+    // None for the generated beep
+    fn resolve(&self, name: &str) -> Result<Option<PathBuf>, String> {
+        self.available()
+            .into_iter()
+            .find(|s| s.name == name)
+            .map(|s| s.path)
+            .ok_or_else(|| format!("Unknown sound: {}", name))
+    }
+
+    // File to play for a notification channel, if the user picked one
+    pub fn channel_sound(&self, kind: NotificationKind) -> Option<PathBuf> {
+        let config = self.config().ok()?;
+        match config.channels.get(kind.channel_id()) {
+            Some(name) => self.resolve(name).ok().flatten(),
+            None => config.notification_sound,
+        }
+    }
+
+    pub fn audio_devices(&self) -> Result<Vec<AudioDevice>, String> {
+        let selected = self.config()?.output_device;
+        let default = cpal::default_host()
+            .default_output_device()
+            .and_then(|d| d.name().ok());
+        Ok(output_devices()?
+            .into_iter()
+            .filter_map(|d| d.name().ok())
+            .map(|id| AudioDevice {
+                is_default: default.as_ref() == Some(&id),
+                selected: selected.as_ref() == Some(&id),
+                id,
+            })
+            .collect())
+    }
+
+    // Opens the configured output without playing anything
+    pub fn probe_output(&self) -> Result<(), String> {
+        let device = self.config()?.output_device;
+        open_output(device.as_deref()).map(|_| ())
+    }
+
+    // `None` plays the system sound for `kind`; `force` ignores the enabled flag, the rate
+    // cap and coalescing
+    pub fn play(
+        &self,
+        path: Option<PathBuf>,
+        kind: NotificationKind,
+        priority: SoundPriority,
+        force: bool,
+    ) {
+        let Ok(config) = self.config() else {
+            return;
+        };
+        if !config.enabled && !force {
+            return;
+        }
+        let (queue, ready) = &*self.queue;
+        let Ok(mut queue) = queue.lock() else {
+            return;
+        };
+        let sound = QueuedSound {
+            path,
+            kind,
+            priority,
+            config,
+        };
+        if queue.push(sound, force) {
+            ready.notify_one();
+        } else {
+            log::debug!("sound dropped by the notification sound queue");
+        }
+    }
+
+    #[cfg_attr(windows, allow(dead_code))]
+    pub fn play_alert(&self) {
+        self.play(
+            self.channel_sound(NotificationKind::Timer),
+            NotificationKind::Timer,
+            SoundPriority::Critical,
+            false,
+        );
+    }
+}
+
+// Checks the file is a supported, decodable clip short enough for a notification
+fn validate_import(path: &Path) -> Result<(), String> {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_lowercase)
+        .unwrap_or_default();
+    if !SOUND_EXTENSIONS.contains(&extension.as_str()) {
+        return Err(format!(
+            "Unsupported sound format; expected one of {}",
+            SOUND_EXTENSIONS.join(", ")
+        ));
+    }
+    let size = fs::metadata(path)
+        .map_err(|e| format!("{}: {}", path.display(), e))?
+        .len();
+    if size > MAX_IMPORT_BYTES {
+        return Err(format!(
+            "Sound files must be under {} KB",
+            MAX_IMPORT_BYTES / 1024
+        ));
+    }
+    let file = File::open(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let decoder = Decoder::new(BufReader::new(file)).map_err(|e| e.to_string())?;
+    // Not every format reports a duration up front, so fall back to counting samples
+    let duration = match decoder.total_duration() {
+        Some(duration) => duration,
+        None => {
+            let rate = decoder.sample_rate() as u64 * decoder.channels() as u64;
+            Duration::from_secs_f64(decoder.count() as f64 / rate.max(1) as f64)
+        }
+    };
+    if duration >= MAX_IMPORT_DURATION {
+        return Err(format!(
+            "Sounds must be shorter than {} seconds",
+            MAX_IMPORT_DURATION.as_secs()
+        ));
+    }
+    Ok(())
+}
+
+fn analyze<S>(name: String, source: S) -> SoundMetadata
+where
+    S: Source,
+    S::Item: Sample,
+{
+    let sample_rate = source.sample_rate();
+    let channels = source.channels();
+    let limit = (MAX_ANALYZED.as_secs() * sample_rate as u64 * channels as u64) as usize;
+    let levels: Vec<f32> = source
+        .take(limit)
+        .map(|s| s.to_f32().abs().min(1.0))
+        .collect();
+    let frames = levels.len() as u64 / channels.max(1) as u64;
+    let duration_ms = frames * 1000 / sample_rate.max(1) as u64;
+    let slice = levels.len().div_ceil(ENVELOPE_POINTS).max(1);
+    let envelope = levels
+        .chunks(slice)
+        .map(|chunk| chunk.iter().copied().fold(0.0, f32::max))
+        .collect();
+    let peak = levels.iter().copied().fold(0.0, f32::max);
+    let clipped_samples = levels.iter().filter(|l| **l >= CLIP_LEVEL).count() as u64;
+
+    let mut warnings = Vec::new();
+    if duration_ms >= MAX_IMPORT_DURATION.as_millis() as u64 {
+        warnings.push(format!(
+            "Longer than {} seconds; notification sounds should be short",
+            MAX_IMPORT_DURATION.as_secs()
+        ));
+    }
+    if clipped_samples > 0 {
+        warnings.push(format!("Clipping in {} samples", clipped_samples));
+    }
+    SoundMetadata {
+        name,
+        duration_ms,
+        sample_rate,
+        channels,
+        envelope,
+        peak,
+        clipped_samples,
+        warnings,
+    }
+}
+
+fn meeting_running() -> bool {
+    let mut sys = System::new();
+    sys.refresh_processes();
+    sys.processes().values().any(|p| {
+        let name = p.name().to_lowercase();
+        MEETING_APPS.iter().any(|app| name.contains(app))
+    })
+}
+
+fn output_devices() -> Result<Vec<cpal::Device>, String> {
+    Ok(cpal::default_host()
+        .output_devices()
+        .map_err(|e| e.to_string())?
+        .collect())
+}
+
+// Opens the chosen device, falling back to the default if it has been unplugged
+fn open_output(device: Option<&str>) -> Result<(OutputStream, OutputStreamHandle), String> {
+    if let Some(name) = device {
+        let found = output_devices()?
+            .into_iter()
+            .find(|d| d.name().is_ok_and(|n| n == name));
+        match found {
+            Some(d) => return OutputStream::try_from_device(&d).map_err(|e| e.to_string()),
+            None => log::warn!("audio device {} not found, using the default", name),
+        }
+    }
+    OutputStream::try_default().map_err(|e| e.to_string())
+}
+
+fn run_player(queue: &(Mutex<SoundQueue>, Condvar)) {
+    let (lock, ready) = queue;
+    loop {
+        let sound = {
+            let Ok(mut state) = lock.lock() else {
+                return;
+            };
+            loop {
+                if let Some(sound) = state.pending.pop_front() {
+                    state.playing = Some(sound.priority);
+                    state.interrupt = false;
+                    state.played.push_back(Instant::now());
+                    break sound;
+                }
+                state = match ready.wait(state) {
+                    Ok(state) => state,
+                    Err(_) => return,
+                };
+            }
+        };
+        let config = &sound.config;
+        let mut volume = config.volume;
+        if config.duck_during_meetings && meeting_running() {
+            volume *= config.duck_volume.clamp(0.0, 1.0);
+        }
+        let interrupted = || lock.lock().map_or(true, |state| state.interrupt);
+        let device = config.output_device.as_deref();
+        let played = match &sound.path {
+            Some(path) => play_blocking(Some(path.as_path()), volume, device, interrupted),
+            None => play_default(sound.kind, volume, device, interrupted),
+        };
+        if let Err(e) = played {
+            log::warn!("failed to play sound: {}", e);
+        }
+        if let Ok(mut state) = lock.lock() {
+            state.playing = None;
+        }
+    }
+}
+
+// The platform's sound for the kind, falling back to the generated beep when it has none
+// or it can't be played
+fn play_default(
+    kind: NotificationKind,
+    volume: f32,
+    device: Option<&str>,
+    interrupted: impl Fn() -> bool,
+) -> Result<(), String> {
+    if let Some(path) = native_sound::find(kind) {
+        #[cfg(target_os = "macos")]
+        let played = native_sound::play(&path, volume, &interrupted);
+        #[cfg(not(target_os = "macos"))]
+        let played = play_blocking(Some(path.as_path()), volume, device, &interrupted);
+        match played {
+            Ok(()) => return Ok(()),
+            Err(e) => log::debug!("system sound {} failed: {}", path.display(), e),
+        }
+    }
+    play_blocking(None, volume, device, interrupted)
+}
+
+// Plays until the sound ends or `interrupted` returns true
+fn play_blocking(
+    path: Option<&Path>,
+    volume: f32,
+    device: Option<&str>,
+    interrupted: impl Fn() -> bool,
+) -> Result<(), String> {
+    let (_stream, handle) = open_output(device)?;
+    let sink = Sink::try_new(&handle).map_err(|e| e.to_string())?;
+    sink.set_volume(volume.clamp(0.0, 1.0));
+    match path {
+        Some(path) => {
+            let file = File::open(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+            let source = Decoder::new(BufReader::new(file)).map_err(|e| e.to_string())?;
+            sink.append(source);
+        }
+        None => sink.append(
+            SineWave::new(BEEP_HZ)
+                .take_duration(BEEP_LENGTH)
+                .amplify(0.3),
+        ),
+    }
+    while !sink.empty() {
+        if interrupted() {
+            sink.stop();
+            break;
+        }
+        std::thread::sleep(INTERRUPT_POLL);
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_sound_enabled(sound: State<'_, SoundManager>, enabled: bool) -> Result<(), String> {
+    sound.update(|c| c.enabled = enabled)?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn list_audio_devices(sound: State<'_, SoundManager>) -> Result<Vec<AudioDevice>, String> {
+    sound.audio_devices()
+}
+
+// `id: None` goes back to following the system default output.
+#[tauri::command]
+pub fn set_audio_device(sound: State<'_, SoundManager>, id: Option<String>) -> Result<(), String> {
+    if let Some(id) = &id {
+        let exists = output_devices()?
+            .iter()
+            .any(|d| d.name().is_ok_and(|n| &n == id));
+        if !exists {
+            return Err(format!("Unknown audio device: {}", id));
+        }
+    }
+    sound.update(|c| c.output_device = id)?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_sound_ducking(
+    sound: State<'_, SoundManager>,
+    enabled: bool,
+    volume: f32,
+) -> Result<(), String> {
+    sound.update(|c| {
+        c.duck_during_meetings = enabled;
+        c.duck_volume = volume.clamp(0.0, 1.0);
+    })?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn list_available_sounds(sound: State<'_, SoundManager>) -> Vec<SoundInfo> {
+    sound.available()
+}
+
+// Plays even when sounds are disabled, since the user asked for it explicitly.
+#[tauri::command]
+pub fn preview_sound(sound: State<'_, SoundManager>, name: String) -> Result<(), String> {
+    let path = sound.resolve(&name)?;
+    sound.play(path, NotificationKind::System, SoundPriority::Normal, true);
+    Ok(())
+}
+
+// The built-in sound is described by the generated beep, since the system sound it
+// stands for differs per channel.
+#[tauri::command]
+pub fn get_sound_metadata(
+    sound: State<'_, SoundManager>,
+    name: String,
+) -> Result<SoundMetadata, String> {
+    match sound.resolve(&name)? {
+        Some(path) => {
+            let file = File::open(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+            let decoder = Decoder::new(BufReader::new(file)).map_err(|e| e.to_string())?;
+            Ok(analyze(name, decoder))
+        }
+        None => Ok(analyze(
+            name,
+            SineWave::new(BEEP_HZ)
+                .take_duration(BEEP_LENGTH)
+                .amplify(0.3),
+        )),
+    }
+}
+
+// `sound: None` restores the channel's default sound.
+#[tauri::command]
+pub fn set_channel_sound(
+    app: AppHandle,
+    manager: State<'_, SoundManager>,
+    channel_id: NotificationKind,
+    sound: Option<String>,
+) -> Result<(), String> {
+    if let Some(name) = &sound {
+        manager.resolve(name)?;
+    }
+    manager.update(|c| match &sound {
+        Some(name) => {
+            c.channels
+                .insert(channel_id.channel_id().to_string(), name.clone());
+        }
+        None => {
+            c.channels.remove(channel_id.channel_id());
+        }
+    })?;
+    // Lets the frontend and notification backends refresh their channel definitions
+    let _ = app.emit(
+        "notification-channel-created",
+        ChannelSound { channel_id, sound },
+    );
+    Ok(())
+}
+
+// Copies a validated audio file into the user sounds directory and assigns it to the
+// channel. Importing a file with an existing name replaces the earlier import.
+#[tauri::command]
+pub fn import_sound(
+    app: AppHandle,
+    manager: State<'_, SoundManager>,
+    path: PathBuf,
+    channel_id: NotificationKind,
+) -> Result<SoundInfo, String> {
+    validate_import(&path)?;
+    let file_name = path.file_name().ok_or("Invalid sound path")?;
+    let name = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .ok_or("Invalid sound file name")?
+        .to_string();
+    if name == BEEP {
+        return Err(format!("\"{}\" is reserved for the built-in sound", BEEP));
+    }
+    fs::create_dir_all(&manager.user_dir).map_err(|e| e.to_string())?;
+    // Drop earlier imports of this name in other formats so the new file wins
+    for (existing, existing_path) in scan(&manager.user_dir) {
+        if existing == name {
+            fs::remove_file(&existing_path).map_err(|e| e.to_string())?;
+        }
+    }
+    let dest = manager.user_dir.join(file_name);
+    let tmp = dest.with_extension("tmp");
+    fs::copy(&path, &tmp).map_err(|e| e.to_string())?;
+    fs::rename(&tmp, &dest).map_err(|e| e.to_string())?;
+
+    set_channel_sound(app, manager, channel_id, Some(name.clone()))?;
+    Ok(SoundInfo {
+        name,
+        source: SoundSource::User,
+        path: Some(dest),
+    })
+}
@@ -0,0 +1,172 @@
+use serde::{Deserialize, Serialize};
+use std::sync::mpsc::{self, Sender};
+use tauri::{AppHandle, Manager, State};
+
+use crate::sound::SoundManager;
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SpeechConfig {
+    // Speak announcements for timer and idle events; `speak` works regardless
+    pub announcements: bool,
+    // Voice id from `list_voices`; None uses the system default
+    pub voice: Option<String>,
+    // Multiplier of the engine's normal rate
+    pub rate: f32,
+    // 0.0 - 1.0 of the engine's range
+    pub volume: f32,
+}
+
+impl Default for SpeechConfig {
+    fn default() -> Self {
+        Self {
+            announcements: false,
+            voice: None,
+            rate: 1.0,
+            volume: 1.0,
+        }
+    }
+}
+
+#[cfg_attr(not(feature = "speech"), allow(dead_code))]
+#[derive(Clone, Serialize)]
+pub struct VoiceInfo {
+    pub id: String,
+    pub name: String,
+    pub language: String,
+}
+
+#[cfg_attr(not(feature = "speech"), allow(dead_code))]
+enum Job {
+    Speak(String, SpeechConfig),
+    Voices(Sender<Result<Vec<VoiceInfo>, String>>),
+}
+
+// The TTS engine isn't Send on every platform, so it lives on its own thread and is
+// fed through a channel. None when built without the `speech` feature.
+pub struct Speech {
+    tx: Option<Sender<Job>>,
+}
+
+impl Speech {
+    pub fn start() -> Self {
+        #[cfg(feature = "speech")]
+        {
+            let (tx, rx) = mpsc::channel();
+            std::thread::spawn(move || engine::run(rx));
+            Self { tx: Some(tx) }
+        }
+        #[cfg(not(feature = "speech"))]
+        {
+            Self { tx: None }
+        }
+    }
+
+    fn send(&self, job: Job) -> Result<(), String> {
+        self.tx
+            .as_ref()
+            .ok_or("Text-to-speech requires a build with the speech feature")?
+            .send(job)
+            .map_err(|_| "Text-to-speech engine has stopped".to_string())
+    }
+}
+
+#[cfg(feature = "speech")]
+mod engine {
+    use super::{Job, SpeechConfig, VoiceInfo};
+    use std::sync::mpsc::Receiver;
+    use tts::Tts;
+
+    fn configure(tts: &mut Tts, config: &SpeechConfig) -> Result<(), tts::Error> {
+        let rate = tts.normal_rate() * config.rate;
+        tts.set_rate(rate.clamp(tts.min_rate(), tts.max_rate()))?;
+        let (min, max) = (tts.min_volume(), tts.max_volume());
+        tts.set_volume(min + (max - min) * config.volume.clamp(0.0, 1.0))?;
+        if let Some(id) = &config.voice {
+            if let Some(voice) = tts.voices()?.into_iter().find(|v| &v.id() == id) {
+                tts.set_voice(&voice)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn run(rx: Receiver<Job>) {
+        let mut tts = match Tts::default() {
+            Ok(tts) => tts,
+            Err(e) => {
+                log::warn!("text-to-speech unavailable: {}", e);
+                return;
+            }
+        };
+        for job in rx {
+            match job {
+                Job::Speak(text, config) => {
+                    let result = configure(&mut tts, &config)
+                        .and_then(|_| tts.speak(text, true).map(|_| ()));
+                    if let Err(e) = result {
+                        log::warn!("failed to speak: {}", e);
+                    }
+                }
+                Job::Voices(reply) => {
+                    let voices = tts.voices().map_err(|e| e.to_string()).map(|voices| {
+                        voices
+                            .into_iter()
+                            .map(|v| VoiceInfo {
+                                id: v.id(),
+                                name: v.name(),
+                                language: v.language().to_string(),
+                            })
+                            .collect()
+                    });
+                    let _ = reply.send(voices);
+                }
+            }
+        }
+    }
+}
+
+// Speaks an event announcement if the user opted in and sounds are enabled.
+pub fn announce(app: &AppHandle, text: &str) {
+    let Ok(config) = app.state::<SoundManager>().config() else {
+        return;
+    };
+    if !config.enabled || !config.speech.announcements {
+        return;
+    }
+    if let Err(e) = app
+        .state::<Speech>()
+        .send(Job::Speak(text.to_string(), config.speech))
+    {
+        log::debug!("announcement skipped: {}", e);
+    }
+}
+
+#[tauri::command]
+pub fn speak(
+    speech: State<'_, Speech>,
+    sound: State<'_, SoundManager>,
+    text: String,
+) -> Result<(), String> {
+    let config = sound.config()?;
+    if !config.enabled {
+        return Err("Sounds are disabled".to_string());
+    }
+    speech.send(Job::Speak(text, config.speech))
+}
+
+#[tauri::command]
+pub fn list_voices(speech: State<'_, Speech>) -> Result<Vec<VoiceInfo>, String> {
+    let (tx, rx) = mpsc::channel();
+    speech.send(Job::Voices(tx))?;
+    rx.recv()
+        .map_err(|_| "Text-to-speech engine has stopped".to_string())?
+}
+
+#[tauri::command]
+pub fn set_speech_settings(
+    sound: State<'_, SoundManager>,
+    settings: SpeechConfig,
+) -> Result<(), String> {
+    sound.update(|c| c.speech = settings)?;
+    Ok(())
+}
@@ -0,0 +1,143 @@
+use chrono::{Duration as ChronoDuration, NaiveDate, Utc};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use tauri::{AppHandle, Manager, State};
+
+use crate::clipboard;
+use crate::holidays::{self, HolidaySettings};
+use crate::integrations::jira;
+use crate::sessions::{DateRange, Session, SessionStore};
+use crate::settings::SettingsStore;
+use crate::tasks_remote::RemoteTasks;
+use crate::time::{self, ReportZone};
+
+const NO_PROJECT: &str = "Other";
+// How far back to look for the previous workday, e.g. over a long holiday
+const WORKDAY_LOOKBACK_DAYS: i64 = 14;
+
+#[derive(Serialize)]
+pub struct Standup {
+    pub date: NaiveDate,
+    pub total_seconds: i64,
+    pub markdown: String,
+}
+
+#[derive(Default)]
+struct TaskSummary {
+    title: String,
+    seconds: i64,
+    notes: Vec<String>,
+}
+
+// The most recent workday before `today`; Monday's standup covers Friday
+fn previous_workday(settings: &HolidaySettings, today: NaiveDate) -> NaiveDate {
+    (1..=WORKDAY_LOOKBACK_DAYS)
+        .map(|days| today - ChronoDuration::days(days))
+        .find(|date| holidays::is_workday(settings, *date))
+        .unwrap_or(today - ChronoDuration::days(1))
+}
+
+// The assigned task's project, else the Jira project key in the title
+fn project_of(session: &Session, projects: &HashMap<u64, String>) -> String {
+    projects
+        .get(&session.task_id)
+        .cloned()
+        .or_else(|| {
+            let key = jira::parse_issue_key(session.title.as_deref()?)?;
+            key.split('-').next().map(str::to_string)
+        })
+        .unwrap_or_else(|| NO_PROJECT.to_string())
+}
+
+fn build_standup(
+    sessions: &[Session],
+    projects: &HashMap<u64, String>,
+    range: DateRange,
+    date: NaiveDate,
+) -> Standup {
+    // Project -> task id -> summary; ordered maps keep the layout stable between runs
+    let mut grouped: BTreeMap<String, BTreeMap<u64, TaskSummary>> = BTreeMap::new();
+    let mut total_seconds = 0;
+    for session in sessions.iter().filter(|s| range.contains(s)) {
+        let seconds = (session.end.min(range.end) - session.start.max(range.start)).num_seconds();
+        total_seconds += seconds;
+        let task = grouped
+            .entry(project_of(session, projects))
+            .or_default()
+            .entry(session.task_id)
+            .or_default();
+        if task.title.is_empty() {
+            task.title = session
+                .title
+                .clone()
+                .unwrap_or_else(|| format!("Task #{}", session.task_id));
+        }
+        task.seconds += seconds;
+        if let Some(note) = session.note.as_deref().map(str::trim) {
+            if !note.is_empty() && !task.notes.iter().any(|n| n == note) {
+                task.notes.push(note.to_string());
+            }
+        }
+    }
+
+    let mut markdown = format!("**{}**\n", date.format("%A, %B %-d"));
+    if grouped.is_empty() {
+        markdown.push_str("- Nothing tracked\n");
+    }
+    for (project, tasks) in grouped {
+        let project_seconds: i64 = tasks.values().map(|t| t.seconds).sum();
+        markdown.push_str(&format!(
+            "- **{}** ({})\n",
+            project,
+            time::format_hours_minutes(project_seconds)
+        ));
+        for task in tasks.into_values() {
+            markdown.push_str(&format!(
+                "  - {} ({})\n",
+                task.title,
+                time::format_hours_minutes(task.seconds)
+            ));
+            for note in task.notes {
+                // Multi-line notes stay inside their bullet
+                markdown.push_str(&format!("    - {}\n", note.replace('\n', " ")));
+            }
+        }
+    }
+    Standup {
+        date,
+        total_seconds,
+        markdown,
+    }
+}
+
+// Markdown summary of the day's sessions, grouped by project. Without a date it covers
+// the previous workday.
+#[tauri::command]
+pub fn generate_standup(
+    app: AppHandle,
+    store: State<'_, SessionStore>,
+    settings: State<'_, SettingsStore>,
+    date: Option<NaiveDate>,
+    copy_to_clipboard: bool,
+) -> Result<Standup, String> {
+    let settings = settings.get()?;
+    let zone = ReportZone::from_settings(&settings);
+    let date =
+        date.unwrap_or_else(|| previous_workday(&settings.holidays, zone.date_of(Utc::now())));
+    let range = DateRange {
+        start: zone.start_of_day(date),
+        end: zone.start_of_day(date + ChronoDuration::days(1)),
+    };
+    let projects: HashMap<u64, String> = app
+        .state::<RemoteTasks>()
+        .get()?
+        .tasks
+        .into_iter()
+        .filter_map(|t| Some((t.id, t.project?)))
+        .collect();
+    let standup = build_standup(&store.in_range(range)?, &projects, range, date);
+    if copy_to_clipboard {
+        clipboard::copy(&app, standup.markdown.clone())?;
+    }
+    Ok(standup)
+}
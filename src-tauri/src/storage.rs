@@ -0,0 +1,99 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tauri::Manager;
+
+use crate::encryption;
+
+// Every JSON store in the app data directory, used for backups and diagnostics
+pub const DATA_FILES: &[&str] = &[
+    "settings.json",
+    "sessions.json",
+    "timer.json",
+    "billing.json",
+    "jira.json",
+    "heuristics.json",
+    "input_stats.json",
+    "goals.json",
+    "notifications.json",
+    "tasks_remote.json",
+    "rules.json",
+    "timeline.json",
+    "git_activity.json",
+    "coding.json",
+    "sync.json",
+    "e2ee.json",
+    "org_policy.json",
+    "kiosk.json",
+    "expenses.json",
+    "screenshots.json",
+    "windows.json",
+];
+
+// Directories whose files are encrypted along with the stores
+pub const SEALED_DIRS: &[&str] = &["screenshots"];
+
+// Subdirectory of the active profile, set once at startup; the default profile keeps
+// its data directly in the app data directory.
+static PROFILE_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+pub fn set_profile_dir(dir: PathBuf) {
+    let _ = PROFILE_DIR.set(dir);
+}
+
+// Resolve a file in the app data directory itself, shared by every profile.
+pub fn root_file(app: &tauri::AppHandle, name: &str) -> Result<PathBuf, String> {
+    let dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(dir.join(name))
+}
+
+// Resolve a file inside the active profile's data directory, creating the directory on
+// first use.
+pub fn data_file(app: &tauri::AppHandle, name: &str) -> Result<PathBuf, String> {
+    let mut dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    if let Some(profile) = PROFILE_DIR.get() {
+        dir.push(profile);
+    }
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(dir.join(name))
+}
+
+// Reads a store, decrypting it when encryption is enabled.
+pub fn read_text(path: &Path) -> Result<String, String> {
+    encryption::read(path)
+}
+
+pub fn load_json<T: DeserializeOwned + Default>(path: &Path) -> Result<T, String> {
+    if !path.exists() {
+        return Ok(T::default());
+    }
+    let contents = read_text(path)?;
+    serde_json::from_str(&contents).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+// Writes to a sibling file and renames it so a crash mid-write never truncates the store
+pub(crate) fn replace(path: &Path, contents: &[u8]) -> Result<(), String> {
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, contents).map_err(|e| e.to_string())?;
+    fs::rename(&tmp, path).map_err(|e| e.to_string())
+}
+
+pub fn write_text(path: &Path, contents: String) -> Result<(), String> {
+    encryption::write(path, contents)
+}
+
+pub fn read_bytes(path: &Path) -> Result<Vec<u8>, String> {
+    encryption::read_bytes(path)
+}
+
+pub fn write_bytes(path: &Path, contents: Vec<u8>) -> Result<(), String> {
+    encryption::write_bytes(path, contents)
+}
+
+pub fn save_json<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+    let contents = serde_json::to_string_pretty(value).map_err(|e| e.to_string())?;
+    write_text(path, contents)
+}
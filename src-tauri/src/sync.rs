@@ -0,0 +1,806 @@
+use chrono::{DateTime, Utc};
+use futures_util::FutureExt;
+use reqwest::header::{ACCEPT_ENCODING, CONTENT_ENCODING, CONTENT_TYPE};
+use reqwest::{Method, Response};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::backend;
+use crate::background;
+use crate::e2ee;
+use crate::network::{self, NetworkStatus};
+use crate::screenshots;
+use crate::sessions::{AuditAction, AuditRecord, Session, SessionStore};
+use crate::settings::SettingsStore;
+use crate::storage;
+
+const SYNC_PATH: &str = "/api/sync/sessions";
+const SYNC_INTERVAL: Duration = Duration::from_secs(5 * 60);
+const ZSTD_LEVEL: i32 = 3;
+
+// Connections scheduled syncs may use; sync_now ignores this
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncNetwork {
+    #[default]
+    Any,
+    NotMetered,
+    WifiOnly,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SyncSettings {
+    pub enabled: bool,
+    pub network: SyncNetwork,
+}
+
+// Link between a local entry and its copy on the backend
+#[derive(Clone, Serialize, Deserialize)]
+struct SyncRecord {
+    local_id: u64,
+    remote_id: String,
+    etag: String,
+    // The version both sides agreed on at the last sync; a local entry that differs
+    // from it has unsynced edits
+    base: Session,
+    // Whether the backend holds the entry end-to-end encrypted
+    #[serde(default)]
+    sealed: bool,
+}
+
+// Both sides changed an entry since the last sync. Neither version is applied until
+// resolve_conflict picks one.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SyncConflict {
+    pub id: u64,
+    pub remote_id: String,
+    // None when deleted on that side
+    pub local: Option<Session>,
+    pub remote: Option<Session>,
+    pub remote_etag: Option<String>,
+    pub remote_updated_at: Option<DateTime<Utc>>,
+    pub detected_at: DateTime<Utc>,
+}
+
+#[derive(Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictStrategy {
+    KeepLocal,
+    KeepRemote,
+    // Fields edited locally win, the rest come from the backend; tags are combined
+    Merge,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SyncStats {
+    pub uploads: u64,
+    // Upload bodies as sent and before compression
+    pub bytes_sent: u64,
+    pub bytes_uncompressed: u64,
+    pub bytes_received: u64,
+    pub skipped_runs: u64,
+    pub last_skip_reason: Option<String>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct SyncData {
+    // Position in the backend's change feed
+    cursor: Option<String>,
+    // The backend's acknowledgement of the last upload, sent back with the next one
+    #[serde(default)]
+    ack_cursor: Option<String>,
+    records: Vec<SyncRecord>,
+    conflicts: Vec<SyncConflict>,
+    last_sync_at: Option<DateTime<Utc>>,
+    last_error: Option<String>,
+    #[serde(default)]
+    stats: SyncStats,
+}
+
+#[derive(Clone, Serialize)]
+pub struct SyncStatus {
+    pub enabled: bool,
+    pub last_sync_at: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+    pub synced_entries: usize,
+    pub conflicts: usize,
+    pub network: NetworkStatus,
+    pub stats: SyncStats,
+}
+
+#[derive(Clone, Default, Serialize)]
+pub struct SyncSummary {
+    pub pulled: usize,
+    pub pushed: usize,
+    pub conflicts: usize,
+}
+
+// One entry from the backend's change feed
+#[derive(Deserialize)]
+struct RemoteChange {
+    id: String,
+    etag: String,
+    #[serde(default)]
+    updated_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    deleted: bool,
+    // The entry and whether the backend holds it sealed
+    #[serde(default, deserialize_with = "e2ee::deserialize_session")]
+    session: Option<(Session, bool)>,
+}
+
+#[derive(Deserialize)]
+struct ChangesPage {
+    changes: Vec<RemoteChange>,
+    cursor: Option<String>,
+    #[serde(default)]
+    has_more: bool,
+}
+
+enum PushOp {
+    Create(Session),
+    Update(SyncRecord, Session),
+    Delete(SyncRecord),
+}
+
+impl PushOp {
+    fn local_id(&self) -> u64 {
+        match self {
+            PushOp::Create(session) => session.id,
+            PushOp::Update(record, _) | PushOp::Delete(record) => record.local_id,
+        }
+    }
+}
+
+// Writes carry the last known etag in if_match, so the backend refuses them when the
+// entry changed there in the meantime.
+#[derive(Serialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+enum BatchOp {
+    Create {
+        local_id: u64,
+        #[serde(serialize_with = "e2ee::serialize_session")]
+        session: Session,
+    },
+    // Only the fields that differ from the last synced version, unless `replace` is
+    // set because the entry is sealed or being unsealed
+    Update {
+        id: String,
+        if_match: String,
+        fields: Map<String, Value>,
+        replace: bool,
+    },
+    Delete {
+        id: String,
+        if_match: String,
+    },
+}
+
+#[derive(Serialize)]
+struct Batch {
+    since: Option<String>,
+    changes: Vec<BatchOp>,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+enum BatchResult {
+    Saved { id: String, etag: String },
+    Deleted,
+    // The backend's current version of an entry whose write was refused
+    Conflict { current: RemoteChange },
+}
+
+// One result per change, in the order they were sent
+#[derive(Deserialize)]
+struct BatchResponse {
+    results: Vec<BatchResult>,
+    #[serde(default)]
+    ack: Option<String>,
+}
+
+pub struct SyncStore {
+    path: PathBuf,
+    data: Mutex<SyncData>,
+}
+
+impl SyncStore {
+    pub fn load(app: &AppHandle) -> Result<Self, String> {
+        let path = storage::data_file(app, "sync.json")?;
+        let data = storage::load_json(&path)?;
+        Ok(Self {
+            path,
+            data: Mutex::new(data),
+        })
+    }
+
+    fn read<T>(&self, f: impl FnOnce(&SyncData) -> T) -> Result<T, String> {
+        let data = self.data.lock().map_err(|e| e.to_string())?;
+        Ok(f(&data))
+    }
+
+    fn update<T>(&self, f: impl FnOnce(&mut SyncData) -> T) -> Result<T, String> {
+        let mut data = self.data.lock().map_err(|e| e.to_string())?;
+        let result = f(&mut data);
+        storage::save_json(&self.path, &*data)?;
+        Ok(result)
+    }
+}
+
+fn local_session(app: &AppHandle, id: u64) -> Result<Option<Session>, String> {
+    app.state::<SessionStore>()
+        .read(|data| data.sessions.iter().find(|s| s.id == id).cloned())
+}
+
+// Writes `session` under `id`, or deletes the entry when None, with an audit record.
+fn apply_local(app: &AppHandle, id: u64, session: Option<Session>) -> Result<(), String> {
+    app.state::<SessionStore>().write(|data| {
+        let index = data.sessions.iter().position(|s| s.id == id);
+        let before = index.map(|i| data.sessions[i].clone());
+        let after = session.map(|s| Session { id, ..s });
+        let action = match (&before, &after) {
+            (None, None) => return Ok(()),
+            (None, Some(_)) => AuditAction::Created,
+            (Some(_), Some(_)) => AuditAction::Edited,
+            (Some(_), None) => AuditAction::Deleted,
+        };
+        match (index, &after) {
+            (Some(i), Some(after)) => data.sessions[i] = after.clone(),
+            (Some(i), None) => {
+                data.sessions.remove(i);
+            }
+            (None, Some(after)) => data.sessions.push(after.clone()),
+            (None, None) => {}
+        }
+        data.audit.push(AuditRecord {
+            entry_id: id,
+            action,
+            at: Utc::now(),
+            before,
+            after,
+        });
+        Ok(())
+    })
+}
+
+fn add_conflict(app: &AppHandle, data: &mut SyncData, conflict: SyncConflict) {
+    log::warn!("sync conflict on entry {}", conflict.id);
+    let _ = app.emit("sync-conflict", &conflict);
+    data.conflicts.retain(|c| c.id != conflict.id);
+    data.conflicts.push(conflict);
+}
+
+// Applies one change from the backend unless the entry also has local edits. Returns
+// whether the change was applied.
+fn apply_remote(app: &AppHandle, change: RemoteChange) -> Result<bool, String> {
+    let sync = app.state::<SyncStore>();
+    let mut data = sync.data.lock().map_err(|e| e.to_string())?;
+    let sealed = change.session.as_ref().map_or(false, |(_, sealed)| *sealed);
+    let remote = if change.deleted {
+        None
+    } else {
+        change.session.map(|(session, _)| session)
+    };
+
+    // A newer remote version of an entry already in conflict replaces the old one
+    if let Some(conflict) = data.conflicts.iter_mut().find(|c| c.remote_id == change.id) {
+        conflict.remote = remote.map(|s| Session {
+            id: conflict.id,
+            ..s
+        });
+        conflict.remote_etag = Some(change.etag);
+        conflict.remote_updated_at = change.updated_at;
+        storage::save_json(&sync.path, &*data)?;
+        return Ok(false);
+    }
+
+    let Some(index) = data.records.iter().position(|r| r.remote_id == change.id) else {
+        // New on the backend
+        let Some(session) = remote else {
+            return Ok(false);
+        };
+        let local_id = app.state::<SessionStore>().write(|d| {
+            d.next_id += 1;
+            Ok(d.next_id)
+        })?;
+        let session = Session {
+            id: local_id,
+            ..session
+        };
+        apply_local(app, local_id, Some(session.clone()))?;
+        data.records.push(SyncRecord {
+            local_id,
+            remote_id: change.id,
+            etag: change.etag,
+            base: session,
+            sealed,
+        });
+        storage::save_json(&sync.path, &*data)?;
+        return Ok(true);
+    };
+
+    let record = data.records[index].clone();
+    // Our own upload coming back through the feed
+    if record.etag == change.etag {
+        return Ok(false);
+    }
+    let remote = remote.map(|s| Session {
+        id: record.local_id,
+        ..s
+    });
+    let local = local_session(app, record.local_id)?;
+    if local.as_ref() == Some(&record.base) {
+        apply_local(app, record.local_id, remote.clone())?;
+        match remote {
+            Some(session) => {
+                data.records[index].etag = change.etag;
+                data.records[index].base = session;
+                data.records[index].sealed = sealed;
+            }
+            None => {
+                data.records.remove(index);
+            }
+        }
+        storage::save_json(&sync.path, &*data)?;
+        return Ok(true);
+    }
+    add_conflict(
+        app,
+        &mut data,
+        SyncConflict {
+            id: record.local_id,
+            remote_id: record.remote_id,
+            local,
+            remote,
+            remote_etag: Some(change.etag),
+            remote_updated_at: change.updated_at,
+            detected_at: Utc::now(),
+        },
+    );
+    storage::save_json(&sync.path, &*data)?;
+    Ok(false)
+}
+
+async fn pull(app: &AppHandle) -> Result<usize, String> {
+    let mut pulled = 0;
+    loop {
+        let cursor = app.state::<SyncStore>().read(|data| data.cursor.clone())?;
+        let mut request =
+            backend::request(app, Method::GET, SYNC_PATH)?.header(ACCEPT_ENCODING, "zstd");
+        if let Some(cursor) = cursor {
+            request = request.query(&[("cursor", cursor)]);
+        }
+        let response = request
+            .send()
+            .await
+            .map_err(|e| e.to_string())?
+            .error_for_status()
+            .map_err(|e| e.to_string())?;
+        let (page, received): (ChangesPage, u64) = read_body(response).await?;
+        app.state::<SyncStore>()
+            .update(|data| data.stats.bytes_received += received)?;
+        for change in page.changes {
+            if apply_remote(app, change)? {
+                pulled += 1;
+            }
+        }
+        app.state::<SyncStore>()
+            .update(|data| data.cursor = page.cursor)?;
+        if !page.has_more {
+            return Ok(pulled);
+        }
+    }
+}
+
+// Entries created, edited or deleted locally since the last sync, except those in
+// conflict. Turning end-to-end encryption on or off makes every entry pending.
+fn pending(app: &AppHandle) -> Result<Vec<PushOp>, String> {
+    let sealed = e2ee::is_enabled();
+    let sessions = app
+        .state::<SessionStore>()
+        .read(|data| data.sessions.clone())?;
+    let (records, conflicts) = app.state::<SyncStore>().read(|data| {
+        let conflicts: Vec<u64> = data.conflicts.iter().map(|c| c.id).collect();
+        (data.records.clone(), conflicts)
+    })?;
+    let mut ops = Vec::new();
+    for session in &sessions {
+        if conflicts.contains(&session.id) {
+            continue;
+        }
+        match records.iter().find(|r| r.local_id == session.id) {
+            None => ops.push(PushOp::Create(session.clone())),
+            Some(record) if record.base != *session || record.sealed != sealed => {
+                ops.push(PushOp::Update(record.clone(), session.clone()))
+            }
+            Some(_) => {}
+        }
+    }
+    for record in records {
+        let deleted = !sessions.iter().any(|s| s.id == record.local_id);
+        if deleted && !conflicts.contains(&record.local_id) {
+            ops.push(PushOp::Delete(record));
+        }
+    }
+    Ok(ops)
+}
+
+// Decodes a JSON body the backend may have zstd-compressed, with its size on the wire.
+async fn read_body<T: DeserializeOwned>(response: Response) -> Result<(T, u64), String> {
+    let compressed = response
+        .headers()
+        .get(CONTENT_ENCODING)
+        .is_some_and(|encoding| encoding == "zstd");
+    let bytes = response.bytes().await.map_err(|e| e.to_string())?;
+    let json = if compressed {
+        zstd::decode_all(&bytes[..]).map_err(|e| e.to_string())?
+    } else {
+        bytes.to_vec()
+    };
+    let body = serde_json::from_slice(&json).map_err(|e| e.to_string())?;
+    Ok((body, bytes.len() as u64))
+}
+
+// Fields of `session` that differ from `base`; removed ones are sent as null.
+fn changed_fields(base: &Session, session: &Session) -> Result<Map<String, Value>, String> {
+    let base = serde_json::to_value(base).map_err(|e| e.to_string())?;
+    let session = serde_json::to_value(session).map_err(|e| e.to_string())?;
+    let (Value::Object(base), Value::Object(mut session)) = (base, session) else {
+        return Err("Entries must serialize to JSON objects".to_string());
+    };
+    for key in base.keys() {
+        session.entry(key.clone()).or_insert(Value::Null);
+    }
+    session.retain(|key, value| base.get(key) != Some(value));
+    Ok(session)
+}
+
+async fn upload(app: &AppHandle, batch: &Batch) -> Result<BatchResponse, String> {
+    let json = serde_json::to_vec(batch).map_err(|e| e.to_string())?;
+    let body = zstd::encode_all(&json[..], ZSTD_LEVEL).map_err(|e| e.to_string())?;
+    let sent = body.len() as u64;
+    let response = backend::request(app, Method::POST, &format!("{}/batch", SYNC_PATH))?
+        .header(CONTENT_TYPE, "application/json")
+        .header(CONTENT_ENCODING, "zstd")
+        .header(ACCEPT_ENCODING, "zstd")
+        .body(body)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?
+        .error_for_status()
+        .map_err(|e| e.to_string())?;
+    let (result, received) = read_body(response).await?;
+    app.state::<SyncStore>().update(|data| {
+        data.stats.uploads += 1;
+        data.stats.bytes_sent += sent;
+        data.stats.bytes_uncompressed += json.len() as u64;
+        data.stats.bytes_received += received;
+    })?;
+    Ok(result)
+}
+
+// Conflict for a local write the backend refused because the entry changed there.
+fn refused(record: SyncRecord, local: Option<Session>, current: RemoteChange) -> SyncConflict {
+    let id = record.local_id;
+    SyncConflict {
+        id,
+        remote_id: record.remote_id,
+        local,
+        remote: current
+            .session
+            .filter(|_| !current.deleted)
+            .map(|s| Session { id, ..s }),
+        remote_etag: Some(current.etag).filter(|etag| !etag.is_empty()),
+        remote_updated_at: current.updated_at,
+        detected_at: Utc::now(),
+    }
+}
+
+// Uploads every pending local change in one compressed batch.
+async fn push(app: &AppHandle) -> Result<usize, String> {
+    let ops = pending(app)?;
+    if ops.is_empty() {
+        return Ok(0);
+    }
+    let sealed = e2ee::is_enabled();
+    let mut changes = Vec::with_capacity(ops.len());
+    for op in &ops {
+        changes.push(match op {
+            PushOp::Create(session) => BatchOp::Create {
+                local_id: session.id,
+                session: session.clone(),
+            },
+            PushOp::Update(record, session) => {
+                let replace = sealed || record.sealed;
+                let fields = if replace {
+                    match e2ee::encode(session)? {
+                        Value::Object(fields) => fields,
+                        _ => return Err("Entries must serialize to JSON objects".to_string()),
+                    }
+                } else {
+                    changed_fields(&record.base, session)?
+                };
+                BatchOp::Update {
+                    id: record.remote_id.clone(),
+                    if_match: record.etag.clone(),
+                    fields,
+                    replace,
+                }
+            }
+            PushOp::Delete(record) => BatchOp::Delete {
+                id: record.remote_id.clone(),
+                if_match: record.etag.clone(),
+            },
+        });
+    }
+    let since = app
+        .state::<SyncStore>()
+        .read(|data| data.ack_cursor.clone())?;
+    let response = upload(app, &Batch { since, changes }).await?;
+    if response.results.len() != ops.len() {
+        return Err(format!(
+            "The backend answered {} of {} uploaded changes",
+            response.results.len(),
+            ops.len()
+        ));
+    }
+
+    let sync = app.state::<SyncStore>();
+    let mut data = sync.data.lock().map_err(|e| e.to_string())?;
+    let mut pushed = 0;
+    for (op, result) in ops.into_iter().zip(response.results) {
+        let id = op.local_id();
+        match (op, result) {
+            (PushOp::Delete(_), BatchResult::Deleted) => {
+                data.records.retain(|r| r.local_id != id);
+                pushed += 1;
+            }
+            (
+                PushOp::Create(session) | PushOp::Update(_, session),
+                BatchResult::Saved {
+                    id: remote_id,
+                    etag,
+                },
+            ) => {
+                data.records.retain(|r| r.local_id != id);
+                data.records.push(SyncRecord {
+                    local_id: id,
+                    remote_id,
+                    etag,
+                    base: session,
+                    sealed,
+                });
+                pushed += 1;
+            }
+            (PushOp::Update(record, session), BatchResult::Conflict { current }) => {
+                add_conflict(app, &mut data, refused(record, Some(session), current));
+            }
+            (PushOp::Delete(record), BatchResult::Conflict { current }) => {
+                add_conflict(app, &mut data, refused(record, None, current));
+            }
+            _ => log::warn!("unexpected sync result for entry {}", id),
+        }
+    }
+    if response.ack.is_some() {
+        data.ack_cursor = response.ack;
+    }
+    storage::save_json(&sync.path, &*data)?;
+    Ok(pushed)
+}
+
+// Pulls the backend's changes, then pushes local ones.
+pub async fn sync(app: &AppHandle) -> Result<SyncSummary, String> {
+    let result = async {
+        let mut summary = SyncSummary {
+            pulled: pull(app).await?,
+            pushed: push(app).await?,
+            ..Default::default()
+        };
+        summary.conflicts = app.state::<SyncStore>().read(|data| data.conflicts.len())?;
+        Ok::<_, String>(summary)
+    }
+    .await;
+    app.state::<SyncStore>().update(|data| match &result {
+        Ok(_) => {
+            data.last_sync_at = Some(Utc::now());
+            data.last_error = None;
+            data.stats.last_skip_reason = None;
+        }
+        Err(e) => data.last_error = Some(e.clone()),
+    })?;
+    if let Ok(summary) = &result {
+        let _ = app.emit("sync-completed", summary);
+    }
+    result
+}
+
+fn settings(app: &AppHandle) -> Option<SyncSettings> {
+    if !backend::is_configured(app) {
+        return None;
+    }
+    let settings = app.state::<SettingsStore>().get().ok()?.sync;
+    settings.enabled.then_some(settings)
+}
+
+// Why the current connection rules out a scheduled sync. An unknown status doesn't,
+// or platforms that can't tell would never sync.
+fn network_block(policy: SyncNetwork) -> Option<&'static str> {
+    let status = network::status();
+    match policy {
+        SyncNetwork::Any => None,
+        SyncNetwork::NotMetered => (status.metered == Some(true)).then_some("metered connection"),
+        SyncNetwork::WifiOnly => (status.wifi == Some(false)).then_some("not on Wi-Fi"),
+    }
+}
+
+// Local changes not pushed yet
+pub fn pending_count(app: &AppHandle) -> Result<usize, String> {
+    Ok(pending(app)?.len())
+}
+
+// Pushes pending entries and screenshot uploads on the way out, under the same
+// conditions as a scheduled run.
+pub async fn flush(app: &AppHandle) {
+    let Some(settings) = settings(app) else {
+        return;
+    };
+    if let Some(reason) = network_block(settings.network) {
+        log::info!("sync flush skipped on exit: {}", reason);
+        return;
+    }
+    if let Err(e) = sync(app).await {
+        log::warn!("session sync failed on exit: {}", e);
+    }
+    if let Err(e) = screenshots::upload_pending(app).await {
+        log::warn!("screenshot upload failed on exit: {}", e);
+    }
+}
+
+pub fn start_scheduler(app: AppHandle) {
+    background::spawn_async(&app, "session_sync", |app, task| {
+        async move {
+            loop {
+                if let Some(settings) = settings(&app) {
+                    if let Some(reason) = network_block(settings.network) {
+                        log::info!("session sync skipped: {}", reason);
+                        let _ = app.state::<SyncStore>().update(|data| {
+                            data.stats.skipped_runs += 1;
+                            data.stats.last_skip_reason = Some(reason.to_string());
+                        });
+                    } else {
+                        if let Err(e) = sync(&app).await {
+                            log::warn!("session sync failed: {}", e);
+                        }
+                        if let Err(e) = screenshots::upload_pending(&app).await {
+                            log::warn!("screenshot upload failed: {}", e);
+                        }
+                    }
+                }
+                if !task.sleep(SYNC_INTERVAL).await {
+                    break;
+                }
+            }
+        }
+        .boxed()
+    });
+}
+
+// Three-way merge against the last synced version
+fn merge(base: &Session, local: &Session, remote: &Session) -> Session {
+    fn pick<T: Clone + PartialEq>(base: &T, local: &T, remote: &T) -> T {
+        if local != base {
+            local.clone()
+        } else {
+            remote.clone()
+        }
+    }
+    let mut tags = remote.tags.clone();
+    for tag in &local.tags {
+        if !tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+            tags.push(tag.clone());
+        }
+    }
+    Session {
+        task_id: pick(&base.task_id, &local.task_id, &remote.task_id),
+        title: pick(&base.title, &local.title, &remote.title),
+        start: pick(&base.start, &local.start, &remote.start),
+        end: pick(&base.end, &local.end, &remote.end),
+        note: pick(&base.note, &local.note, &remote.note),
+        tags,
+        ..remote.clone()
+    }
+}
+
+#[tauri::command]
+pub fn get_sync_conflicts(sync: State<'_, SyncStore>) -> Result<Vec<SyncConflict>, String> {
+    sync.read(|data| data.conflicts.clone())
+}
+
+// Settles a conflict locally; the outcome is uploaded on the next sync.
+#[tauri::command]
+pub fn resolve_conflict(
+    app: AppHandle,
+    id: u64,
+    strategy: ConflictStrategy,
+) -> Result<Option<Session>, String> {
+    let sync = app.state::<SyncStore>();
+    let mut data = sync.data.lock().map_err(|e| e.to_string())?;
+    let conflict = data
+        .conflicts
+        .iter()
+        .find(|c| c.id == id)
+        .cloned()
+        .ok_or_else(|| format!("No sync conflict for entry {}", id))?;
+    let index = data.records.iter().position(|r| r.local_id == id);
+    let resolved = match strategy {
+        ConflictStrategy::KeepLocal => conflict.local.clone(),
+        ConflictStrategy::KeepRemote => conflict.remote.clone(),
+        ConflictStrategy::Merge => {
+            let (Some(local), Some(remote), Some(index)) =
+                (&conflict.local, &conflict.remote, index)
+            else {
+                return Err("Only entries edited on both sides can be merged".to_string());
+            };
+            Some(merge(&data.records[index].base, local, remote))
+        }
+    };
+    apply_local(&app, id, resolved)?;
+
+    // Rebase on the backend's version, so the next push sends whatever differs from it
+    match (index, conflict.remote, conflict.remote_etag) {
+        (Some(index), Some(remote), Some(etag)) => {
+            data.records[index].base = remote;
+            data.records[index].etag = etag;
+        }
+        // Gone from the backend: a kept entry is uploaded again as new
+        (Some(index), _, _) => {
+            data.records.remove(index);
+        }
+        (None, _, _) => {}
+    }
+    data.conflicts.retain(|c| c.id != id);
+    storage::save_json(&sync.path, &*data)?;
+    drop(data);
+    local_session(&app, id)
+}
+
+#[tauri::command]
+pub async fn sync_now(app: AppHandle) -> Result<SyncSummary, String> {
+    if !backend::is_configured(&app) {
+        return Err("The FTT backend is not configured".to_string());
+    }
+    sync(&app).await
+}
+
+#[tauri::command]
+pub fn get_sync_status(
+    sync: State<'_, SyncStore>,
+    settings: State<'_, SettingsStore>,
+) -> Result<SyncStatus, String> {
+    let enabled = settings.get()?.sync.enabled;
+    sync.read(|data| SyncStatus {
+        enabled,
+        last_sync_at: data.last_sync_at,
+        last_error: data.last_error.clone(),
+        synced_entries: data.records.len(),
+        conflicts: data.conflicts.len(),
+        network: network::status(),
+        stats: data.stats.clone(),
+    })
+}
+
+#[tauri::command]
+pub fn set_sync_settings(
+    settings: State<'_, SettingsStore>,
+    sync: SyncSettings,
+) -> Result<(), String> {
+    settings.update(|s| s.sync = sync)?;
+    Ok(())
+}
@@ -0,0 +1,106 @@
+use chrono::{DateTime, Utc};
+use futures_util::FutureExt;
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::backend;
+use crate::background;
+use crate::storage;
+
+const REFRESH_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct AssignedTask {
+    pub id: u64,
+    pub title: String,
+    #[serde(default)]
+    pub project: Option<String>,
+    #[serde(default)]
+    pub status: Option<String>,
+    #[serde(default)]
+    pub due: Option<DateTime<Utc>>,
+}
+
+// Last successful fetch, kept on disk so the task picker works offline
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct AssignedTasks {
+    pub tasks: Vec<AssignedTask>,
+    pub fetched_at: Option<DateTime<Utc>>,
+}
+
+pub struct RemoteTasks {
+    path: PathBuf,
+    data: Mutex<AssignedTasks>,
+}
+
+impl RemoteTasks {
+    pub fn load(app: &AppHandle) -> Result<Self, String> {
+        let path = storage::data_file(app, "tasks_remote.json")?;
+        let data = storage::load_json(&path)?;
+        Ok(Self {
+            path,
+            data: Mutex::new(data),
+        })
+    }
+
+    pub fn get(&self) -> Result<AssignedTasks, String> {
+        Ok(self.data.lock().map_err(|e| e.to_string())?.clone())
+    }
+
+    fn replace(&self, tasks: Vec<AssignedTask>) -> Result<AssignedTasks, String> {
+        let mut data = self.data.lock().map_err(|e| e.to_string())?;
+        *data = AssignedTasks {
+            tasks,
+            fetched_at: Some(Utc::now()),
+        };
+        storage::save_json(&self.path, &*data)?;
+        Ok(data.clone())
+    }
+}
+
+pub async fn refresh(app: &AppHandle) -> Result<AssignedTasks, String> {
+    let tasks: Vec<AssignedTask> = backend::request(app, Method::GET, "/api/tasks/assigned")?
+        .send()
+        .await
+        .map_err(|e| e.to_string())?
+        .error_for_status()
+        .map_err(|e| e.to_string())?
+        .json()
+        .await
+        .map_err(|e| e.to_string())?;
+    let updated = app.state::<RemoteTasks>().replace(tasks)?;
+    let _ = app.emit("tasks-updated", &updated);
+    Ok(updated)
+}
+
+pub fn start_refresh(app: AppHandle) {
+    background::spawn_async(&app, "assigned_tasks", |app, task| {
+        async move {
+            loop {
+                if backend::is_configured(&app) {
+                    if let Err(e) = refresh(&app).await {
+                        log::warn!("assigned task refresh failed: {}", e);
+                    }
+                }
+                if !task.sleep(REFRESH_INTERVAL).await {
+                    break;
+                }
+            }
+        }
+        .boxed()
+    });
+}
+
+#[tauri::command]
+pub fn get_assigned_tasks(tasks: State<'_, RemoteTasks>) -> Result<AssignedTasks, String> {
+    tasks.get()
+}
+
+#[tauri::command]
+pub async fn refresh_tasks(app: AppHandle) -> Result<AssignedTasks, String> {
+    refresh(&app).await
+}
@@ -0,0 +1,102 @@
+use chrono::{
+    DateTime, Duration as ChronoDuration, Local, LocalResult, NaiveDate, NaiveDateTime, Offset,
+    TimeZone, Utc,
+};
+use chrono_tz::Tz;
+use tauri::State;
+
+use crate::settings::{AppSettings, SettingsStore};
+
+// Timestamps are stored in UTC; the zone only matters when grouping by calendar day or
+// week, where a day can be 23 or 25 hours long across a DST change.
+#[derive(Clone, Copy)]
+pub enum ReportZone {
+    Local,
+    Named(Tz),
+}
+
+impl ReportZone {
+    pub fn from_settings(settings: &AppSettings) -> Self {
+        match settings.report_timezone.as_deref().map(str::parse::<Tz>) {
+            Some(Ok(tz)) => ReportZone::Named(tz),
+            Some(Err(e)) => {
+                log::warn!("invalid report timezone, using local time: {}", e);
+                ReportZone::Local
+            }
+            None => ReportZone::Local,
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            ReportZone::Local => "local".to_string(),
+            ReportZone::Named(tz) => tz.name().to_string(),
+        }
+    }
+
+    pub fn date_of(&self, at: DateTime<Utc>) -> NaiveDate {
+        match self {
+            ReportZone::Local => at.with_timezone(&Local).date_naive(),
+            ReportZone::Named(tz) => at.with_timezone(tz).date_naive(),
+        }
+    }
+
+    // Wall-clock time in this zone.
+    pub fn local_time(&self, at: DateTime<Utc>) -> NaiveDateTime {
+        match self {
+            ReportZone::Local => at.with_timezone(&Local).naive_local(),
+            ReportZone::Named(tz) => at.with_timezone(tz).naive_local(),
+        }
+    }
+
+    // First instant of `date` in this zone.
+    pub fn start_of_day(&self, date: NaiveDate) -> DateTime<Utc> {
+        let midnight = date.and_hms_opt(0, 0, 0).unwrap_or_default();
+        match self {
+            ReportZone::Local => resolve(&Local, midnight),
+            ReportZone::Named(tz) => resolve(tz, midnight),
+        }
+    }
+}
+
+// Map a local wall-clock time to UTC. Ambiguous times (clocks going back) take the
+// earlier instant; times skipped by a DST jump move forward to the first valid minute.
+fn resolve<Z: TimeZone>(zone: &Z, mut naive: NaiveDateTime) -> DateTime<Utc> {
+    for _ in 0..24 * 60 {
+        match zone.from_local_datetime(&naive) {
+            LocalResult::Single(dt) | LocalResult::Ambiguous(dt, _) => {
+                return dt.with_timezone(&Utc)
+            }
+            LocalResult::None => naive += ChronoDuration::minutes(1),
+        }
+    }
+    Utc.from_utc_datetime(&naive)
+}
+
+// "5 h 12 m", dropping whichever part is zero
+pub fn format_hours_minutes(seconds: i64) -> String {
+    let minutes = seconds.max(0) / 60;
+    match (minutes / 60, minutes % 60) {
+        (0, m) => format!("{} m", m),
+        (h, 0) => format!("{} h", h),
+        (h, m) => format!("{} h {} m", h, m),
+    }
+}
+
+// UTC offset of the machine's local zone at `at`, recorded alongside stored timestamps so
+// the wall-clock time the user saw can be recovered even after they change zones.
+pub fn local_offset_secs(at: DateTime<Utc>) -> i32 {
+    at.with_timezone(&Local).offset().fix().local_minus_utc()
+}
+
+#[tauri::command]
+pub fn set_report_timezone(
+    settings: State<'_, SettingsStore>,
+    tz: Option<String>,
+) -> Result<(), String> {
+    if let Some(name) = &tz {
+        name.parse::<Tz>().map_err(|e| e.to_string())?;
+    }
+    settings.update(|s| s.report_timezone = tz)?;
+    Ok(())
+}
@@ -0,0 +1,266 @@
+use chrono::{DateTime, NaiveDate, TimeDelta, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+
+use crate::active_window;
+use crate::background;
+use crate::browser::BrowserBridge;
+use crate::idle::{IdleMonitor, IdleState};
+use crate::settings::SettingsStore;
+use crate::storage;
+use crate::time::ReportZone;
+
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(60);
+const MINUTES_PER_DAY: usize = 24 * 60;
+
+// One character per minute in a stored day
+const NO_DATA: char = ' ';
+const ACTIVE: char = 'a';
+const IDLE: char = 'i';
+const LOCKED: char = 'l';
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct AppCategory {
+    pub name: String,
+    // Matched against the app name, case-insensitive substring; first category wins
+    pub apps: Vec<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TimelineSettings {
+    pub enabled: bool,
+    pub categories: Vec<AppCategory>,
+}
+
+impl Default for TimelineSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            categories: Vec::new(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MinuteState {
+    NoData,
+    Active,
+    Idle,
+    Locked,
+}
+
+#[derive(Serialize)]
+pub struct TimelineMinute {
+    pub state: MinuteState,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct Timeline {
+    pub date: NaiveDate,
+    pub timezone: String,
+    // Instant of minutes[0]; a day is 23 or 25 hours long across a DST change
+    pub start: DateTime<Utc>,
+    pub minutes: Vec<TimelineMinute>,
+}
+
+// A UTC day of samples: `states` has one char per minute and `apps` indexes into
+// TimelineData::apps, offset by one so 0 means no app.
+#[derive(Clone, Serialize, Deserialize)]
+struct TimelineDay {
+    states: String,
+    apps: Vec<u32>,
+}
+
+impl Default for TimelineDay {
+    fn default() -> Self {
+        Self {
+            states: NO_DATA.to_string().repeat(MINUTES_PER_DAY),
+            apps: vec![0; MINUTES_PER_DAY],
+        }
+    }
+}
+
+// Pre-aggregated minute table: a fixed-size row per day, so reading a day is a lookup
+// rather than a scan of raw events.
+#[derive(Default, Serialize, Deserialize)]
+struct TimelineData {
+    apps: Vec<String>,
+    days: BTreeMap<NaiveDate, TimelineDay>,
+}
+
+pub struct TimelineStore {
+    path: PathBuf,
+    data: Mutex<TimelineData>,
+}
+
+fn state_char(state: IdleState) -> char {
+    match state {
+        IdleState::Locked => LOCKED,
+        state if state.is_idle() => IDLE,
+        _ => ACTIVE,
+    }
+}
+
+fn minute_state(c: char) -> MinuteState {
+    match c {
+        ACTIVE => MinuteState::Active,
+        IDLE => MinuteState::Idle,
+        LOCKED => MinuteState::Locked,
+        _ => MinuteState::NoData,
+    }
+}
+
+fn category(categories: &[AppCategory], app: &str) -> Option<String> {
+    let app = app.to_lowercase();
+    categories
+        .iter()
+        .find(|c| c.apps.iter().any(|a| app.contains(&a.to_lowercase())))
+        .map(|c| c.name.clone())
+}
+
+impl TimelineStore {
+    pub fn load(app: &AppHandle) -> Result<Self, String> {
+        let path = storage::data_file(app, "timeline.json")?;
+        let data = storage::load_json(&path)?;
+        Ok(Self {
+            path,
+            data: Mutex::new(data),
+        })
+    }
+
+    fn record(&self, at: DateTime<Utc>, state: char, app: Option<&str>) -> Result<(), String> {
+        let mut data = self.data.lock().map_err(|e| e.to_string())?;
+        let app_index = match app {
+            Some(name) => match data.apps.iter().position(|a| a == name) {
+                Some(i) => i as u32 + 1,
+                None => {
+                    data.apps.push(name.to_string());
+                    data.apps.len() as u32
+                }
+            },
+            None => 0,
+        };
+        let minute = (at.hour() * 60 + at.minute()) as usize;
+        let day = data.days.entry(at.date_naive()).or_default();
+        day.states
+            .replace_range(minute..minute + 1, state.encode_utf8(&mut [0; 4]));
+        day.apps[minute] = app_index;
+        storage::save_json(&self.path, &*data)
+    }
+
+    // Drops days that ended before `before`. Returns the number removed.
+    pub fn prune(&self, before: DateTime<Utc>) -> Result<usize, String> {
+        let mut data = self.data.lock().map_err(|e| e.to_string())?;
+        let len = data.days.len();
+        data.days.retain(|date, _| *date >= before.date_naive());
+        let removed = len - data.days.len();
+        if removed > 0 {
+            storage::save_json(&self.path, &*data)?;
+        }
+        Ok(removed)
+    }
+
+    pub fn day(
+        &self,
+        date: NaiveDate,
+        zone: ReportZone,
+        categories: &[AppCategory],
+    ) -> Result<Timeline, String> {
+        let start = zone.start_of_day(date);
+        let end = date
+            .succ_opt()
+            .map(|d| zone.start_of_day(d))
+            .ok_or("Date out of range")?;
+        let data = self.data.lock().map_err(|e| e.to_string())?;
+        let mut minutes = Vec::with_capacity(MINUTES_PER_DAY);
+        let mut at = start;
+        while at < end {
+            let index = (at.hour() * 60 + at.minute()) as usize;
+            let sample = data.days.get(&at.date_naive()).map(|day| {
+                let state = day.states.as_bytes()[index] as char;
+                let app = day.apps[index]
+                    .checked_sub(1)
+                    .and_then(|i| data.apps.get(i as usize));
+                (state, app)
+            });
+            minutes.push(match sample {
+                Some((state, app)) => TimelineMinute {
+                    state: minute_state(state),
+                    app: app.cloned(),
+                    category: app.and_then(|a| category(categories, a)),
+                },
+                None => TimelineMinute {
+                    state: MinuteState::NoData,
+                    app: None,
+                    category: None,
+                },
+            });
+            at += TimeDelta::minutes(1);
+        }
+        Ok(Timeline {
+            date,
+            timezone: zone.name(),
+            start,
+            minutes,
+        })
+    }
+}
+
+fn sample(app: &AppHandle) -> Result<(), String> {
+    if !app.state::<SettingsStore>().get()?.timeline.enabled {
+        return Ok(());
+    }
+    let state = state_char(app.state::<IdleMonitor>().state());
+    // Only the app name (and a browser tab's domain) is kept, never the window title
+    let focused = if state == ACTIVE {
+        let bridge = app.state::<BrowserBridge>();
+        active_window::current().ok().map(|w| bridge.label(&w.app))
+    } else {
+        None
+    };
+    app.state::<TimelineStore>()
+        .record(Utc::now(), state, focused.as_deref())
+}
+
+pub fn start_sampler(app: AppHandle) {
+    background::spawn_thread(&app, "timeline", |app, task| {
+        while task.sleep_blocking(SAMPLE_INTERVAL) {
+            if let Err(e) = sample(&app) {
+                log::warn!("failed to record timeline: {}", e);
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub fn get_timeline(
+    store: State<'_, TimelineStore>,
+    settings: State<'_, SettingsStore>,
+    date: NaiveDate,
+) -> Result<Timeline, String> {
+    let settings = settings.get()?;
+    store.day(
+        date,
+        ReportZone::from_settings(&settings),
+        &settings.timeline.categories,
+    )
+}
+
+#[tauri::command]
+pub fn set_timeline_settings(
+    settings: State<'_, SettingsStore>,
+    timeline: TimelineSettings,
+) -> Result<(), String> {
+    settings.update(|s| s.timeline = timeline)?;
+    Ok(())
+}
@@ -0,0 +1,546 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::background;
+use crate::badge::{self, BadgeState};
+use crate::focus::FocusBreach;
+use crate::idle::IdleMonitor;
+use crate::notifications::{self, NotificationImportance, NotificationKind};
+use crate::sessions::{NewSession, Session, SessionStore};
+#[cfg(not(windows))]
+use crate::sound::SoundManager;
+use crate::speech;
+use crate::storage;
+use crate::tray;
+use crate::tray_icon;
+
+#[derive(Clone, Serialize)]
+pub struct TimerTick {
+    pub task_id: u64,
+    pub title: Option<String>,
+    pub elapsed_seconds: u64,
+    pub remaining_seconds: Option<u64>,
+    pub paused: bool,
+}
+
+const MAIN_TIMER_ID: &str = "main";
+const TICK_INTERVAL: Duration = Duration::from_secs(1);
+// A tick that arrives this much later than expected (by either clock), or a wall clock
+// that moved this far from the monotonic one, means the machine slept or the clock was
+// changed.
+const CLOCK_JUMP: Duration = Duration::from_secs(30);
+
+#[derive(Clone, Serialize)]
+pub struct ClockJump {
+    // Last moment the timers are known to have been running
+    pub suspended_at: DateTime<Utc>,
+    pub resumed_at: DateTime<Utc>,
+    pub gap_seconds: i64,
+    pub split_session_ids: Vec<u64>,
+}
+
+fn main_timer_id() -> String {
+    MAIN_TIMER_ID.to_string()
+}
+
+fn default_extend_secs() -> u64 {
+    5 * 60
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CountdownAction {
+    #[default]
+    Stop,
+    // Keep running and alert again after another `extend_secs`
+    Extend,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Countdown {
+    pub duration_secs: u64,
+    #[serde(default)]
+    pub on_complete: CountdownAction,
+    #[serde(default = "default_extend_secs")]
+    pub extend_secs: u64,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ActiveTimer {
+    #[serde(default = "main_timer_id")]
+    pub id: String,
+    pub task_id: u64,
+    pub title: Option<String>,
+    pub started_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub focus_breaches: Vec<FocusBreach>,
+    // Set for countdown timers; stopwatch timers have none
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub countdown: Option<Countdown>,
+    // Notes appended while running; saved with the session
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+impl ActiveTimer {
+    pub fn elapsed_seconds(&self) -> u64 {
+        (Utc::now() - self.started_at).num_seconds().max(0) as u64
+    }
+
+    pub fn remaining_seconds(&self) -> Option<u64> {
+        let countdown = self.countdown.as_ref()?;
+        Some(
+            countdown
+                .duration_secs
+                .saturating_sub(self.elapsed_seconds()),
+        )
+    }
+}
+
+#[derive(Clone, Serialize)]
+pub struct TimerInfo {
+    #[serde(flatten)]
+    pub timer: ActiveTimer,
+    pub elapsed_seconds: u64,
+    pub remaining_seconds: Option<u64>,
+    pub primary: bool,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct TimerSet {
+    timers: Vec<ActiveTimer>,
+    primary: Option<String>,
+}
+
+// Owns the running timers: any number of named timers may run concurrently, one of
+// which is the primary shown in the tray, badge and `get_timer_state`. Timers are
+// persisted so they survive an app restart; stopping one turns it into a session.
+pub struct TimerManager {
+    path: PathBuf,
+    timers: Mutex<TimerSet>,
+}
+
+impl TimerManager {
+    pub fn load(app: &AppHandle) -> Result<Self, String> {
+        let path = storage::data_file(app, "timer.json")?;
+        let timers = storage::load_json(&path)?;
+        Ok(Self {
+            path,
+            timers: Mutex::new(timers),
+        })
+    }
+
+    // The primary timer
+    pub fn active(&self) -> Option<ActiveTimer> {
+        let set = self.timers.lock().ok()?;
+        let primary = set.primary.as_ref()?;
+        set.timers.iter().find(|t| &t.id == primary).cloned()
+    }
+
+    pub fn list(&self) -> Vec<TimerInfo> {
+        let Ok(set) = self.timers.lock() else {
+            return Vec::new();
+        };
+        set.timers
+            .iter()
+            .map(|timer| TimerInfo {
+                elapsed_seconds: timer.elapsed_seconds(),
+                remaining_seconds: timer.remaining_seconds(),
+                primary: set.primary.as_ref() == Some(&timer.id),
+                timer: timer.clone(),
+            })
+            .collect()
+    }
+
+    // Switch the primary timer to a new task, stopping whatever was primary.
+    pub fn start(
+        &self,
+        app: &AppHandle,
+        task_id: u64,
+        title: Option<String>,
+    ) -> Result<ActiveTimer, String> {
+        self.start_with(app, task_id, title, None)
+    }
+
+    pub fn start_with(
+        &self,
+        app: &AppHandle,
+        task_id: u64,
+        title: Option<String>,
+        countdown: Option<Countdown>,
+    ) -> Result<ActiveTimer, String> {
+        self.stop(app)?;
+        self.start_named(app, main_timer_id(), task_id, title, true, countdown)
+    }
+
+    // Start a concurrent timer. Restarting an id that is already running stops it first.
+    pub fn start_named(
+        &self,
+        app: &AppHandle,
+        id: String,
+        task_id: u64,
+        title: Option<String>,
+        make_primary: bool,
+        countdown: Option<Countdown>,
+    ) -> Result<ActiveTimer, String> {
+        self.stop_named(app, &id)?;
+
+        let timer = ActiveTimer {
+            id,
+            task_id,
+            title,
+            started_at: Utc::now(),
+            focus_breaches: Vec::new(),
+            countdown,
+            note: None,
+        };
+        let mut set = self.timers.lock().map_err(|e| e.to_string())?;
+        set.timers.push(timer.clone());
+        if make_primary || set.primary.is_none() {
+            set.primary = Some(timer.id.clone());
+        }
+        storage::save_json(&self.path, &*set)?;
+        drop(set);
+
+        let _ = app.emit("timer-started", &timer);
+        Ok(timer)
+    }
+
+    pub fn set_primary(&self, id: &str) -> Result<(), String> {
+        let mut set = self.timers.lock().map_err(|e| e.to_string())?;
+        if !set.timers.iter().any(|t| t.id == id) {
+            return Err(format!("Timer {} is not running", id));
+        }
+        set.primary = Some(id.to_string());
+        storage::save_json(&self.path, &*set)
+    }
+
+    // Stop the primary timer; the longest-running remaining timer becomes primary.
+    pub fn stop(&self, app: &AppHandle) -> Result<Option<Session>, String> {
+        let primary = match self.timers.lock() {
+            Ok(set) => set.primary.clone(),
+            Err(e) => return Err(e.to_string()),
+        };
+        match primary {
+            Some(id) => self.stop_named(app, &id),
+            None => Ok(None),
+        }
+    }
+
+    // Returns the number of breaches logged against the timer so far.
+    pub fn record_focus_breach(&self, id: &str, breach: FocusBreach) -> Result<usize, String> {
+        let mut set = self.timers.lock().map_err(|e| e.to_string())?;
+        let timer = set
+            .timers
+            .iter_mut()
+            .find(|t| t.id == id)
+            .ok_or_else(|| format!("Timer {} is not running", id))?;
+        timer.focus_breaches.push(breach);
+        let count = timer.focus_breaches.len();
+        storage::save_json(&self.path, &*set)?;
+        Ok(count)
+    }
+
+    // Appends a line to the note of the given timer, or the primary timer.
+    pub fn append_note(&self, id: Option<&str>, text: &str) -> Result<ActiveTimer, String> {
+        let text = text.trim();
+        if text.is_empty() {
+            return Err("Note is empty".to_string());
+        }
+        let mut set = self.timers.lock().map_err(|e| e.to_string())?;
+        let id = match id {
+            Some(id) => id.to_string(),
+            None => set.primary.clone().ok_or("No timer is running")?,
+        };
+        let timer = set
+            .timers
+            .iter_mut()
+            .find(|t| t.id == id)
+            .ok_or_else(|| format!("Timer {} is not running", id))?;
+        timer.note = Some(match timer.note.take() {
+            Some(note) => format!("{}\n{}", note, text),
+            None => text.to_string(),
+        });
+        let timer = timer.clone();
+        storage::save_json(&self.path, &*set)?;
+        Ok(timer)
+    }
+
+    // Countdown timers whose time is up
+    fn due_countdowns(&self) -> Vec<ActiveTimer> {
+        let Ok(set) = self.timers.lock() else {
+            return Vec::new();
+        };
+        set.timers
+            .iter()
+            .filter(|t| t.remaining_seconds() == Some(0))
+            .cloned()
+            .collect()
+    }
+
+    fn extend_countdown(&self, id: &str) -> Result<(), String> {
+        let mut set = self.timers.lock().map_err(|e| e.to_string())?;
+        if let Some(countdown) = set
+            .timers
+            .iter_mut()
+            .find(|t| t.id == id)
+            .and_then(|t| t.countdown.as_mut())
+        {
+            countdown.duration_secs += countdown.extend_secs.max(60);
+        }
+        storage::save_json(&self.path, &*set)
+    }
+
+    // Ends a session for every running timer at `end` and restarts the timers at `resume`,
+    // so time across a sleep or clock change is never counted.
+    pub fn split_at(
+        &self,
+        app: &AppHandle,
+        end: DateTime<Utc>,
+        resume: DateTime<Utc>,
+    ) -> Result<Vec<Session>, String> {
+        let mut set = self.timers.lock().map_err(|e| e.to_string())?;
+        let mut finished = Vec::new();
+        for timer in set.timers.iter_mut() {
+            let tracked = (end - timer.started_at).num_seconds().max(0) as u64;
+            if tracked >= 1 {
+                finished.push(NewSession {
+                    task_id: timer.task_id,
+                    title: timer.title.clone(),
+                    start: timer.started_at,
+                    end,
+                    // The resumed timer keeps the note; both halves carry it
+                    note: timer.note.clone(),
+                    tags: Vec::new(),
+                    manual: false,
+                    focus_breaches: std::mem::take(&mut timer.focus_breaches),
+                });
+            }
+            if let Some(countdown) = timer.countdown.as_mut() {
+                countdown.duration_secs = countdown.duration_secs.saturating_sub(tracked).max(1);
+            }
+            timer.started_at = resume;
+        }
+        storage::save_json(&self.path, &*set)?;
+        drop(set);
+
+        let sessions = app.state::<SessionStore>();
+        finished
+            .into_iter()
+            .map(|new| sessions.insert(new))
+            .collect()
+    }
+
+    pub fn stop_all(&self, app: &AppHandle) -> Result<(), String> {
+        let ids: Vec<String> = match self.timers.lock() {
+            Ok(set) => set.timers.iter().map(|t| t.id.clone()).collect(),
+            Err(e) => return Err(e.to_string()),
+        };
+        for id in ids {
+            self.stop_named(app, &id)?;
+        }
+        Ok(())
+    }
+
+    pub fn stop_named(&self, app: &AppHandle, id: &str) -> Result<Option<Session>, String> {
+        let mut set = self.timers.lock().map_err(|e| e.to_string())?;
+        let Some(index) = set.timers.iter().position(|t| t.id == id) else {
+            return Ok(None);
+        };
+        let timer = set.timers.remove(index);
+        if set.primary.as_deref() == Some(id) {
+            set.primary = set.timers.first().map(|t| t.id.clone());
+        }
+        storage::save_json(&self.path, &*set)?;
+        drop(set);
+
+        // Nothing worth recording for a timer that was started and stopped within a second
+        let now = Utc::now();
+        if timer.started_at + chrono::Duration::seconds(1) > now {
+            return Ok(None);
+        }
+        let session = app.state::<SessionStore>().insert(NewSession {
+            task_id: timer.task_id,
+            title: timer.title,
+            start: timer.started_at,
+            end: now,
+            note: timer.note,
+            tags: Vec::new(),
+            manual: false,
+            focus_breaches: timer.focus_breaches,
+        })?;
+        let _ = app.emit("timer-stopped", &session);
+        Ok(Some(session))
+    }
+}
+
+#[tauri::command]
+pub fn list_timers(timer: State<'_, TimerManager>) -> Vec<TimerInfo> {
+    timer.list()
+}
+
+#[tauri::command]
+pub fn set_primary_timer(timer: State<'_, TimerManager>, id: String) -> Result<(), String> {
+    timer.set_primary(&id)
+}
+
+#[tauri::command]
+pub fn append_session_note(
+    app: AppHandle,
+    timer: State<'_, TimerManager>,
+    text: String,
+    id: Option<String>,
+) -> Result<ActiveTimer, String> {
+    let updated = timer.append_note(id.as_deref(), &text)?;
+    let _ = app.emit("timer-note-updated", &updated);
+    Ok(updated)
+}
+
+fn format_clock(seconds: u64) -> String {
+    format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+fn tooltip(tick: &TimerTick) -> String {
+    let name = tick
+        .title
+        .clone()
+        .unwrap_or_else(|| format!("Task #{}", tick.task_id));
+    match tick.remaining_seconds {
+        Some(remaining) => format!("{} - {} left", name, format_clock(remaining)),
+        None => format!("{} - {}", name, format_clock(tick.elapsed_seconds)),
+    }
+}
+
+fn handle_clock_jump(app: &AppHandle, suspended_at: DateTime<Utc>, resumed_at: DateTime<Utc>) {
+    let sessions = match app
+        .state::<TimerManager>()
+        .split_at(app, suspended_at, resumed_at)
+    {
+        Ok(sessions) => sessions,
+        Err(e) => {
+            log::error!("failed to split timers at clock jump: {}", e);
+            return;
+        }
+    };
+    let jump = ClockJump {
+        suspended_at,
+        resumed_at,
+        gap_seconds: (resumed_at - suspended_at).num_seconds(),
+        split_session_ids: sessions.iter().map(|s| s.id).collect(),
+    };
+    log::info!(
+        "clock jumped by {}s; split {} running timer(s)",
+        jump.gap_seconds,
+        sessions.len()
+    );
+    let _ = app.emit("clock-jump", &jump);
+}
+
+fn complete_countdown(app: &AppHandle, timer: &ActiveTimer) {
+    let manager = app.state::<TimerManager>();
+    let name = timer
+        .title
+        .clone()
+        .unwrap_or_else(|| format!("Task #{}", timer.task_id));
+    let extend = timer
+        .countdown
+        .as_ref()
+        .is_some_and(|c| c.on_complete == CountdownAction::Extend);
+    let (result, body) = if extend {
+        (
+            manager.extend_countdown(&timer.id),
+            format!("Time's up for {}. The timer keeps running.", name),
+        )
+    } else {
+        (
+            manager.stop_named(app, &timer.id).map(|_| ()),
+            format!("Time's up for {}. The timer has been stopped.", name),
+        )
+    };
+    if let Err(e) = result {
+        log::error!("failed to complete countdown {}: {}", timer.id, e);
+    }
+    notifications::notify(
+        app,
+        NotificationKind::Timer,
+        NotificationImportance::High,
+        "Countdown finished",
+        &body,
+    );
+    speech::announce(app, &format!("Time's up for {}", name));
+    // Windows toasts play the channel's own alarm sound
+    #[cfg(not(windows))]
+    app.state::<SoundManager>().play_alert();
+}
+
+// Emits `timer-tick` once a second while a timer runs so native surfaces (badge, tray)
+// stay current even when the window is hidden.
+pub fn start_ticker(app: AppHandle) {
+    background::spawn_thread(&app, "timer_ticker", |app, task| {
+        let mut was_running = false;
+        // Instant stops during sleep on some platforms and keeps counting on others, so both
+        // clocks are compared on every tick.
+        let mut last_tick = (Instant::now(), Utc::now());
+        while task.sleep_blocking(TICK_INTERVAL) {
+            let now = (Instant::now(), Utc::now());
+            let monotonic = now.0 - last_tick.0;
+            let wall = now.1 - last_tick.1;
+            let drift = wall - chrono::Duration::from_std(monotonic).unwrap_or_default();
+            if monotonic >= CLOCK_JUMP || drift.num_seconds().unsigned_abs() >= CLOCK_JUMP.as_secs()
+            {
+                let suspended_at =
+                    last_tick.1 + chrono::Duration::seconds(TICK_INTERVAL.as_secs() as i64);
+                handle_clock_jump(&app, suspended_at, now.1);
+            }
+            last_tick = now;
+
+            for timer in app.state::<TimerManager>().due_countdowns() {
+                complete_countdown(&app, &timer);
+            }
+
+            let Some(active) = app.state::<TimerManager>().active() else {
+                if was_running {
+                    badge::update(&app, BadgeState::Cleared);
+                    tray::set_tooltip(&app, None);
+                    tray_icon::update(&app, None, None);
+                    #[cfg(target_os = "macos")]
+                    tray::update_menu_bar(&app, None);
+                    was_running = false;
+                }
+                continue;
+            };
+            was_running = true;
+
+            let tick = TimerTick {
+                task_id: active.task_id,
+                elapsed_seconds: active.elapsed_seconds(),
+                remaining_seconds: active.remaining_seconds(),
+                paused: app.state::<IdleMonitor>().is_idle(),
+                title: active.title,
+            };
+            let state = if tick.paused {
+                BadgeState::Paused
+            } else {
+                BadgeState::Running {
+                    elapsed_seconds: tick.elapsed_seconds,
+                }
+            };
+            badge::update(&app, state);
+            tray::set_tooltip(&app, Some(&tooltip(&tick)));
+            let duration = active.countdown.as_ref().map(|c| c.duration_secs);
+            tray_icon::update(&app, Some(&tick), duration);
+            #[cfg(target_os = "macos")]
+            tray::update_menu_bar(&app, Some(&tick));
+            let _ = app.emit("timer-tick", &tick);
+        }
+    });
+}
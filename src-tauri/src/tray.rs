@@ -0,0 +1,349 @@
+use chrono::{Duration as ChronoDuration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+use tauri::menu::{CheckMenuItem, IsMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu};
+use tauri::tray::TrayIconBuilder;
+use tauri::{AppHandle, Listener, Manager, State, Wry};
+
+use crate::app_lock::{self, AppLock};
+use crate::background;
+use crate::profiles::{self, ProfileStore};
+use crate::reports;
+use crate::sessions::{DateRange, SessionStore};
+use crate::settings::SettingsStore;
+use crate::time::{self, ReportZone};
+use crate::timer::TimerManager;
+#[cfg(target_os = "macos")]
+use crate::timer::TimerTick;
+
+pub(crate) const TRAY_ID: &str = "main-tray";
+const RECENT_TASK_PREFIX: &str = "recent-task:";
+const RECENT_TASK_LIMIT: usize = 5;
+const PROFILE_PREFIX: &str = "profile:";
+const SUMMARY_INTERVAL: Duration = Duration::from_secs(5 * 60);
+const SUMMARY_TASK_LIMIT: usize = 3;
+
+// Text shown next to the tray icon in the macOS menu bar
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MenuBarSettings {
+    pub show_elapsed: bool,
+    // Prefix the time with a short project code, e.g. "FTT 1:23:45"
+    pub show_project: bool,
+}
+
+impl Default for MenuBarSettings {
+    fn default() -> Self {
+        Self {
+            show_elapsed: true,
+            show_project: false,
+        }
+    }
+}
+
+// Today's total and the tasks with the most time, including running timers
+fn today_summary(app: &AppHandle) -> Result<(i64, Vec<(String, i64)>), String> {
+    let zone = ReportZone::from_settings(&app.state::<SettingsStore>().get()?);
+    let now = Utc::now();
+    let today = zone.date_of(now);
+    let range = DateRange {
+        start: zone.start_of_day(today),
+        end: zone.start_of_day(today + ChronoDuration::days(1)),
+    };
+    let sessions = app.state::<SessionStore>().in_range(range)?;
+    let report = reports::build_report(&sessions, &[], range, zone, None);
+
+    let mut tasks: HashMap<u64, (Option<String>, i64)> = report
+        .tasks
+        .into_iter()
+        .map(|t| (t.task_id, (t.title, t.seconds)))
+        .collect();
+    let mut total = report.total_seconds;
+    for running in app.state::<TimerManager>().list() {
+        let seconds = (now - running.timer.started_at.max(range.start))
+            .num_seconds()
+            .max(0);
+        let entry = tasks
+            .entry(running.timer.task_id)
+            .or_insert((running.timer.title.clone(), 0));
+        entry.1 += seconds;
+        total += seconds;
+    }
+
+    let mut tasks: Vec<(String, i64)> = tasks
+        .into_iter()
+        .filter(|(_, (_, seconds))| *seconds >= 60)
+        .map(|(id, (title, seconds))| (title.unwrap_or_else(|| format!("Task #{}", id)), seconds))
+        .collect();
+    tasks.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    tasks.truncate(SUMMARY_TASK_LIMIT);
+    Ok((total, tasks))
+}
+
+// The tray is usable without unlocking, so it shows no tracked data and offers no
+// actions on it while locked
+fn is_locked(app: &AppHandle) -> bool {
+    app.try_state::<AppLock>()
+        .is_some_and(|lock| lock.is_locked())
+}
+
+// Disabled items at the top of the menu; left out while the app is locked
+fn summary_items(app: &AppHandle) -> tauri::Result<Vec<MenuItem<Wry>>> {
+    if is_locked(app) {
+        return Ok(Vec::new());
+    }
+    let (total, tasks) = match today_summary(app) {
+        Ok(summary) => summary,
+        Err(e) => {
+            log::warn!("failed to summarize today for the tray: {}", e);
+            return Ok(Vec::new());
+        }
+    };
+    let mut items = vec![MenuItem::with_id(
+        app,
+        "summary",
+        format!("Today: {}", time::format_hours_minutes(total)),
+        false,
+        None::<&str>,
+    )?];
+    for (i, (title, seconds)) in tasks.into_iter().enumerate() {
+        let id = format!("summary:{}", i);
+        let label = format!("{}: {}", title, time::format_hours_minutes(seconds));
+        items.push(MenuItem::with_id(app, id, label, false, None::<&str>)?);
+    }
+    Ok(items)
+}
+
+fn build_menu(app: &AppHandle) -> tauri::Result<Menu<Wry>> {
+    let show_i = MenuItem::with_id(app, "show", "Show Window", true, None::<&str>)?;
+    let quit_i = MenuItem::with_id(app, "quit", "Exit", true, None::<&str>)?;
+    let locked = is_locked(app);
+
+    // Quick-switcher for the most recently tracked tasks
+    let recent = if locked {
+        Vec::new()
+    } else {
+        app.state::<SessionStore>()
+            .recent_tasks(RECENT_TASK_LIMIT)
+            .unwrap_or_default()
+    };
+    let recent_items = recent
+        .iter()
+        .map(|(task_id, title)| {
+            let label = title
+                .clone()
+                .unwrap_or_else(|| format!("Task #{}", task_id));
+            let id = format!("{}{}", RECENT_TASK_PREFIX, task_id);
+            MenuItem::with_id(app, id, label, true, None::<&str>)
+        })
+        .collect::<tauri::Result<Vec<_>>>()?;
+    let recent_refs: Vec<&dyn IsMenuItem<Wry>> = recent_items
+        .iter()
+        .map(|item| item as &dyn IsMenuItem<Wry>)
+        .collect();
+    let recent_menu =
+        Submenu::with_items(app, "Recent Tasks", !recent_refs.is_empty(), &recent_refs)?;
+
+    // The running profile is checked; picking another one restarts into it
+    let profile_items = app
+        .state::<ProfileStore>()
+        .list()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|p| {
+            let id = format!("{}{}", PROFILE_PREFIX, p.id);
+            CheckMenuItem::with_id(app, id, p.name, true, p.active, None::<&str>)
+        })
+        .collect::<tauri::Result<Vec<_>>>()?;
+    let profile_refs: Vec<&dyn IsMenuItem<Wry>> = profile_items
+        .iter()
+        .map(|item| item as &dyn IsMenuItem<Wry>)
+        .collect();
+    let profile_menu = Submenu::with_items(app, "Profile", !locked, &profile_refs)?;
+
+    let summary = summary_items(app)?;
+    let separator = PredefinedMenuItem::separator(app)?;
+    let mut items: Vec<&dyn IsMenuItem<Wry>> = summary
+        .iter()
+        .map(|item| item as &dyn IsMenuItem<Wry>)
+        .collect();
+    if !items.is_empty() {
+        items.push(&separator);
+    }
+    items.extend([
+        &show_i as &dyn IsMenuItem<Wry>,
+        &recent_menu,
+        &profile_menu,
+        &quit_i,
+    ]);
+    Menu::with_items(app, &items)
+}
+
+fn on_recent_task(app: &AppHandle, task_id: u64) {
+    let title = app
+        .state::<SessionStore>()
+        .recent_tasks(RECENT_TASK_LIMIT)
+        .unwrap_or_default()
+        .into_iter()
+        .find(|(id, _)| *id == task_id)
+        .and_then(|(_, title)| title);
+    if let Err(e) = app.state::<TimerManager>().start(app, task_id, title) {
+        log::error!("failed to start timer from tray: {}", e);
+    }
+}
+
+pub fn refresh_menu(app: &AppHandle) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    match build_menu(app) {
+        Ok(menu) => {
+            let _ = tray.set_menu(Some(menu));
+        }
+        Err(e) => log::error!("failed to rebuild tray menu: {}", e),
+    }
+}
+
+// The Jira project key if the title has one, otherwise the initials of the first words.
+#[cfg(target_os = "macos")]
+fn project_abbreviation(title: &str) -> Option<String> {
+    if let Some(key) = crate::integrations::jira::parse_issue_key(title) {
+        return key.split('-').next().map(str::to_string);
+    }
+    let initials: String = title
+        .split_whitespace()
+        .filter_map(|word| word.chars().find(|c| c.is_alphanumeric()))
+        .take(3)
+        .flat_map(char::to_uppercase)
+        .collect();
+    (!initials.is_empty()).then_some(initials)
+}
+
+#[cfg(target_os = "macos")]
+fn menu_bar_text(tick: &TimerTick, settings: &MenuBarSettings) -> String {
+    let seconds = tick.remaining_seconds.unwrap_or(tick.elapsed_seconds);
+    let clock = format!(
+        "{}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    );
+    let mut text = String::from(if tick.paused { "⏸" } else { "▶" });
+    if settings.show_project {
+        if let Some(code) = tick.title.as_deref().and_then(project_abbreviation) {
+            text.push(' ');
+            text.push_str(&code);
+        }
+    }
+    text.push(' ');
+    text.push_str(&clock);
+    text
+}
+
+// Updates the menu bar text from the timer tick; `None` clears it when no timer runs.
+#[cfg(target_os = "macos")]
+pub fn update_menu_bar(app: &AppHandle, tick: Option<&TimerTick>) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    let settings = app
+        .state::<SettingsStore>()
+        .get()
+        .map(|s| s.menu_bar)
+        .unwrap_or_default();
+    let text = tick
+        .filter(|_| settings.show_elapsed)
+        .map(|tick| menu_bar_text(tick, &settings));
+    let _ = tray.set_title(text.as_deref());
+}
+
+// Prefixed with the profile name once more than one profile exists.
+pub fn set_tooltip(app: &AppHandle, text: Option<&str>) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    let text = match (app.state::<ProfileStore>().active_name(), text) {
+        (Some(profile), Some(text)) => Some(format!("{} · {}", profile, text)),
+        (profile, text) => profile.or(text.map(str::to_string)),
+    };
+    let _ = tray.set_tooltip(text.as_deref());
+}
+
+pub fn create_tray(app: &AppHandle) {
+    let menu = build_menu(app).unwrap();
+
+    // Create tray
+    let tray = TrayIconBuilder::with_id(TRAY_ID)
+        .icon(app.default_window_icon().unwrap().clone())
+        .menu(&menu)
+        .on_menu_event(|app, event| match event.id.as_ref() {
+            "show" => {
+                crate::show_main_window(app);
+            }
+            "quit" => {
+                crate::lifecycle::quit(app);
+            }
+            id => {
+                if let Some(reason) = app_lock::refusal(app) {
+                    log::warn!("tray action {} refused: {}", id, reason);
+                } else if let Some(profile) = id.strip_prefix(PROFILE_PREFIX) {
+                    let app = app.clone();
+                    let profile = profile.to_string();
+                    tauri::async_runtime::spawn(async move {
+                        if let Err(e) = profiles::switch(&app, &profile).await {
+                            log::error!("failed to switch profile from tray: {}", e);
+                        }
+                    });
+                } else if let Some(task_id) = id
+                    .strip_prefix(RECENT_TASK_PREFIX)
+                    .and_then(|id| id.parse().ok())
+                {
+                    on_recent_task(app, task_id);
+                }
+            }
+        })
+        .build(app)
+        .unwrap();
+
+    // Store tray
+    app.manage(tray);
+    if let Some(renderer) = crate::tray_icon::TrayIconRenderer::new(app) {
+        app.manage(renderer);
+    }
+    set_tooltip(app, None);
+
+    // Starting a timer changes which tasks are recent; stopping one or locking the app
+    // changes today's summary
+    for event in [
+        "timer-started",
+        "timer-stopped",
+        "app-locked",
+        "app-unlocked",
+    ] {
+        let handle = app.clone();
+        app.listen(event, move |_| refresh_menu(&handle));
+    }
+
+    // Keeps today's totals current while timers run
+    background::spawn_thread(app, "tray_summary", |app, task| {
+        while task.sleep_blocking(SUMMARY_INTERVAL) {
+            refresh_menu(&app);
+        }
+    });
+}
+
+#[tauri::command]
+pub fn set_menu_bar_display(
+    settings: State<'_, SettingsStore>,
+    show_elapsed: bool,
+    show_project: bool,
+) -> Result<(), String> {
+    settings.update(|s| {
+        s.menu_bar = MenuBarSettings {
+            show_elapsed,
+            show_project,
+        }
+    })?;
+    Ok(())
+}
@@ -0,0 +1,272 @@
+// Tray icon with the running timer drawn into it, for trays that can't show text next to
+// the icon (Windows, most Linux panels).
+use image::imageops::{self, FilterType};
+use image::{Rgba, RgbaImage};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::image::Image;
+use tauri::{AppHandle, Manager, State};
+
+use crate::settings::SettingsStore;
+use crate::timer::TimerTick;
+
+const SIZE: u32 = 32;
+const RING_WIDTH: f32 = 4.0;
+// The ring advances in steps so the icon is only redrawn a few times a minute
+const RING_STEPS: u64 = 60;
+const GLYPH_SCALE: u32 = 2;
+
+const RUNNING: Rgba<u8> = Rgba([46, 204, 113, 255]);
+const PAUSED: Rgba<u8> = Rgba([241, 196, 15, 255]);
+const ENDING: Rgba<u8> = Rgba([231, 76, 60, 255]);
+const TRACK: Rgba<u8> = Rgba([128, 128, 128, 90]);
+const LABEL_BACKGROUND: Rgba<u8> = Rgba([0, 0, 0, 200]);
+const LABEL_TEXT: Rgba<u8> = Rgba([255, 255, 255, 255]);
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrayIconStyle {
+    // The app icon, unchanged
+    Static,
+    // Elapsed minutes (remaining for countdowns) in a label over the icon
+    Minutes,
+    // A ring around the icon that fills over each hour, or over the countdown
+    Ring,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TrayIconSettings {
+    pub style: TrayIconStyle,
+}
+
+impl Default for TrayIconSettings {
+    fn default() -> Self {
+        Self {
+            style: TrayIconStyle::Ring,
+        }
+    }
+}
+
+// What the icon currently shows; a tick that renders the same frame is skipped
+#[derive(Clone, PartialEq, Eq)]
+enum Frame {
+    Default,
+    Minutes { label: String, color: Rgba<u8> },
+    Ring { step: u64, color: Rgba<u8> },
+}
+
+pub struct TrayIconRenderer {
+    base: RgbaImage,
+    shown: Mutex<Frame>,
+}
+
+impl TrayIconRenderer {
+    pub fn new(app: &AppHandle) -> Option<Self> {
+        let icon = app.default_window_icon()?;
+        let base = RgbaImage::from_raw(icon.width(), icon.height(), icon.rgba().to_vec())?;
+        Some(Self {
+            base,
+            shown: Mutex::new(Frame::Default),
+        })
+    }
+
+    fn render(&self, frame: &Frame) -> RgbaImage {
+        match frame {
+            Frame::Default => self.base.clone(),
+            Frame::Minutes { label, color } => {
+                let mut canvas = imageops::resize(&self.base, SIZE, SIZE, FilterType::Lanczos3);
+                draw_label(&mut canvas, label, *color);
+                canvas
+            }
+            Frame::Ring { step, color } => {
+                let mut canvas = RgbaImage::new(SIZE, SIZE);
+                let inner = SIZE - 2 * (RING_WIDTH as u32 + 1);
+                let icon = imageops::resize(&self.base, inner, inner, FilterType::Lanczos3);
+                let offset = ((SIZE - inner) / 2) as i64;
+                imageops::overlay(&mut canvas, &icon, offset, offset);
+                draw_ring(&mut canvas, *step as f32 / RING_STEPS as f32, *color);
+                canvas
+            }
+        }
+    }
+}
+
+fn blend(canvas: &mut RgbaImage, x: u32, y: u32, color: Rgba<u8>, coverage: f32) {
+    let alpha = color[3] as f32 / 255.0 * coverage.clamp(0.0, 1.0);
+    if alpha <= 0.0 {
+        return;
+    }
+    let pixel = canvas.get_pixel_mut(x, y);
+    let below = pixel[3] as f32 / 255.0;
+    let out = alpha + below * (1.0 - alpha);
+    for i in 0..3 {
+        let mixed = (color[i] as f32 * alpha + pixel[i] as f32 * below * (1.0 - alpha)) / out;
+        pixel[i] = mixed.round() as u8;
+    }
+    pixel[3] = (out * 255.0).round() as u8;
+}
+
+// Clockwise from twelve o'clock; the unfilled part is drawn as a faint track
+fn draw_ring(canvas: &mut RgbaImage, progress: f32, color: Rgba<u8>) {
+    let center = SIZE as f32 / 2.0;
+    let outer = center - 0.5;
+    let inner = outer - RING_WIDTH;
+    for y in 0..SIZE {
+        for x in 0..SIZE {
+            let dx = x as f32 + 0.5 - center;
+            let dy = y as f32 + 0.5 - center;
+            let distance = (dx * dx + dy * dy).sqrt();
+            // Antialiased edges: partial coverage within half a pixel of either radius
+            let coverage =
+                (outer - distance + 0.5).clamp(0.0, 1.0) * (distance - inner + 0.5).clamp(0.0, 1.0);
+            if coverage <= 0.0 {
+                continue;
+            }
+            let angle = dx.atan2(-dy).rem_euclid(std::f32::consts::TAU);
+            let filled = angle / std::f32::consts::TAU < progress;
+            blend(canvas, x, y, if filled { color } else { TRACK }, coverage);
+        }
+    }
+}
+
+// 3x5 bitmaps, one row per byte with the three low bits left to right
+fn glyph(c: char) -> Option<[u8; 5]> {
+    Some(match c {
+        '0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+        '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+        '2' => [0b111, 0b001, 0b111, 0b100, 0b111],
+        '3' => [0b111, 0b001, 0b111, 0b001, 0b111],
+        '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+        '5' => [0b111, 0b100, 0b111, 0b001, 0b111],
+        '6' => [0b111, 0b100, 0b111, 0b101, 0b111],
+        '7' => [0b111, 0b001, 0b010, 0b010, 0b010],
+        '8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+        '9' => [0b111, 0b101, 0b111, 0b001, 0b111],
+        'h' => [0b100, 0b100, 0b111, 0b101, 0b101],
+        _ => return None,
+    })
+}
+
+// A dark strip along the bottom edge with the label centered in it, underlined in the
+// state color
+fn draw_label(canvas: &mut RgbaImage, label: &str, color: Rgba<u8>) {
+    let glyphs: Vec<[u8; 5]> = label.chars().filter_map(glyph).collect();
+    let advance = 4 * GLYPH_SCALE;
+    let width = (glyphs.len() as u32 * advance).saturating_sub(GLYPH_SCALE);
+    let height = 5 * GLYPH_SCALE;
+    let top = SIZE - height - 4;
+    for y in top..SIZE {
+        for x in 0..SIZE {
+            let fill = if y >= SIZE - 2 {
+                color
+            } else {
+                LABEL_BACKGROUND
+            };
+            blend(canvas, x, y, fill, 1.0);
+        }
+    }
+    let left = SIZE.saturating_sub(width) / 2;
+    for (i, rows) in glyphs.iter().enumerate() {
+        let x0 = left + i as u32 * advance;
+        for (row, bits) in rows.iter().enumerate() {
+            for col in 0..3 {
+                if bits & (0b100 >> col) == 0 {
+                    continue;
+                }
+                for dy in 0..GLYPH_SCALE {
+                    for dx in 0..GLYPH_SCALE {
+                        let x = x0 + col * GLYPH_SCALE + dx;
+                        let y = top + 1 + row as u32 * GLYPH_SCALE + dy;
+                        if x < SIZE && y < SIZE {
+                            blend(canvas, x, y, LABEL_TEXT, 1.0);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Minutes up to 99, then whole hours, so the label never needs more than three glyphs
+fn minutes_label(seconds: u64) -> String {
+    let minutes = seconds / 60;
+    if minutes < 100 {
+        minutes.to_string()
+    } else {
+        format!("{}h", (minutes / 60).min(99))
+    }
+}
+
+fn frame(tick: &TimerTick, duration: Option<u64>, style: TrayIconStyle) -> Frame {
+    // Countdowns turn red in their last tenth
+    let ending = matches!(
+        (tick.remaining_seconds, duration),
+        (Some(remaining), Some(total)) if remaining * 10 <= total
+    );
+    let color = if tick.paused {
+        PAUSED
+    } else if ending {
+        ENDING
+    } else {
+        RUNNING
+    };
+    match style {
+        TrayIconStyle::Static => Frame::Default,
+        TrayIconStyle::Minutes => Frame::Minutes {
+            label: minutes_label(tick.remaining_seconds.unwrap_or(tick.elapsed_seconds)),
+            color,
+        },
+        TrayIconStyle::Ring => {
+            let step = match duration.filter(|total| *total > 0) {
+                Some(total) => tick.elapsed_seconds.min(total) * RING_STEPS / total,
+                None => tick.elapsed_seconds % 3600 * RING_STEPS / 3600,
+            };
+            Frame::Ring { step, color }
+        }
+    }
+}
+
+// Redraws the tray icon from the timer tick; `None` restores the plain icon. `duration`
+// is the countdown length, if the timer has one.
+pub fn update(app: &AppHandle, tick: Option<&TimerTick>, duration: Option<u64>) {
+    let Some(renderer) = app.try_state::<TrayIconRenderer>() else {
+        return;
+    };
+    let Some(tray) = app.tray_by_id(crate::tray::TRAY_ID) else {
+        return;
+    };
+    let style = app
+        .state::<SettingsStore>()
+        .get()
+        .map(|s| s.tray_icon.style)
+        .unwrap_or(TrayIconStyle::Static);
+    let next = match tick {
+        Some(tick) => frame(tick, duration, style),
+        None => Frame::Default,
+    };
+    let Ok(mut shown) = renderer.shown.lock() else {
+        return;
+    };
+    if *shown == next {
+        return;
+    }
+    let image = renderer.render(&next);
+    let (width, height) = image.dimensions();
+    match tray.set_icon(Some(Image::new_owned(image.into_raw(), width, height))) {
+        Ok(()) => *shown = next,
+        Err(e) => log::warn!("failed to update tray icon: {}", e),
+    }
+}
+
+#[tauri::command]
+pub fn set_tray_icon_style(
+    app: AppHandle,
+    settings: State<'_, SettingsStore>,
+    style: TrayIconStyle,
+) -> Result<(), String> {
+    settings.update(|s| s.tray_icon.style = style)?;
+    // Shown again with the new style on the next tick
+    update(&app, None, None);
+    Ok(())
+}
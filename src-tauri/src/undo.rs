@@ -0,0 +1,106 @@
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::sessions::{AuditAction, AuditRecord, Session, SessionData, SessionStore};
+
+const MAX_UNDO_ENTRIES: usize = 50;
+// Older operations can only be recovered from a backup
+const UNDO_RETENTION: TimeDelta = TimeDelta::days(7);
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UndoKind {
+    Delete,
+    Edit,
+    DiscardIdle,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct UndoEntry {
+    pub kind: UndoKind,
+    pub at: DateTime<Utc>,
+    // Entries as they were before the operation, restored by id
+    pub before: Vec<Session>,
+    // Entries the operation created, removed on undo
+    pub created: Vec<u64>,
+}
+
+fn prune(data: &mut SessionData) {
+    let cutoff = Utc::now() - UNDO_RETENTION;
+    data.undo.retain(|entry| entry.at >= cutoff);
+    let overflow = data.undo.len().saturating_sub(MAX_UNDO_ENTRIES);
+    data.undo.drain(..overflow);
+}
+
+// Journals an operation; call inside the SessionStore::write that performs it.
+pub fn record(data: &mut SessionData, kind: UndoKind, before: Vec<Session>, created: Vec<u64>) {
+    data.undo.push(UndoEntry {
+        kind,
+        at: Utc::now(),
+        before,
+        created,
+    });
+    prune(data);
+}
+
+fn revert(data: &mut SessionData, entry: &UndoEntry) {
+    for id in &entry.created {
+        if let Some(index) = data.sessions.iter().position(|s| s.id == *id) {
+            let removed = data.sessions.remove(index);
+            data.audit.push(AuditRecord {
+                entry_id: *id,
+                action: AuditAction::Deleted,
+                at: Utc::now(),
+                before: Some(removed),
+                after: None,
+            });
+        }
+    }
+    for session in &entry.before {
+        let current = data.sessions.iter().position(|s| s.id == session.id);
+        let previous = current.map(|index| data.sessions[index].clone());
+        match current {
+            Some(index) => data.sessions[index] = session.clone(),
+            None => data.sessions.push(session.clone()),
+        }
+        data.audit.push(AuditRecord {
+            entry_id: session.id,
+            action: AuditAction::Restored,
+            at: Utc::now(),
+            before: previous,
+            after: Some(session.clone()),
+        });
+    }
+}
+
+// Reverts the `n` most recent operations, newest first, and returns them.
+#[tauri::command]
+pub fn undo_last(
+    store: State<'_, SessionStore>,
+    n: Option<usize>,
+) -> Result<Vec<UndoEntry>, String> {
+    store.write(|data| {
+        prune(data);
+        let keep = data.undo.len().saturating_sub(n.unwrap_or(1));
+        let undone: Vec<UndoEntry> = data.undo.drain(keep..).rev().collect();
+        for entry in &undone {
+            revert(data, entry);
+        }
+        Ok(undone)
+    })
+}
+
+// Newest first
+#[tauri::command]
+pub fn get_undo_history(store: State<'_, SessionStore>) -> Result<Vec<UndoEntry>, String> {
+    let cutoff = Utc::now() - UNDO_RETENTION;
+    store.read(|data| {
+        data.undo
+            .iter()
+            .rev()
+            .filter(|entry| entry.at >= cutoff)
+            .cloned()
+            .collect()
+    })
+}
@@ -0,0 +1,136 @@
+use futures_util::FutureExt;
+use serde::Serialize;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tauri_plugin_updater::{Update, UpdaterExt};
+
+use crate::background;
+use crate::lifecycle;
+use crate::settings::{ReleaseChannel, SettingsStore};
+
+const STABLE_ENDPOINT: &str =
+    "https://github.com/Ali-Fani-Org/project_ftt_frontend/releases/latest/download/latest.json";
+const BETA_ENDPOINT: &str =
+    "https://github.com/Ali-Fani-Org/project_ftt_frontend/releases/download/beta/latest.json";
+const CHECK_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+
+#[derive(Clone, Serialize)]
+pub struct UpdateInfo {
+    pub version: String,
+    pub current_version: String,
+    pub notes: Option<String>,
+    pub date: Option<String>,
+}
+
+#[derive(Clone, Serialize)]
+struct UpdateProgress {
+    downloaded: u64,
+    total: Option<u64>,
+}
+
+impl From<&Update> for UpdateInfo {
+    fn from(update: &Update) -> Self {
+        UpdateInfo {
+            version: update.version.clone(),
+            current_version: update.current_version.clone(),
+            notes: update.body.clone(),
+            date: update.date.map(|d| d.to_string()),
+        }
+    }
+}
+
+fn endpoint(channel: ReleaseChannel) -> &'static str {
+    match channel {
+        ReleaseChannel::Stable => STABLE_ENDPOINT,
+        ReleaseChannel::Beta => BETA_ENDPOINT,
+    }
+}
+
+// Without the public key from tauri.conf.json, downloaded updates can't be verified
+fn has_pubkey(app: &AppHandle) -> bool {
+    app.config()
+        .plugins
+        .0
+        .get("updater")
+        .and_then(|updater| updater.get("pubkey"))
+        .and_then(|key| key.as_str())
+        .is_some_and(|key| !key.trim().is_empty())
+}
+
+async fn find_update(app: &AppHandle) -> Result<Option<Update>, String> {
+    if !has_pubkey(app) {
+        log::error!("updater public key is missing from tauri.conf.json; updates are disabled");
+        return Err(
+            "This build has no updater public key, so updates can't be verified".to_string(),
+        );
+    }
+    let channel = app.state::<SettingsStore>().get()?.release_channel;
+    let url = tauri::Url::parse(endpoint(channel)).map_err(|e| e.to_string())?;
+    let updater = app
+        .updater_builder()
+        .endpoints(vec![url])
+        .map_err(|e| e.to_string())?
+        .build()
+        .map_err(|e| e.to_string())?;
+    let update = updater.check().await.map_err(|e| e.to_string())?;
+    if let Some(update) = &update {
+        let _ = app.emit("update-available", UpdateInfo::from(update));
+    }
+    Ok(update)
+}
+
+// Check once shortly after launch and then periodically for as long as the app runs.
+pub fn spawn_periodic_checks(app: AppHandle) {
+    background::spawn_async(&app, "updater", |app, task| {
+        async move {
+            if !task.sleep(Duration::from_secs(30)).await {
+                return;
+            }
+            loop {
+                if let Err(e) = find_update(&app).await {
+                    log::warn!("update check failed: {}", e);
+                }
+                if !task.sleep(CHECK_INTERVAL).await {
+                    break;
+                }
+            }
+        }
+        .boxed()
+    });
+}
+
+#[tauri::command]
+pub async fn check_for_updates(app: AppHandle) -> Result<Option<UpdateInfo>, String> {
+    Ok(find_update(&app).await?.as_ref().map(UpdateInfo::from))
+}
+
+#[tauri::command]
+pub async fn download_and_install(app: AppHandle) -> Result<(), String> {
+    let update = find_update(&app)
+        .await?
+        .ok_or_else(|| "No update available".to_string())?;
+
+    let mut downloaded: u64 = 0;
+    update
+        .download_and_install(
+            |chunk, total| {
+                downloaded += chunk as u64;
+                let _ = app.emit("update-progress", UpdateProgress { downloaded, total });
+            },
+            || {},
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+
+    // Through the normal shutdown, so running timers and pending sync are saved first
+    lifecycle::restart(&app).await
+}
+
+#[tauri::command]
+pub fn set_release_channel(
+    store: State<'_, SettingsStore>,
+    channel: ReleaseChannel,
+) -> Result<(), String> {
+    store.update(|s| s.release_channel = channel)?;
+    Ok(())
+}
@@ -0,0 +1,297 @@
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Listener, Manager, State};
+
+use crate::secrets;
+use crate::settings::SettingsStore;
+
+// All hook secrets, as one JSON object of hook id -> secret
+pub(crate) const SECRETS_KEY: &str = "webhook_secrets";
+const MAX_ATTEMPTS: u32 = 4;
+const FIRST_RETRY_DELAY: Duration = Duration::from_secs(2);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+const LOG_CAPACITY: usize = 200;
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEvent {
+    TimerStarted,
+    TimerStopped,
+    UserIdle,
+    UserActive,
+    GoalCompleted,
+}
+
+impl WebhookEvent {
+    const ALL: [WebhookEvent; 5] = [
+        WebhookEvent::TimerStarted,
+        WebhookEvent::TimerStopped,
+        WebhookEvent::UserIdle,
+        WebhookEvent::UserActive,
+        WebhookEvent::GoalCompleted,
+    ];
+
+    // The app event it is fired from; also sent as the payload's `event`
+    fn app_event(self) -> &'static str {
+        match self {
+            WebhookEvent::TimerStarted => "timer-started",
+            WebhookEvent::TimerStopped => "timer-stopped",
+            WebhookEvent::UserIdle => "user-idle",
+            WebhookEvent::UserActive => "user-active",
+            WebhookEvent::GoalCompleted => "goal-completed",
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Webhook {
+    pub id: String,
+    pub url: String,
+    // Events to send; empty sends all of them
+    #[serde(default)]
+    pub events: Vec<WebhookEvent>,
+    #[serde(default = "enabled_default")]
+    pub enabled: bool,
+}
+
+fn enabled_default() -> bool {
+    true
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WebhookSettings {
+    pub hooks: Vec<Webhook>,
+}
+
+#[derive(Clone, Serialize)]
+pub struct WebhookDelivery {
+    pub hook_id: String,
+    pub event: WebhookEvent,
+    pub at: DateTime<Utc>,
+    pub attempts: u32,
+    pub status: Option<u16>,
+    pub error: Option<String>,
+    pub delivered: bool,
+}
+
+// Most recent deliveries, newest last; kept in memory only
+#[derive(Default)]
+pub struct WebhookLog {
+    deliveries: Mutex<VecDeque<WebhookDelivery>>,
+}
+
+impl WebhookLog {
+    fn push(&self, delivery: WebhookDelivery) {
+        if let Ok(mut deliveries) = self.deliveries.lock() {
+            if deliveries.len() == LOG_CAPACITY {
+                deliveries.pop_front();
+            }
+            deliveries.push_back(delivery);
+        }
+    }
+}
+
+fn load_secrets() -> Result<HashMap<String, String>, String> {
+    match secrets::get(SECRETS_KEY)? {
+        Some(json) => serde_json::from_str(&json).map_err(|e| e.to_string()),
+        None => Ok(HashMap::new()),
+    }
+}
+
+fn sign(secret: &str, body: &[u8]) -> Result<String, String> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).map_err(|e| e.to_string())?;
+    mac.update(body);
+    let hex: String = mac
+        .finalize()
+        .into_bytes()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    Ok(format!("sha256={}", hex))
+}
+
+// One POST; Err carries whether the failure is worth retrying.
+async fn post(
+    client: &reqwest::Client,
+    hook: &Webhook,
+    event: WebhookEvent,
+    body: &[u8],
+    signature: Option<&str>,
+) -> (Option<u16>, Result<(), (String, bool)>) {
+    let mut request = client
+        .post(&hook.url)
+        .timeout(REQUEST_TIMEOUT)
+        .header("Content-Type", "application/json")
+        .header("X-FTT-Event", event.app_event())
+        .body(body.to_vec());
+    if let Some(signature) = signature {
+        request = request.header("X-FTT-Signature", signature);
+    }
+    match request.send().await {
+        Ok(response) => {
+            let status = response.status();
+            let result = if status.is_success() {
+                Ok(())
+            } else {
+                // Client errors won't change on retry, except rate limiting
+                let retry = status.is_server_error() || status.as_u16() == 429;
+                Err((format!("HTTP {}", status), retry))
+            };
+            (Some(status.as_u16()), result)
+        }
+        Err(e) => (None, Err((e.to_string(), true))),
+    }
+}
+
+async fn deliver(app: AppHandle, hook: Webhook, event: WebhookEvent, body: Vec<u8>) {
+    let signature = match load_secrets() {
+        Ok(secrets) => secrets.get(&hook.id).map(|s| sign(s, &body)).transpose(),
+        Err(e) => Err(e),
+    };
+    let mut delivery = WebhookDelivery {
+        hook_id: hook.id.clone(),
+        event,
+        at: Utc::now(),
+        attempts: 0,
+        status: None,
+        error: None,
+        delivered: false,
+    };
+    let signature = match signature {
+        Ok(signature) => signature,
+        Err(e) => {
+            // Never send unsigned when a secret is configured but unreadable
+            delivery.error = Some(format!("Failed to sign: {}", e));
+            app.state::<WebhookLog>().push(delivery);
+            return;
+        }
+    };
+
+    let client = reqwest::Client::new();
+    let mut delay = FIRST_RETRY_DELAY;
+    loop {
+        delivery.attempts += 1;
+        let (status, result) = post(&client, &hook, event, &body, signature.as_deref()).await;
+        delivery.status = status;
+        match result {
+            Ok(()) => {
+                delivery.delivered = true;
+                delivery.error = None;
+                break;
+            }
+            Err((e, retry)) => {
+                delivery.error = Some(e);
+                if !retry || delivery.attempts >= MAX_ATTEMPTS {
+                    break;
+                }
+            }
+        }
+        tokio::time::sleep(delay).await;
+        delay *= 2;
+    }
+    if let Some(e) = &delivery.error {
+        log::warn!(
+            "webhook {} failed after {} attempts: {}",
+            hook.id,
+            delivery.attempts,
+            e
+        );
+    }
+    app.state::<WebhookLog>().push(delivery);
+}
+
+fn fire(app: &AppHandle, event: WebhookEvent, payload: &str) {
+    let hooks = match app.state::<SettingsStore>().get() {
+        Ok(settings) => settings.webhooks.hooks,
+        Err(e) => {
+            log::warn!("failed to read webhook settings: {}", e);
+            return;
+        }
+    };
+    let hooks: Vec<Webhook> = hooks
+        .into_iter()
+        .filter(|h| h.enabled && (h.events.is_empty() || h.events.contains(&event)))
+        .collect();
+    if hooks.is_empty() {
+        return;
+    }
+    let data = serde_json::from_str(payload).unwrap_or(serde_json::Value::Null);
+    let body = serde_json::json!({
+        "event": event.app_event(),
+        "timestamp": Utc::now(),
+        "data": data,
+    })
+    .to_string()
+    .into_bytes();
+    for hook in hooks {
+        tauri::async_runtime::spawn(deliver(app.clone(), hook, event, body.clone()));
+    }
+}
+
+pub fn register(app: &AppHandle) {
+    app.manage(WebhookLog::default());
+    for event in WebhookEvent::ALL {
+        let handle = app.clone();
+        app.listen(event.app_event(), move |e| {
+            fire(&handle, event, e.payload())
+        });
+    }
+}
+
+#[tauri::command]
+pub fn set_webhooks(settings: State<'_, SettingsStore>, hooks: Vec<Webhook>) -> Result<(), String> {
+    for hook in &hooks {
+        let url = reqwest::Url::parse(&hook.url).map_err(|e| format!("{}: {}", hook.url, e))?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(format!(
+                "{}: only http and https URLs are supported",
+                hook.url
+            ));
+        }
+    }
+    // Drop secrets of hooks that were removed
+    let mut stored = load_secrets()?;
+    let len = stored.len();
+    stored.retain(|id, _| hooks.iter().any(|h| &h.id == id));
+    if stored.len() != len {
+        secrets::set(
+            SECRETS_KEY,
+            &serde_json::to_string(&stored).map_err(|e| e.to_string())?,
+        )?;
+    }
+    settings.update(|s| s.webhooks.hooks = hooks)?;
+    Ok(())
+}
+
+// None or an empty string removes the secret; deliveries are then sent unsigned.
+#[tauri::command]
+pub fn set_webhook_secret(id: String, secret: Option<String>) -> Result<(), String> {
+    let mut stored = load_secrets()?;
+    match secret.filter(|s| !s.is_empty()) {
+        Some(secret) => stored.insert(id, secret),
+        None => stored.remove(&id),
+    };
+    secrets::set(
+        SECRETS_KEY,
+        &serde_json::to_string(&stored).map_err(|e| e.to_string())?,
+    )
+}
+
+#[tauri::command]
+pub fn get_webhook_deliveries(
+    log: State<'_, WebhookLog>,
+    hook_id: Option<String>,
+) -> Result<Vec<WebhookDelivery>, String> {
+    let deliveries = log.deliveries.lock().map_err(|e| e.to_string())?;
+    Ok(deliveries
+        .iter()
+        .filter(|d| hook_id.as_ref().map_or(true, |id| &d.hook_id == id))
+        .cloned()
+        .collect())
+}
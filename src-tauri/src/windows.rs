@@ -0,0 +1,214 @@
+// Auxiliary windows that show one part of the frontend on its own, so e.g. reports can
+// stay open next to the main window. Each remembers where it was and how big it was.
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{
+    AppHandle, LogicalPosition, LogicalSize, Manager, State, WebviewUrl, WebviewWindow,
+    WebviewWindowBuilder, WindowEvent,
+};
+
+use crate::storage;
+
+struct WindowKind {
+    name: &'static str,
+    title: &'static str,
+    // Frontend route rendered in the window
+    route: &'static str,
+    width: f64,
+    height: f64,
+}
+
+// Labels match the names, and are listed in the default capability
+const WINDOWS: &[WindowKind] = &[
+    WindowKind {
+        name: "dashboard",
+        title: "Dashboard",
+        route: "dashboard",
+        width: 900.0,
+        height: 640.0,
+    },
+    WindowKind {
+        name: "reports",
+        title: "Reports",
+        route: "reports",
+        width: 1000.0,
+        height: 700.0,
+    },
+    WindowKind {
+        name: "settings",
+        title: "Settings",
+        route: "settings",
+        width: 720.0,
+        height: 600.0,
+    },
+];
+
+const MIN_WIDTH: f64 = 400.0;
+const MIN_HEIGHT: f64 = 300.0;
+
+// Logical pixels
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct WindowGeometry {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct WindowData {
+    geometry: HashMap<String, WindowGeometry>,
+}
+
+pub struct WindowStore {
+    path: PathBuf,
+    data: Mutex<WindowData>,
+}
+
+impl WindowStore {
+    pub fn load(app: &AppHandle) -> Result<Self, String> {
+        let path = storage::data_file(app, "windows.json")?;
+        let data = storage::load_json(&path)?;
+        Ok(Self {
+            path,
+            data: Mutex::new(data),
+        })
+    }
+
+    fn geometry(&self, name: &str) -> Option<WindowGeometry> {
+        self.data.lock().ok()?.geometry.get(name).copied()
+    }
+
+    fn save(&self, name: &str, geometry: WindowGeometry) -> Result<(), String> {
+        let mut data = self.data.lock().map_err(|e| e.to_string())?;
+        data.geometry.insert(name.to_string(), geometry);
+        storage::save_json(&self.path, &*data)
+    }
+}
+
+fn kind(name: &str) -> Result<&'static WindowKind, String> {
+    WINDOWS
+        .iter()
+        .find(|w| w.name == name)
+        .ok_or_else(|| format!("Unknown window \"{}\"", name))
+}
+
+// Whether a connected monitor contains the logical point, so a window saved on a since
+// disconnected display isn't restored off screen
+pub fn on_screen(app: &AppHandle, x: f64, y: f64) -> bool {
+    app.available_monitors()
+        .unwrap_or_default()
+        .iter()
+        .any(|monitor| {
+            let scale = monitor.scale_factor();
+            let origin = monitor.position().to_logical::<f64>(scale);
+            let size = monitor.size().to_logical::<f64>(scale);
+            x >= origin.x
+                && y >= origin.y
+                && x < origin.x + size.width
+                && y < origin.y + size.height
+        })
+}
+
+fn save_geometry(window: &WebviewWindow) {
+    let Ok(scale) = window.scale_factor() else {
+        return;
+    };
+    let (Ok(position), Ok(size)) = (window.outer_position(), window.inner_size()) else {
+        return;
+    };
+    // Minimized windows report a bogus position and size on some platforms
+    if window.is_minimized().unwrap_or(false) {
+        return;
+    }
+    let position: LogicalPosition<f64> = position.to_logical(scale);
+    let size: LogicalSize<f64> = size.to_logical(scale);
+    let geometry = WindowGeometry {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+    };
+    if let Err(e) = window.state::<WindowStore>().save(window.label(), geometry) {
+        log::warn!("failed to save {} window geometry: {}", window.label(), e);
+    }
+}
+
+fn build(app: &AppHandle, kind: &WindowKind) -> Result<WebviewWindow, String> {
+    let saved = app
+        .state::<WindowStore>()
+        .geometry(kind.name)
+        .filter(|g| on_screen(app, g.x, g.y));
+    let mut builder = WebviewWindowBuilder::new(app, kind.name, WebviewUrl::App(kind.route.into()))
+        .title(format!("Time Tracker · {}", kind.title))
+        .decorations(false)
+        .min_inner_size(MIN_WIDTH, MIN_HEIGHT);
+    builder = match saved {
+        Some(g) => builder
+            .inner_size(g.width.max(MIN_WIDTH), g.height.max(MIN_HEIGHT))
+            .position(g.x, g.y),
+        None => builder.inner_size(kind.width, kind.height).center(),
+    };
+    let window = builder.build().map_err(|e| e.to_string())?;
+
+    let handle = window.clone();
+    window.on_window_event(move |event| {
+        if let WindowEvent::CloseRequested { .. } = event {
+            save_geometry(&handle);
+        }
+    });
+    Ok(window)
+}
+
+// app.exit skips CloseRequested, so open windows are saved and closed here
+pub fn on_exit(app: &AppHandle) {
+    for kind in WINDOWS {
+        if let Some(window) = app.get_webview_window(kind.name) {
+            save_geometry(&window);
+            let _ = window.destroy();
+        }
+    }
+}
+
+// Opens the named window, or brings it to the front if it's already open.
+#[tauri::command]
+pub fn open_window(app: AppHandle, name: String) -> Result<(), String> {
+    let kind = kind(&name)?;
+    let window = match app.get_webview_window(kind.name) {
+        Some(window) => window,
+        None => build(&app, kind)?,
+    };
+    let _ = window.unminimize();
+    window.show().map_err(|e| e.to_string())?;
+    window.set_focus().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn close_window(app: AppHandle, name: String) -> Result<(), String> {
+    let kind = kind(&name)?;
+    match app.get_webview_window(kind.name) {
+        Some(window) => window.close().map_err(|e| e.to_string()),
+        None => Ok(()),
+    }
+}
+
+// Names of the auxiliary windows that are currently open
+#[tauri::command]
+pub fn list_open_windows(app: AppHandle) -> Vec<String> {
+    WINDOWS
+        .iter()
+        .filter(|w| app.get_webview_window(w.name).is_some())
+        .map(|w| w.name.to_string())
+        .collect()
+}
+
+// Forgets saved geometry, so the window opens centered at its default size next time.
+#[tauri::command]
+pub fn reset_window_geometry(store: State<'_, WindowStore>, name: String) -> Result<(), String> {
+    let kind = kind(&name)?;
+    let mut data = store.data.lock().map_err(|e| e.to_string())?;
+    data.geometry.remove(kind.name);
+    storage::save_json(&store.path, &*data)
+}
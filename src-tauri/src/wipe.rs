@@ -0,0 +1,138 @@
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use reqwest::Method;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, State};
+
+use crate::background::TaskRegistry;
+use crate::idle::IdleMonitor;
+use crate::integrations::{jira, mqtt, slack};
+use crate::profiles::{ProfileStore, DEFAULT_PROFILE};
+use crate::{app_lock, backend, e2ee, email, encryption, local_api, privacy, secrets, webhooks};
+
+// How long a token from request_data_wipe stays valid
+const CONFIRM_TTL: Duration = Duration::from_secs(120);
+const DELETION_PATH: &str = "/api/me/data";
+
+// Every keyring entry the app writes, per profile
+const SECRET_KEYS: &[&str] = &[
+    backend::TOKEN_KEY,
+    jira::TOKEN_KEY,
+    slack::TOKEN_KEY,
+    local_api::TOKEN_KEY,
+    app_lock::PIN_KEY,
+    encryption::KEY_NAME,
+    e2ee::KEY_NAME,
+    privacy::SALT_KEY,
+    email::PASSWORD_KEY,
+    webhooks::SECRETS_KEY,
+    mqtt::PASSWORD_KEY,
+];
+
+// One-time token the UI must echo back, so a single stray call can't wipe anything
+#[derive(Default)]
+pub struct WipeConfirmation {
+    pending: Mutex<Option<(String, Instant)>>,
+}
+
+impl WipeConfirmation {
+    fn take(&self, given: &str) -> Result<(), String> {
+        let pending = self.pending.lock().map_err(|e| e.to_string())?.take();
+        match pending {
+            Some((token, issued)) if token == given && issued.elapsed() < CONFIRM_TTL => Ok(()),
+            Some(_) => Err("The confirmation token is invalid or has expired".to_string()),
+            None => Err("Request a confirmation token first".to_string()),
+        }
+    }
+}
+
+async fn request_remote_deletion(app: &AppHandle) -> Result<(), String> {
+    backend::request(app, Method::DELETE, DELETION_PATH)?
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+fn delete_secrets(app: &AppHandle) {
+    let profiles = app
+        .state::<ProfileStore>()
+        .list()
+        .map(|list| list.into_iter().map(|p| p.id).collect())
+        .unwrap_or_else(|_| vec![DEFAULT_PROFILE.to_string()]);
+    for profile in &profiles {
+        let profile = (profile != DEFAULT_PROFILE).then_some(profile.as_str());
+        for key in SECRET_KEYS {
+            if let Err(e) = secrets::delete_for(profile, key) {
+                log::warn!("failed to delete keyring entry {}: {}", key, e);
+            }
+        }
+    }
+}
+
+// Data, settings, backups, diagnostics and screenshots for every profile, plus logs and
+// caches. Some of these resolve to the same directory on a given platform.
+fn app_dirs(app: &AppHandle) -> Vec<PathBuf> {
+    let path = app.path();
+    let mut dirs: Vec<PathBuf> = [
+        path.app_data_dir(),
+        path.app_local_data_dir(),
+        path.app_config_dir(),
+        path.app_cache_dir(),
+        path.app_log_dir(),
+    ]
+    .into_iter()
+    .filter_map(Result::ok)
+    .collect();
+    dirs.sort();
+    dirs.dedup();
+    dirs
+}
+
+#[tauri::command]
+pub fn request_data_wipe(confirmation: State<'_, WipeConfirmation>) -> Result<String, String> {
+    let token: String = rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(16)
+        .map(char::from)
+        .collect();
+    *confirmation.pending.lock().map_err(|e| e.to_string())? =
+        Some((token.clone(), Instant::now()));
+    Ok(token)
+}
+
+// Erases everything the app has stored on this machine and exits. When the backend is
+// configured it is asked to delete the account's data first; if that fails nothing is
+// wiped unless `local_only` is set, so the user can retry once online.
+#[tauri::command]
+pub async fn delete_all_my_data(
+    app: AppHandle,
+    confirm_token: String,
+    local_only: Option<bool>,
+) -> Result<(), String> {
+    app.state::<WipeConfirmation>().take(&confirm_token)?;
+
+    if backend::is_configured(&app) && !local_only.unwrap_or(false) {
+        request_remote_deletion(&app)
+            .await
+            .map_err(|e| format!("The backend did not confirm deletion: {}", e))?;
+    }
+
+    log::warn!("deleting all local data");
+    app.state::<TaskRegistry>().stop_all();
+    app.state::<IdleMonitor>().stop();
+    delete_secrets(&app);
+    for dir in app_dirs(&app) {
+        if let Err(e) = std::fs::remove_dir_all(&dir) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                log::error!("failed to remove {}: {}", dir.display(), e);
+            }
+        }
+    }
+    // Exits without the normal shutdown, which would save the running timers (and the
+    // plugin stores) back into the directories just removed
+    std::process::exit(0);
+}
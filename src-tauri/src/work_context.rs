@@ -0,0 +1,232 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::Duration;
+use sysinfo::Networks;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::background;
+use crate::idle::IdleSettings;
+use crate::profiles::{self, DEFAULT_PROFILE};
+use crate::settings::SettingsStore;
+use crate::timer::TimerManager;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(60);
+// Interface names that indicate a VPN tunnel, matched case-insensitively as prefixes or
+// substrings
+const VPN_PREFIXES: &[&str] = &["tun", "tap", "wg", "utun", "ppp", "ipsec"];
+const VPN_NAMES: &[&str] = &["vpn", "wireguard", "tailscale", "nordlynx", "zerotier"];
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct DefaultTask {
+    pub task_id: u64,
+    #[serde(default)]
+    pub title: Option<String>,
+}
+
+// A rule matches when every condition it sets holds; one with none always matches, as
+// a fallback at the end of the list
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ContextRule {
+    pub name: String,
+    #[serde(default)]
+    pub ssid: Option<String>,
+    #[serde(default)]
+    pub vpn: Option<bool>,
+    // Profile to switch to; the switch waits until no timer is running
+    #[serde(default)]
+    pub profile: Option<String>,
+    // Offered as the task to start while this context applies
+    #[serde(default)]
+    pub default_task: Option<DefaultTask>,
+    #[serde(default)]
+    pub idle_threshold_secs: Option<u64>,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ContextSettings {
+    pub enabled: bool,
+    // First match wins
+    pub rules: Vec<ContextRule>,
+}
+
+#[derive(Clone, Default, Serialize)]
+pub struct WorkContext {
+    pub ssid: Option<String>,
+    pub vpn: bool,
+    // The matching rule, if any
+    pub rule: Option<ContextRule>,
+}
+
+#[derive(Default)]
+pub struct ContextDetector {
+    current: Mutex<WorkContext>,
+}
+
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+fn run(program: &str, args: &[&str]) -> Option<String> {
+    let output = std::process::Command::new(program)
+        .args(args)
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(target_os = "linux")]
+fn current_ssid() -> Option<String> {
+    run("nmcli", &["-t", "-f", "active,ssid", "dev", "wifi"])
+        .and_then(|out| {
+            out.lines()
+                .find_map(|line| line.strip_prefix("yes:").map(str::to_string))
+        })
+        .or_else(|| run("iwgetid", &["-r"]).map(|out| out.trim().to_string()))
+        .filter(|ssid| !ssid.is_empty())
+}
+
+#[cfg(target_os = "macos")]
+fn current_ssid() -> Option<String> {
+    run("networksetup", &["-getairportnetwork", "en0"])?
+        .trim()
+        .strip_prefix("Current Wi-Fi Network: ")
+        .map(str::to_string)
+}
+
+#[cfg(windows)]
+fn current_ssid() -> Option<String> {
+    use std::os::windows::process::CommandExt;
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+    let output = std::process::Command::new("netsh")
+        .args(["wlan", "show", "interfaces"])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .ok()?;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            // "SSID", not "BSSID"
+            (key.trim() == "SSID").then(|| value.trim().to_string())
+        })
+        .filter(|ssid| !ssid.is_empty())
+}
+
+#[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+fn current_ssid() -> Option<String> {
+    None
+}
+
+fn vpn_active() -> bool {
+    Networks::new_with_refreshed_list().iter().any(|(name, _)| {
+        let name = name.to_lowercase();
+        VPN_PREFIXES.iter().any(|p| name.starts_with(p))
+            || VPN_NAMES.iter().any(|n| name.contains(n))
+    })
+}
+
+fn matches(rule: &ContextRule, ssid: Option<&str>, vpn: bool) -> bool {
+    rule.ssid
+        .as_deref()
+        .map_or(true, |wanted| ssid == Some(wanted))
+        && rule.vpn.map_or(true, |wanted| wanted == vpn)
+}
+
+fn detect(settings: &ContextSettings) -> WorkContext {
+    let ssid = current_ssid();
+    let vpn = vpn_active();
+    let rule = settings
+        .rules
+        .iter()
+        .find(|r| matches(r, ssid.as_deref(), vpn))
+        .cloned();
+    WorkContext { ssid, vpn, rule }
+}
+
+// Applies the current context's idle threshold on top of the saved settings.
+pub fn adjust_idle(app: &AppHandle, idle: &mut IdleSettings) {
+    let Some(detector) = app.try_state::<ContextDetector>() else {
+        return;
+    };
+    let threshold = detector
+        .current
+        .lock()
+        .ok()
+        .and_then(|c| c.rule.as_ref()?.idle_threshold_secs);
+    if let Some(threshold) = threshold {
+        idle.threshold_secs = threshold;
+    }
+}
+
+// Switching restarts the app, so it only happens with no timer running.
+fn switch_profile(app: &AppHandle, profile: &str) {
+    if profiles::active_id().unwrap_or(DEFAULT_PROFILE) == profile
+        || !app.state::<TimerManager>().list().is_empty()
+    {
+        return;
+    }
+    log::info!("switching to profile {} for the current context", profile);
+    let app = app.clone();
+    let profile = profile.to_string();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = profiles::switch(&app, &profile).await {
+            log::warn!("context profile switch failed: {}", e);
+        }
+    });
+}
+
+fn poll(app: &AppHandle) -> Result<(), String> {
+    let settings = app.state::<SettingsStore>().get()?.context;
+    let context = if settings.enabled {
+        detect(&settings)
+    } else {
+        WorkContext::default()
+    };
+    let changed = {
+        let mut current = app
+            .state::<ContextDetector>()
+            .current
+            .lock()
+            .map_err(|e| e.to_string())?;
+        let changed =
+            current.rule.as_ref().map(|r| &r.name) != context.rule.as_ref().map(|r| &r.name);
+        *current = context.clone();
+        changed
+    };
+    if changed {
+        let _ = app.emit("context-changed", &context);
+    }
+    if let Some(profile) = context.rule.as_ref().and_then(|r| r.profile.as_deref()) {
+        switch_profile(app, profile);
+    }
+    Ok(())
+}
+
+pub fn start_detector(app: AppHandle) {
+    app.manage(ContextDetector::default());
+    background::spawn_thread(&app, "work_context", |app, task| loop {
+        if let Err(e) = poll(&app) {
+            log::warn!("context detection failed: {}", e);
+        }
+        if !task.sleep_blocking(POLL_INTERVAL) {
+            break;
+        }
+    });
+}
+
+#[tauri::command]
+pub fn get_current_context(detector: State<'_, ContextDetector>) -> Result<WorkContext, String> {
+    Ok(detector.current.lock().map_err(|e| e.to_string())?.clone())
+}
+
+#[tauri::command]
+pub fn set_context_rules(
+    app: AppHandle,
+    settings: State<'_, SettingsStore>,
+    context: ContextSettings,
+) -> Result<WorkContext, String> {
+    settings.update(|s| s.context = context)?;
+    poll(&app)?;
+    get_current_context(app.state::<ContextDetector>())
+}
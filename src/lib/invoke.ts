@@ -0,0 +1,27 @@
+import { invoke as coreInvoke, type InvokeArgs, type InvokeOptions } from '@tauri-apps/api/core';
+
+// The backend can't see when an async command finishes or whether a command failed, so
+// every call is timed here and the outcome reported for get_command_metrics.
+const REPORT_COMMAND = 'record_command_result';
+
+function report(command: string, ok: boolean, start: number) {
+  coreInvoke(REPORT_COMMAND, { command, ok, durationMs: performance.now() - start }).catch(
+    () => {}
+  );
+}
+
+export async function invoke<T>(
+  command: string,
+  args?: InvokeArgs,
+  options?: InvokeOptions
+): Promise<T> {
+  const start = performance.now();
+  try {
+    const result = await coreInvoke<T>(command, args, options);
+    report(command, true, start);
+    return result;
+  } catch (error) {
+    report(command, false, start);
+    throw error;
+  }
+}